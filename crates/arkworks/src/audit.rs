@@ -0,0 +1,269 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    fmt,
+};
+
+use ark_ff::PrimeField;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+
+use crate::circuit::{SudokuCircuit, SynthesizedCells};
+
+const UNSOLVED_RULES: [&str; 3] = ["unsolved range", "givens", "hash"];
+const SOLVED_RULES: [&str; 5] = ["range", "givens", "rows", "columns", "boxes"];
+const UNIQUENESS_RULES: [&str; 3] = ["rows", "columns", "boxes"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grid {
+    Unsolved,
+    Solved,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub grid: Grid,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let grid = match self.grid {
+            Grid::Unsolved => "unsolved",
+            Grid::Solved => "solved",
+        };
+        write!(f, "{}[{}][{}]", grid, self.row, self.col)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Finding {
+    /// The bit only appears in constraints that involve no other variable
+    /// (such as its booleanity check), so it can take any value.
+    Unconstrained { cell: Cell, bit: usize },
+    /// Some bit of the cell is missing from every constraint of the rule.
+    MissingRule { cell: Cell, rule: &'static str },
+    /// The two cells share a row, column or box but no uniqueness
+    /// constraint compares them.
+    MissingPair { a: Cell, b: Cell },
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Finding::Unconstrained { cell, bit } => {
+                write!(f, "bit {} of {} is unconstrained", bit, cell)
+            }
+            Finding::MissingRule { cell, rule } => {
+                write!(f, "{} is not covered by the {} rule", cell, rule)
+            }
+            Finding::MissingPair { a, b } => write!(f, "{} and {} are never compared", a, b),
+        }
+    }
+}
+
+/// Synthesizes the circuit and reports every cell that is not tied into the
+/// constraints it is expected to be part of. An empty result means each
+/// cell is covered by all of its rules and each pair of peers is compared.
+pub fn audit<F: PrimeField>(circuit: SudokuCircuit<F>) -> Result<Vec<Finding>, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    let cells = circuit.synthesize(cs.clone())?;
+    audit_cells(cs, &cells)
+}
+
+pub(crate) fn audit_cells<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    cells: &SynthesizedCells<F>,
+) -> Result<Vec<Finding>, SynthesisError> {
+    cs.finalize();
+    let matrices = cs.to_matrices().ok_or(SynthesisError::AssignmentMissing)?;
+    let num_instance = matrices.num_instance_variables;
+
+    // Variables used by each constraint, without the constant `one`
+    let constraint_vars = (0..matrices.num_constraints)
+        .map(|i| {
+            matrices.a[i]
+                .iter()
+                .chain(&matrices.b[i])
+                .chain(&matrices.c[i])
+                .map(|(_, var)| *var)
+                .filter(|var| *var != 0)
+                .collect::<BTreeSet<usize>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut cell_vars = Vec::with_capacity(162);
+    let mut owner = HashMap::new();
+    for (grid, rows) in [
+        (Grid::Unsolved, &cells.unsolved),
+        (Grid::Solved, &cells.solved),
+    ] {
+        for (row, line) in rows.iter().enumerate() {
+            for (col, value) in line.iter().enumerate() {
+                let cell = Cell { grid, row, col };
+                let vars = value
+                    .to_bits_le()?
+                    .iter()
+                    .filter_map(|bit| match bit {
+                        Boolean::Is(bit) => bit.variable().get_index_unchecked(num_instance),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                for var in &vars {
+                    owner.insert(*var, cell);
+                }
+                cell_vars.push((cell, vars));
+            }
+        }
+    }
+
+    let mut findings = vec![];
+
+    let mut linked = vec![false; num_instance + matrices.num_witness_variables];
+    for vars in constraint_vars.iter().filter(|vars| vars.len() > 1) {
+        vars.iter().for_each(|var| linked[*var] = true);
+    }
+    for (cell, vars) in &cell_vars {
+        for (bit, var) in vars.iter().enumerate() {
+            if !linked[*var] {
+                findings.push(Finding::Unconstrained { cell: *cell, bit });
+            }
+        }
+    }
+
+    for (rule, range) in &cells.groups {
+        let touched = constraint_vars[range.clone()]
+            .iter()
+            .flatten()
+            .copied()
+            .collect::<HashSet<usize>>();
+        for (cell, vars) in &cell_vars {
+            let rules = match cell.grid {
+                Grid::Unsolved => &UNSOLVED_RULES[..],
+                Grid::Solved => &SOLVED_RULES[..],
+            };
+            if rules.contains(rule) && !vars.iter().all(|var| touched.contains(var)) {
+                findings.push(Finding::MissingRule {
+                    cell: *cell,
+                    rule: *rule,
+                });
+            }
+        }
+    }
+
+    let mut compared = HashSet::new();
+    for (_, range) in cells
+        .groups
+        .iter()
+        .filter(|(rule, _)| UNIQUENESS_RULES.contains(rule))
+    {
+        for vars in &constraint_vars[range.clone()] {
+            let touched = vars
+                .iter()
+                .filter_map(|var| owner.get(var))
+                .filter(|cell| cell.grid == Grid::Solved)
+                .map(|cell| (cell.row, cell.col))
+                .collect::<BTreeSet<_>>();
+            for a in &touched {
+                for b in touched.range(a..).skip(1) {
+                    compared.insert((*a, *b));
+                }
+            }
+        }
+    }
+    for a in 0..81 {
+        for b in a + 1..81 {
+            let (a, b) = ((a / 9, a % 9), (b / 9, b % 9));
+            let same_box = a.0 / 3 == b.0 / 3 && a.1 / 3 == b.1 / 3;
+            if (a.0 == b.0 || a.1 == b.1 || same_box) && !compared.contains(&(a, b)) {
+                let solved = |(row, col)| Cell {
+                    grid: Grid::Solved,
+                    row,
+                    col,
+                };
+                findings.push(Finding::MissingPair {
+                    a: solved(a),
+                    b: solved(b),
+                });
+            }
+        }
+    }
+
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+
+    use super::{audit, audit_cells, Cell, Finding, Grid};
+    use crate::{
+        circuit::{SudokuCircuit, SynthesizedCells},
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    #[test]
+    fn test_audit_sudoku_circuit() {
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let findings = audit(circuit).unwrap();
+        assert!(findings.is_empty(), "{:?}", findings);
+    }
+
+    #[test]
+    fn test_audit_skipped_cell() {
+        // A "range" rule that forgets solved[4][4]
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let alloc = |grid: [[u8; 9]; 9]| {
+            grid.iter()
+                .map(|line| {
+                    line.iter()
+                        .map(|value| UInt8::new_witness(cs.clone(), || Ok(*value)).unwrap())
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+        let unsolved = alloc(UNSOLVED);
+        let solved = alloc(SOLVED);
+        let start = cs.num_constraints();
+        for (i, line) in solved.iter().enumerate() {
+            for (j, value) in line.iter().enumerate() {
+                if (i, j) != (4, 4) {
+                    value.enforce_not_equal(&UInt8::constant(0)).unwrap();
+                }
+            }
+        }
+        let cells = SynthesizedCells {
+            unsolved,
+            solved,
+            groups: vec![("range", start..cs.num_constraints())],
+        };
+
+        let findings = audit_cells(cs, &cells).unwrap();
+        let skipped = Cell {
+            grid: Grid::Solved,
+            row: 4,
+            col: 4,
+        };
+        assert!(findings.contains(&Finding::MissingRule {
+            cell: skipped,
+            rule: "range"
+        }));
+        assert!(findings.contains(&Finding::Unconstrained {
+            cell: skipped,
+            bit: 0
+        }));
+        assert!(!findings.iter().any(|finding| matches!(
+            finding,
+            Finding::MissingRule { cell, .. } if cell.grid == Grid::Solved && *cell != skipped
+        )));
+    }
+}
@@ -0,0 +1,132 @@
+use std::time::Instant;
+
+use ark_ec::{AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, PrimeField, UniformRand, Zero};
+use ark_groth16::{prepare_inputs, verify_proof, Groth16, PreparedVerifyingKey, Proof};
+use ark_relations::r1cs::SynthesisError;
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+
+use crate::{
+    circuit::SudokuCircuit,
+    hash::PuzzleHash,
+    parameters::{unsolved_hash, SOLVED, UNSOLVED},
+};
+
+/// Verifies several Groth16 proofs at once. Each verification equation
+/// e(A, B) = e(alpha, beta) · e(inputs, gamma) · e(C, delta) is raised to a
+/// random power and the results multiplied, so the pairings with gamma and
+/// delta are shared and a single final exponentiation is done. A batch with
+/// an invalid proof passes with negligible probability over the choice of
+/// the random coefficients.
+pub fn verify_batch<E: PairingEngine, R: RngCore>(
+    pvk: &PreparedVerifyingKey<E>,
+    instances: &[(Vec<E::Fr>, Proof<E>)],
+    rng: &mut R,
+) -> Result<bool, SynthesisError> {
+    let mut pairs = Vec::with_capacity(instances.len() + 2);
+    let mut inputs_acc = E::G1Projective::zero();
+    let mut c_acc = E::G1Projective::zero();
+    let mut r_sum = E::Fr::zero();
+    for (public_inputs, proof) in instances {
+        let r = E::Fr::rand(rng);
+        let r_repr = r.into_repr();
+        inputs_acc += prepare_inputs(pvk, public_inputs)?.mul(r_repr);
+        c_acc += proof.c.mul(r_repr);
+        r_sum += r;
+        pairs.push((proof.a.mul(r_repr).into_affine().into(), proof.b.into()));
+    }
+    pairs.push((inputs_acc.into_affine().into(), pvk.gamma_g2_neg_pc.clone()));
+    pairs.push((c_acc.into_affine().into(), pvk.delta_g2_neg_pc.clone()));
+
+    let test = E::final_exponentiation(&E::miller_loop(pairs.iter()))
+        .ok_or(SynthesisError::UnexpectedIdentity)?;
+    Ok(test == pvk.alpha_g1_beta_g2.pow(r_sum.into_repr()))
+}
+
+/// Proves the sample sudoku `batch_size` times and compares verifying the
+/// proofs one by one with `verify_batch`.
+pub fn run_batch_verify<E: PairingEngine, R: RngCore + CryptoRng>(batch_size: usize, rng: &mut R) {
+    let circuit = SudokuCircuit::<E::Fr> {
+        unsolved_hash: unsolved_hash(UNSOLVED),
+        unsolved: UNSOLVED,
+        solved: SOLVED,
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
+    };
+    let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), rng).unwrap();
+    let pvk = Groth16::<E>::process_vk(&vk).unwrap();
+    let instances = (0..batch_size)
+        .map(|_| {
+            let proof = Groth16::<E>::prove(&pk, circuit.clone(), rng).unwrap();
+            (vec![circuit.unsolved_hash], proof)
+        })
+        .collect::<Vec<_>>();
+
+    let single_start = Instant::now();
+    for (public_inputs, proof) in &instances {
+        assert!(verify_proof(&pvk, proof, public_inputs).unwrap());
+    }
+    let single_time = single_start.elapsed();
+    println!(
+        "verify {} proofs one by one {}ms, {}s",
+        batch_size,
+        single_time.as_millis(),
+        single_time.as_secs()
+    );
+
+    let batch_start = Instant::now();
+    assert!(verify_batch(&pvk, &instances, rng).unwrap());
+    let batch_time = batch_start.elapsed();
+    println!(
+        "verify {} proofs in a batch {}ms, {}s",
+        batch_size,
+        batch_time.as_millis(),
+        batch_time.as_secs()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr};
+    use ark_groth16::Groth16;
+    use ark_snark::SNARK;
+
+    use super::verify_batch;
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    #[test]
+    fn test_verify_batch() {
+        let mut rng = ark_std::test_rng();
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let pvk = Groth16::<Bn254>::process_vk(&vk).unwrap();
+        let mut instances = (0..3)
+            .map(|_| {
+                let proof = Groth16::<Bn254>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+                (vec![circuit.unsolved_hash], proof)
+            })
+            .collect::<Vec<_>>();
+
+        assert!(verify_batch(&pvk, &[], &mut rng).unwrap());
+        assert!(verify_batch(&pvk, &instances, &mut rng).unwrap());
+
+        // one wrong input fails the whole batch
+        instances[1].0 = vec![Fr::from(1u64)];
+        assert!(!verify_batch(&pvk, &instances, &mut rng).unwrap());
+
+        // and so does a wrong number of inputs
+        instances[1].0 = vec![];
+        assert!(verify_batch(&pvk, &instances, &mut rng).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+use ark_ff::{FpParameters, PrimeField};
+use ark_relations::r1cs::{ConstraintMatrices, Matrix, SynthesisError};
+use num_bigint::BigUint;
+use serde_json::{json, Value};
+
+use crate::{circuit::SudokuCircuit, r1cs::circuit_matrices, solidity::decimal};
+
+// Sparse entries as [row, column, value]
+fn matrix_json<F: PrimeField>(matrix: &Matrix<F>) -> Value {
+    Value::Array(
+        matrix
+            .iter()
+            .enumerate()
+            .flat_map(|(row, lc)| {
+                lc.iter()
+                    .map(move |(coeff, col)| json!([row, col, decimal(coeff)]))
+            })
+            .collect(),
+    )
+}
+
+/// The constraint system as a CCS instance (Setty, Thaler and Wahby) in
+/// JSON. R1CS is the CCS with matrices A, B, C, multisets {0, 1} and {2}
+/// and constants 1 and -1, i.e. A·z ∘ B·z - C·z = 0. The assignment is
+/// z = (1, x, w) in the arkworks variable order, `l` being the length of x.
+pub fn ccs_json<F: PrimeField>(matrices: &ConstraintMatrices<F>) -> Value {
+    let modulus: BigUint = F::Params::MODULUS.into();
+    json!({
+        "field": modulus.to_string(),
+        "m": matrices.num_constraints,
+        "n": matrices.num_instance_variables + matrices.num_witness_variables,
+        "l": matrices.num_instance_variables - 1,
+        "t": 3,
+        "q": 2,
+        "d": 2,
+        "matrices": [
+            matrix_json(&matrices.a),
+            matrix_json(&matrices.b),
+            matrix_json(&matrices.c),
+        ],
+        "multisets": [[0, 1], [2]],
+        "constants": [decimal(&F::one()), decimal(&-F::one())],
+    })
+}
+
+/// Synthesizes the circuit and returns it as a CCS instance.
+pub fn export_ccs<F: PrimeField>(circuit: SudokuCircuit<F>) -> Result<Value, SynthesisError> {
+    Ok(ccs_json(&circuit_matrices(circuit)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use std::str::FromStr;
+
+    use ark_ff::{One, Zero};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    use super::export_ccs;
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    #[test]
+    fn test_export_ccs() {
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let ccs = export_ccs(circuit.clone()).unwrap();
+
+        // the satisfying assignment, in the same variable order
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        let cs = cs.into_inner().unwrap();
+        let z = cs
+            .instance_assignment
+            .iter()
+            .chain(&cs.witness_assignment)
+            .copied()
+            .collect::<Vec<_>>();
+
+        let m = ccs["m"].as_u64().unwrap() as usize;
+        assert_eq!(ccs["n"].as_u64().unwrap() as usize, z.len());
+        assert_eq!(ccs["l"], 1);
+
+        // Σ c_i · ∘_{j ∈ S_i} M_j·z = 0
+        let products = ccs["matrices"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|matrix| {
+                let mut product = vec![Fr::zero(); m];
+                for entry in matrix.as_array().unwrap() {
+                    let row = entry[0].as_u64().unwrap() as usize;
+                    let col = entry[1].as_u64().unwrap() as usize;
+                    let coeff = Fr::from_str(entry[2].as_str().unwrap()).unwrap();
+                    product[row] += coeff * z[col];
+                }
+                product
+            })
+            .collect::<Vec<_>>();
+        let constants = ccs["constants"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| Fr::from_str(c.as_str().unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(constants, vec![Fr::one(), -Fr::one()]);
+        for row in 0..m {
+            let mut sum = Fr::zero();
+            for (constant, multiset) in constants.iter().zip(ccs["multisets"].as_array().unwrap()) {
+                let mut term = *constant;
+                for j in multiset.as_array().unwrap() {
+                    term *= products[j.as_u64().unwrap() as usize][row];
+                }
+                sum += term;
+            }
+            assert!(sum.is_zero(), "row {} is not satisfied", row);
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use ark_ec::{msm::VariableBaseMSM, AffineCurve, PairingEngine, ProjectiveCurve};
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_groth16::{ProvingKey, VerifyingKey};
+use ark_poly::{EvaluationDomain, Radix2EvaluationDomain};
+use ark_relations::r1cs::{
+    ConstraintSynthesizer, ConstraintSystem, Matrix, OptimizationGoal, SynthesisError,
+    SynthesisMode,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Read, SerializationError, Write};
+use ark_std::rand::{rngs::StdRng, CryptoRng, RngCore, SeedableRng};
+use sha2::{Digest, Sha256};
+
+/// The output of a phase 1 ceremony: the powers of a secret tau, with
+/// alpha and beta, as stored by `keys::write_key`. A circuit whose QAP
+/// domain has `n` elements needs `n` powers in G2 and of alpha and beta, and
+/// `2n - 1` powers in G1.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct PowersOfTau<E: PairingEngine> {
+    pub tau_g1: Vec<E::G1Affine>,
+    pub tau_g2: Vec<E::G2Affine>,
+    pub alpha_tau_g1: Vec<E::G1Affine>,
+    pub beta_tau_g1: Vec<E::G1Affine>,
+    pub beta_g2: E::G2Affine,
+}
+
+impl<E: PairingEngine> PowersOfTau<E> {
+    /// Powers of tau of the given degree from a single party knowing tau,
+    /// alpha and beta, only meant for tests.
+    pub fn new_insecure<R: RngCore>(degree: usize, rng: &mut R) -> Self {
+        let tau = E::Fr::rand(rng);
+        let alpha = E::Fr::rand(rng);
+        let beta = E::Fr::rand(rng);
+        let powers = |scale: E::Fr, len: usize| {
+            let mut power = scale;
+            (0..len)
+                .map(|_| {
+                    let current = power;
+                    power *= tau;
+                    current.into_repr()
+                })
+                .collect::<Vec<_>>()
+        };
+        let g1 = E::G1Affine::prime_subgroup_generator();
+        let g2 = E::G2Affine::prime_subgroup_generator();
+        let g1_powers = |scale: E::Fr, len: usize| {
+            let powers = powers(scale, len)
+                .into_iter()
+                .map(|power| g1.mul(power))
+                .collect::<Vec<_>>();
+            E::G1Projective::batch_normalization_into_affine(&powers)
+        };
+        let tau_g2 = powers(E::Fr::one(), degree)
+            .into_iter()
+            .map(|power| g2.mul(power))
+            .collect::<Vec<_>>();
+
+        Self {
+            tau_g1: g1_powers(E::Fr::one(), 2 * degree - 1),
+            tau_g2: E::G2Projective::batch_normalization_into_affine(&tau_g2),
+            alpha_tau_g1: g1_powers(alpha, degree),
+            beta_tau_g1: g1_powers(beta, degree),
+            beta_g2: g2.mul(beta.into_repr()).into_affine(),
+        }
+    }
+}
+
+/// What a participant of phase 2 publishes: delta in G1 after their
+/// contribution, and a proof that they know the factor `x` it was multiplied
+/// by, as `s` and `s·x` in G1 and `r·x` in G2 for a point `r` derived from
+/// the transcript.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Contribution<E: PairingEngine> {
+    pub delta_g1: E::G1Affine,
+    pub s: E::G1Affine,
+    pub s_delta: E::G1Affine,
+    pub r_delta: E::G2Affine,
+}
+
+/// The circuit specific phase 2 of the Groth16 setup (Bowe, Gabizon and
+/// Miers). The parameters start from the powers of tau with gamma and delta
+/// equal to one, then each participant multiplies delta by a secret factor,
+/// dividing the L and H queries by it. The proving key is sound as long as
+/// one participant of each phase forgets their secrets.
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Phase2<E: PairingEngine> {
+    pub pk: ProvingKey<E>,
+    pub contributions: Vec<Contribution<E>>,
+}
+
+// Adds coeff·basis[row] to the query of every variable in the row, for each
+// row of the matrix
+fn accumulate<G: ProjectiveCurve>(query: &mut [G], matrix: &Matrix<G::ScalarField>, basis: &[G]) {
+    for (row, lc) in matrix.iter().enumerate() {
+        for (coeff, var) in lc {
+            let mut term = basis[row];
+            if !coeff.is_one() {
+                term *= *coeff;
+            }
+            query[*var] += term;
+        }
+    }
+}
+
+// The Lagrange basis of the domain evaluated at tau, from the powers of tau
+fn lagrange<G: ProjectiveCurve>(
+    domain: &Radix2EvaluationDomain<G::ScalarField>,
+    powers: &[G::Affine],
+) -> Vec<G> {
+    let powers = powers[..domain.size()]
+        .iter()
+        .map(|power| power.into_projective())
+        .collect::<Vec<G>>();
+    domain.ifft(&powers)
+}
+
+// p·x = q·y, for the pairs (p, p·x) and (q, q·y)
+fn same_ratio<E: PairingEngine>(
+    g1: (E::G1Affine, E::G1Affine),
+    g2: (E::G2Affine, E::G2Affine),
+) -> bool {
+    E::pairing(g1.0, g2.1) == E::pairing(g1.1, g2.0)
+}
+
+// The same random linear combination of both queries
+fn random_combination<G: AffineCurve, R: RngCore>(
+    before: &[G],
+    after: &[G],
+    rng: &mut R,
+) -> (G, G) {
+    let scalars = (0..before.len())
+        .map(|_| G::ScalarField::rand(rng).into_repr())
+        .collect::<Vec<_>>();
+    (
+        VariableBaseMSM::multi_scalar_mul(before, &scalars).into_affine(),
+        VariableBaseMSM::multi_scalar_mul(after, &scalars).into_affine(),
+    )
+}
+
+impl<E: PairingEngine> Phase2<E> {
+    /// The parameters of `circuit` before any contribution.
+    pub fn new<C: ConstraintSynthesizer<E::Fr>>(
+        circuit: C,
+        powers: &PowersOfTau<E>,
+    ) -> Result<Self, SynthesisError> {
+        // Same synthesis as the Groth16 generator and prover
+        let cs = ConstraintSystem::new_ref();
+        cs.set_optimization_goal(OptimizationGoal::Constraints);
+        cs.set_mode(SynthesisMode::Setup);
+        circuit.generate_constraints(cs.clone())?;
+        cs.finalize();
+        let matrices = cs.to_matrices().ok_or(SynthesisError::AssignmentMissing)?;
+        let num_instance = matrices.num_instance_variables;
+        let num_variables = num_instance + matrices.num_witness_variables;
+        let num_constraints = matrices.num_constraints;
+
+        let domain = Radix2EvaluationDomain::<E::Fr>::new(num_constraints + num_instance)
+            .ok_or(SynthesisError::PolynomialDegreeTooLarge)?;
+        let size = domain.size();
+        if powers.tau_g1.len() < 2 * size - 1
+            || powers.tau_g2.len() < size
+            || powers.alpha_tau_g1.len() < size
+            || powers.beta_tau_g1.len() < size
+        {
+            return Err(SynthesisError::PolynomialDegreeTooLarge);
+        }
+
+        let tau_lagrange_g1 = lagrange::<E::G1Projective>(&domain, &powers.tau_g1);
+        let tau_lagrange_g2 = lagrange::<E::G2Projective>(&domain, &powers.tau_g2);
+        let alpha_lagrange_g1 = lagrange::<E::G1Projective>(&domain, &powers.alpha_tau_g1);
+        let beta_lagrange_g1 = lagrange::<E::G1Projective>(&domain, &powers.beta_tau_g1);
+
+        // The inputs are also constrained by the rows after the constraints,
+        // with the input as the only term of A
+        let mut a_query = vec![E::G1Projective::zero(); num_variables];
+        a_query[..num_instance]
+            .copy_from_slice(&tau_lagrange_g1[num_constraints..num_constraints + num_instance]);
+        accumulate(&mut a_query, &matrices.a, &tau_lagrange_g1);
+        let mut b_g1_query = vec![E::G1Projective::zero(); num_variables];
+        accumulate(&mut b_g1_query, &matrices.b, &tau_lagrange_g1);
+        let mut b_g2_query = vec![E::G2Projective::zero(); num_variables];
+        accumulate(&mut b_g2_query, &matrices.b, &tau_lagrange_g2);
+
+        // beta·A + alpha·B + C of each variable
+        let mut abc = vec![E::G1Projective::zero(); num_variables];
+        abc[..num_instance]
+            .copy_from_slice(&beta_lagrange_g1[num_constraints..num_constraints + num_instance]);
+        accumulate(&mut abc, &matrices.a, &beta_lagrange_g1);
+        accumulate(&mut abc, &matrices.b, &alpha_lagrange_g1);
+        accumulate(&mut abc, &matrices.c, &tau_lagrange_g1);
+
+        // (tau^n - 1)·tau^i, the vanishing polynomial of the domain times the
+        // powers of tau
+        let h_query = (0..size - 1)
+            .map(|i| powers.tau_g1[i + size].into_projective() - powers.tau_g1[i].into_projective())
+            .collect::<Vec<_>>();
+
+        let vk = VerifyingKey {
+            alpha_g1: powers.alpha_tau_g1[0],
+            beta_g2: powers.beta_g2,
+            gamma_g2: powers.tau_g2[0],
+            delta_g2: powers.tau_g2[0],
+            gamma_abc_g1: E::G1Projective::batch_normalization_into_affine(&abc[..num_instance]),
+        };
+        Ok(Self {
+            pk: ProvingKey {
+                vk,
+                beta_g1: powers.beta_tau_g1[0],
+                delta_g1: powers.tau_g1[0],
+                a_query: E::G1Projective::batch_normalization_into_affine(&a_query),
+                b_g1_query: E::G1Projective::batch_normalization_into_affine(&b_g1_query),
+                b_g2_query: E::G2Projective::batch_normalization_into_affine(&b_g2_query),
+                h_query: E::G1Projective::batch_normalization_into_affine(&h_query),
+                l_query: E::G1Projective::batch_normalization_into_affine(&abc[num_instance..]),
+            },
+            contributions: vec![],
+        })
+    }
+
+    // The point r of the proof of knowledge, which can't be chosen by the
+    // participant as it depends on the previous contributions and on s
+    fn transcript_point(
+        previous: &[Contribution<E>],
+        s: &E::G1Affine,
+        s_delta: &E::G1Affine,
+    ) -> E::G2Affine {
+        let mut transcript = vec![];
+        previous.serialize(&mut transcript).unwrap();
+        s.serialize(&mut transcript).unwrap();
+        s_delta.serialize(&mut transcript).unwrap();
+        let seed = Sha256::digest(&transcript);
+        E::G2Projective::rand(&mut StdRng::from_seed(seed.into())).into_affine()
+    }
+
+    /// Multiplies delta by a secret factor, dropped once the contribution is
+    /// recorded.
+    pub fn contribute<R: RngCore + CryptoRng>(&mut self, rng: &mut R) {
+        let mut delta = E::Fr::rand(rng);
+        while delta.is_zero() {
+            delta = E::Fr::rand(rng);
+        }
+        let delta_inverse = delta.inverse().unwrap().into_repr();
+        let delta_repr = delta.into_repr();
+
+        let s = E::G1Projective::rand(rng).into_affine();
+        let s_delta = s.mul(delta_repr).into_affine();
+        let r = Self::transcript_point(&self.contributions, &s, &s_delta);
+
+        let pk = &mut self.pk;
+        pk.delta_g1 = pk.delta_g1.mul(delta_repr).into_affine();
+        pk.vk.delta_g2 = pk.vk.delta_g2.mul(delta_repr).into_affine();
+        let divide = |query: &[E::G1Affine]| {
+            let query = query
+                .iter()
+                .map(|point| point.mul(delta_inverse))
+                .collect::<Vec<_>>();
+            E::G1Projective::batch_normalization_into_affine(&query)
+        };
+        pk.l_query = divide(&pk.l_query);
+        pk.h_query = divide(&pk.h_query);
+
+        self.contributions.push(Contribution {
+            delta_g1: pk.delta_g1,
+            s,
+            s_delta,
+            r_delta: r.mul(delta_repr).into_affine(),
+        });
+    }
+
+    /// Checks that the parameters are those of `circuit` over `powers`
+    /// updated by the recorded contributions, each with a valid proof of
+    /// knowledge. The queries are compared through random linear
+    /// combinations drawn from `rng`.
+    pub fn verify<C: ConstraintSynthesizer<E::Fr>, R: RngCore>(
+        &self,
+        circuit: C,
+        powers: &PowersOfTau<E>,
+        rng: &mut R,
+    ) -> Result<bool, SynthesisError> {
+        let initial = Self::new(circuit, powers)?.pk;
+        let pk = &self.pk;
+
+        // Everything but delta, L and H is fixed by phase 1 and the circuit
+        if pk.vk.alpha_g1 != initial.vk.alpha_g1
+            || pk.vk.beta_g2 != initial.vk.beta_g2
+            || pk.vk.gamma_g2 != initial.vk.gamma_g2
+            || pk.vk.gamma_abc_g1 != initial.vk.gamma_abc_g1
+            || pk.beta_g1 != initial.beta_g1
+            || pk.a_query != initial.a_query
+            || pk.b_g1_query != initial.b_g1_query
+            || pk.b_g2_query != initial.b_g2_query
+            || pk.l_query.len() != initial.l_query.len()
+            || pk.h_query.len() != initial.h_query.len()
+        {
+            return Ok(false);
+        }
+
+        let mut delta_g1 = initial.delta_g1;
+        for (i, contribution) in self.contributions.iter().enumerate() {
+            if contribution.s.is_zero() || contribution.delta_g1.is_zero() {
+                return Ok(false);
+            }
+            let r = Self::transcript_point(
+                &self.contributions[..i],
+                &contribution.s,
+                &contribution.s_delta,
+            );
+            // The participant knows the factor, and delta was multiplied by it
+            if !same_ratio::<E>(
+                (contribution.s, contribution.s_delta),
+                (r, contribution.r_delta),
+            ) || !same_ratio::<E>((delta_g1, contribution.delta_g1), (r, contribution.r_delta))
+            {
+                return Ok(false);
+            }
+            delta_g1 = contribution.delta_g1;
+        }
+
+        let g2 = initial.vk.delta_g2;
+        if pk.delta_g1 != delta_g1
+            || !same_ratio::<E>((initial.delta_g1, pk.delta_g1), (g2, pk.vk.delta_g2))
+        {
+            return Ok(false);
+        }
+
+        // L and H are divided by the same delta
+        let (l_before, l_after) = random_combination(&initial.l_query, &pk.l_query, rng);
+        let (h_before, h_after) = random_combination(&initial.h_query, &pk.h_query, rng);
+        Ok(same_ratio::<E>((l_after, l_before), (g2, pk.vk.delta_g2))
+            && same_ratio::<E>((h_after, h_before), (g2, pk.vk.delta_g2)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr};
+    use ark_groth16::Groth16;
+    use ark_relations::{
+        lc,
+        r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+    };
+    use ark_snark::SNARK;
+
+    use super::{Phase2, PowersOfTau};
+
+    // x^3 + x + 5 = y, with y public. The sudoku circuit is too large for
+    // the group FFTs of a test.
+    #[derive(Clone)]
+    struct Cubic {
+        x: Fr,
+    }
+
+    impl ConstraintSynthesizer<Fr> for Cubic {
+        fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+            let x_value = self.x;
+            let y = cs.new_input_variable(|| {
+                Ok(x_value * x_value * x_value + x_value + Fr::from(5u64))
+            })?;
+            let x = cs.new_witness_variable(|| Ok(x_value))?;
+            let x_square = cs.new_witness_variable(|| Ok(x_value * x_value))?;
+            let x_cube = cs.new_witness_variable(|| Ok(x_value * x_value * x_value))?;
+            cs.enforce_constraint(lc!() + x, lc!() + x, lc!() + x_square)?;
+            cs.enforce_constraint(lc!() + x_square, lc!() + x, lc!() + x_cube)?;
+            cs.enforce_constraint(
+                lc!() + x_cube + x + (Fr::from(5u64), Variable::One),
+                lc!() + Variable::One,
+                lc!() + y,
+            )?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_phase2() {
+        let mut rng = ark_std::test_rng();
+        let circuit = Cubic { x: Fr::from(3u64) };
+        let powers = PowersOfTau::<Bn254>::new_insecure(8, &mut rng);
+
+        let mut phase2 = Phase2::new(circuit.clone(), &powers).unwrap();
+        assert!(phase2.verify(circuit.clone(), &powers, &mut rng).unwrap());
+        phase2.contribute(&mut rng);
+        phase2.contribute(&mut rng);
+        assert!(phase2.verify(circuit.clone(), &powers, &mut rng).unwrap());
+
+        let proof = Groth16::<Bn254>::prove(&phase2.pk, circuit.clone(), &mut rng).unwrap();
+        let pvk = Groth16::<Bn254>::process_vk(&phase2.pk.vk).unwrap();
+        assert!(
+            Groth16::<Bn254>::verify_with_processed_vk(&pvk, &[Fr::from(35u64)], &proof).unwrap()
+        );
+        assert!(
+            !Groth16::<Bn254>::verify_with_processed_vk(&pvk, &[Fr::from(36u64)], &proof).unwrap()
+        );
+
+        // a contribution replaced without a proof of knowledge
+        let mut forged = phase2.clone();
+        forged.contributions.pop();
+        assert!(!forged.verify(circuit.clone(), &powers, &mut rng).unwrap());
+
+        // a query that doesn't follow delta
+        let mut forged = phase2.clone();
+        forged.pk.h_query.swap(0, 1);
+        assert!(!forged.verify(circuit.clone(), &powers, &mut rng).unwrap());
+
+        // too few powers for the circuit
+        let powers = PowersOfTau::<Bn254>::new_insecure(4, &mut rng);
+        assert!(Phase2::new(circuit, &powers).is_err());
+    }
+}
@@ -1,17 +1,21 @@
-use std::ops::{MulAssign, Sub};
+use std::ops::{MulAssign, Range, RangeInclusive};
 
-use ark_crypto_primitives::{crh::sha256::constraints::Sha256Gadget, CRHSchemeGadget};
 use ark_ff::PrimeField;
 use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
 use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
-use sha2::Sha256;
+use zk_sudoku_core::{public_input::ProverId, Puzzle, Solution};
+
+use crate::hash::PuzzleHash;
 
 #[tracing::instrument(target = "r1cs", skip(value))]
-fn enforce_range<F: PrimeField>(value: &UInt8<F>) -> Result<(), SynthesisError> {
+fn enforce_range<F: PrimeField>(
+    value: &UInt8<F>,
+    range: RangeInclusive<u32>,
+) -> Result<(), SynthesisError> {
     let self_bits = value.to_bits_le()?;
     let self_fe = Boolean::le_bits_to_fp_var(&self_bits)?;
-    let mut res_mul = (&self_fe).sub(FpVar::one());
-    for i in 2..=9u32 {
+    let mut res_mul = FpVar::one();
+    for i in range {
         let res = &self_fe - FpVar::Constant(i.into());
         res_mul.mul_assign(res)
     }
@@ -24,24 +28,62 @@ pub struct SudokuCircuit<F: PrimeField> {
     pub unsolved_hash: F,
     pub unsolved: [[u8; 9]; 9],
     pub solved: [[u8; 9]; 9],
+    /// How `unsolved_hash` is computed, in and out of the circuit.
+    pub hash: PuzzleHash,
+    /// Hashed after the cells if set, so the proof only verifies for this
+    /// prover. The keys are shared by every id but differ from those without
+    /// one.
+    pub prover_id: Option<ProverId>,
 }
 
-impl<F: PrimeField> ConstraintSynthesizer<F> for SudokuCircuit<F> {
-    fn generate_constraints(
+/// The allocated cells of a synthesized circuit, together with the range of
+/// constraint indices produced by each rule.
+pub(crate) struct SynthesizedCells<F: PrimeField> {
+    pub unsolved: Vec<Vec<UInt8<F>>>,
+    pub solved: Vec<Vec<UInt8<F>>>,
+    pub groups: Vec<(&'static str, Range<usize>)>,
+}
+
+impl<F: PrimeField> SudokuCircuit<F> {
+    pub fn new(puzzle: &Puzzle, solution: &Solution) -> Self {
+        Self::with_hash(puzzle, solution, PuzzleHash::Sha256)
+    }
+
+    pub fn with_hash(puzzle: &Puzzle, solution: &Solution, hash: PuzzleHash) -> Self {
+        Self {
+            unsolved_hash: hash.hash(*puzzle.cells()),
+            unsolved: *puzzle.cells(),
+            solved: *solution.cells(),
+            hash,
+            prover_id: None,
+        }
+    }
+
+    /// Binds the proof to `prover_id`, updating the public input.
+    pub fn with_prover_id(mut self, prover_id: ProverId) -> Self {
+        self.unsolved_hash = self
+            .hash
+            .hash_with_prover_id(self.unsolved, Some(&prover_id));
+        self.prover_id = Some(prover_id);
+        self
+    }
+
+    pub(crate) fn synthesize(
         self,
         cs: ark_relations::r1cs::ConstraintSystemRef<F>,
-    ) -> ark_relations::r1cs::Result<()> {
+    ) -> ark_relations::r1cs::Result<SynthesizedCells<F>> {
         let mut unsolved_var = Vec::with_capacity(9);
         let mut solved_var = Vec::with_capacity(9);
 
-        let sha256_parameter =
-            <Sha256Gadget<F> as CRHSchemeGadget<Sha256, F>>::ParametersVar::new_constant(
-                cs.clone(),
-                (),
-            )?;
+        let first_constraint = cs.num_constraints();
+        let mut groups: Vec<(&'static str, Range<usize>)> = Vec::with_capacity(8);
+        let mut close_group = |name: &'static str| {
+            let start = groups
+                .last()
+                .map_or(first_constraint, |(_, range)| range.end);
+            groups.push((name, start..cs.num_constraints()));
+        };
 
-        // Check if the numbers of the solved sudoku are >=1 and <=9
-        // Each number in the solved sudoku is checked to see if it is >=1 and <=9
         for i in 0..9 {
             unsolved_var.push(Vec::with_capacity(9));
             solved_var.push(Vec::with_capacity(9));
@@ -55,10 +97,28 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SudokuCircuit<F> {
                     ark_relations::ns!(cs, "solved"),
                     || Ok(self.solved[i][j]),
                 )?);
+            }
+        }
+        close_group("allocation");
+
+        // Check if the numbers of the solved sudoku are >=1 and <=9
+        // Each number in the solved sudoku is checked to see if it is >=1 and <=9
+        for i in 0..9 {
+            for j in 0..9 {
+                enforce_range(&solved_var[i][j], 1..=9)?;
+            }
+        }
+        close_group("range");
 
-                enforce_range(&solved_var[i][j])?;
+        // Check if the numbers of the unsolved sudoku are >=0 and <=9
+        // The hash alone would commit to any byte, so a puzzle with cells
+        // above 9 must be rejected here
+        for i in 0..9 {
+            for j in 0..9 {
+                enforce_range(&unsolved_var[i][j], 0..=9)?;
             }
         }
+        close_group("unsolved range");
 
         let zero_var = UInt8::new_constant(ark_relations::ns!(cs, "zero"), 0u8)?;
         // Check if unsolved is the initial state of solved
@@ -70,6 +130,7 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SudokuCircuit<F> {
                 unsolved_var[i][j].conditional_enforce_equal(&solved_var[i][j], &is_zero.not())?;
             }
         }
+        close_group("givens");
 
         // Check if each row in solved has all the numbers from 1 to 9, both included
         // For each element in solved, check that this element is not equal
@@ -81,6 +142,7 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SudokuCircuit<F> {
                 }
             }
         }
+        close_group("rows");
 
         // Check if each column in solved has all the numbers from 1 to 9, both included
         // For each element in solved, check that this element is not equal
@@ -92,17 +154,18 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SudokuCircuit<F> {
                 }
             }
         }
+        close_group("columns");
 
         // Check if each square in solved has all the numbers from 1 to 9, both included
         // For each square and for each element in each square, check that the
-        // element is not equal to previous elements in the same square
-
+        // element is not equal to the elements of the previous rows of the same
+        // square, except the one in its own column (rows and columns are covered above)
         for i in [0, 3, 6] {
             for j in [0, 3, 6] {
                 for k in i..i + 3 {
                     for l in j..j + 3 {
-                        for m in i..=k {
-                            for n in j..l {
+                        for m in i..k {
+                            for n in (j..j + 3).filter(|n| *n != l) {
                                 solved_var[m][n].enforce_not_equal(&solved_var[k][l])?
                             }
                         }
@@ -110,29 +173,106 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for SudokuCircuit<F> {
                 }
             }
         }
+        close_group("boxes");
 
-        let hash_input = unsolved_var
-            .into_iter()
-            .map(|row| row.into_iter().map(|u8_var| u8_var))
+        let mut hash_input = unsolved_var
+            .iter()
             .flatten()
+            .cloned()
             .collect::<Vec<UInt8<F>>>();
+        if let Some(prover_id) = self.prover_id {
+            hash_input.extend(UInt8::new_witness_vec(
+                ark_relations::ns!(cs, "prover id"),
+                &prover_id,
+            )?);
+        }
 
-        let hash_result =
-            Sha256Gadget::<F>::evaluate(&sha256_parameter, &hash_input)?.to_bytes()?;
-
-        // print!("[");
-        // hash_result
-        //     .iter()
-        //     .for_each(|a| print!("{}, ", a.value().unwrap()));
-        // print!("]\n");
-
-        let hash_fe = Boolean::le_bits_to_fp_var(&hash_result[0..31].to_bits_le()?)?;
-        // println!("hash_fe: {}", hash_fe.value()?);
+        let hash_fe = self.hash.hash_var(cs.clone(), &hash_input)?;
 
         let expected = FpVar::new_input(cs.clone(), || Ok(self.unsolved_hash))?;
 
         hash_fe.enforce_equal(&expected)?;
+        close_group("hash");
 
-        Ok(())
+        Ok(SynthesizedCells {
+            unsolved: unsolved_var,
+            solved: solved_var,
+            groups,
+        })
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for SudokuCircuit<F> {
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<F>,
+    ) -> ark_relations::r1cs::Result<()> {
+        self.synthesize(cs).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+
+    use super::SudokuCircuit;
+    use crate::{
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    #[test]
+    fn test_unsolved_out_of_range() {
+        let mut unsolved = UNSOLVED;
+        unsolved[0][0] = 10;
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(unsolved),
+            unsolved,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+
+        let cs = ConstraintSystem::new_ref();
+        let cells = circuit.synthesize(cs.clone()).unwrap();
+        // Without a tracing layer the unsatisfied constraint is reported by index
+        let index: usize = cs.which_is_unsatisfied().unwrap().unwrap().parse().unwrap();
+        let (rule, _) = cells
+            .groups
+            .iter()
+            .find(|(_, range)| range.contains(&index))
+            .unwrap();
+        assert_eq!(*rule, "unsolved range");
+    }
+
+    #[test]
+    fn test_prover_id() {
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: Default::default(),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Poseidon,
+            prover_id: None,
+        }
+        .with_prover_id([1; 20]);
+        let cs = ConstraintSystem::new_ref();
+        circuit.clone().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // the hash of the puzzle alone, or bound to another prover
+        for unsolved_hash in [
+            PuzzleHash::Poseidon.hash(UNSOLVED),
+            PuzzleHash::Poseidon.hash_with_prover_id(UNSOLVED, Some(&[2; 20])),
+        ] {
+            let cs = ConstraintSystem::new_ref();
+            SudokuCircuit {
+                unsolved_hash,
+                ..circuit.clone()
+            }
+            .generate_constraints(cs.clone())
+            .unwrap();
+            assert!(!cs.is_satisfied().unwrap());
+        }
     }
 }
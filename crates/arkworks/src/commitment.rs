@@ -0,0 +1,122 @@
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+
+use crate::{
+    circuit::SudokuCircuit,
+    hash::{poseidon_bytes, poseidon_bytes_var},
+};
+
+/// Poseidon of the 81 solved cells, then the little endian bytes of the
+/// salt. The salt keeps the commitment hiding, a puzzle having few solutions
+/// to try.
+pub fn commit_solution<F: PrimeField>(solved: [[u8; 9]; 9], salt: F) -> F {
+    let mut bytes = solved.iter().flatten().copied().collect::<Vec<u8>>();
+    // as many bytes as `FpVar::to_bytes` gives
+    let salt_len = (F::size_in_bits() + 7) / 8;
+    bytes.extend_from_slice(&salt.into_repr().to_bytes_le()[..salt_len]);
+    poseidon_bytes(&bytes)
+}
+
+/// Checks a revealed solution and salt against the commitment stored when
+/// the proof was accepted. The reveal still has to be checked to solve the
+/// puzzle, which the proof guarantees for the committed solution only.
+pub fn verify_reveal<F: PrimeField>(commitment: F, solved: [[u8; 9]; 9], salt: F) -> bool {
+    commit_solution(solved, salt) == commitment
+}
+
+/// The sudoku circuit with a second public input, the commitment to its
+/// solution, so that a contract paying for the solution can store it along
+/// with the proof and release the payment on a matching reveal.
+#[derive(Clone)]
+pub struct CommittedSudokuCircuit<F: PrimeField> {
+    pub circuit: SudokuCircuit<F>,
+    pub salt: F,
+    pub solution_commitment: F,
+}
+
+impl<F: PrimeField> CommittedSudokuCircuit<F> {
+    pub fn new(circuit: SudokuCircuit<F>, salt: F) -> Self {
+        Self {
+            solution_commitment: commit_solution(circuit.solved, salt),
+            circuit,
+            salt,
+        }
+    }
+
+    /// The public inputs in allocation order: the hash of the puzzle, then
+    /// the commitment to the solution.
+    pub fn public_inputs(&self) -> [F; 2] {
+        [self.circuit.unsolved_hash, self.solution_commitment]
+    }
+}
+
+impl<F: PrimeField> ConstraintSynthesizer<F> for CommittedSudokuCircuit<F> {
+    fn generate_constraints(self, cs: ConstraintSystemRef<F>) -> Result<(), SynthesisError> {
+        let cells = self.circuit.synthesize(cs.clone())?;
+
+        let mut bytes = cells.solved.into_iter().flatten().collect::<Vec<_>>();
+        let salt = FpVar::new_witness(cs.clone(), || Ok(self.salt))?;
+        bytes.extend(salt.to_bytes()?);
+        let commitment = poseidon_bytes_var(cs.clone(), &bytes)?;
+
+        let expected = FpVar::new_input(cs, || Ok(self.solution_commitment))?;
+        commitment.enforce_equal(&expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_snark::SNARK;
+    use ark_std::UniformRand;
+
+    use super::{commit_solution, verify_reveal, CommittedSudokuCircuit};
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{SOLVED, SOLVED_UNMATCH, UNSOLVED},
+    };
+
+    #[test]
+    fn test_solution_commitment() {
+        let mut rng = ark_std::test_rng();
+        let salt = Fr::rand(&mut rng);
+        // poseidon for the puzzle too, to keep the proof fast
+        let circuit = CommittedSudokuCircuit::new(
+            SudokuCircuit {
+                unsolved_hash: PuzzleHash::Poseidon.hash(UNSOLVED),
+                unsolved: UNSOLVED,
+                solved: SOLVED,
+                hash: PuzzleHash::Poseidon,
+                prover_id: None,
+            },
+            salt,
+        );
+
+        let cs = ConstraintSystem::new_ref();
+        circuit.clone().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        assert_eq!(cs.num_instance_variables(), 3);
+
+        let mut wrong = circuit.clone();
+        wrong.solution_commitment = commit_solution(SOLVED_UNMATCH, salt);
+        let cs = ConstraintSystem::new_ref();
+        wrong.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        let (pk, vk) =
+            Groth16::<Bls12_381>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bls12_381>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+        assert!(Groth16::<Bls12_381>::verify(&vk, &circuit.public_inputs(), &proof).unwrap());
+
+        assert!(verify_reveal(circuit.solution_commitment, SOLVED, salt));
+        assert!(!verify_reveal(
+            circuit.solution_commitment,
+            SOLVED,
+            salt + Fr::from(1u64)
+        ));
+    }
+}
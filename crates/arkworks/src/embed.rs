@@ -0,0 +1,139 @@
+use std::fmt::Write as _;
+
+use ark_bn254::{Bn254, Fq12, Fq2};
+use ark_ec::bn::G2Prepared;
+use ark_groth16::{PreparedVerifyingKey, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+type Bn254G2Prepared = G2Prepared<ark_bn254::Parameters>;
+
+fn write_prepared(
+    bytes: &mut Vec<u8>,
+    prepared: &Bn254G2Prepared,
+) -> Result<(), SerializationError> {
+    (prepared.ell_coeffs.len() as u64).serialize(&mut *bytes)?;
+    for (c0, c1, c2) in &prepared.ell_coeffs {
+        c0.serialize_unchecked(&mut *bytes)?;
+        c1.serialize_unchecked(&mut *bytes)?;
+        c2.serialize_unchecked(&mut *bytes)?;
+    }
+    prepared.infinity.serialize(&mut *bytes)
+}
+
+fn read_prepared(reader: &mut &[u8]) -> Result<Bn254G2Prepared, SerializationError> {
+    let len = u64::deserialize(&mut *reader)? as usize;
+    let ell_coeffs = (0..len)
+        .map(|_| {
+            Ok((
+                Fq2::deserialize_unchecked(&mut *reader)?,
+                Fq2::deserialize_unchecked(&mut *reader)?,
+                Fq2::deserialize_unchecked(&mut *reader)?,
+            ))
+        })
+        .collect::<Result<Vec<_>, SerializationError>>()?;
+    Ok(Bn254G2Prepared {
+        ell_coeffs,
+        infinity: bool::deserialize(&mut *reader)?,
+    })
+}
+
+/// The prepared verifying key with the pairing of alpha and beta and the
+/// line coefficients of gamma and delta already computed, uncompressed so
+/// loading it is only copies.
+pub fn prepared_vk_bytes(pvk: &PreparedVerifyingKey<Bn254>) -> Result<Vec<u8>, SerializationError> {
+    let mut bytes = vec![];
+    pvk.vk.serialize_unchecked(&mut bytes)?;
+    pvk.alpha_g1_beta_g2.serialize_unchecked(&mut bytes)?;
+    write_prepared(&mut bytes, &pvk.gamma_g2_neg_pc)?;
+    write_prepared(&mut bytes, &pvk.delta_g2_neg_pc)?;
+    Ok(bytes)
+}
+
+/// Reads back the output of `prepared_vk_bytes`. The points are not checked,
+/// so the bytes must come from a trusted source such as a constant of the
+/// binary.
+pub fn prepared_vk_from_bytes(
+    mut bytes: &[u8],
+) -> Result<PreparedVerifyingKey<Bn254>, SerializationError> {
+    let reader = &mut bytes;
+    let vk = VerifyingKey::deserialize_unchecked(&mut *reader)?;
+    let alpha_g1_beta_g2 = Fq12::deserialize_unchecked(&mut *reader)?;
+    let gamma_g2_neg_pc = read_prepared(reader)?;
+    let delta_g2_neg_pc = read_prepared(reader)?;
+    if !reader.is_empty() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok(PreparedVerifyingKey {
+        vk,
+        alpha_g1_beta_g2,
+        gamma_g2_neg_pc,
+        delta_g2_neg_pc,
+    })
+}
+
+/// Renders the prepared verifying key as a Rust byte array constant named
+/// `name`, to be written to a file included by the verifier, e.g. from a
+/// build script. The key is then loaded with `prepared_vk_from_bytes`.
+pub fn rust_constant(
+    name: &str,
+    pvk: &PreparedVerifyingKey<Bn254>,
+) -> Result<String, SerializationError> {
+    let bytes = prepared_vk_bytes(pvk)?;
+    let mut source = format!(
+        "/// Generated by `zk_sudoku_arkworks::embed::rust_constant`.\npub const {}: [u8; {}] = [",
+        name,
+        bytes.len()
+    );
+    for (i, byte) in bytes.iter().enumerate() {
+        if i % 16 == 0 {
+            source.push_str("\n   ");
+        }
+        write!(source, " 0x{:02x},", byte).unwrap();
+    }
+    source.push_str("\n];\n");
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr};
+    use ark_groth16::Groth16;
+    use ark_snark::SNARK;
+
+    use super::{prepared_vk_bytes, prepared_vk_from_bytes, rust_constant};
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    #[test]
+    fn test_embedded_vk() {
+        let mut rng = ark_std::test_rng();
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+        let pvk = Groth16::<Bn254>::process_vk(&vk).unwrap();
+
+        let bytes = prepared_vk_bytes(&pvk).unwrap();
+        let embedded = prepared_vk_from_bytes(&bytes).unwrap();
+        assert_eq!(embedded, pvk);
+        assert!(Groth16::<Bn254>::verify_with_processed_vk(
+            &embedded,
+            &[circuit.unsolved_hash],
+            &proof
+        )
+        .unwrap());
+        assert!(prepared_vk_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        let source = rust_constant("SUDOKU_VK", &pvk).unwrap();
+        assert!(source.contains(&format!("pub const SUDOKU_VK: [u8; {}] = [", bytes.len())));
+        assert_eq!(source.matches("0x").count(), bytes.len());
+    }
+}
@@ -0,0 +1,111 @@
+use std::time::Instant;
+
+use ark_ec::PairingEngine;
+use ark_ff::PrimeField;
+use ark_gm17::GM17;
+use ark_relations::r1cs::{ConstraintLayer, ConstraintSynthesizer, ConstraintSystem, TracingMode};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+use zk_sudoku_core::report::Report;
+
+use crate::{
+    circuit::SudokuCircuit,
+    groth16::check_invalid_circuits,
+    hash::PuzzleHash,
+    parameters::{unsolved_hash, SOLVED, UNSOLVED},
+};
+
+/// Same as `run_groth16` with GM17, whose proofs are simulation-extractable
+/// and so can't be rerandomized into another valid proof.
+pub fn run_gm17<F, E, R>(curve: &'static str, rng: &mut R) -> Report
+where
+    E: PairingEngine + PairingEngine<Fr = F>,
+    F: PrimeField,
+    R: RngCore + CryptoRng,
+    SudokuCircuit<F>: ConstraintSynthesizer<<E as PairingEngine>::Fr>,
+{
+    // First, some boilerplat that helps with debugging
+    let mut layer = ConstraintLayer::default();
+    layer.mode = TracingMode::OnlyConstraints;
+    let subscriber = tracing_subscriber::Registry::default().with(layer);
+    let _guard = tracing::subscriber::set_default(subscriber);
+    // should success
+    let circuit_to_verify_success: SudokuCircuit<<E as PairingEngine>::Fr> = SudokuCircuit {
+        unsolved_hash: unsolved_hash(UNSOLVED),
+        unsolved: UNSOLVED,
+        solved: SOLVED,
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
+    };
+
+    let cs = ConstraintSystem::new_ref();
+    circuit_to_verify_success
+        .clone()
+        .generate_constraints(cs.clone())
+        .unwrap();
+    // Let's check whether the constraint system is satisfied
+    let is_satisfied = cs.is_satisfied().unwrap();
+    assert!(is_satisfied);
+    let num_constraints = cs.num_constraints();
+    check_invalid_circuits::<F>();
+
+    let circuit_defining_cs: SudokuCircuit<F> = SudokuCircuit {
+        unsolved_hash: Default::default(),
+        unsolved: Default::default(),
+        solved: Default::default(),
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
+    };
+
+    let setup_start = Instant::now();
+    let (pk, vk) = GM17::<E>::circuit_specific_setup(circuit_defining_cs, rng).unwrap();
+    let processed_vk = GM17::process_vk(&vk).unwrap();
+    let setup_time = setup_start.elapsed();
+    println!(
+        "setup time {}ms, {}s",
+        setup_time.as_millis(),
+        setup_time.as_secs()
+    );
+
+    let prove_start = Instant::now();
+    let proof = GM17::prove(&pk, circuit_to_verify_success.clone(), rng).unwrap();
+    let prove_time = prove_start.elapsed();
+    println!(
+        "prove time {}ms, {}s",
+        prove_time.as_millis(),
+        prove_time.as_secs()
+    );
+    println!("proof len: {}", proof.serialized_size());
+
+    let verify_start = Instant::now();
+    let valid_proof = GM17::verify_with_processed_vk(
+        &processed_vk,
+        &[circuit_to_verify_success.unsolved_hash],
+        &proof,
+    )
+    .unwrap();
+    let verify_time = verify_start.elapsed();
+    println!(
+        "verify time {}ms, {}s",
+        verify_time.as_millis(),
+        verify_time.as_secs()
+    );
+    assert!(valid_proof);
+
+    let invalid_proof = GM17::verify_with_processed_vk(&processed_vk, &[F::one()], &proof).unwrap();
+    assert!(!invalid_proof);
+
+    Report {
+        backend: "gm17",
+        curve,
+        config: None,
+        num_constraints,
+        setup_time,
+        prove_time,
+        verify_time,
+        proof_len: proof.serialized_size(),
+        extra: vec![],
+    }
+}
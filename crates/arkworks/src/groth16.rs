@@ -6,10 +6,13 @@ use ark_groth16::Groth16;
 use ark_relations::r1cs::{ConstraintLayer, ConstraintSynthesizer, ConstraintSystem, TracingMode};
 use ark_serialize::CanonicalSerialize;
 use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
 use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+use zk_sudoku_core::report::Report;
 
 use crate::{
     circuit::SudokuCircuit,
+    hash::PuzzleHash,
     parameters::{
         unsolved_hash, SOLVED, SOLVED_REPEATED_IN_COLUMN, SOLVED_REPEATED_IN_ROW,
         SOLVED_REPEATED_IN_SQUARE,
@@ -17,10 +20,11 @@ use crate::{
     parameters::{SOLVED_UNMATCH, UNSOLVED},
 };
 
-pub fn run_groth16<F, E>()
+pub fn run_groth16<F, E, R>(curve: &'static str, rng: &mut R) -> Report
 where
     E: PairingEngine + PairingEngine<Fr = F>,
     F: PrimeField,
+    R: RngCore + CryptoRng,
     SudokuCircuit<F>: ConstraintSynthesizer<<E as PairingEngine>::Fr>,
 {
     // First, some boilerplat that helps with debugging
@@ -33,6 +37,8 @@ where
         unsolved_hash: unsolved_hash(UNSOLVED),
         unsolved: UNSOLVED,
         solved: SOLVED,
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
     };
 
     let cs = ConstraintSystem::new_ref();
@@ -43,12 +49,90 @@ where
     // Let's check whether the constraint system is satisfied
     let is_satisfied = cs.is_satisfied().unwrap();
     assert!(is_satisfied);
+    let num_constraints = cs.num_constraints();
+    check_invalid_circuits::<F>();
+
+    let circuit_defining_cs: SudokuCircuit<F> = SudokuCircuit {
+        unsolved_hash: Default::default(),
+        unsolved: Default::default(),
+        solved: Default::default(),
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
+    };
+
+    let setup_start = Instant::now();
+    let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit_defining_cs, rng).unwrap();
+    let processed_vk = Groth16::process_vk(&vk).unwrap();
+    let setup_time = setup_start.elapsed();
+    println!(
+        "setup time {}ms, {}s",
+        setup_time.as_millis(),
+        setup_time.as_secs()
+    );
+
+    let cs = ConstraintSystem::new_ref();
+    circuit_to_verify_success
+        .clone()
+        .generate_constraints(cs.clone())
+        .unwrap();
+    // Let's check whether the constraint system is satisfied
+    let is_satisfied = cs.is_satisfied().unwrap();
+
+    assert!(is_satisfied);
+
+    let prove_start = Instant::now();
+    let proof = Groth16::prove(&pk, circuit_to_verify_success.clone(), rng).unwrap();
+    let prove_time = prove_start.elapsed();
+    println!(
+        "prove time {}ms, {}s",
+        prove_time.as_millis(),
+        prove_time.as_secs()
+    );
+    println!("proof len: {}", proof.serialized_size());
+
+    let verify_start = Instant::now();
+    let valid_proof = Groth16::verify_with_processed_vk(
+        &processed_vk,
+        &[circuit_to_verify_success.unsolved_hash],
+        &proof,
+    )
+    .unwrap();
+    let verify_time = verify_start.elapsed();
+    println!(
+        "verify time {}ms, {}s",
+        verify_time.as_millis(),
+        verify_time.as_secs()
+    );
+    assert!(valid_proof);
+
+    let invalid_proof =
+        Groth16::verify_with_processed_vk(&processed_vk, &[F::one()], &proof).unwrap();
+    assert!(!invalid_proof);
+
+    Report {
+        backend: "groth16",
+        curve,
+        config: None,
+        num_constraints,
+        setup_time,
+        prove_time,
+        verify_time,
+        proof_len: proof.serialized_size(),
+        extra: vec![],
+    }
+}
+
+/// Checks that the circuit is unsatisfied by each kind of invalid solution
+/// and by a wrong hash of the puzzle.
+pub(crate) fn check_invalid_circuits<F: PrimeField>() {
     {
         // should failed by out of bound
-        let mut circuit_to_failed_oob: SudokuCircuit<<E as PairingEngine>::Fr> = SudokuCircuit {
+        let mut circuit_to_failed_oob: SudokuCircuit<F> = SudokuCircuit {
             unsolved_hash: unsolved_hash(UNSOLVED),
             unsolved: UNSOLVED,
             solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
         };
         circuit_to_failed_oob.solved[0][8] = 10;
         let cs = ConstraintSystem::new_ref();
@@ -62,10 +146,12 @@ where
     }
     {
         // should failed by out of bound
-        let circuit_to_failed_unmatch: SudokuCircuit<<E as PairingEngine>::Fr> = SudokuCircuit {
+        let circuit_to_failed_unmatch: SudokuCircuit<F> = SudokuCircuit {
             unsolved_hash: unsolved_hash(UNSOLVED),
             unsolved: UNSOLVED,
             solved: SOLVED_UNMATCH,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
         };
         let cs = ConstraintSystem::new_ref();
         circuit_to_failed_unmatch
@@ -78,12 +164,13 @@ where
     }
     {
         // should failed by repeated numbers in a row
-        let circuit_to_failed_repeated_in_row: SudokuCircuit<<E as PairingEngine>::Fr> =
-            SudokuCircuit {
-                unsolved_hash: unsolved_hash(UNSOLVED),
-                unsolved: UNSOLVED,
-                solved: SOLVED_REPEATED_IN_ROW,
-            };
+        let circuit_to_failed_repeated_in_row: SudokuCircuit<F> = SudokuCircuit {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED_REPEATED_IN_ROW,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
 
         let cs = ConstraintSystem::new_ref();
         circuit_to_failed_repeated_in_row
@@ -96,12 +183,13 @@ where
     }
     {
         // should failed by repeated numbers in a column
-        let circuit_to_failed_repeated_in_column: SudokuCircuit<<E as PairingEngine>::Fr> =
-            SudokuCircuit {
-                unsolved_hash: unsolved_hash(UNSOLVED),
-                unsolved: UNSOLVED,
-                solved: SOLVED_REPEATED_IN_COLUMN,
-            };
+        let circuit_to_failed_repeated_in_column: SudokuCircuit<F> = SudokuCircuit {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED_REPEATED_IN_COLUMN,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
 
         let cs = ConstraintSystem::new_ref();
         circuit_to_failed_repeated_in_column
@@ -115,12 +203,13 @@ where
 
     {
         // should failed by repeated numbers in a square
-        let circuit_to_failed_repeated_in_suqare: SudokuCircuit<<E as PairingEngine>::Fr> =
-            SudokuCircuit {
-                unsolved_hash: unsolved_hash(UNSOLVED),
-                unsolved: UNSOLVED,
-                solved: SOLVED_REPEATED_IN_SQUARE,
-            };
+        let circuit_to_failed_repeated_in_suqare: SudokuCircuit<F> = SudokuCircuit {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED_REPEATED_IN_SQUARE,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
 
         let cs = ConstraintSystem::new_ref();
         circuit_to_failed_repeated_in_suqare
@@ -135,12 +224,13 @@ where
     {
         // should failed by hash unmatch
         let unsolved_hash = unsolved_hash::<F>(UNSOLVED).add(F::from(1u32));
-        let circuit_to_failed_hash_unmatch: SudokuCircuit<<E as PairingEngine>::Fr> =
-            SudokuCircuit {
-                unsolved_hash,
-                unsolved: UNSOLVED,
-                solved: SOLVED_REPEATED_IN_SQUARE,
-            };
+        let circuit_to_failed_hash_unmatch: SudokuCircuit<F> = SudokuCircuit {
+            unsolved_hash,
+            unsolved: UNSOLVED,
+            solved: SOLVED_REPEATED_IN_SQUARE,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
 
         let cs = ConstraintSystem::new_ref();
         circuit_to_failed_hash_unmatch
@@ -151,60 +241,4 @@ where
         let is_satisfied = cs.is_satisfied().unwrap();
         assert!(!is_satisfied);
     }
-
-    let circuit_defining_cs: SudokuCircuit<F> = SudokuCircuit {
-        unsolved_hash: Default::default(),
-        unsolved: Default::default(),
-        solved: Default::default(),
-    };
-    let mut rng = ark_std::test_rng();
-
-    let setup_start = Instant::now();
-    let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit_defining_cs, &mut rng).unwrap();
-    let processed_vk = Groth16::process_vk(&vk).unwrap();
-    let setup_time = setup_start.elapsed();
-    println!(
-        "setup time {}ms, {}s",
-        setup_time.as_millis(),
-        setup_time.as_secs()
-    );
-
-    let cs = ConstraintSystem::new_ref();
-    circuit_to_verify_success
-        .clone()
-        .generate_constraints(cs.clone())
-        .unwrap();
-    // Let's check whether the constraint system is satisfied
-    let is_satisfied = cs.is_satisfied().unwrap();
-
-    assert!(is_satisfied);
-
-    let prove_start = Instant::now();
-    let proof = Groth16::prove(&pk, circuit_to_verify_success.clone(), &mut rng).unwrap();
-    let prove_time = prove_start.elapsed();
-    println!(
-        "prove time {}ms, {}s",
-        prove_time.as_millis(),
-        prove_time.as_secs()
-    );
-    println!("proof len: {}", proof.serialized_size());
-
-    let verify_start = Instant::now();
-    let valid_proof = Groth16::verify_with_processed_vk(
-        &processed_vk,
-        &[circuit_to_verify_success.unsolved_hash],
-        &proof,
-    )
-    .unwrap();
-    let verify_time = verify_start.elapsed();
-    println!(
-        "verify time {}ms, {}s",
-        verify_time.as_millis(),
-        verify_time.as_secs()
-    );
-    assert!(valid_proof);
-
-    let invalid_proof =
-        Groth16::verify_with_processed_vk(&processed_vk, &[F::one()], &proof).unwrap();
-    assert!(!invalid_proof);
 }
@@ -0,0 +1,253 @@
+use ark_crypto_primitives::{crh::sha256::constraints::Sha256Gadget, CRHSchemeGadget};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{
+        constraints::PoseidonSpongeVar, find_poseidon_ark_and_mds, PoseidonParameters,
+        PoseidonSponge,
+    },
+    CryptographicSponge, FieldBasedCryptographicSponge,
+};
+use sha2::Sha256;
+use zk_sudoku_core::public_input::{keccak256_input, preimage, sha256_input, ProverId};
+
+use crate::keccak;
+
+const RATE: usize = 2;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 57;
+const ALPHA: u64 = 5;
+
+/// The hash committing to the unsolved sudoku, which is the only public input
+/// of the circuit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PuzzleHash {
+    /// sha256 of the 81 cells, truncated to 31 bytes.
+    #[default]
+    Sha256,
+    /// Poseidon (x^5, width 3, see `poseidon_parameters`) of the 81 cells.
+    /// Costs a few hundred constraints where sha256 costs tens of thousands.
+    Poseidon,
+    /// keccak256 of the 81 cells, its first 31 bytes read as a big endian
+    /// integer, so that a Solidity verifier gets the public input from the
+    /// `keccak256` opcode.
+    Keccak256,
+}
+
+impl PuzzleHash {
+    pub fn hash<F: PrimeField>(&self, unsolved: [[u8; 9]; 9]) -> F {
+        self.hash_with_prover_id(unsolved, None)
+    }
+
+    /// The hash of the cells followed by the prover id, see
+    /// `zk_sudoku_core::public_input::preimage`.
+    pub fn hash_with_prover_id<F: PrimeField>(
+        &self,
+        unsolved: [[u8; 9]; 9],
+        prover_id: Option<&ProverId>,
+    ) -> F {
+        match self {
+            PuzzleHash::Sha256 => F::from_le_bytes_mod_order(&sha256_input(&unsolved, prover_id)),
+            PuzzleHash::Poseidon => poseidon_bytes(&preimage(&unsolved, prover_id)),
+            PuzzleHash::Keccak256 => {
+                F::from_le_bytes_mod_order(&keccak256_input(&unsolved, prover_id))
+            }
+        }
+    }
+
+    /// Same as `hash_with_prover_id`, over the allocated preimage.
+    pub(crate) fn hash_var<F: PrimeField>(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        preimage: &[UInt8<F>],
+    ) -> Result<FpVar<F>, SynthesisError> {
+        match self {
+            PuzzleHash::Sha256 => {
+                let parameters =
+                    <Sha256Gadget<F> as CRHSchemeGadget<Sha256, F>>::ParametersVar::new_constant(
+                        cs,
+                        (),
+                    )?;
+                let hash_result = Sha256Gadget::<F>::evaluate(&parameters, preimage)?.to_bytes()?;
+                Boolean::le_bits_to_fp_var(&hash_result[0..31].to_bits_le()?)
+            }
+            PuzzleHash::Poseidon => poseidon_bytes_var(cs, preimage),
+            PuzzleHash::Keccak256 => {
+                let mut hash_result = keccak::keccak256(preimage)?;
+                hash_result.truncate(31);
+                hash_result.reverse();
+                Boolean::le_bits_to_fp_var(&hash_result.to_bits_le()?)
+            }
+        }
+    }
+}
+
+/// Poseidon with x^5 over a state of 3, i.e. rate 2 and capacity 1, with 8
+/// full and 57 partial rounds: the instance of the Poseidon paper for the
+/// ~255 bit scalar fields of bls12-381 and bn254 at 128 bits of security.
+/// The round constants and the MDS matrix come from the Grain LFSR of the
+/// reference implementation, so the permutation matches its test vectors.
+pub fn poseidon_parameters<F: PrimeField>() -> PoseidonParameters<F> {
+    let (ark, mds) = find_poseidon_ark_and_mds::<F>(
+        F::size_in_bits() as u64,
+        RATE,
+        FULL_ROUNDS as u64,
+        PARTIAL_ROUNDS as u64,
+        0,
+    );
+    PoseidonParameters {
+        full_rounds: FULL_ROUNDS,
+        partial_rounds: PARTIAL_ROUNDS,
+        alpha: ALPHA,
+        ark,
+        mds,
+        rate: RATE,
+        capacity: 1,
+    }
+}
+
+/// The Poseidon sponge over the length of `bytes` then the bytes, which
+/// ark-sponge packs into field elements, 31 bytes each on these fields. The
+/// length keeps a preimage from colliding with the same bytes followed by
+/// zeros.
+pub(crate) fn poseidon_bytes<F: PrimeField>(bytes: &[u8]) -> F {
+    let mut sponge = PoseidonSponge::new(&poseidon_parameters());
+    sponge.absorb(&length_prefixed(bytes));
+    sponge.squeeze_native_field_elements(1)[0]
+}
+
+/// Same as `poseidon_bytes`, over allocated bytes.
+pub(crate) fn poseidon_bytes_var<F: PrimeField>(
+    cs: ConstraintSystemRef<F>,
+    bytes: &[UInt8<F>],
+) -> Result<FpVar<F>, SynthesisError> {
+    let mut input = vec![UInt8::constant(length(bytes))];
+    input.extend_from_slice(bytes);
+    let mut sponge = PoseidonSpongeVar::new(cs, &poseidon_parameters());
+    sponge.absorb(&input)?;
+    Ok(sponge.squeeze_field_elements(1)?.remove(0))
+}
+
+fn length<T>(bytes: &[T]) -> u8 {
+    u8::try_from(bytes.len()).expect("the hashed preimages are shorter than 256 bytes")
+}
+
+fn length_prefixed(bytes: &[u8]) -> Vec<u8> {
+    let mut input = vec![length(bytes)];
+    input.extend_from_slice(bytes);
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ark_bls12_381::Fr;
+    use ark_ff::PrimeField;
+    use ark_r1cs_std::{alloc::AllocVar, uint8::UInt8, R1CSVar};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_sponge::{
+        poseidon::PoseidonSponge, Absorb, CryptographicSponge, FieldBasedCryptographicSponge,
+    };
+
+    use super::{poseidon_bytes, poseidon_bytes_var, poseidon_parameters, PuzzleHash};
+    use crate::{
+        circuit::SudokuCircuit,
+        parameters::{SOLVED, UNSOLVED},
+    };
+
+    fn num_constraints(hash: PuzzleHash) -> usize {
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: hash.hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash,
+            prover_id: None,
+        };
+        let cs = ConstraintSystem::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        cs.num_constraints()
+    }
+
+    #[test]
+    fn test_poseidon_constraints() {
+        let sha256 = num_constraints(PuzzleHash::Sha256);
+        let poseidon = num_constraints(PuzzleHash::Poseidon);
+        println!(
+            "constraints with sha256: {}, with poseidon: {}, saved: {}",
+            sha256,
+            poseidon,
+            sha256 - poseidon
+        );
+        assert!(poseidon < sha256);
+    }
+
+    #[test]
+    fn test_keccak256_hash() {
+        // Satisfied only if the gadget truncates like keccak_unsolved_hash
+        println!(
+            "constraints with keccak256: {}",
+            num_constraints(PuzzleHash::Keccak256)
+        );
+    }
+
+    #[test]
+    fn test_poseidon_hash_unmatch() {
+        let mut unsolved = UNSOLVED;
+        unsolved[4][4] = 6;
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: PuzzleHash::Poseidon.hash(unsolved),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Poseidon,
+            prover_id: None,
+        };
+        let cs = ConstraintSystem::new_ref();
+        circuit.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    fn permute_one_two<F: PrimeField + Absorb>() -> Vec<F> {
+        let mut sponge = PoseidonSponge::new(&poseidon_parameters::<F>());
+        sponge.absorb(&vec![F::from(1u64), F::from(2u64)]);
+        sponge.squeeze_native_field_elements(2)
+    }
+
+    #[test]
+    fn test_poseidon_known_answer_bls12_381() {
+        // Rate outputs of the x5_255_3 reference permutation on [0, 1, 2]
+        let expected = [
+            "37068251774887509885063625701815026138353041152735229476479055620962268601796",
+            "26763157702141528937904191329664859174584798817251788852101947537759678822298",
+        ];
+        let expected: Vec<Fr> = expected.iter().map(|s| Fr::from_str(s).unwrap()).collect();
+        assert_eq!(permute_one_two::<Fr>(), expected);
+    }
+
+    #[test]
+    fn test_poseidon_known_answer_bn254() {
+        // Rate outputs of the x5_254_3 reference permutation on [0, 1, 2]
+        let expected = [
+            "7142104613055408817911962100316808866448378443474503659992478482890339429929",
+            "6549537674122432311777789598043107870002137484850126429160507761192163713804",
+        ];
+        let expected: Vec<ark_bn254::Fr> = expected
+            .iter()
+            .map(|s| ark_bn254::Fr::from_str(s).unwrap())
+            .collect();
+        assert_eq!(permute_one_two::<ark_bn254::Fr>(), expected);
+    }
+
+    #[test]
+    fn test_poseidon_bytes_gadget() {
+        let bytes: Vec<u8> = (0..100).collect();
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let vars = UInt8::new_witness_vec(cs.clone(), &bytes).unwrap();
+        let hash = poseidon_bytes_var(cs.clone(), &vars).unwrap();
+        assert_eq!(hash.value().unwrap(), poseidon_bytes::<Fr>(&bytes));
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,160 @@
+use ark_ff::PrimeField;
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::SynthesisError;
+
+// Rotation offsets of rho, indexed by x + 5 * y
+const RHO: [usize; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+// Bytes absorbed per permutation by keccak256
+const RATE: usize = 136;
+
+/// A 64 bits lane of the state, least significant bit first.
+type Lane<F> = Vec<Boolean<F>>;
+
+fn xor<F: PrimeField>(a: &[Boolean<F>], b: &[Boolean<F>]) -> Result<Lane<F>, SynthesisError> {
+    a.iter().zip(b).map(|(a, b)| a.xor(b)).collect()
+}
+
+fn rotate_left<F: PrimeField>(lane: &[Boolean<F>], by: usize) -> Lane<F> {
+    (0..64).map(|i| lane[(i + 64 - by) % 64].clone()).collect()
+}
+
+fn keccak_f<F: PrimeField>(state: &mut [Lane<F>]) -> Result<(), SynthesisError> {
+    for round_constant in ROUND_CONSTANTS {
+        // theta
+        let mut parities = Vec::with_capacity(5);
+        for x in 0..5 {
+            let mut parity = state[x].clone();
+            for y in 1..5 {
+                parity = xor(&parity, &state[x + 5 * y])?;
+            }
+            parities.push(parity);
+        }
+        for x in 0..5 {
+            let d = xor(
+                &parities[(x + 4) % 5],
+                &rotate_left(&parities[(x + 1) % 5], 1),
+            )?;
+            for y in 0..5 {
+                state[x + 5 * y] = xor(&state[x + 5 * y], &d)?;
+            }
+        }
+
+        // rho and pi
+        let mut b = vec![Vec::new(); 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] = rotate_left(&state[x + 5 * y], RHO[x + 5 * y]);
+            }
+        }
+
+        // chi
+        for x in 0..5 {
+            for y in 0..5 {
+                state[x + 5 * y] = (0..64)
+                    .map(|i| {
+                        let masked = b[(x + 1) % 5 + 5 * y][i]
+                            .not()
+                            .and(&b[(x + 2) % 5 + 5 * y][i])?;
+                        b[x + 5 * y][i].xor(&masked)
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+        }
+
+        // iota, xoring constants adds no constraint
+        let constant = (0..64)
+            .map(|i| Boolean::constant((round_constant >> i) & 1 == 1))
+            .collect::<Vec<_>>();
+        state[0] = xor(&state[0], &constant)?;
+    }
+    Ok(())
+}
+
+/// Keccak256 (the padding of the EVM opcode, not the one of SHA3-256) of
+/// fewer than 136 bytes, which is a single permutation.
+pub(crate) fn keccak256<F: PrimeField>(
+    input: &[UInt8<F>],
+) -> Result<Vec<UInt8<F>>, SynthesisError> {
+    assert!(
+        input.len() < RATE,
+        "keccak256 gadget only absorbs one block"
+    );
+
+    let mut block = input.to_bits_le()?;
+    let mut padding = vec![0u8; RATE - input.len()];
+    padding[0] ^= 0x01;
+    padding[RATE - input.len() - 1] ^= 0x80;
+    for byte in padding {
+        block.extend((0..8).map(|i| Boolean::constant((byte >> i) & 1 == 1)));
+    }
+
+    let mut state = block
+        .chunks(64)
+        .map(|lane| lane.to_vec())
+        .collect::<Vec<_>>();
+    state.resize(25, vec![Boolean::FALSE; 64]);
+    keccak_f(&mut state)?;
+
+    Ok(state[..4]
+        .concat()
+        .chunks(8)
+        .map(UInt8::from_bits_le)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use digest::Digest;
+
+    use super::keccak256;
+
+    #[test]
+    fn test_keccak256_gadget() {
+        for len in [0, 1, 81, 135] {
+            let input = (0..len).map(|i| (i * 7 % 10) as u8).collect::<Vec<u8>>();
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let input_var = UInt8::new_witness_vec(cs.clone(), &input).unwrap();
+            let digest = keccak256(&input_var).unwrap();
+
+            assert!(cs.is_satisfied().unwrap());
+            assert_eq!(
+                digest.value().unwrap(),
+                sha3::Keccak256::digest(&input).to_vec()
+            );
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use ark_ec::PairingEngine;
+use ark_ff::PrimeField;
+use ark_groth16::ProvingKey;
+use ark_marlin::{AHPForR1CS, IndexVerifierKey};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::PolynomialCommitment;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError, SynthesisMode};
+pub use zk_sudoku_core::keys::{read_key, write_key};
+
+use crate::{circuit::SudokuCircuit, hash::PuzzleHash};
+
+/// Why a key of the sudoku circuit could not be generated or checked.
+#[derive(Debug)]
+pub enum CircuitKeyError {
+    Synthesis(SynthesisError),
+    /// The universal setup failed, with the error of the commitment scheme.
+    Setup(String),
+    /// The key was generated for another circuit, e.g. with another puzzle
+    /// hash.
+    Mismatch,
+}
+
+/// The error of the key files, see `check_groth16_keys` and
+/// `check_marlin_index` to check a key read back belongs to the sudoku
+/// circuit.
+pub type KeyError = zk_sudoku_core::keys::KeyError<CircuitKeyError>;
+
+fn circuit_defining_cs<F: PrimeField>(hash: PuzzleHash) -> SudokuCircuit<F> {
+    SudokuCircuit {
+        unsolved_hash: Default::default(),
+        unsolved: Default::default(),
+        solved: Default::default(),
+        hash,
+        prover_id: None,
+    }
+}
+
+/// Checks that the shape of the Groth16 proving key, whose queries have one
+/// element per variable, matches the sudoku circuit using `hash`.
+pub fn check_groth16_keys<E: PairingEngine>(
+    pk: &ProvingKey<E>,
+    hash: PuzzleHash,
+) -> Result<(), KeyError> {
+    let cs = ConstraintSystem::<E::Fr>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    circuit_defining_cs::<E::Fr>(hash)
+        .generate_constraints(cs.clone())
+        .map_err(|err| KeyError::Backend(CircuitKeyError::Synthesis(err)))?;
+    let num_instance = cs.num_instance_variables();
+    let num_witness = cs.num_witness_variables();
+
+    if pk.vk.gamma_abc_g1.len() == num_instance
+        && pk.l_query.len() == num_witness
+        && pk.a_query.len() == num_instance + num_witness
+        && pk.b_g2_query.len() == num_instance + num_witness
+    {
+        Ok(())
+    } else {
+        Err(KeyError::Backend(CircuitKeyError::Mismatch))
+    }
+}
+
+/// Checks that the Marlin index verifying key was derived from the sudoku
+/// circuit using `hash`.
+pub fn check_marlin_index<F, PC>(
+    index_vk: &IndexVerifierKey<F, PC>,
+    hash: PuzzleHash,
+) -> Result<(), KeyError>
+where
+    F: PrimeField,
+    PC: PolynomialCommitment<F, DensePolynomial<F>>,
+{
+    let index = AHPForR1CS::index(circuit_defining_cs::<F>(hash))
+        .map_err(|_| KeyError::Backend(CircuitKeyError::Mismatch))?;
+    let expected = &index.index_info;
+    let info = &index_vk.index_info;
+
+    if info.num_variables == expected.num_variables
+        && info.num_constraints == expected.num_constraints
+        && info.num_non_zero == expected.num_non_zero
+        && info.num_instance_variables == expected.num_instance_variables
+    {
+        Ok(())
+    } else {
+        Err(KeyError::Backend(CircuitKeyError::Mismatch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use ark_bn254::{Bn254, Fr};
+    use ark_groth16::{Groth16, ProvingKey};
+    use ark_snark::SNARK;
+
+    use super::{check_groth16_keys, read_key, write_key, CircuitKeyError, KeyError};
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    #[test]
+    fn test_groth16_key_file() {
+        let mut rng = ark_std::test_rng();
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let (pk, _) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+
+        let path = env::temp_dir().join("zk_sudoku_groth16_bn254.pk");
+        write_key(&pk, &path).unwrap();
+        let pk = read_key::<ProvingKey<Bn254>>(&path).unwrap();
+        check_groth16_keys(&pk, PuzzleHash::Sha256).unwrap();
+        assert!(matches!(
+            check_groth16_keys(&pk, PuzzleHash::Poseidon),
+            Err(KeyError::Backend(CircuitKeyError::Mismatch))
+        ));
+
+        let proof = Groth16::<Bn254>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+        assert!(Groth16::<Bn254>::verify(&pk.vk, &[circuit.unsolved_hash], &proof).unwrap());
+    }
+}
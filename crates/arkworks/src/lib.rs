@@ -1,43 +1,179 @@
+use ark_ec::{AffineCurve, PairingEngine};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::{ipa_pc::InnerProductArgPC, marlin_pc::MarlinKZG10};
+use ark_std::rand::{CryptoRng, RngCore};
 use blake2::Blake2s;
+use rand_core::OsRng;
+use zk_sudoku_core::report::{print_table, Report};
 
-use crate::{groth16::run_groth16, marlin::run_marlin};
+use crate::{
+    batch::run_batch_verify,
+    circuit::SudokuCircuit,
+    gm17::run_gm17,
+    groth16::run_groth16,
+    hash::PuzzleHash,
+    marlin::run_marlin,
+    parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    recursion::run_recursion,
+    report::ConstraintReport,
+};
 
+pub mod audit;
+pub mod batch;
+pub mod ccs;
+pub mod ceremony;
 pub mod circuit;
+pub mod commitment;
+pub mod embed;
+pub mod gm17;
 pub mod groth16;
+pub mod hash;
+pub mod keccak;
+pub mod keys;
 pub mod marlin;
+#[cfg(feature = "parallel")]
+pub mod parallel;
 pub mod parameters;
+pub mod prover;
+pub mod r1cs;
+pub mod recursion;
+pub mod report;
+pub mod snarkjs;
+pub mod solidity;
 
-pub fn test_arkworks() {
-    println!("-----------------------------");
-    println!("Run Groth16 with bls12-381...");
-    println!("-----------------------------");
-    run_groth16::<ark_bls12_381::Fr, ark_bls12_381::Bls12_381>();
-    println!("-----------------------------");
-    println!("Run Groth16 with bn254...");
-    println!("-----------------------------");
-    run_groth16::<ark_bn254::Fr, ark_bn254::Bn254>();
+type Kzg10<E> = MarlinKZG10<E, DensePolynomial<<E as PairingEngine>::Fr>>;
+// No trusted setup, the generators are hashed to the curve, but the
+// verification is linear in the degree
+type Ipa<G> = InnerProductArgPC<G, Blake2s, DensePolynomial<<G as AffineCurve>::ScalarField>>;
+
+fn header(title: &str) {
     println!("-----------------------------");
-    println!("Run Marlin with KZG10<bls12-381> and Blake2s...");
+    println!("{}", title);
     println!("-----------------------------");
-    run_marlin::<
+}
+
+/// Runs every backend on the sample sudoku with randomness from the OS and
+/// returns their reports, also printed as a table.
+pub fn test_arkworks() -> Vec<Report> {
+    test_arkworks_with_rng(&mut OsRng)
+}
+
+/// Same as `test_arkworks`, drawing the randomness of the setups and proofs
+/// from `rng`, e.g. a seeded RNG for reproducible runs.
+pub fn test_arkworks_with_rng<R: RngCore + CryptoRng + Send>(rng: &mut R) -> Vec<Report> {
+    let mut reports = vec![];
+
+    header("Constraints per rule...");
+    let circuit = SudokuCircuit::<ark_bls12_381::Fr> {
+        unsolved_hash: unsolved_hash(UNSOLVED),
+        unsolved: UNSOLVED,
+        solved: SOLVED,
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
+    };
+    println!("{}", ConstraintReport::new(circuit).unwrap());
+
+    header("Run Groth16 with bls12-381...");
+    reports.push(run_groth16::<ark_bls12_381::Fr, ark_bls12_381::Bls12_381, _>("bls12-381", rng));
+    header("Run Groth16 with bn254...");
+    reports.push(run_groth16::<ark_bn254::Fr, ark_bn254::Bn254, _>(
+        "bn254", rng,
+    ));
+    header("Run Groth16 with bls12-377...");
+    reports.push(run_groth16::<ark_bls12_377::Fr, ark_bls12_377::Bls12_377, _>("bls12-377", rng));
+    header("Run Groth16 with bw6-761...");
+    reports.push(run_groth16::<ark_bw6_761::Fr, ark_bw6_761::BW6_761, _>(
+        "bw6-761", rng,
+    ));
+    header("Run Groth16 with mnt4-298...");
+    reports.push(run_groth16::<ark_mnt4_298::Fr, ark_mnt4_298::MNT4_298, _>(
+        "mnt4-298", rng,
+    ));
+    header("Run Groth16 with mnt6-298...");
+    reports.push(run_groth16::<ark_mnt6_298::Fr, ark_mnt6_298::MNT6_298, _>(
+        "mnt6-298", rng,
+    ));
+    header("Batch verify Groth16 with bn254...");
+    run_batch_verify::<ark_bn254::Bn254, _>(16, rng);
+    #[cfg(feature = "parallel")]
+    {
+        header("Groth16 prove time by thread count with bn254...");
+        let max_threads = rayon::current_num_threads();
+        let mut thread_counts = [1, 2, 4, max_threads]
+            .into_iter()
+            .filter(|n| *n <= max_threads)
+            .collect::<Vec<_>>();
+        thread_counts.dedup();
+        parallel::run_thread_scaling::<ark_bn254::Bn254, _>(&thread_counts, rng);
+    }
+
+    header("Run GM17 with bls12-381...");
+    reports.push(run_gm17::<ark_bls12_381::Fr, ark_bls12_381::Bls12_381, _>(
+        "bls12-381",
+        rng,
+    ));
+    header("Run GM17 with bn254...");
+    reports.push(run_gm17::<ark_bn254::Fr, ark_bn254::Bn254, _>("bn254", rng));
+
+    // The scalar field of mnt6-298 only has a subgroup of order 2^17, too
+    // small for the Marlin domains of this circuit
+    header("Run Marlin with KZG10<bls12-381> and Blake2s...");
+    reports.push(run_marlin::<
         ark_bls12_381::Fr,
-        ark_poly_commit::marlin_pc::MarlinKZG10<
-            ark_bls12_381::Bls12_381,
-            ark_poly::univariate::DensePolynomial<ark_bls12_381::Fr>,
-        >,
+        Kzg10<ark_bls12_381::Bls12_381>,
         Blake2s,
-    >();
-    println!("-----------------------------");
-    println!("Run Marlin with KZG10<bn254> and Blake2s...");
-    println!("-----------------------------");
-    run_marlin::<
+        _,
+    >("marlin", "bls12-381", rng));
+    header("Run Marlin with KZG10<bn254> and Blake2s...");
+    reports.push(run_marlin::<
         ark_bn254::Fr,
-        ark_poly_commit::marlin_pc::MarlinKZG10<
-            ark_bn254::Bn254,
-            ark_poly::univariate::DensePolynomial<ark_bn254::Fr>,
-        >,
+        Kzg10<ark_bn254::Bn254>,
+        Blake2s,
+        _,
+    >("marlin", "bn254", rng));
+    header("Run Marlin with KZG10<bls12-377> and Blake2s...");
+    reports.push(run_marlin::<
+        ark_bls12_377::Fr,
+        Kzg10<ark_bls12_377::Bls12_377>,
         Blake2s,
-    >();
+        _,
+    >("marlin", "bls12-377", rng));
+    header("Run Marlin with KZG10<bw6-761> and Blake2s...");
+    reports.push(run_marlin::<
+        ark_bw6_761::Fr,
+        Kzg10<ark_bw6_761::BW6_761>,
+        Blake2s,
+        _,
+    >("marlin", "bw6-761", rng));
+    header("Run Marlin with KZG10<mnt4-298> and Blake2s...");
+    reports.push(run_marlin::<
+        ark_mnt4_298::Fr,
+        Kzg10<ark_mnt4_298::MNT4_298>,
+        Blake2s,
+        _,
+    >("marlin", "mnt4-298", rng));
+
+    header("Run Marlin with IPA<bls12-381> and Blake2s...");
+    reports.push(run_marlin::<
+        ark_bls12_381::Fr,
+        Ipa<ark_bls12_381::G1Affine>,
+        Blake2s,
+        _,
+    >("marlin-ipa", "bls12-381", rng));
+    header("Run Marlin with IPA<bn254> and Blake2s...");
+    reports.push(run_marlin::<
+        ark_bn254::Fr,
+        Ipa<ark_bn254::G1Affine>,
+        Blake2s,
+        _,
+    >("marlin-ipa", "bn254", rng));
+
+    header("Run Groth16<bls12-377> verified in Groth16<bw6-761>...");
+    run_recursion(rng);
+
+    header("Summary");
+    print_table(&reports);
+    reports
 }
 
 
@@ -1,4 +1,4 @@
-use std::time::Instant;
+use std::{env, path::Path, time::Instant};
 
 use ark_ff::PrimeField;
 use ark_marlin::{AHPForR1CS, Marlin};
@@ -6,20 +6,62 @@ use ark_poly::univariate::DensePolynomial;
 use ark_poly_commit::{PCUniversalParams, PolynomialCommitment};
 use ark_relations::r1cs::{ConstraintLayer, ConstraintSynthesizer, ConstraintSystem, TracingMode};
 use ark_serialize::CanonicalSerialize;
+use ark_std::rand::{CryptoRng, RngCore};
 use digest::Digest;
 use tracing_subscriber::prelude::__tracing_subscriber_SubscriberExt;
+use zk_sudoku_core::report::Report;
 
 use crate::{
     circuit::SudokuCircuit,
+    hash::PuzzleHash,
+    keys::{read_key, write_key, CircuitKeyError, KeyError},
     parameters::UNSOLVED,
     parameters::{unsolved_hash, SOLVED},
 };
 
-pub fn run_marlin<F, PC, D>()
+/// Returns the universal SRS for circuits of the given size. It is read from
+/// `dir` if a previous run stored one under the same `name` with the same
+/// maximum degree, otherwise the universal setup is run and its output stored
+/// there. `name` tells apart the curves and polynomial commitments.
+pub fn cached_universal_srs<F, PC, D, R>(
+    dir: impl AsRef<Path>,
+    name: &str,
+    num_constraints: usize,
+    num_variables: usize,
+    num_non_zero: usize,
+    rng: &mut R,
+) -> Result<PC::UniversalParams, KeyError>
 where
     F: PrimeField,
     PC: PolynomialCommitment<F, DensePolynomial<F>>,
     D: Digest,
+    R: RngCore,
+{
+    let max_degree = AHPForR1CS::<F>::max_degree(num_constraints, num_variables, num_non_zero)
+        .map_err(|err| KeyError::Backend(CircuitKeyError::Setup(format!("{:?}", err))))?;
+    let path = dir
+        .as_ref()
+        .join(format!("marlin_srs_{}_{}.bin", name, max_degree));
+    if path.exists() {
+        return read_key(&path);
+    }
+
+    let universal_srs =
+        Marlin::<F, PC, D>::universal_setup(num_constraints, num_variables, num_non_zero, rng)
+            .map_err(|err| KeyError::Backend(CircuitKeyError::Setup(format!("{:?}", err))))?;
+    std::fs::create_dir_all(dir)?;
+    write_key(&universal_srs, &path)?;
+    Ok(universal_srs)
+}
+
+/// Runs Marlin on the sample sudoku with the polynomial commitment `PC`,
+/// reported as `backend`.
+pub fn run_marlin<F, PC, D, R>(backend: &'static str, curve: &'static str, rng: &mut R) -> Report
+where
+    F: PrimeField,
+    PC: PolynomialCommitment<F, DensePolynomial<F>>,
+    D: Digest,
+    R: RngCore + CryptoRng,
 {
     // First, some boilerplat that helps with debugging
     let mut layer = ConstraintLayer::default();
@@ -33,6 +75,8 @@ where
         unsolved_hash: unsolved_hash(UNSOLVED),
         unsolved: UNSOLVED,
         solved: SOLVED,
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
     };
 
     let cs = ConstraintSystem::new_ref();
@@ -49,6 +93,8 @@ where
         unsolved_hash: Default::default(),
         unsolved: Default::default(),
         solved: Default::default(),
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
     };
 
     let index = AHPForR1CS::index(circuit_defining_cs.clone()).unwrap();
@@ -65,12 +111,16 @@ where
         index.max_degree()
     );
 
-    let mut rng = &mut ark_std::test_rng();
-
     let setup_start = Instant::now();
-    let universal_srs =
-        Marlin::<F, PC, D>::universal_setup(num_constraints, num_variables, num_non_zero, rng)
-            .unwrap();
+    let universal_srs = cached_universal_srs::<F, PC, D, _>(
+        env::temp_dir().join("zk_sudoku"),
+        &format!("{}_{}", curve, backend),
+        num_constraints,
+        num_variables,
+        num_non_zero,
+        rng,
+    )
+    .unwrap();
     let setup_time = setup_start.elapsed();
     println!(
         "setup time {}ms, {}s, max_degree: {}",
@@ -85,7 +135,7 @@ where
 
     let prove_start = Instant::now();
     let proof =
-        Marlin::<F, PC, D>::prove(&index_pk, circuit_to_verify_against.clone(), &mut rng).unwrap();
+        Marlin::<F, PC, D>::prove(&index_pk, circuit_to_verify_against.clone(), rng).unwrap();
     let prove_time = prove_start.elapsed();
     println!(
         "prove time {}ms, {}s",
@@ -99,7 +149,7 @@ where
         &index_vk,
         &[circuit_to_verify_against.unsolved_hash],
         &proof,
-        &mut rng,
+        rng,
     )
     .unwrap();
     let verify_time = verify_start.elapsed();
@@ -110,7 +160,52 @@ where
     );
     assert!(valid_proof);
 
-    let invalid_proof =
-        Marlin::<F, PC, D>::verify(&index_vk, &[F::one()], &proof, &mut rng).unwrap();
+    let invalid_proof = Marlin::<F, PC, D>::verify(&index_vk, &[F::one()], &proof, rng).unwrap();
     assert!(!invalid_proof);
+
+    Report {
+        backend,
+        curve,
+        config: None,
+        num_constraints,
+        setup_time,
+        prove_time,
+        verify_time,
+        proof_len: proof.serialized_size(),
+        extra: vec![],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use ark_bls12_381::{Bls12_381, Fr};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_poly_commit::{marlin_pc::MarlinKZG10, PCUniversalParams};
+    use blake2::Blake2s;
+
+    use super::cached_universal_srs;
+
+    type PC = MarlinKZG10<Bls12_381, DensePolynomial<Fr>>;
+
+    #[test]
+    fn test_cached_universal_srs() {
+        let dir = env::temp_dir().join("zk_sudoku_srs_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut rng = ark_std::test_rng();
+
+        let srs =
+            cached_universal_srs::<Fr, PC, Blake2s, _>(&dir, "bls12-381", 16, 16, 32, &mut rng)
+                .unwrap();
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        // the same degree is read back instead of being generated again
+        let cached =
+            cached_universal_srs::<Fr, PC, Blake2s, _>(&dir, "bls12-381", 16, 16, 32, &mut rng)
+                .unwrap();
+        assert_eq!(cached.max_degree(), srs.max_degree());
+        assert_eq!(cached.powers_of_g, srs.powers_of_g);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    }
 }
@@ -0,0 +1,68 @@
+use std::time::Instant;
+
+use ark_ec::PairingEngine;
+use ark_groth16::Groth16;
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+
+use crate::{
+    circuit::SudokuCircuit,
+    hash::PuzzleHash,
+    parameters::{unsolved_hash, SOLVED, UNSOLVED},
+};
+
+/// Runs `f` on a rayon pool of `num_threads` threads, so the parallel
+/// arkworks code it calls (MSMs, FFTs) uses at most that many threads.
+pub fn with_threads<T: Send>(num_threads: usize, f: impl FnOnce() -> T + Send) -> T {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()
+        .expect("failed to build the thread pool")
+        .install(f)
+}
+
+/// Proves the sample sudoku with Groth16 once per thread count and prints
+/// the prove times, to compare single and multi-threaded proving.
+pub fn run_thread_scaling<E, R>(thread_counts: &[usize], rng: &mut R)
+where
+    E: PairingEngine,
+    R: RngCore + CryptoRng + Send,
+{
+    let circuit = SudokuCircuit::<E::Fr> {
+        unsolved_hash: unsolved_hash(UNSOLVED),
+        unsolved: UNSOLVED,
+        solved: SOLVED,
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
+    };
+    let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit.clone(), rng).unwrap();
+    let pvk = Groth16::<E>::process_vk(&vk).unwrap();
+
+    for &num_threads in thread_counts {
+        let (proof, prove_time) = with_threads(num_threads, || {
+            let prove_start = Instant::now();
+            let proof = Groth16::<E>::prove(&pk, circuit.clone(), rng).unwrap();
+            (proof, prove_start.elapsed())
+        });
+        println!(
+            "prove time with {} threads {}ms, {}s",
+            num_threads,
+            prove_time.as_millis(),
+            prove_time.as_secs()
+        );
+        assert!(
+            Groth16::<E>::verify_with_processed_vk(&pvk, &[circuit.unsolved_hash], &proof).unwrap()
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::with_threads;
+
+    #[test]
+    fn test_with_threads() {
+        assert_eq!(with_threads(1, rayon::current_num_threads), 1);
+        assert_eq!(with_threads(3, rayon::current_num_threads), 3);
+    }
+}
@@ -1,87 +1,20 @@
 use ark_ff::PrimeField;
-use digest::Digest;
+use zk_sudoku_core::public_input::{keccak256_input, sha256_input};
 
-pub const UNSOLVED: [[u8; 9]; 9] = [
-    [0, 0, 0, 0, 0, 6, 0, 0, 0],
-    [0, 0, 7, 2, 0, 0, 8, 0, 0],
-    [9, 0, 6, 8, 0, 0, 0, 1, 0],
-    [3, 0, 0, 7, 0, 0, 0, 2, 9],
-    [0, 0, 0, 0, 0, 0, 0, 0, 0],
-    [4, 0, 0, 5, 0, 0, 0, 7, 0],
-    [6, 5, 0, 1, 0, 0, 0, 0, 0],
-    [8, 0, 1, 0, 5, 0, 3, 0, 0],
-    [7, 9, 2, 0, 0, 0, 0, 0, 4],
-];
-
-pub const SOLVED: [[u8; 9]; 9] = [
-    [1, 8, 4, 3, 7, 6, 2, 9, 5],
-    [5, 3, 7, 2, 9, 1, 8, 4, 6],
-    [9, 2, 6, 8, 4, 5, 7, 1, 3],
-    [3, 6, 5, 7, 1, 8, 4, 2, 9],
-    [2, 7, 8, 4, 6, 9, 5, 3, 1],
-    [4, 1, 9, 5, 3, 2, 6, 7, 8],
-    [6, 5, 3, 1, 2, 4, 9, 8, 7],
-    [8, 4, 1, 9, 5, 7, 3, 6, 2],
-    [7, 9, 2, 6, 8, 3, 1, 5, 4],
-];
-
-pub const SOLVED_UNMATCH: [[u8; 9]; 9] = [
-    [1, 2, 7, 5, 8, 4, 6, 9, 3],
-    [8, 5, 6, 3, 7, 9, 1, 2, 4],
-    [3, 4, 9, 6, 2, 1, 8, 7, 5],
-    [4, 7, 1, 9, 5, 8, 2, 3, 6],
-    [2, 6, 8, 7, 1, 3, 5, 4, 9],
-    [9, 3, 5, 4, 6, 2, 7, 1, 8],
-    [5, 8, 3, 2, 9, 7, 4, 6, 1],
-    [7, 1, 4, 8, 3, 6, 9, 5, 2],
-    [6, 9, 2, 1, 4, 5, 3, 8, 7],
-];
-
-pub const SOLVED_REPEATED_IN_ROW: [[u8; 9]; 9] = [
-    [1, 8, 4, 3, 7, 6, 2, 9, 1],
-    [5, 3, 7, 2, 9, 1, 8, 4, 6],
-    [9, 2, 6, 8, 4, 5, 7, 1, 3],
-    [3, 6, 5, 7, 1, 8, 4, 2, 9],
-    [2, 7, 8, 4, 6, 9, 5, 3, 1],
-    [4, 1, 9, 5, 3, 2, 6, 7, 8],
-    [6, 5, 3, 1, 2, 4, 9, 8, 7],
-    [8, 4, 1, 9, 5, 7, 3, 6, 2],
-    [7, 9, 2, 6, 8, 3, 1, 5, 4],
-];
-
-pub const SOLVED_REPEATED_IN_COLUMN: [[u8; 9]; 9] = [
-    [1, 8, 4, 3, 7, 6, 2, 9, 5],
-    [5, 3, 7, 2, 9, 1, 8, 4, 6],
-    [9, 2, 6, 8, 4, 5, 7, 1, 3],
-    [3, 6, 5, 7, 1, 8, 4, 2, 9],
-    [2, 7, 8, 4, 6, 9, 5, 3, 1],
-    [4, 1, 9, 5, 3, 2, 6, 7, 8],
-    [6, 5, 3, 1, 2, 4, 9, 8, 7],
-    [8, 4, 1, 9, 5, 7, 3, 6, 2],
-    [4, 9, 2, 6, 8, 3, 1, 5, 7],
-];
-
-pub const SOLVED_REPEATED_IN_SQUARE: [[u8; 9]; 9] = [
-    [1, 8, 4, 3, 7, 6, 2, 9, 5],
-    [5, 3, 7, 2, 9, 1, 8, 4, 6],
-    [9, 2, 1, 8, 4, 5, 7, 6, 3],
-    [3, 6, 5, 7, 1, 8, 4, 2, 9],
-    [2, 7, 8, 4, 6, 9, 5, 3, 1],
-    [4, 1, 9, 5, 3, 2, 6, 7, 8],
-    [6, 5, 3, 1, 2, 4, 9, 8, 7],
-    [8, 4, 6, 9, 5, 7, 3, 1, 2],
-    [7, 9, 2, 6, 8, 3, 1, 5, 4],
-];
+pub use zk_sudoku_core::sample::{
+    SOLVED, SOLVED_REPEATED_IN_COLUMN, SOLVED_REPEATED_IN_ROW, SOLVED_REPEATED_IN_SQUARE,
+    SOLVED_UNMATCH, UNSOLVED,
+};
 
+/// The public input of the SHA-256 circuit, see
+/// `zk_sudoku_core::public_input::sha256_input`.
 pub fn unsolved_hash<F: PrimeField>(unsolved: [[u8; 9]; 9]) -> F {
-    let hash_result = sha2::Sha256::digest(
-        &unsolved
-            .into_iter()
-            .map(|a| a.into_iter().map(|a| a))
-            .flatten()
-            .collect::<Vec<u8>>(),
-    )
-    .to_vec();
+    F::from_le_bytes_mod_order(&sha256_input(&unsolved, None))
+}
 
-    F::from_le_bytes_mod_order(&hash_result[..31])
+/// The public input of the Keccak256 circuit, see
+/// `zk_sudoku_core::public_input::keccak256_input`. On the EVM this is
+/// `uint256(keccak256(cells)) >> 8`.
+pub fn keccak_unsolved_hash<F: PrimeField>(unsolved: [[u8; 9]; 9]) -> F {
+    F::from_le_bytes_mod_order(&keccak256_input(&unsolved, None))
 }
@@ -0,0 +1,103 @@
+use std::marker::PhantomData;
+
+use ark_ec::PairingEngine;
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, ProvingKey};
+use ark_relations::r1cs::SynthesisError;
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::{circuit::SudokuCircuit, hash::PuzzleHash};
+
+/// Groth16 over the pairing `E`, with the puzzle committed to by its hash as
+/// the only public input.
+pub struct Groth16Prover<E> {
+    /// Hash of the puzzle, sha256 unless set otherwise.
+    pub hash: PuzzleHash,
+    _marker: PhantomData<E>,
+}
+
+impl<E> Groth16Prover<E> {
+    pub fn with_hash(hash: PuzzleHash) -> Self {
+        Self {
+            hash,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<E> Default for Groth16Prover<E> {
+    fn default() -> Self {
+        Self::with_hash(PuzzleHash::Sha256)
+    }
+}
+
+impl<E: PairingEngine> SudokuProver for Groth16Prover<E> {
+    type ProvingKey = ProvingKey<E>;
+    type VerifyingKey = PreparedVerifyingKey<E>;
+    type Proof = Proof<E>;
+    type Error = SynthesisError;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        let circuit_defining_cs: SudokuCircuit<E::Fr> = SudokuCircuit {
+            unsolved_hash: Default::default(),
+            unsolved: Default::default(),
+            solved: Default::default(),
+            hash: self.hash,
+            prover_id: None,
+        };
+        let (pk, vk) = Groth16::<E>::circuit_specific_setup(circuit_defining_cs, rng)?;
+        let processed_vk = Groth16::<E>::process_vk(&vk)?;
+        Ok((pk, processed_vk))
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        let circuit = SudokuCircuit::<E::Fr>::with_hash(puzzle, solution, self.hash);
+        Groth16::<E>::prove(pk, circuit, rng)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        Groth16::<E>::verify_with_processed_vk(vk, &[self.hash.hash(*puzzle.cells())], proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Bls12_381;
+    use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+    use super::Groth16Prover;
+    use crate::parameters::{SOLVED, UNSOLVED};
+
+    #[test]
+    fn test_groth16_prover() {
+        let mut rng = ark_std::test_rng();
+        let prover = Groth16Prover::<Bls12_381>::default();
+
+        let puzzle = Puzzle::new(UNSOLVED).unwrap();
+        let solution = Solution::new(SOLVED).unwrap();
+
+        let (pk, vk) = prover.setup(&mut rng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = UNSOLVED;
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+    }
+}
@@ -0,0 +1,165 @@
+use std::io::{self, Write};
+
+use ark_ff::{BigInteger, FpParameters, PrimeField};
+use ark_relations::r1cs::{ConstraintMatrices, ConstraintSystem, SynthesisError};
+
+use crate::circuit::SudokuCircuit;
+
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINT_SECTION: u32 = 2;
+const WIRE2LABEL_SECTION: u32 = 3;
+
+// Field elements are written as little endian integers, not in Montgomery form
+fn field_bytes<F: PrimeField>(x: &F) -> Vec<u8> {
+    x.into_repr().to_bytes_le()
+}
+
+fn write_section<W: Write>(writer: &mut W, section: u32, content: &[u8]) -> io::Result<()> {
+    writer.write_all(&section.to_le_bytes())?;
+    writer.write_all(&(content.len() as u64).to_le_bytes())?;
+    writer.write_all(content)
+}
+
+fn write_lc<F: PrimeField>(content: &mut Vec<u8>, row: &[(F, usize)]) {
+    content.extend((row.len() as u32).to_le_bytes());
+    for (coeff, wire) in row {
+        content.extend((*wire as u32).to_le_bytes());
+        content.extend(field_bytes(coeff));
+    }
+}
+
+/// Writes the matrices in circom's `.r1cs` format. The wires follow the
+/// arkworks variable order: the constant `one`, the public inputs, then every
+/// witness, which are all reported as private inputs since arkworks does not
+/// tell them apart from intermediate signals. Each wire is labelled by its
+/// index.
+pub fn write_r1cs<F: PrimeField, W: Write>(
+    matrices: &ConstraintMatrices<F>,
+    mut writer: W,
+) -> io::Result<()> {
+    let prime = F::Params::MODULUS.to_bytes_le();
+    let num_wires = matrices.num_instance_variables + matrices.num_witness_variables;
+
+    let mut header = vec![];
+    header.extend((prime.len() as u32).to_le_bytes());
+    header.extend(&prime);
+    header.extend((num_wires as u32).to_le_bytes());
+    // no public outputs, the public inputs and the private inputs
+    header.extend(0u32.to_le_bytes());
+    header.extend(((matrices.num_instance_variables - 1) as u32).to_le_bytes());
+    header.extend((matrices.num_witness_variables as u32).to_le_bytes());
+    header.extend((num_wires as u64).to_le_bytes());
+    header.extend((matrices.num_constraints as u32).to_le_bytes());
+
+    let mut constraints = vec![];
+    for ((a, b), c) in matrices.a.iter().zip(&matrices.b).zip(&matrices.c) {
+        write_lc(&mut constraints, a);
+        write_lc(&mut constraints, b);
+        write_lc(&mut constraints, c);
+    }
+
+    let labels = (0..num_wires as u64)
+        .flat_map(u64::to_le_bytes)
+        .collect::<Vec<_>>();
+
+    writer.write_all(b"r1cs")?;
+    writer.write_all(&1u32.to_le_bytes())?;
+    writer.write_all(&3u32.to_le_bytes())?;
+    write_section(&mut writer, HEADER_SECTION, &header)?;
+    write_section(&mut writer, CONSTRAINT_SECTION, &constraints)?;
+    write_section(&mut writer, WIRE2LABEL_SECTION, &labels)
+}
+
+pub(crate) fn circuit_matrices<F: PrimeField>(
+    circuit: SudokuCircuit<F>,
+) -> Result<ConstraintMatrices<F>, SynthesisError> {
+    let cs = ConstraintSystem::<F>::new_ref();
+    circuit.synthesize(cs.clone())?;
+    cs.finalize();
+    cs.to_matrices().ok_or(SynthesisError::AssignmentMissing)
+}
+
+/// Synthesizes the circuit and returns the content of its `.r1cs` file.
+pub fn export_r1cs<F: PrimeField>(circuit: SudokuCircuit<F>) -> Result<Vec<u8>, SynthesisError> {
+    let matrices = circuit_matrices(circuit)?;
+    let mut r1cs = vec![];
+    write_r1cs(&matrices, &mut r1cs).expect("writing to a Vec does not fail");
+    Ok(r1cs)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_ff::{BigInteger, FpParameters, PrimeField};
+
+    use super::export_r1cs;
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+        u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_export_r1cs() {
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let r1cs = export_r1cs(circuit).unwrap();
+
+        assert_eq!(&r1cs[..4], b"r1cs");
+        assert_eq!(u32_at(&r1cs, 4), 1);
+        assert_eq!(u32_at(&r1cs, 8), 3);
+
+        // header section
+        assert_eq!(u32_at(&r1cs, 12), 1);
+        let header_size = u64_at(&r1cs, 16) as usize;
+        let header = &r1cs[24..24 + header_size];
+        assert_eq!(u32_at(header, 0), 32);
+        assert_eq!(
+            &header[4..36],
+            &<Fr as PrimeField>::Params::MODULUS.to_bytes_le()[..]
+        );
+        let num_wires = u32_at(header, 36);
+        assert_eq!(u32_at(header, 40), 0);
+        // the hash of the unsolved sudoku is the only public input
+        assert_eq!(u32_at(header, 44), 1);
+        assert_eq!(1 + 1 + u32_at(header, 48), num_wires);
+        assert_eq!(u64_at(header, 52), num_wires as u64);
+        let num_constraints = u32_at(header, 60);
+        assert!(num_constraints > 0);
+
+        // constraint section, every linear combination is well formed
+        let offset = 24 + header_size;
+        assert_eq!(u32_at(&r1cs, offset), 2);
+        let constraints_size = u64_at(&r1cs, offset + 4) as usize;
+        let constraints = &r1cs[offset + 12..offset + 12 + constraints_size];
+        let mut position = 0;
+        for _ in 0..num_constraints * 3 {
+            let num_terms = u32_at(constraints, position) as usize;
+            position += 4;
+            for _ in 0..num_terms {
+                assert!(u32_at(constraints, position) < num_wires);
+                position += 4 + 32;
+            }
+        }
+        assert_eq!(position, constraints_size);
+
+        // wire2label section
+        let offset = offset + 12 + constraints_size;
+        assert_eq!(u32_at(&r1cs, offset), 3);
+        assert_eq!(u64_at(&r1cs, offset + 4), 8 * num_wires as u64);
+        assert_eq!(r1cs.len(), offset + 12 + 8 * num_wires as usize);
+    }
+}
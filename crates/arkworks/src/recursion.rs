@@ -0,0 +1,193 @@
+use std::time::Instant;
+
+use ark_bls12_377::{constraints::PairingVar, Bls12_377, Fq, Fr};
+use ark_bw6_761::BW6_761;
+use ark_crypto_primitives_snark::snark::{BooleanInputVar, FromFieldElementsGadget, SNARKGadget};
+use ark_groth16::{constraints::Groth16VerifierGadget, Groth16, Proof, VerifyingKey};
+use ark_r1cs_std::prelude::*;
+use ark_relations::{
+    ns,
+    r1cs::{ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, SynthesisError},
+};
+use ark_serialize::CanonicalSerialize;
+use ark_snark::SNARK;
+use ark_std::rand::{CryptoRng, RngCore};
+
+use crate::{
+    circuit::SudokuCircuit,
+    hash::PuzzleHash,
+    parameters::{unsolved_hash, SOLVED, UNSOLVED},
+};
+
+type InnerSNARK = Groth16<Bls12_377>;
+type InnerSNARKGadget = Groth16VerifierGadget<Bls12_377, PairingVar>;
+
+/// Verifies a Groth16 proof of the sudoku circuit over BLS12-377 inside a
+/// circuit over the scalar field of BW6-761, which is the base field of
+/// BLS12-377, so the pairing checks are native. The inner verifying key is
+/// a constant, and the hash of the unsolved sudoku stays the public input,
+/// repacked by `outer_inputs`.
+#[derive(Clone)]
+pub struct RecursionCircuit {
+    pub vk: VerifyingKey<Bls12_377>,
+    pub unsolved_hash: Fr,
+    pub proof: Proof<Bls12_377>,
+}
+
+impl RecursionCircuit {
+    /// The circuit for the setup, the proof and the hash being placeholders.
+    pub fn setup(vk: VerifyingKey<Bls12_377>) -> Self {
+        Self {
+            vk,
+            unsolved_hash: Fr::default(),
+            proof: Proof::default(),
+        }
+    }
+}
+
+impl ConstraintSynthesizer<Fq> for RecursionCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fq>) -> Result<(), SynthesisError> {
+        let input = <InnerSNARKGadget as SNARKGadget<Fr, Fq, InnerSNARK>>::InputVar::new_input(
+            ns!(cs, "unsolved hash"),
+            || Ok(vec![self.unsolved_hash]),
+        )?;
+        let proof = <InnerSNARKGadget as SNARKGadget<Fr, Fq, InnerSNARK>>::ProofVar::new_witness(
+            ns!(cs, "proof"),
+            || Ok(self.proof),
+        )?;
+        let vk =
+            <InnerSNARKGadget as SNARKGadget<Fr, Fq, InnerSNARK>>::VerifyingKeyVar::new_constant(
+                ns!(cs, "vk"),
+                self.vk,
+            )?;
+        InnerSNARKGadget::verify(&vk, &input, &proof)?.enforce_equal(&Boolean::TRUE)
+    }
+}
+
+/// The public inputs of the outer circuit for the given hash.
+pub fn outer_inputs(unsolved_hash: Fr) -> Vec<Fq> {
+    BooleanInputVar::<Fr, Fq>::repack_input(&vec![unsolved_hash])
+}
+
+pub fn run_recursion<R: RngCore + CryptoRng>(rng: &mut R) {
+    let circuit = SudokuCircuit::<Fr> {
+        unsolved_hash: unsolved_hash(UNSOLVED),
+        unsolved: UNSOLVED,
+        solved: SOLVED,
+        hash: PuzzleHash::Sha256,
+        prover_id: None,
+    };
+
+    let (inner_pk, inner_vk) = InnerSNARK::circuit_specific_setup(circuit.clone(), rng).unwrap();
+    let inner_start = Instant::now();
+    let inner_proof = InnerSNARK::prove(&inner_pk, circuit.clone(), rng).unwrap();
+    let inner_time = inner_start.elapsed();
+    println!(
+        "inner prove time {}ms, {}s",
+        inner_time.as_millis(),
+        inner_time.as_secs()
+    );
+
+    let outer_circuit = RecursionCircuit {
+        vk: inner_vk.clone(),
+        unsolved_hash: circuit.unsolved_hash,
+        proof: inner_proof,
+    };
+    let cs = ConstraintSystem::new_ref();
+    outer_circuit
+        .clone()
+        .generate_constraints(cs.clone())
+        .unwrap();
+    assert!(cs.is_satisfied().unwrap());
+    println!("outer num constraints: {}", cs.num_constraints());
+
+    let setup_start = Instant::now();
+    let (pk, vk) =
+        Groth16::<BW6_761>::circuit_specific_setup(RecursionCircuit::setup(inner_vk), rng).unwrap();
+    let processed_vk = Groth16::<BW6_761>::process_vk(&vk).unwrap();
+    let setup_time = setup_start.elapsed();
+    println!(
+        "outer setup time {}ms, {}s",
+        setup_time.as_millis(),
+        setup_time.as_secs()
+    );
+
+    let prove_start = Instant::now();
+    let proof = Groth16::<BW6_761>::prove(&pk, outer_circuit, rng).unwrap();
+    let prove_time = prove_start.elapsed();
+    println!(
+        "outer prove time {}ms, {}s",
+        prove_time.as_millis(),
+        prove_time.as_secs()
+    );
+    println!("outer proof len: {}", proof.serialized_size());
+
+    let verify_start = Instant::now();
+    let valid_proof = Groth16::<BW6_761>::verify_with_processed_vk(
+        &processed_vk,
+        &outer_inputs(circuit.unsolved_hash),
+        &proof,
+    )
+    .unwrap();
+    let verify_time = verify_start.elapsed();
+    println!(
+        "outer verify time {}ms, {}s",
+        verify_time.as_millis(),
+        verify_time.as_secs()
+    );
+    assert!(valid_proof);
+
+    let invalid_proof = Groth16::<BW6_761>::verify_with_processed_vk(
+        &processed_vk,
+        &outer_inputs(Fr::from(1u64)),
+        &proof,
+    )
+    .unwrap();
+    assert!(!invalid_proof);
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_377::{Bls12_377, Fr};
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_snark::SNARK;
+
+    use super::RecursionCircuit;
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    #[test]
+    fn test_recursion_circuit() {
+        let mut rng = ark_std::test_rng();
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let (pk, vk) =
+            Groth16::<Bls12_377>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bls12_377>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+
+        let outer = RecursionCircuit {
+            vk,
+            unsolved_hash: circuit.unsolved_hash,
+            proof,
+        };
+        let cs = ConstraintSystem::new_ref();
+        outer.clone().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        // the inner proof doesn't hold for another puzzle
+        let mut other = outer;
+        other.unsolved_hash = Fr::from(1u64);
+        let cs = ConstraintSystem::new_ref();
+        other.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,93 @@
+use std::fmt;
+
+use ark_ff::PrimeField;
+use ark_relations::r1cs::{ConstraintSystem, SynthesisError};
+
+use crate::circuit::SudokuCircuit;
+
+/// The number of constraints of each rule of the circuit, in the order they
+/// are synthesized, to see where the cost of the circuit goes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintReport {
+    pub groups: Vec<(&'static str, usize)>,
+    pub num_constraints: usize,
+}
+
+impl ConstraintReport {
+    pub fn new<F: PrimeField>(circuit: SudokuCircuit<F>) -> Result<Self, SynthesisError> {
+        let cs = ConstraintSystem::<F>::new_ref();
+        let cells = circuit.synthesize(cs.clone())?;
+        Ok(Self {
+            groups: cells
+                .groups
+                .into_iter()
+                .map(|(rule, range)| (rule, range.len()))
+                .collect(),
+            num_constraints: cs.num_constraints(),
+        })
+    }
+}
+
+impl fmt::Display for ConstraintReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{:<16} {:>12} {:>8}", "rule", "constraints", "share")?;
+        for (rule, count) in &self.groups {
+            writeln!(
+                f,
+                "{:<16} {:>12} {:>7.1}%",
+                rule,
+                count,
+                100.0 * *count as f64 / self.num_constraints as f64
+            )?;
+        }
+        write!(f, "{:<16} {:>12}", "total", self.num_constraints)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+
+    use super::ConstraintReport;
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    #[test]
+    fn test_constraint_report() {
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let report = ConstraintReport::new(circuit).unwrap();
+        let rules = report
+            .groups
+            .iter()
+            .map(|(rule, _)| *rule)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rules,
+            [
+                "allocation",
+                "range",
+                "unsolved range",
+                "givens",
+                "rows",
+                "columns",
+                "boxes",
+                "hash"
+            ]
+        );
+        assert_eq!(
+            report.groups.iter().map(|(_, count)| count).sum::<usize>(),
+            report.num_constraints
+        );
+        // 8 bits per cell, each constrained to be boolean
+        assert_eq!(report.groups[0].1, 2 * 81 * 8);
+    }
+}
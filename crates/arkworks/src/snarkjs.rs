@@ -0,0 +1,161 @@
+use ark_bn254::{Bn254, Fq2, Fq6, Fr, G1Affine, G2Affine};
+use ark_ec::PairingEngine;
+use ark_groth16::{Proof, VerifyingKey};
+use serde_json::{json, Value};
+
+use crate::solidity::decimal;
+
+// snarkjs writes points in projective coordinates, (0, 1, 0) being the point
+// at infinity
+fn g1_json(p: &G1Affine) -> Value {
+    if p.infinity {
+        json!(["0", "1", "0"])
+    } else {
+        json!([decimal(&p.x), decimal(&p.y), "1"])
+    }
+}
+
+fn fq2_json(x: &Fq2) -> Value {
+    json!([decimal(&x.c0), decimal(&x.c1)])
+}
+
+fn g2_json(p: &G2Affine) -> Value {
+    if p.infinity {
+        json!([["0", "0"], ["1", "0"], ["0", "0"]])
+    } else {
+        json!([fq2_json(&p.x), fq2_json(&p.y), ["1", "0"]])
+    }
+}
+
+fn fq6_json(x: &Fq6) -> Value {
+    json!([fq2_json(&x.c0), fq2_json(&x.c1), fq2_json(&x.c2)])
+}
+
+/// The content of snarkjs' `proof.json`.
+pub fn proof_json(proof: &Proof<Bn254>) -> Value {
+    json!({
+        "pi_a": g1_json(&proof.a),
+        "pi_b": g2_json(&proof.b),
+        "pi_c": g1_json(&proof.c),
+        "protocol": "groth16",
+        "curve": "bn128",
+    })
+}
+
+/// The content of snarkjs' `public.json`.
+pub fn public_json(public_inputs: &[Fr]) -> Value {
+    Value::Array(
+        public_inputs
+            .iter()
+            .map(|x| Value::String(decimal(x)))
+            .collect(),
+    )
+}
+
+/// The content of snarkjs' `verification_key.json`.
+pub fn verification_key_json(vk: &VerifyingKey<Bn254>) -> Value {
+    let alphabeta = Bn254::pairing(vk.alpha_g1, vk.beta_g2);
+    json!({
+        "protocol": "groth16",
+        "curve": "bn128",
+        "nPublic": vk.gamma_abc_g1.len() - 1,
+        "vk_alpha_1": g1_json(&vk.alpha_g1),
+        "vk_beta_2": g2_json(&vk.beta_g2),
+        "vk_gamma_2": g2_json(&vk.gamma_g2),
+        "vk_delta_2": g2_json(&vk.delta_g2),
+        "vk_alphabeta_12": [fq6_json(&alphabeta.c0), fq6_json(&alphabeta.c1)],
+        "IC": vk.gamma_abc_g1.iter().map(g1_json).collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+    use ark_ff::Zero;
+    use ark_groth16::{Groth16, Proof, VerifyingKey};
+    use ark_snark::SNARK;
+    use serde_json::Value;
+
+    use super::{proof_json, public_json, verification_key_json};
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{unsolved_hash, SOLVED, UNSOLVED},
+    };
+
+    // Reads the files back the way snarkjs does
+    fn fq(x: &Value) -> Fq {
+        Fq::from_str(x.as_str().unwrap()).unwrap()
+    }
+
+    fn fq2(x: &Value) -> Fq2 {
+        Fq2::new(fq(&x[0]), fq(&x[1]))
+    }
+
+    fn g1(p: &Value) -> G1Affine {
+        if fq(&p[2]).is_zero() {
+            G1Affine::zero()
+        } else {
+            G1Affine::new(fq(&p[0]), fq(&p[1]), false)
+        }
+    }
+
+    fn g2(p: &Value) -> G2Affine {
+        if fq2(&p[2]).is_zero() {
+            G2Affine::zero()
+        } else {
+            G2Affine::new(fq2(&p[0]), fq2(&p[1]), false)
+        }
+    }
+
+    #[test]
+    fn test_snarkjs_json() {
+        let mut rng = ark_std::test_rng();
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: unsolved_hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Sha256,
+            prover_id: None,
+        };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+
+        let proof_json = proof_json(&proof);
+        assert_eq!(proof_json["pi_a"][2], "1");
+        assert_eq!(proof_json["pi_b"][2], serde_json::json!(["1", "0"]));
+
+        let public = public_json(&[circuit.unsolved_hash]);
+        assert_eq!(public.as_array().unwrap().len(), 1);
+
+        let vk_json = verification_key_json(&vk);
+        assert_eq!(vk_json["nPublic"], 1);
+        assert_eq!(vk_json["IC"].as_array().unwrap().len(), 2);
+        assert!(vk_json["vk_alphabeta_12"][1][2][1].is_string());
+
+        let parsed_proof = Proof {
+            a: g1(&proof_json["pi_a"]),
+            b: g2(&proof_json["pi_b"]),
+            c: g1(&proof_json["pi_c"]),
+        };
+        let parsed_vk = VerifyingKey {
+            alpha_g1: g1(&vk_json["vk_alpha_1"]),
+            beta_g2: g2(&vk_json["vk_beta_2"]),
+            gamma_g2: g2(&vk_json["vk_gamma_2"]),
+            delta_g2: g2(&vk_json["vk_delta_2"]),
+            gamma_abc_g1: vk_json["IC"].as_array().unwrap().iter().map(g1).collect(),
+        };
+        let inputs = public
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| Fr::from_str(x.as_str().unwrap()).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(parsed_proof, proof);
+        assert_eq!(parsed_vk, vk);
+        assert!(Groth16::<Bn254>::verify(&parsed_vk, &inputs, &parsed_proof).unwrap());
+        assert!(!Groth16::<Bn254>::verify(&parsed_vk, &[Fr::from(1u64)], &parsed_proof).unwrap());
+    }
+}
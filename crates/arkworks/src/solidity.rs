@@ -0,0 +1,175 @@
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ark_groth16::{Proof, VerifyingKey};
+use digest::Digest;
+use num_bigint::BigUint;
+
+const TEMPLATE: &str = include_str!("../templates/verifier.sol");
+
+pub(crate) fn decimal<F: PrimeField>(x: &F) -> String {
+    let n: BigUint = x.into_repr().into();
+    n.to_string()
+}
+
+// The precompiles take the point at infinity as (0, 0)
+fn g1_coordinates(p: &G1Affine) -> [Fq; 2] {
+    if p.infinity {
+        [Fq::from(0u64); 2]
+    } else {
+        [p.x, p.y]
+    }
+}
+
+// The precompiles take the imaginary part of an Fq2 element first
+fn g2_coordinates(p: &G2Affine) -> [[Fq; 2]; 2] {
+    if p.infinity {
+        [[Fq::from(0u64); 2]; 2]
+    } else {
+        [[p.x.c1, p.x.c0], [p.y.c1, p.y.c0]]
+    }
+}
+
+fn g1_constants(name: &str, p: &G1Affine) -> String {
+    let [x, y] = g1_coordinates(p);
+    format!(
+        "    uint256 constant {name}_X = {};\n    uint256 constant {name}_Y = {};\n",
+        decimal(&x),
+        decimal(&y),
+        name = name
+    )
+}
+
+fn g2_constants(name: &str, p: &G2Affine) -> String {
+    let [[x1, x0], [y1, y0]] = g2_coordinates(p);
+    [("X1", x1), ("X0", x0), ("Y1", y1), ("Y0", y0)]
+        .iter()
+        .map(|(part, x)| format!("    uint256 constant {}_{} = {};\n", name, part, decimal(x)))
+        .collect()
+}
+
+/// Renders a `SudokuVerifier` contract checking proofs against `vk`, with a
+/// `verifyProof(a, b, c, input)` entry point taking the same arguments as the
+/// snarkjs verifiers.
+pub fn verifier_contract(vk: &VerifyingKey<Bn254>) -> String {
+    let mut constants = g1_constants("ALPHA", &vk.alpha_g1);
+    constants += &g2_constants("BETA", &vk.beta_g2);
+    constants += &g2_constants("GAMMA", &vk.gamma_g2);
+    constants += &g2_constants("DELTA", &vk.delta_g2);
+    for (i, p) in vk.gamma_abc_g1.iter().enumerate() {
+        constants += &g1_constants(&format!("IC{}", i), p);
+    }
+
+    let accumulate = (1..vk.gamma_abc_g1.len())
+        .map(|i| {
+            format!(
+                "        x = ecAdd(x, ecMul([IC{}_X, IC{}_Y], input[{}]));\n",
+                i,
+                i,
+                i - 1
+            )
+        })
+        .collect::<String>();
+
+    TEMPLATE
+        .replace("{{constants}}", constants.trim_end())
+        .replace("{{accumulate}}", accumulate.trim_end())
+        .replace("{{input_length}}", &(vk.gamma_abc_g1.len() - 1).to_string())
+}
+
+/// ABI encodes a call to `verifyProof` of the contract above, selector
+/// included.
+pub fn verify_proof_calldata(proof: &Proof<Bn254>, public_inputs: &[Fr]) -> Vec<u8> {
+    let signature = format!(
+        "verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[{}])",
+        public_inputs.len()
+    );
+    let mut calldata = sha3::Keccak256::digest(signature.as_bytes())[..4].to_vec();
+
+    // Static arrays are encoded in place, one big endian word per element
+    let words = g1_coordinates(&proof.a)
+        .iter()
+        .chain(g2_coordinates(&proof.b).iter().flatten())
+        .chain(g1_coordinates(&proof.c).iter())
+        .map(|x| x.into_repr().to_bytes_be())
+        .chain(public_inputs.iter().map(|x| x.into_repr().to_bytes_be()))
+        .collect::<Vec<_>>();
+    for word in words {
+        calldata.extend(std::iter::repeat(0).take(32 - word.len()));
+        calldata.extend(word);
+    }
+    calldata
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fq, Fq2, Fr, G1Affine, G2Affine};
+    use ark_ff::PrimeField;
+    use ark_groth16::{Groth16, Proof};
+    use ark_snark::SNARK;
+    use digest::Digest;
+
+    use super::{verifier_contract, verify_proof_calldata};
+    use crate::{
+        circuit::SudokuCircuit,
+        hash::PuzzleHash,
+        parameters::{SOLVED, UNSOLVED},
+    };
+
+    // Reads the calldata back the way the contract does
+    fn decode_calldata(calldata: &[u8]) -> (Proof<Bn254>, Vec<Fr>) {
+        let words = calldata[4..].chunks(32).collect::<Vec<_>>();
+        let fq = |i: usize| Fq::from_be_bytes_mod_order(words[i]);
+        let g1 = |i: usize| G1Affine::new(fq(i), fq(i + 1), false);
+        let proof = Proof {
+            a: g1(0),
+            b: G2Affine::new(Fq2::new(fq(3), fq(2)), Fq2::new(fq(5), fq(4)), false),
+            c: g1(6),
+        };
+        let inputs = words[8..]
+            .iter()
+            .map(|word| Fr::from_be_bytes_mod_order(word))
+            .collect();
+        (proof, inputs)
+    }
+
+    #[test]
+    fn test_solidity_verifier() {
+        let mut rng = ark_std::test_rng();
+        let circuit = SudokuCircuit::<Fr> {
+            unsolved_hash: PuzzleHash::Keccak256.hash(UNSOLVED),
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            hash: PuzzleHash::Keccak256,
+            prover_id: None,
+        };
+        let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(circuit.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, circuit.clone(), &mut rng).unwrap();
+
+        let contract = verifier_contract(&vk);
+        assert!(!contract.contains("{{"));
+        assert!(contract.contains("uint256 constant IC1_Y = "));
+        assert!(!contract.contains("IC2_X"));
+        assert!(contract.contains("x = ecAdd(x, ecMul([IC1_X, IC1_Y], input[0]));"));
+        assert!(contract.contains("uint256[1] calldata input"));
+
+        // selector, then a, b, c and the hash
+        let calldata = verify_proof_calldata(&proof, &[circuit.unsolved_hash]);
+        assert_eq!(calldata.len(), 4 + 32 * (2 + 4 + 2 + 1));
+        assert_eq!(
+            calldata[..4],
+            sha3::Keccak256::digest(b"verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[1])")
+                [..4]
+        );
+
+        let (decoded, inputs) = decode_calldata(&calldata);
+        assert_eq!(decoded, proof);
+        assert_eq!(inputs, [circuit.unsolved_hash]);
+        assert!(Groth16::<Bn254>::verify(&vk, &inputs, &decoded).unwrap());
+
+        // a flipped input word no longer verifies
+        let mut calldata = calldata;
+        *calldata.last_mut().unwrap() ^= 1;
+        let (decoded, inputs) = decode_calldata(&calldata);
+        assert!(!Groth16::<Bn254>::verify(&vk, &inputs, &decoded).unwrap());
+    }
+}
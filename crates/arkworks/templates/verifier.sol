@@ -0,0 +1,90 @@
+// SPDX-License-Identifier: MIT
+//
+// Generated by zk_sudoku_arkworks::solidity from a Groth16 verifying key on bn254.
+//
+// Written against the bn254 precompiles of EIP-196 (ecAdd at 0x06, ecMul at
+// 0x07) and EIP-197 (ecPairing at 0x08). A proof (A, B, C) of the inputs x is
+// accepted when
+//
+//     e(-A, B) * e(alpha, beta) * e(IC[0] + sum x[i] IC[i + 1], gamma) * e(C, delta) == 1
+//
+// G2 points are given imaginary part first, the order ecPairing reads them in.
+pragma solidity ^0.8.4;
+
+contract SudokuVerifier {
+    // Base field of bn254
+    uint256 constant Q = 21888242871839275222246405745257275088696311157297823662689037894645226208583;
+    // Scalar field of bn254, the public inputs must be reduced
+    uint256 constant R = 21888242871839275222246405745257275088548364400416034343698204186575808495617;
+
+{{constants}}
+
+    function ecAdd(uint256[2] memory p, uint256[2] memory q) internal view returns (uint256[2] memory r) {
+        uint256[4] memory input = [p[0], p[1], q[0], q[1]];
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x06, input, 0x80, r, 0x40)
+        }
+        require(ok, "ecAdd failed");
+    }
+
+    function ecMul(uint256[2] memory p, uint256 s) internal view returns (uint256[2] memory r) {
+        uint256[3] memory input = [p[0], p[1], s];
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x07, input, 0x60, r, 0x40)
+        }
+        require(ok, "ecMul failed");
+    }
+
+    function verifyProof(
+        uint256[2] calldata a,
+        uint256[2][2] calldata b,
+        uint256[2] calldata c,
+        uint256[{{input_length}}] calldata input
+    ) external view returns (bool) {
+        for (uint256 i = 0; i < input.length; i++) {
+            require(input[i] < R, "input not in the scalar field");
+        }
+
+        uint256[2] memory x = [IC0_X, IC0_Y];
+{{accumulate}}
+
+        // Assigned one by one, an inline array of 24 words is too deep for the stack
+        uint256[24] memory pairs;
+        // -A, B
+        pairs[0] = a[0];
+        pairs[1] = (Q - (a[1] % Q)) % Q;
+        pairs[2] = b[0][0];
+        pairs[3] = b[0][1];
+        pairs[4] = b[1][0];
+        pairs[5] = b[1][1];
+        // alpha, beta
+        pairs[6] = ALPHA_X;
+        pairs[7] = ALPHA_Y;
+        pairs[8] = BETA_X1;
+        pairs[9] = BETA_X0;
+        pairs[10] = BETA_Y1;
+        pairs[11] = BETA_Y0;
+        // vk_x, gamma
+        pairs[12] = x[0];
+        pairs[13] = x[1];
+        pairs[14] = GAMMA_X1;
+        pairs[15] = GAMMA_X0;
+        pairs[16] = GAMMA_Y1;
+        pairs[17] = GAMMA_Y0;
+        // C, delta
+        pairs[18] = c[0];
+        pairs[19] = c[1];
+        pairs[20] = DELTA_X1;
+        pairs[21] = DELTA_X0;
+        pairs[22] = DELTA_Y1;
+        pairs[23] = DELTA_Y0;
+        uint256[1] memory out;
+        bool ok;
+        assembly {
+            ok := staticcall(gas(), 0x08, pairs, 0x300, out, 0x20)
+        }
+        return ok && out[0] == 1;
+    }
+}
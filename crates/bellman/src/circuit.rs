@@ -0,0 +1,212 @@
+use bellman::{Circuit, ConstraintSystem, LinearCombination, SynthesisError, Variable};
+use ff::PrimeField;
+use zk_sudoku_core::{Puzzle, Solution};
+
+/// An allocated cell, with its value kept around to compute the witnesses of
+/// the constraints using it.
+#[derive(Clone, Copy)]
+struct Cell<S: PrimeField> {
+    var: Variable,
+    value: S,
+}
+
+// Enforces (x - 1) * (x - 2) * ... * (x - 9) = 0, one constraint per factor
+fn enforce_range<S: PrimeField, CS: ConstraintSystem<S>>(
+    mut cs: CS,
+    x: Cell<S>,
+) -> Result<(), SynthesisError> {
+    let factor = |k: u64| LinearCombination::zero() + x.var - (S::from(k), CS::one());
+    let mut product = factor(1);
+    let mut product_value = x.value - S::from(1);
+    for k in 2..=9u64 {
+        if k == 9 {
+            cs.enforce(
+                || "product is zero",
+                |_| product.clone(),
+                |_| factor(k),
+                |lc| lc,
+            );
+        } else {
+            product_value *= x.value - S::from(k);
+            let next = cs.alloc(|| format!("partial product {}", k), || Ok(product_value))?;
+            cs.enforce(
+                || format!("product {}", k),
+                |_| product.clone(),
+                |_| factor(k),
+                |lc| lc + next,
+            );
+            product = LinearCombination::zero() + next;
+        }
+    }
+    Ok(())
+}
+
+// a != b iff a - b has an inverse
+fn enforce_not_equal<S: PrimeField, CS: ConstraintSystem<S>>(
+    mut cs: CS,
+    a: Cell<S>,
+    b: Cell<S>,
+) -> Result<(), SynthesisError> {
+    let inverse = cs.alloc(
+        || "inverse",
+        || Option::from((a.value - b.value).invert()).ok_or(SynthesisError::DivisionByZero),
+    )?;
+    cs.enforce(
+        || "difference is invertible",
+        |lc| lc + a.var - b.var,
+        |lc| lc + inverse,
+        |lc| lc + CS::one(),
+    );
+    Ok(())
+}
+
+// Implements a circuit that checks if a sudoku works.
+#[derive(Debug, Default, Clone)]
+pub struct SudokuCircuit {
+    pub unsolved: [[u64; 9]; 9],
+    pub solved: [[u64; 9]; 9],
+}
+
+impl SudokuCircuit {
+    pub fn new(puzzle: &Puzzle, solution: &Solution) -> Self {
+        Self {
+            unsolved: puzzle.map(u64::from),
+            solved: solution.map(u64::from),
+        }
+    }
+}
+
+impl<S: PrimeField> Circuit<S> for SudokuCircuit {
+    fn synthesize<CS: ConstraintSystem<S>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+        let mut unsolved_vars = Vec::with_capacity(9);
+        let mut solved_vars = Vec::with_capacity(9);
+        for i in 0..9 {
+            let mut unsolved_line = Vec::with_capacity(9);
+            let mut solved_line = Vec::with_capacity(9);
+            for j in 0..9 {
+                let value = S::from(self.unsolved[i][j]);
+                let var = cs.alloc_input(|| format!("unsolved {} {}", i, j), || Ok(value))?;
+                unsolved_line.push(Cell { var, value });
+
+                let value = S::from(self.solved[i][j]);
+                let var = cs.alloc(|| format!("solved {} {}", i, j), || Ok(value))?;
+                solved_line.push(Cell { var, value });
+            }
+            unsolved_vars.push(unsolved_line);
+            solved_vars.push(solved_line);
+        }
+
+        // Check if the numbers of the solved sudoku are >=1 and <=9
+        // Each number in the solved sudoku is checked to see if it is >=1 and <=9
+        for (i, line) in solved_vars.iter().enumerate() {
+            for (j, solved) in line.iter().enumerate() {
+                enforce_range(cs.namespace(|| format!("range {} {}", i, j)), *solved)?;
+            }
+        }
+
+        // Check if unsolved is the initial state of solved
+        // unsolved[i][j] * (solved[i][j] - unsolved[i][j]) = 0, so if
+        // unsolved[i][j] is not zero, solved[i][j] is equal to unsolved[i][j]
+        for (i, (unsolved_line, solved_line)) in unsolved_vars.iter().zip(&solved_vars).enumerate()
+        {
+            for (j, (unsolved, solved)) in unsolved_line.iter().zip(solved_line).enumerate() {
+                cs.enforce(
+                    || format!("given {} {}", i, j),
+                    |lc| lc + unsolved.var,
+                    |lc| lc + solved.var - unsolved.var,
+                    |lc| lc,
+                );
+            }
+        }
+
+        // Check if each row in solved has all the numbers from 1 to 9, both included
+        // For each element in solved, check that this element is not equal
+        // to previous elements in the same row
+        for (i, line) in solved_vars.iter().enumerate() {
+            for (j, solved) in line.iter().enumerate() {
+                for (k, previous) in line[..j].iter().enumerate() {
+                    enforce_not_equal(
+                        cs.namespace(|| format!("row {} {} {}", i, j, k)),
+                        *previous,
+                        *solved,
+                    )?;
+                }
+            }
+        }
+
+        // Check if each column in solved has all the numbers from 1 to 9, both included
+        // For each element in solved, check that this element is not equal
+        // to previous elements in the same column
+        for (i, line) in solved_vars.iter().enumerate() {
+            for (j, solved) in line.iter().enumerate() {
+                for (k, previous) in solved_vars[..i].iter().enumerate() {
+                    enforce_not_equal(
+                        cs.namespace(|| format!("column {} {} {}", i, j, k)),
+                        previous[j],
+                        *solved,
+                    )?;
+                }
+            }
+        }
+
+        // Check if each square in solved has all the numbers from 1 to 9, both included
+        // For each square and for each element in each square, check that the
+        // element is not equal to the elements of the previous rows of the same
+        // square, except the one in its own column (rows and columns are covered above)
+        for i in [0, 3, 6] {
+            for j in [0, 3, 6] {
+                for k in i..i + 3 {
+                    for l in j..j + 3 {
+                        for m in i..k {
+                            for n in (j..j + 3).filter(|n| *n != l) {
+                                enforce_not_equal(
+                                    cs.namespace(|| format!("box {} {} {} {}", k, l, m, n)),
+                                    solved_vars[m][n],
+                                    solved_vars[k][l],
+                                )?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bellman::{gadgets::test::TestConstraintSystem, Circuit};
+    use bls12_381::Scalar;
+    use zk_sudoku_core::sample::{widen, SOLVED, UNSOLVED};
+
+    use super::SudokuCircuit;
+
+    fn is_satisfied(circuit: SudokuCircuit) -> bool {
+        let mut cs = TestConstraintSystem::<Scalar>::new();
+        // Invalid witnesses may fail to allocate, e.g. the inverse of zero
+        circuit.synthesize(&mut cs).is_ok() && cs.is_satisfied()
+    }
+
+    #[test]
+    fn test_sudoku_circuit() {
+        let circuit = SudokuCircuit {
+            unsolved: widen(UNSOLVED),
+            solved: widen(SOLVED),
+        };
+        assert!(is_satisfied(circuit.clone()));
+
+        let mut out_of_range = circuit.clone();
+        out_of_range.solved[0][8] = 10;
+        assert!(!is_satisfied(out_of_range));
+
+        let mut unmatch = circuit.clone();
+        unmatch.unsolved[0][0] = 2;
+        assert!(!is_satisfied(unmatch));
+
+        let mut repeated = circuit;
+        repeated.solved[0].swap(0, 1);
+        assert!(!is_satisfied(repeated));
+    }
+}
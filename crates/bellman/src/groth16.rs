@@ -0,0 +1,76 @@
+use std::time::Instant;
+
+use bellman::{
+    gadgets::test::TestConstraintSystem,
+    groth16::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+    },
+    Circuit,
+};
+use bls12_381::{Bls12, Scalar};
+use rand_core::OsRng;
+use zk_sudoku_core::sample;
+
+use crate::circuit::SudokuCircuit;
+
+const UNSOLVED: [[u64; 9]; 9] = sample::widen(sample::UNSOLVED);
+const SOLVED: [[u64; 9]; 9] = sample::widen(sample::SOLVED);
+
+/// Runs Groth16 over bls12-381, printing the same figures as the arkworks
+/// `run_groth16` so both can be compared.
+pub fn run_groth16() {
+    let circuit = SudokuCircuit {
+        unsolved: UNSOLVED,
+        solved: SOLVED,
+    };
+
+    let synthesis_start = Instant::now();
+    let mut cs = TestConstraintSystem::<Scalar>::new();
+    circuit.clone().synthesize(&mut cs).unwrap();
+    let synthesis_time = synthesis_start.elapsed();
+    assert!(cs.is_satisfied());
+    println!(
+        "num constraints: {}, synthesis time {}ms",
+        cs.num_constraints(),
+        synthesis_time.as_millis()
+    );
+
+    let setup_start = Instant::now();
+    let params =
+        generate_random_parameters::<Bls12, _, _>(SudokuCircuit::default(), &mut OsRng).unwrap();
+    let pvk = prepare_verifying_key(&params.vk);
+    let setup_time = setup_start.elapsed();
+    println!(
+        "setup time {}ms, {}s",
+        setup_time.as_millis(),
+        setup_time.as_secs()
+    );
+
+    let prove_start = Instant::now();
+    let proof = create_random_proof(circuit, &params, &mut OsRng).unwrap();
+    let prove_time = prove_start.elapsed();
+    println!(
+        "prove time {}ms, {}s",
+        prove_time.as_millis(),
+        prove_time.as_secs()
+    );
+
+    let public_inputs = UNSOLVED
+        .iter()
+        .flatten()
+        .map(|x| Scalar::from(*x))
+        .collect::<Vec<_>>();
+    let verify_start = Instant::now();
+    let valid_proof = verify_proof(&pvk, &proof, &public_inputs).is_ok();
+    let verify_time = verify_start.elapsed();
+    println!(
+        "verify time {}ms, {}s",
+        verify_time.as_millis(),
+        verify_time.as_secs()
+    );
+    assert!(valid_proof);
+
+    let mut invalid_inputs = public_inputs;
+    invalid_inputs[40] = Scalar::from(6);
+    assert!(verify_proof(&pvk, &proof, &invalid_inputs).is_err());
+}
@@ -0,0 +1,12 @@
+use crate::groth16::run_groth16;
+
+pub mod circuit;
+pub mod groth16;
+pub mod prover;
+
+pub fn test_bellman() {
+    println!("-----------------------------");
+    println!("Run bellman Groth16 with bls12-381...");
+    println!("-----------------------------");
+    run_groth16();
+}
\ No newline at end of file
@@ -0,0 +1,101 @@
+use bellman::{
+    groth16::{
+        create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
+        Parameters, PreparedVerifyingKey, Proof,
+    },
+    SynthesisError, VerificationError,
+};
+use bls12_381::{Bls12, Scalar};
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::circuit::SudokuCircuit;
+
+/// bellman's Groth16 over bls12-381, with the 81 cells of the puzzle as
+/// public inputs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BellmanProver;
+
+/// bellman reports synthesis and verification failures with distinct types.
+#[derive(Debug)]
+pub enum BellmanError {
+    Synthesis(SynthesisError),
+    Verification(VerificationError),
+}
+
+impl From<SynthesisError> for BellmanError {
+    fn from(err: SynthesisError) -> Self {
+        BellmanError::Synthesis(err)
+    }
+}
+
+impl SudokuProver for BellmanProver {
+    type ProvingKey = Parameters<Bls12>;
+    type VerifyingKey = PreparedVerifyingKey<Bls12>;
+    type Proof = Proof<Bls12>;
+    type Error = BellmanError;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        let params = generate_random_parameters::<Bls12, _, _>(SudokuCircuit::default(), rng)?;
+        let pvk = prepare_verifying_key(&params.vk);
+        Ok((params, pvk))
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        Ok(create_random_proof(
+            SudokuCircuit::new(puzzle, solution),
+            pk,
+            rng,
+        )?)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        let public_inputs = puzzle
+            .iter()
+            .map(|x| Scalar::from(x as u64))
+            .collect::<Vec<_>>();
+        match verify_proof(vk, proof, &public_inputs) {
+            Ok(()) => Ok(true),
+            Err(VerificationError::InvalidProof) => Ok(false),
+            Err(err) => Err(BellmanError::Verification(err)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{sample, Puzzle, SudokuProver};
+
+    use super::BellmanProver;
+
+    #[test]
+    fn test_bellman_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let prover = BellmanProver;
+
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+    }
+}
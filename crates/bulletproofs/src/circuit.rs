@@ -0,0 +1,134 @@
+use bulletproofs::r1cs::{ConstraintSystem, LinearCombination, R1CSError, Variable};
+use curve25519_dalek_ng::scalar::Scalar;
+
+/// A cell of the solution: a constant for the givens of the puzzle, an
+/// allocated variable for the empty cells. `value` is only known to the
+/// prover.
+#[derive(Clone)]
+struct Cell {
+    lc: LinearCombination,
+    value: Option<Scalar>,
+}
+
+// Enforces (x - 1) * (x - 2) * ... * (x - 9) = 0, one multiplier per factor
+fn enforce_range<CS: ConstraintSystem>(cs: &mut CS, x: &Cell) {
+    let factor = |k: u64| x.lc.clone() - Scalar::from(k);
+    let (_, _, mut product) = cs.multiply(factor(1), factor(2));
+    for k in 3..=9 {
+        let (_, _, next) = cs.multiply(product.into(), factor(k));
+        product = next;
+    }
+    cs.constrain(product.into());
+}
+
+// a != b iff a - b has an inverse
+fn enforce_not_equal<CS: ConstraintSystem>(
+    cs: &mut CS,
+    a: &Cell,
+    b: &Cell,
+) -> Result<(), R1CSError> {
+    let assignment = match (a.value, b.value) {
+        (Some(a), Some(b)) => Some((a - b, (a - b).invert())),
+        _ => None,
+    };
+    let (difference, _inverse, out) = cs.allocate_multiplier(assignment)?;
+    cs.constrain(difference - (a.lc.clone() - b.lc.clone()));
+    cs.constrain(out - Scalar::one());
+    Ok(())
+}
+
+/// The sudoku statement over a Bulletproofs constraint system. The puzzle is
+/// known to both sides and fixes the givens as constants, only the prover
+/// passes the solution. The givens are assumed not to repeat each other, as
+/// checked by `Puzzle::new`.
+pub fn sudoku_gadget<CS: ConstraintSystem>(
+    cs: &mut CS,
+    unsolved: &[[u8; 9]; 9],
+    solved: Option<&[[u8; 9]; 9]>,
+) -> Result<(), R1CSError> {
+    // Bind the proof to the puzzle
+    let puzzle = unsolved.iter().flatten().copied().collect::<Vec<u8>>();
+    cs.transcript().append_message(b"puzzle", &puzzle);
+
+    let mut solved_vars: Vec<Vec<Cell>> = Vec::with_capacity(9);
+    for i in 0..9 {
+        let mut line = Vec::with_capacity(9);
+        for j in 0..9 {
+            let cell = if unsolved[i][j] != 0 {
+                let value = Scalar::from(unsolved[i][j] as u64);
+                Cell {
+                    lc: value.into(),
+                    value: Some(value),
+                }
+            } else {
+                let value = solved.map(|solved| Scalar::from(solved[i][j] as u64));
+                let var: Variable = cs.allocate(value)?;
+                Cell {
+                    lc: var.into(),
+                    value,
+                }
+            };
+            line.push(cell);
+        }
+        solved_vars.push(line);
+    }
+
+    // Check if the numbers of the solved sudoku are >=1 and <=9
+    // The givens are constants checked by the verifier, only the empty cells
+    // need to be constrained
+    for i in 0..9 {
+        for j in 0..9 {
+            if unsolved[i][j] == 0 {
+                enforce_range(cs, &solved_vars[i][j]);
+            }
+        }
+    }
+
+    // Check if each row in solved has all the numbers from 1 to 9, both included
+    // For each element in solved, check that this element is not equal
+    // to previous elements in the same row
+    for i in 0..9 {
+        for j in 0..9 {
+            for k in 0..j {
+                if unsolved[i][k] == 0 || unsolved[i][j] == 0 {
+                    enforce_not_equal(cs, &solved_vars[i][k], &solved_vars[i][j])?;
+                }
+            }
+        }
+    }
+
+    // Check if each column in solved has all the numbers from 1 to 9, both included
+    // For each element in solved, check that this element is not equal
+    // to previous elements in the same column
+    for i in 0..9 {
+        for j in 0..9 {
+            for k in 0..i {
+                if unsolved[k][j] == 0 || unsolved[i][j] == 0 {
+                    enforce_not_equal(cs, &solved_vars[k][j], &solved_vars[i][j])?;
+                }
+            }
+        }
+    }
+
+    // Check if each square in solved has all the numbers from 1 to 9, both included
+    // For each square and for each element in each square, check that the
+    // element is not equal to the elements of the previous rows of the same
+    // square, except the one in its own column (rows and columns are covered above)
+    for i in [0, 3, 6] {
+        for j in [0, 3, 6] {
+            for k in i..i + 3 {
+                for l in j..j + 3 {
+                    for m in i..k {
+                        for n in (j..j + 3).filter(|n| *n != l) {
+                            if unsolved[m][n] == 0 || unsolved[k][l] == 0 {
+                                enforce_not_equal(cs, &solved_vars[m][n], &solved_vars[k][l])?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
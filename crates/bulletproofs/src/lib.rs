@@ -0,0 +1,12 @@
+use crate::r1cs::run_r1cs;
+
+pub mod circuit;
+pub mod prover;
+pub mod r1cs;
+
+pub fn test_bulletproofs() {
+    println!("-----------------------------");
+    println!("Run Bulletproofs R1CS with ristretto255...");
+    println!("-----------------------------");
+    run_r1cs();
+}
\ No newline at end of file
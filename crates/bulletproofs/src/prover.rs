@@ -0,0 +1,114 @@
+use bulletproofs::{
+    r1cs::{Prover, R1CSError, R1CSProof, Verifier},
+    BulletproofGens, PedersenGens,
+};
+use merlin::Transcript;
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::circuit::sudoku_gadget;
+
+const LABEL: &[u8] = b"zk_sudoku";
+
+/// Bulletproofs R1CS over ristretto255. There is no trusted setup, the keys
+/// are the generators, which both sides derive the same way.
+#[derive(Debug, Clone, Copy)]
+pub struct BulletproofsProver {
+    /// Number of multipliers the generators can cover.
+    pub capacity: usize,
+}
+
+impl Default for BulletproofsProver {
+    fn default() -> Self {
+        Self { capacity: 1 << 12 }
+    }
+}
+
+#[derive(Clone)]
+pub struct BulletproofsKeys {
+    pub pc_gens: PedersenGens,
+    pub bp_gens: BulletproofGens,
+}
+
+impl BulletproofsKeys {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            pc_gens: PedersenGens::default(),
+            bp_gens: BulletproofGens::new(capacity, 1),
+        }
+    }
+}
+
+impl SudokuProver for BulletproofsProver {
+    type ProvingKey = BulletproofsKeys;
+    type VerifyingKey = BulletproofsKeys;
+    type Proof = R1CSProof;
+    type Error = R1CSError;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        _rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        let keys = BulletproofsKeys::new(self.capacity);
+        Ok((keys.clone(), keys))
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        _rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        let mut prover = Prover::new(&pk.pc_gens, Transcript::new(LABEL));
+        sudoku_gadget(&mut prover, puzzle.cells(), Some(solution.cells()))?;
+        prover.prove(&pk.bp_gens)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        let mut verifier = Verifier::new(Transcript::new(LABEL));
+        sudoku_gadget(&mut verifier, puzzle.cells(), None)?;
+        match verifier.verify(proof, &vk.pc_gens, &vk.bp_gens) {
+            Ok(()) => Ok(true),
+            Err(R1CSError::VerificationError) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{sample, Puzzle, Solution, SudokuProver};
+
+    use super::BulletproofsProver;
+
+    #[test]
+    fn test_bulletproofs_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let prover = BulletproofsProver::default();
+
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+
+        // A solution breaking a row is caught by the verifier, not the prover
+        let mut invalid = *solution.cells();
+        invalid[4].swap(0, 1);
+        let proof = prover
+            .prove(&pk, &puzzle, &Solution::new_unchecked(invalid), &mut OsRng)
+            .unwrap();
+        assert!(!prover.verify(&vk, &puzzle, &proof).unwrap());
+    }
+}
@@ -0,0 +1,48 @@
+use std::time::Instant;
+
+use bulletproofs::r1cs::{ConstraintSystem, Prover, Verifier};
+use merlin::Transcript;
+use zk_sudoku_core::sample::{SOLVED, UNSOLVED};
+
+use crate::{circuit::sudoku_gadget, prover::BulletproofsKeys};
+
+/// Proves and verifies the sample sudoku, printing the figures to compare
+/// with the pairing based backends: no setup, but a logarithmic proof and a
+/// linear verifier.
+pub fn run_r1cs() {
+    let setup_start = Instant::now();
+    let keys = BulletproofsKeys::new(1 << 12);
+    let setup_time = setup_start.elapsed();
+    println!(
+        "generators time {}ms, {}s",
+        setup_time.as_millis(),
+        setup_time.as_secs()
+    );
+
+    let prove_start = Instant::now();
+    let mut prover = Prover::new(&keys.pc_gens, Transcript::new(b"zk_sudoku"));
+    sudoku_gadget(&mut prover, &UNSOLVED, Some(&SOLVED)).unwrap();
+    println!("multipliers: {}", prover.metrics().multipliers);
+    let proof = prover.prove(&keys.bp_gens).unwrap();
+    let prove_time = prove_start.elapsed();
+    println!(
+        "prove time {}ms, {}s",
+        prove_time.as_millis(),
+        prove_time.as_secs()
+    );
+    println!("proof len: {}", proof.serialized_size());
+
+    let verify_start = Instant::now();
+    let mut verifier = Verifier::new(Transcript::new(b"zk_sudoku"));
+    sudoku_gadget(&mut verifier, &UNSOLVED, None).unwrap();
+    let valid_proof = verifier
+        .verify(&proof, &keys.pc_gens, &keys.bp_gens)
+        .is_ok();
+    let verify_time = verify_start.elapsed();
+    println!(
+        "verify time {}ms, {}s",
+        verify_time.as_millis(),
+        verify_time.as_secs()
+    );
+    assert!(valid_proof);
+}
@@ -0,0 +1,54 @@
+// Implements a program that checks if a sudoku works.
+// Both grids are the 81 cells row by row. The unsolved grid is returned, so
+// it is in the public output of the proof and the verifier can check which
+// puzzle was solved.
+#[executable]
+fn main(unsolved: Array<u8>, solved: Array<u8>) -> Array<u8> {
+    assert(unsolved.len() == 81, 'unsolved has not 81 cells');
+    assert(solved.len() == 81, 'solved has not 81 cells');
+
+    // Check if the numbers of the solved sudoku are >=1 and <=9
+    // Check if unsolved is the initial state of solved
+    // If unsolved[i] is not zero, it means that solved[i] is equal to unsolved[i]
+    for i in 0..81_usize {
+        let cell = *solved.at(i);
+        assert(cell >= 1 && cell <= 9, 'solved cell out of range');
+        let given = *unsolved.at(i);
+        assert(given == 0 || given == cell, 'given cell changed');
+    }
+
+    // Check if each row, column and square in solved has all the numbers
+    // from 1 to 9, both included: its 9 cells are in range, so no number may
+    // be seen twice
+    for group in 0..27_usize {
+        let mut seen: u16 = 0;
+        for k in 0..9_usize {
+            let bit = pow2(*solved.at(cell_index(group, k)));
+            assert(seen & bit == 0, 'repeated number');
+            seen = seen | bit;
+        }
+    }
+
+    unsolved
+}
+
+// The index of cell `k` of a group: the rows, then the columns, then the
+// squares
+fn cell_index(group: usize, k: usize) -> usize {
+    if group < 9 {
+        group * 9 + k
+    } else if group < 18 {
+        k * 9 + group - 9
+    } else {
+        let square = group - 18;
+        (square / 3 * 3 + k / 3) * 9 + square % 3 * 3 + k % 3
+    }
+}
+
+fn pow2(n: u8) -> u16 {
+    let mut result: u16 = 1;
+    for _ in 0..n {
+        result = result * 2;
+    }
+    result
+}
@@ -0,0 +1,5 @@
+pub use crate::scarb::test_cairo;
+
+pub mod project;
+pub mod prover;
+pub mod scarb;
@@ -0,0 +1,311 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::Arc,
+};
+
+use serde_json::Value;
+use tempfile::TempDir;
+
+const SCARB_TOML: &str = include_str!("../circuit/Scarb.toml");
+const LIB_CAIRO: &str = include_str!("../circuit/src/lib.cairo");
+const PACKAGE: &str = "sudoku";
+
+#[derive(Debug)]
+pub enum CairoError {
+    Io(io::Error),
+    /// scarb ran but exited with an error, other than rejecting a proof.
+    Scarb {
+        command: String,
+        stderr: String,
+    },
+    /// The proof written by `scarb prove` is not JSON.
+    Proof(serde_json::Error),
+}
+
+impl From<io::Error> for CairoError {
+    fn from(err: io::Error) -> Self {
+        CairoError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for CairoError {
+    fn from(err: serde_json::Error) -> Self {
+        CairoError::Proof(err)
+    }
+}
+
+// The arguments of `main` the way `scarb execute` reads them, one felt per
+// entry: the length of each array, then its cells row by row
+fn arguments(unsolved: &[[u8; 9]; 9], solved: &[[u8; 9]; 9]) -> String {
+    let felts = [unsolved, solved]
+        .iter()
+        .flat_map(|cells| std::iter::once(81).chain(cells.iter().flatten().map(|&x| x as u64)))
+        .map(|x| format!("\"{:#x}\"", x))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("[{}]", felts)
+}
+
+// The output segment of the proof, from its public memory: the address and
+// the 8 little endian u32 limbs of each felt. Felts of more than 64 bits,
+// which the program never outputs, are skipped.
+fn public_output(proof: &Value) -> Vec<u64> {
+    let output = &proof["claim"]["public_data"]["public_memory"]["output"];
+    output
+        .as_array()
+        .map(|entries| {
+            entries
+                .iter()
+                .filter_map(|entry| {
+                    let limbs = entry[1]
+                        .as_array()?
+                        .iter()
+                        .map(Value::as_u64)
+                        .collect::<Option<Vec<_>>>()?;
+                    if limbs.len() != 8 || limbs[2..].iter().any(|&limb| limb != 0) {
+                        return None;
+                    }
+                    Some(limbs[0] | limbs[1] << 32)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// `main` returns the unsolved grid, which the runner outputs after the panic
+// flag as the length of the array and its cells
+fn outputs_puzzle(proof: &Value, unsolved: &[[u8; 9]; 9]) -> bool {
+    let expected = std::iter::once(81)
+        .chain(unsolved.iter().flatten().map(|&x| x as u64))
+        .collect::<Vec<_>>();
+    public_output(proof).ends_with(&expected)
+}
+
+// The steps printed by `scarb execute --print-resource-usage`
+fn steps(stdout: &str) -> usize {
+    stdout
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("steps:"))
+        .and_then(|steps| steps.trim().parse().ok())
+        .unwrap_or_default()
+}
+
+// scarb exits with 1 both on a rejected proof and on errors, only its log
+// tells them apart
+fn is_rejection(output: &Output) -> bool {
+    let log = String::from_utf8_lossy(&output.stderr).to_lowercase()
+        + &String::from_utf8_lossy(&output.stdout).to_lowercase();
+    output.status.code() == Some(1)
+        && (log.contains("verification failed") || log.contains("invalid proof"))
+}
+
+/// The Cairo sudoku program written out as a scarb package. `scarb execute`
+/// runs it, and `scarb prove` and `scarb verify` prove and verify the run
+/// with the stwo prover built into scarb, found on the `PATH`, so no proving
+/// stack is linked in.
+///
+/// The `prove` and `verify` subcommands came with scarb 2.10, the package
+/// asks for the `cairo_execute` of 2.11.4. Each run of `scarb execute` is
+/// saved to a new `execution<N>` directory, the one proved being the last.
+///
+/// The package lives in a temporary directory shared by the clones, removed
+/// once the last of them is dropped.
+#[derive(Debug, Clone)]
+pub struct CairoProject {
+    dir: Arc<TempDir>,
+}
+
+impl CairoProject {
+    /// Writes the embedded package in a new temporary directory.
+    pub fn create() -> Result<Self, CairoError> {
+        let dir = tempfile::Builder::new()
+            .prefix("zk_sudoku_cairo")
+            .tempdir()?;
+        fs::create_dir_all(dir.path().join("src"))?;
+        fs::write(dir.path().join("Scarb.toml"), SCARB_TOML)?;
+        fs::write(dir.path().join("src").join("lib.cairo"), LIB_CAIRO)?;
+        Ok(Self { dir: Arc::new(dir) })
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    fn executions(&self) -> PathBuf {
+        self.dir().join("target").join("execute").join(PACKAGE)
+    }
+
+    // The id of the last run of `scarb execute`
+    fn last_execution(&self) -> Result<usize, CairoError> {
+        let mut last = None;
+        for entry in fs::read_dir(self.executions())? {
+            let name = entry?.file_name();
+            if let Some(id) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("execution"))
+                .and_then(|id| id.parse::<usize>().ok())
+            {
+                last = last.max(Some(id));
+            }
+        }
+        last.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no execution").into())
+    }
+
+    fn spawn(&self, args: &[&str]) -> Result<Output, CairoError> {
+        Ok(Command::new("scarb")
+            .args(args)
+            .current_dir(self.dir())
+            .output()?)
+    }
+
+    fn run(&self, args: &[&str]) -> Result<Output, CairoError> {
+        let output = self.spawn(args)?;
+        if output.status.success() {
+            Ok(output)
+        } else {
+            Err(scarb_error(args, &output))
+        }
+    }
+
+    /// Compiles the program to its executable.
+    pub fn compile(&self) -> Result<(), CairoError> {
+        self.run(&["build"]).map(|_| ())
+    }
+
+    /// Runs the program on the grids, returning the id of the execution and
+    /// its steps. An invalid solution fails the run.
+    pub fn execute(
+        &self,
+        unsolved: &[[u8; 9]; 9],
+        solved: &[[u8; 9]; 9],
+    ) -> Result<(usize, usize), CairoError> {
+        let path = self.dir().join("arguments.json");
+        fs::write(&path, arguments(unsolved, solved))?;
+        let output = self.run(&[
+            "execute",
+            "--arguments-file",
+            &path.to_string_lossy(),
+            "--print-resource-usage",
+        ])?;
+        let steps = steps(&String::from_utf8_lossy(&output.stdout));
+        Ok((self.last_execution()?, steps))
+    }
+
+    /// Proves an execution of `execute`, returning the JSON proof.
+    pub fn prove_execution(&self, id: usize) -> Result<Vec<u8>, CairoError> {
+        self.run(&["prove", "--execution-id", &id.to_string()])?;
+        let proof = self
+            .executions()
+            .join(format!("execution{}", id))
+            .join("proof")
+            .join("proof.json");
+        Ok(fs::read(proof)?)
+    }
+
+    pub fn prove(
+        &self,
+        unsolved: &[[u8; 9]; 9],
+        solved: &[[u8; 9]; 9],
+    ) -> Result<Vec<u8>, CairoError> {
+        let (id, _) = self.execute(unsolved, solved)?;
+        self.prove_execution(id)
+    }
+
+    /// `Ok(false)` when the proof does not output `unsolved` or scarb
+    /// rejects it, an error when scarb fails for any other reason.
+    pub fn verify(&self, unsolved: &[[u8; 9]; 9], proof: &[u8]) -> Result<bool, CairoError> {
+        if !outputs_puzzle(&serde_json::from_slice(proof)?, unsolved) {
+            return Ok(false);
+        }
+
+        let dir = self.dir().join("verify");
+        fs::create_dir_all(&dir)?;
+        let path = dir.join("proof.json");
+        fs::write(&path, proof)?;
+        let args = ["verify", "--proof-file", &path.to_string_lossy()];
+        let output = self.spawn(&args)?;
+        if output.status.success() {
+            Ok(true)
+        } else if is_rejection(&output) {
+            Ok(false)
+        } else {
+            Err(scarb_error(&args, &output))
+        }
+    }
+}
+
+fn scarb_error(args: &[&str], output: &Output) -> CairoError {
+    CairoError::Scarb {
+        command: format!("scarb {}", args.join(" ")),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        os::unix::process::ExitStatusExt,
+        process::{ExitStatus, Output},
+    };
+
+    use serde_json::json;
+
+    use super::{arguments, is_rejection, outputs_puzzle, steps};
+
+    #[test]
+    fn test_arguments() {
+        let mut cells = [[0u8; 9]; 9];
+        cells[0][1] = 6;
+        let arguments = serde_json::from_str::<Vec<String>>(&arguments(&cells, &cells)).unwrap();
+        assert_eq!(arguments.len(), 2 * 82);
+        assert_eq!(arguments[0], "0x51");
+        assert_eq!(arguments[2], "0x6");
+        assert_eq!(arguments[82], "0x51");
+    }
+
+    #[test]
+    fn test_outputs_puzzle() {
+        let mut cells = [[0u8; 9]; 9];
+        cells[0][1] = 6;
+        // the panic flag, then the array
+        let felts = [0, 81]
+            .into_iter()
+            .chain(cells.iter().flatten().map(|&x| x as u32));
+        let output = felts
+            .enumerate()
+            .map(|(i, x)| json!([100 + i, [x, 0, 0, 0, 0, 0, 0, 0]]))
+            .collect::<Vec<_>>();
+        let proof =
+            json!({ "claim": { "public_data": { "public_memory": { "output": output } } } });
+        assert!(outputs_puzzle(&proof, &cells));
+
+        let mut other = cells;
+        other[4][4] = 6;
+        assert!(!outputs_puzzle(&proof, &other));
+        assert!(!outputs_puzzle(&json!({}), &cells));
+    }
+
+    #[test]
+    fn test_steps() {
+        let stdout = "Resources:\n\tsteps: 8052\n\tmemory holes: 12\n";
+        assert_eq!(steps(stdout), 8052);
+        assert_eq!(steps(""), 0);
+    }
+
+    #[test]
+    fn test_is_rejection() {
+        let output = |code: i32, stderr: &str| Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: vec![],
+            stderr: stderr.as_bytes().to_vec(),
+        };
+        assert!(is_rejection(&output(1, "error: Verification failed")));
+        assert!(!is_rejection(&output(
+            1,
+            "error: failed to read proof file"
+        )));
+        assert!(!is_rejection(&output(134, "verification failed")));
+    }
+}
@@ -0,0 +1,54 @@
+use std::time::Instant;
+
+use zk_sudoku_core::{
+    report::{print_table, Report},
+    sample::{SOLVED, UNSOLVED},
+};
+
+use crate::project::{CairoError, CairoProject};
+
+/// Proves and verifies the sample sudoku through scarb. The compile is
+/// reported as the setup, the execution as part of the proof and its steps
+/// as the constraints. The timings include spawning scarb and the file
+/// round trips, which the native backends don't pay. A rejected proof is an
+/// error.
+pub fn run_scarb() -> Result<Report, CairoError> {
+    let project = CairoProject::create()?;
+
+    let compile_start = Instant::now();
+    project.compile()?;
+    let compile_time = compile_start.elapsed();
+
+    let prove_start = Instant::now();
+    let (execution, steps) = project.execute(&UNSOLVED, &SOLVED)?;
+    let proof = project.prove_execution(execution)?;
+    let prove_time = prove_start.elapsed();
+
+    let verify_start = Instant::now();
+    let valid_proof = project.verify(&UNSOLVED, &proof)?;
+    let verify_time = verify_start.elapsed();
+    if !valid_proof {
+        return Err(CairoError::Scarb {
+            command: "scarb verify".into(),
+            stderr: "the proof of the sample sudoku is rejected".into(),
+        });
+    }
+
+    Ok(Report {
+        backend: "cairo",
+        curve: "m31",
+        config: Some("stwo"),
+        num_constraints: steps,
+        setup_time: compile_time,
+        prove_time,
+        verify_time,
+        proof_len: proof.len(),
+        extra: vec![],
+    })
+}
+
+pub fn test_cairo() -> Vec<Report> {
+    let reports = vec![run_scarb().unwrap()];
+    print_table(&reports);
+    reports
+}
@@ -0,0 +1,107 @@
+//! The side of the CLI: a request goes to the daemon when one listens on
+//! the socket and is handled in process otherwise, so the commands work the
+//! same either way, only faster with a daemon.
+
+use std::{io, os::unix::net::UnixStream, path::Path};
+
+use crate::{
+    protocol::{read_frame, write_frame, Request, Response},
+    service::Service,
+    DaemonError,
+};
+
+/// Sends `request` to the daemon listening on `socket`, `Ok(None)` when
+/// there is none.
+pub fn send(socket: &Path, request: &Request) -> Result<Option<Response>, DaemonError> {
+    let mut stream = match UnixStream::connect(socket) {
+        Ok(stream) => stream,
+        Err(err)
+            if matches!(
+                err.kind(),
+                io::ErrorKind::NotFound | io::ErrorKind::ConnectionRefused
+            ) =>
+        {
+            return Ok(None)
+        }
+        Err(err) => return Err(err.into()),
+    };
+    write_frame(&mut stream, &request.to_bytes())?;
+    let body = read_frame(&mut stream)?;
+    Ok(Some(Response::from_bytes(&body)?))
+}
+
+/// Sends `request` to the daemon, or handles it with the keys it needs
+/// loaded from `params`, as `Service::load`. The stats are the daemon's
+/// only.
+pub fn request(
+    socket: &Path,
+    params: Option<&Path>,
+    request: Request,
+) -> Result<Response, DaemonError> {
+    if let Some(response) = send(socket, &request)? {
+        return Ok(response);
+    }
+    let hashes = match request {
+        Request::Prove { hash, .. } => vec![hash],
+        Request::Verify { .. } => vec![],
+        Request::Stats => {
+            return Err(DaemonError::Protocol(format!(
+                "no daemon listens on {}",
+                socket.display()
+            )))
+        }
+    };
+    Ok(Service::load(params, &hashes)?.handle(request))
+}
+
+/// A grid written as its 81 cells row by row, `0` or `.` for an empty cell,
+/// the whitespace between them being skipped.
+pub fn parse_grid(text: &str) -> Result<[[u8; 9]; 9], DaemonError> {
+    let cells = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| match c {
+            '.' => Ok(0),
+            '0'..='9' => Ok(c as u8 - b'0'),
+            _ => Err(DaemonError::Protocol(format!("{:?} is not a cell", c))),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if cells.len() != 81 {
+        return Err(DaemonError::Protocol(format!(
+            "expected 81 cells, got {}",
+            cells.len()
+        )));
+    }
+    let mut grid = [[0; 9]; 9];
+    for (cell, value) in grid.iter_mut().flatten().zip(cells) {
+        *cell = value;
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use zk_sudoku_core::sample;
+
+    use super::parse_grid;
+
+    #[test]
+    fn test_parse_grid() {
+        let text = sample::UNSOLVED
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&cell| char::from_digit(cell.into(), 10).unwrap())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(parse_grid(&text).unwrap(), sample::UNSOLVED);
+        assert_eq!(
+            parse_grid(&text.replace('0', ".")).unwrap(),
+            sample::UNSOLVED
+        );
+        assert!(parse_grid(&text[1..]).is_err());
+        assert!(parse_grid(&text.replace('0', "x")).is_err());
+    }
+}
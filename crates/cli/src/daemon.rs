@@ -0,0 +1,215 @@
+//! The daemon: a `Service` whose keys are loaded once, serving the requests
+//! of `protocol` on a Unix domain socket, each connection on its own thread
+//! so that a long proof does not hold back the other requests.
+
+use std::{
+    env, fs, io,
+    os::unix::net::{UnixListener, UnixStream},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    protocol::{read_frame, write_frame, Request, Response},
+    service::Service,
+    DaemonError,
+};
+
+/// The time a request is given before it is answered with an error, enough
+/// for a proof with the keccak256 hash on a single core.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How often the daemon checks for a shutdown between connections.
+const POLL: Duration = Duration::from_millis(50);
+
+/// `zk-sudoku.sock` in `XDG_RUNTIME_DIR`, or in the temporary directory
+/// where there is none.
+pub fn default_socket() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(env::temp_dir)
+        .join("zk-sudoku.sock")
+}
+
+/// Serves `service` on `socket` until `shutdown` is set, by SIGINT or
+/// SIGTERM in `zk-sudoku daemon`. New connections are then refused, the
+/// requests in flight are answered and the socket file is removed.
+///
+/// A request that takes longer than `timeout` is answered with an error.
+/// Its thread cannot be stopped though, it keeps the core until the proof
+/// is done and its result is dropped.
+pub fn run(
+    service: Service,
+    socket: &Path,
+    timeout: Duration,
+    shutdown: &AtomicBool,
+) -> Result<(), DaemonError> {
+    // the file of a daemon that did not shut down gracefully
+    if socket.exists() {
+        if UnixStream::connect(socket).is_ok() {
+            return Err(DaemonError::Protocol(format!(
+                "a daemon already listens on {}",
+                socket.display()
+            )));
+        }
+        fs::remove_file(socket)?;
+    }
+    let listener = UnixListener::bind(socket)?;
+    listener.set_nonblocking(true)?;
+
+    let service = Arc::new(service);
+    let mut connections = vec![];
+    while !shutdown.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let service = service.clone();
+                connections.push(thread::spawn(move || serve(&service, stream, timeout)));
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL),
+            // e.g. out of file descriptors, which the end of a connection fixes
+            Err(err) => {
+                eprintln!("zk-sudoku daemon: {}", err);
+                thread::sleep(POLL);
+            }
+        }
+        connections.retain(|connection| !connection.is_finished());
+    }
+
+    drop(listener);
+    fs::remove_file(socket)?;
+    for connection in connections {
+        // a panic is already printed by its thread
+        let _ = connection.join();
+    }
+    Ok(())
+}
+
+// Reads one request, answers it and closes the connection
+fn serve(service: &Arc<Service>, mut stream: UnixStream, timeout: Duration) {
+    let request = stream
+        .set_nonblocking(false)
+        .and_then(|()| stream.set_read_timeout(Some(timeout)))
+        .map_err(DaemonError::from)
+        .and_then(|()| read_frame(&mut stream))
+        .and_then(|body| Request::from_bytes(&body));
+    let response = match request {
+        Ok(request) => handle(service, request, timeout),
+        Err(err) => Response::Error(format!("{:?}", err)),
+    };
+    // the client may have given up, there is no one to tell then
+    let _ = write_frame(&mut stream, &response.to_bytes());
+}
+
+fn handle(service: &Arc<Service>, request: Request, timeout: Duration) -> Response {
+    let (sender, receiver) = mpsc::channel();
+    let worker = service.clone();
+    thread::spawn(move || {
+        let _ = sender.send(worker.handle(request));
+    });
+    receiver.recv_timeout(timeout).unwrap_or_else(|_| {
+        service.record_timeout();
+        Response::Error(format!("the request did not finish in {:?}", timeout))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+        time::Duration,
+    };
+
+    use zk_sudoku_core::sample;
+    use zk_sudoku_halo2_kzg::PuzzleHash;
+
+    use super::{handle, run, DEFAULT_TIMEOUT, POLL};
+    use crate::{
+        client::send,
+        protocol::{Request, Response},
+        service::Service,
+    };
+
+    fn prove_request(hash: PuzzleHash) -> Request {
+        Request::Prove {
+            hash,
+            puzzle: sample::puzzle(),
+            solution: sample::solution(),
+        }
+    }
+
+    #[test]
+    fn test_daemon() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("zk-sudoku.sock");
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let service = Service::load(None, &[PuzzleHash::Poseidon]).unwrap();
+        let daemon = {
+            let socket = socket.clone();
+            let shutdown = shutdown.clone();
+            thread::spawn(move || run(service, &socket, DEFAULT_TIMEOUT, &shutdown))
+        };
+        while !socket.exists() {
+            thread::sleep(POLL);
+        }
+
+        // two clients at once
+        let provers = (0..2)
+            .map(|_| {
+                let socket = socket.clone();
+                thread::spawn(move || send(&socket, &prove_request(PuzzleHash::Poseidon)))
+            })
+            .collect::<Vec<_>>();
+        for prover in provers {
+            let Some(Response::Manifest(manifest)) = prover.join().unwrap().unwrap() else {
+                panic!("no manifest");
+            };
+            let verify = Request::Verify {
+                manifest: manifest.clone(),
+            };
+            assert_eq!(
+                send(&socket, &verify).unwrap(),
+                Some(Response::Verified(true))
+            );
+            // without the daemon, with the key generated from the manifest
+            let local = Service::load(None, &[]).unwrap();
+            assert_eq!(local.handle(verify), Response::Verified(true));
+        }
+
+        let keccak = send(&socket, &prove_request(PuzzleHash::Keccak256)).unwrap();
+        assert!(matches!(keccak, Some(Response::Error(_))));
+
+        let Some(Response::Stats(stats)) = send(&socket, &Request::Stats).unwrap() else {
+            panic!("no stats");
+        };
+        assert_eq!(stats["keys"][0]["hash"], "poseidon");
+        assert_eq!(stats["requests"]["prove"], 3);
+        assert_eq!(stats["requests"]["verify"], 2);
+        assert_eq!(stats["requests"]["failed"], 1);
+
+        shutdown.store(true, Ordering::Relaxed);
+        daemon.join().unwrap().unwrap();
+        assert!(!socket.exists());
+        assert_eq!(send(&socket, &Request::Stats).unwrap(), None);
+    }
+
+    #[test]
+    fn test_timeout() {
+        let service = Arc::new(Service::load(None, &[PuzzleHash::Poseidon]).unwrap());
+        let response = handle(
+            &service,
+            prove_request(PuzzleHash::Poseidon),
+            Duration::from_millis(1),
+        );
+        assert!(matches!(response, Response::Error(_)));
+        assert_eq!(service.stats()["requests"]["timed_out"], 1);
+    }
+}
@@ -0,0 +1,46 @@
+//! The `zk-sudoku` CLI of the halo2 KZG backend and its daemon, which keeps
+//! the keys in memory between the commands.
+
+use std::io;
+
+use zk_sudoku_core::GridError;
+use zk_sudoku_halo2_kzg::{halo2_proofs::plonk::Error, manifest::ManifestError};
+
+pub mod client;
+pub mod daemon;
+pub mod protocol;
+pub mod service;
+
+#[derive(Debug)]
+pub enum DaemonError {
+    Io(io::Error),
+    /// A message that is not one of `protocol`, or a bad argument.
+    Protocol(String),
+    Grid(GridError),
+    Plonk(Error),
+    Manifest(ManifestError),
+}
+
+impl From<io::Error> for DaemonError {
+    fn from(err: io::Error) -> Self {
+        DaemonError::Io(err)
+    }
+}
+
+impl From<GridError> for DaemonError {
+    fn from(err: GridError) -> Self {
+        DaemonError::Grid(err)
+    }
+}
+
+impl From<Error> for DaemonError {
+    fn from(err: Error) -> Self {
+        DaemonError::Plonk(err)
+    }
+}
+
+impl From<ManifestError> for DaemonError {
+    fn from(err: ManifestError) -> Self {
+        DaemonError::Manifest(err)
+    }
+}
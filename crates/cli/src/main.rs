@@ -0,0 +1,159 @@
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::ExitCode,
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+
+use signal_hook::consts::{SIGINT, SIGTERM};
+use zk_sudoku_cli::{
+    client::{parse_grid, request},
+    daemon::{self, default_socket, DEFAULT_TIMEOUT},
+    protocol::{puzzle_hash, Request, Response},
+    service::Service,
+    DaemonError,
+};
+use zk_sudoku_core::{Puzzle, Solution};
+use zk_sudoku_halo2_kzg::PuzzleHash;
+
+const USAGE: &str = "usage:
+    zk-sudoku daemon [--socket PATH] [--params PATH] [--hash NAME]... [--timeout SECONDS]
+    zk-sudoku prove [--socket PATH] [--params PATH] [--hash NAME] PUZZLE SOLUTION MANIFEST
+    zk-sudoku verify [--socket PATH] MANIFEST
+    zk-sudoku stats [--socket PATH]
+
+The grids are files of 81 cells row by row, 0 or . for an empty cell. The
+hashes are poseidon, the default, and keccak256. The parameters are written
+by Params::write of halo2-axiom, and generated for this run only without
+--params. prove and verify go to the daemon listening on the socket, and run
+in process when there is none.";
+
+struct Args {
+    command: String,
+    socket: PathBuf,
+    params: Option<PathBuf>,
+    hashes: Vec<PuzzleHash>,
+    timeout: Duration,
+    paths: Vec<PathBuf>,
+}
+
+fn usage(message: &str) -> DaemonError {
+    DaemonError::Protocol(format!("{}\n\n{}", message, USAGE))
+}
+
+fn parse_args() -> Result<Args, DaemonError> {
+    let mut args = env::args().skip(1);
+    let command = args.next().ok_or_else(|| usage("no command"))?;
+    let mut parsed = Args {
+        command,
+        socket: default_socket(),
+        params: None,
+        hashes: vec![],
+        timeout: DEFAULT_TIMEOUT,
+        paths: vec![],
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| usage(&format!("{} takes a value", arg)))
+        };
+        match arg.as_str() {
+            "--socket" => parsed.socket = value()?.into(),
+            "--params" => parsed.params = Some(value()?.into()),
+            "--hash" => parsed.hashes.push(puzzle_hash(&value()?)?),
+            "--timeout" => {
+                let seconds = value()?
+                    .parse()
+                    .map_err(|_| usage("--timeout takes seconds"))?;
+                parsed.timeout = Duration::from_secs(seconds);
+            }
+            _ if arg.starts_with("--") => return Err(usage(&format!("unknown option {}", arg))),
+            _ => parsed.paths.push(arg.into()),
+        }
+    }
+    if parsed.hashes.is_empty() {
+        parsed.hashes.push(PuzzleHash::default());
+    }
+    Ok(parsed)
+}
+
+fn run() -> Result<ExitCode, DaemonError> {
+    let args = parse_args()?;
+    let params = args.params.as_deref();
+    let response = match (args.command.as_str(), &args.paths[..]) {
+        ("daemon", []) => {
+            let shutdown = Arc::new(AtomicBool::new(false));
+            for signal in [SIGINT, SIGTERM] {
+                signal_hook::flag::register(signal, shutdown.clone())?;
+            }
+            let service = Service::load(params, &args.hashes)?;
+            println!("zk-sudoku daemon listening on {}", args.socket.display());
+            daemon::run(service, &args.socket, args.timeout, &shutdown)?;
+            return Ok(ExitCode::SUCCESS);
+        }
+        ("prove", [puzzle, solution, manifest]) => {
+            let prove = Request::Prove {
+                hash: args.hashes[0],
+                puzzle: Puzzle::new(parse_grid(&fs::read_to_string(puzzle)?)?)?,
+                solution: Solution::new(parse_grid(&fs::read_to_string(solution)?)?)?,
+            };
+            match request(&args.socket, params, prove)? {
+                Response::Manifest(bytes) => {
+                    fs::write(manifest, &bytes)?;
+                    println!("wrote {} bytes to {}", bytes.len(), manifest.display());
+                    return Ok(ExitCode::SUCCESS);
+                }
+                response => response,
+            }
+        }
+        ("verify", [manifest]) => {
+            let verify = Request::Verify {
+                manifest: fs::read(manifest)?,
+            };
+            request(&args.socket, params, verify)?
+        }
+        ("stats", []) => request(&args.socket, params, Request::Stats)?,
+        _ => return Err(usage(&format!("bad arguments for {}", args.command))),
+    };
+
+    match response {
+        Response::Verified(true) => println!("valid"),
+        Response::Verified(false) => {
+            println!("rejected");
+            return Ok(ExitCode::FAILURE);
+        }
+        Response::Stats(stats) => println!("{:#}", stats),
+        Response::Error(err) => {
+            eprintln!("zk-sudoku: {}", err);
+            return Ok(ExitCode::FAILURE);
+        }
+        Response::Manifest(_) => {
+            eprintln!("zk-sudoku: a manifest in answer to {}", args.command);
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(code) => code,
+        Err(DaemonError::Protocol(message)) => {
+            eprintln!("zk-sudoku: {}", message);
+            ExitCode::from(2)
+        }
+        Err(DaemonError::Io(err)) => {
+            eprintln!("zk-sudoku: {}", err);
+            ExitCode::from(2)
+        }
+        Err(DaemonError::Grid(err)) => {
+            eprintln!("zk-sudoku: {}", err);
+            ExitCode::from(2)
+        }
+        Err(err) => {
+            eprintln!("zk-sudoku: {:?}", err);
+            ExitCode::from(2)
+        }
+    }
+}
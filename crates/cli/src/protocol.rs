@@ -0,0 +1,205 @@
+//! The messages between the CLI and the daemon. Each is a frame, the length
+//! of its body as 4 little endian bytes then the body, whose first byte is
+//! the kind of message. A connection carries one request and its response.
+
+use std::io::{self, Read, Write};
+
+use serde_json::Value;
+use zk_sudoku_core::{Puzzle, Solution};
+use zk_sudoku_halo2_kzg::PuzzleHash;
+
+use crate::DaemonError;
+
+/// The largest frame read, well above a manifest with parameters of
+/// `manifest::MAX_K`, so that a peer cannot have the other side allocate
+/// any length it sends.
+pub const MAX_FRAME: usize = 64 << 20;
+
+/// The puzzle hashes by their byte in a prove request.
+const HASHES: [PuzzleHash; 2] = [PuzzleHash::Poseidon, PuzzleHash::Keccak256];
+
+/// The puzzle hash named `name`, as in `PuzzleHash::name`.
+pub fn puzzle_hash(name: &str) -> Result<PuzzleHash, DaemonError> {
+    HASHES
+        .into_iter()
+        .find(|hash| hash.name() == name)
+        .ok_or_else(|| DaemonError::Protocol(format!("unknown puzzle hash {}", name)))
+}
+
+pub fn write_frame<W: Write>(writer: &mut W, body: &[u8]) -> io::Result<()> {
+    writer.write_all(&(body.len() as u32).to_le_bytes())?;
+    writer.write_all(body)?;
+    writer.flush()
+}
+
+pub fn read_frame<R: Read>(reader: &mut R) -> Result<Vec<u8>, DaemonError> {
+    let mut len = [0; 4];
+    reader.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_FRAME {
+        return Err(DaemonError::Protocol(format!(
+            "frame of {} bytes, the largest is {}",
+            len, MAX_FRAME
+        )));
+    }
+    let mut body = vec![0; len];
+    reader.read_exact(&mut body)?;
+    Ok(body)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Request {
+    /// Proves that `solution` solves `puzzle` with the keys of `hash`.
+    Prove {
+        hash: PuzzleHash,
+        puzzle: Puzzle,
+        solution: Solution,
+    },
+    /// Verifies the bytes of a `zk_sudoku_halo2_kzg::Manifest`.
+    Verify {
+        manifest: Vec<u8>,
+    },
+    Stats,
+}
+
+impl Request {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Request::Prove {
+                hash,
+                puzzle,
+                solution,
+            } => {
+                let hash = HASHES.iter().position(|other| other == hash).unwrap() as u8;
+                let mut bytes = vec![0, hash];
+                bytes.extend(puzzle.cells().iter().flatten());
+                bytes.extend(solution.cells().iter().flatten());
+                bytes
+            }
+            Request::Verify { manifest } => [&[1], &manifest[..]].concat(),
+            Request::Stats => vec![2],
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DaemonError> {
+        match bytes.split_first() {
+            Some((0, [hash, cells @ ..])) if cells.len() == 2 * 81 => {
+                let hash = *HASHES.get(*hash as usize).ok_or_else(|| {
+                    DaemonError::Protocol(format!("unknown puzzle hash {}", hash))
+                })?;
+                Ok(Request::Prove {
+                    hash,
+                    puzzle: Puzzle::new(grid(&cells[..81]))?,
+                    solution: Solution::new(grid(&cells[81..]))?,
+                })
+            }
+            Some((1, manifest)) => Ok(Request::Verify {
+                manifest: manifest.to_vec(),
+            }),
+            Some((2, [])) => Ok(Request::Stats),
+            _ => Err(DaemonError::Protocol("malformed request".into())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Response {
+    /// The manifest of the proof of a prove request.
+    Manifest(Vec<u8>),
+    /// Whether the proof of a verify request is valid.
+    Verified(bool),
+    Stats(Value),
+    /// The request failed, timed out or could not be read.
+    Error(String),
+}
+
+impl Response {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Response::Manifest(manifest) => [&[0], &manifest[..]].concat(),
+            Response::Verified(valid) => vec![1, *valid as u8],
+            Response::Stats(stats) => [&[2], stats.to_string().as_bytes()].concat(),
+            Response::Error(err) => [&[3], err.as_bytes()].concat(),
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DaemonError> {
+        match bytes.split_first() {
+            Some((0, manifest)) => Ok(Response::Manifest(manifest.to_vec())),
+            Some((1, [valid @ (0 | 1)])) => Ok(Response::Verified(*valid == 1)),
+            Some((2, stats)) => serde_json::from_slice(stats)
+                .map(Response::Stats)
+                .map_err(|err| DaemonError::Protocol(format!("malformed stats: {}", err))),
+            Some((3, err)) => Ok(Response::Error(String::from_utf8_lossy(err).into_owned())),
+            _ => Err(DaemonError::Protocol("malformed response".into())),
+        }
+    }
+}
+
+// 81 cells row by row
+fn grid(cells: &[u8]) -> [[u8; 9]; 9] {
+    let mut grid = [[0; 9]; 9];
+    for (cell, byte) in grid.iter_mut().flatten().zip(cells) {
+        *cell = *byte;
+    }
+    grid
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use zk_sudoku_core::sample;
+    use zk_sudoku_halo2_kzg::PuzzleHash;
+
+    use super::{read_frame, write_frame, Request, Response, MAX_FRAME};
+
+    #[test]
+    fn test_messages() {
+        let requests = [
+            Request::Prove {
+                hash: PuzzleHash::Keccak256,
+                puzzle: sample::puzzle(),
+                solution: sample::solution(),
+            },
+            Request::Verify {
+                manifest: vec![1, 2, 3],
+            },
+            Request::Stats,
+        ];
+        for request in &requests {
+            assert_eq!(&Request::from_bytes(&request.to_bytes()).unwrap(), request);
+        }
+
+        let responses = [
+            Response::Manifest(vec![1, 2, 3]),
+            Response::Verified(false),
+            Response::Stats(json!({ "requests": 1 })),
+            Response::Error("timed out".into()),
+        ];
+        for response in responses {
+            assert_eq!(
+                Response::from_bytes(&response.to_bytes()).unwrap(),
+                response
+            );
+        }
+
+        // a solution that is not one
+        let mut bytes = requests[0].to_bytes();
+        bytes[2 + 81] = 10;
+        assert!(Request::from_bytes(&bytes).is_err());
+        assert!(Request::from_bytes(&[0, 2]).is_err());
+        assert!(Request::from_bytes(&[2, 0]).is_err());
+        assert!(Response::from_bytes(&[1, 2]).is_err());
+    }
+
+    #[test]
+    fn test_frames() {
+        let mut bytes = vec![];
+        write_frame(&mut bytes, b"sudoku").unwrap();
+        assert_eq!(read_frame(&mut &bytes[..]).unwrap(), b"sudoku");
+        assert!(read_frame(&mut &bytes[..5]).is_err());
+
+        let len = (MAX_FRAME as u32 + 1).to_le_bytes();
+        assert!(read_frame(&mut &len[..]).is_err());
+    }
+}
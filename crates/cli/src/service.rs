@@ -0,0 +1,195 @@
+//! The keys and the handling of the requests, by the daemon or by the CLI
+//! itself when no daemon is running.
+
+use std::{
+    fs::File,
+    io::BufReader,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use rand::rngs::OsRng;
+use serde_json::{json, Value};
+use zk_sudoku_core::{Puzzle, Solution};
+use zk_sudoku_halo2_kzg::{
+    circuit::rows,
+    halo2_proofs::{
+        halo2curves::bn256::Bn256,
+        poly::{commitment::Params, kzg::commitment::ParamsKZG},
+        SerdeFormat,
+    },
+    keygen, prove, verify, KzgProvingKey, KzgVerifyingKey, Manifest, PuzzleHash,
+};
+
+use crate::{
+    protocol::{Request, Response},
+    DaemonError,
+};
+
+struct Keys {
+    pk: KzgProvingKey,
+    vk: KzgVerifyingKey,
+    /// The parameters as a manifest has them, to tell the manifests made
+    /// with these keys.
+    params: Vec<u8>,
+    keygen_time: Duration,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    prove: AtomicUsize,
+    verify: AtomicUsize,
+    stats: AtomicUsize,
+    failed: AtomicUsize,
+    timed_out: AtomicUsize,
+}
+
+pub struct Service {
+    keys: Vec<Keys>,
+    started: Instant,
+    counters: Counters,
+}
+
+fn params_bytes(params: &ParamsKZG<Bn256>) -> Vec<u8> {
+    let mut bytes = vec![];
+    params
+        .write_custom(&mut bytes, SerdeFormat::Processed)
+        .expect("writing to a vec does not fail");
+    bytes
+}
+
+impl Service {
+    /// Reads the parameters written by `Params::write` at `params` and
+    /// generates the keys of the circuit with each of `hashes`. Without a
+    /// file the parameters are generated for the largest `k` of `hashes`,
+    /// which is for local runs only, as `zk_sudoku_halo2_kzg::setup`.
+    pub fn load(params: Option<&Path>, hashes: &[PuzzleHash]) -> Result<Self, DaemonError> {
+        let mut keys = vec![];
+        if let Some(k) = hashes.iter().map(|hash| hash.k()).max() {
+            let params = match params {
+                Some(path) => ParamsKZG::<Bn256>::read(&mut BufReader::new(File::open(path)?))?,
+                None => ParamsKZG::setup(k, OsRng),
+            };
+            for &hash in hashes {
+                if params.k() < hash.k() {
+                    return Err(DaemonError::Protocol(format!(
+                        "the parameters have k = {}, the circuit with {} needs {}",
+                        params.k(),
+                        hash.name(),
+                        hash.k()
+                    )));
+                }
+                let keygen_start = Instant::now();
+                let (pk, vk) = keygen(params.clone(), hash)?;
+                keys.push(Keys {
+                    pk,
+                    vk,
+                    params: params_bytes(&params),
+                    keygen_time: keygen_start.elapsed(),
+                });
+            }
+        }
+        Ok(Self {
+            keys,
+            started: Instant::now(),
+            counters: Counters::default(),
+        })
+    }
+
+    /// Handles `request`, its errors being sent back as `Response::Error`.
+    pub fn handle(&self, request: Request) -> Response {
+        let response = match request {
+            Request::Prove {
+                hash,
+                puzzle,
+                solution,
+            } => {
+                self.counters.prove.fetch_add(1, Ordering::Relaxed);
+                self.prove(hash, &puzzle, &solution).map(Response::Manifest)
+            }
+            Request::Verify { manifest } => {
+                self.counters.verify.fetch_add(1, Ordering::Relaxed);
+                self.verify(&manifest).map(Response::Verified)
+            }
+            Request::Stats => {
+                self.counters.stats.fetch_add(1, Ordering::Relaxed);
+                Ok(Response::Stats(self.stats()))
+            }
+        };
+        response.unwrap_or_else(|err| {
+            self.counters.failed.fetch_add(1, Ordering::Relaxed);
+            Response::Error(format!("{:?}", err))
+        })
+    }
+
+    /// Counts a request answered with a timeout, see `daemon`.
+    pub fn record_timeout(&self) {
+        self.counters.timed_out.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn keys(&self, hash: PuzzleHash) -> Result<&Keys, DaemonError> {
+        self.keys
+            .iter()
+            .find(|keys| keys.vk.hash == hash)
+            .ok_or_else(|| {
+                DaemonError::Protocol(format!("no keys loaded for the {} hash", hash.name()))
+            })
+    }
+
+    fn prove(
+        &self,
+        hash: PuzzleHash,
+        puzzle: &Puzzle,
+        solution: &Solution,
+    ) -> Result<Vec<u8>, DaemonError> {
+        puzzle.check(solution)?;
+        let keys = self.keys(hash)?;
+        let proof = prove(&keys.pk, puzzle, solution, &mut OsRng)?;
+        Ok(Manifest::new(&keys.vk, *puzzle, proof).to_bytes())
+    }
+
+    // With the loaded key when the manifest has the loaded parameters, or
+    // the key generated from its parameters as `Manifest::verify`
+    fn verify(&self, bytes: &[u8]) -> Result<bool, DaemonError> {
+        let manifest = Manifest::from_bytes(bytes)?;
+        let params = params_bytes(&manifest.params);
+        match self
+            .keys
+            .iter()
+            .find(|keys| keys.vk.hash == manifest.hash && keys.params == params)
+        {
+            Some(keys) => Ok(verify(&keys.vk, &manifest.puzzle, &manifest.proof)?),
+            None => Ok(manifest.verify()?),
+        }
+    }
+
+    /// The loaded keys and the requests handled so far, times in
+    /// milliseconds as in the reports.
+    pub fn stats(&self) -> Value {
+        let keys = self
+            .keys
+            .iter()
+            .map(|keys| {
+                json!({
+                    "hash": keys.vk.hash.name(),
+                    "k": keys.vk.params.k(),
+                    "rows": rows(keys.vk.hash),
+                    "keygen_ms": keys.keygen_time.as_millis() as u64,
+                })
+            })
+            .collect::<Vec<_>>();
+        let count = |counter: &AtomicUsize| counter.load(Ordering::Relaxed);
+        json!({
+            "uptime_ms": self.started.elapsed().as_millis() as u64,
+            "keys": keys,
+            "requests": {
+                "prove": count(&self.counters.prove),
+                "verify": count(&self.counters.verify),
+                "stats": count(&self.counters.stats),
+                "failed": count(&self.counters.failed),
+                "timed_out": count(&self.counters.timed_out),
+            },
+        })
+    }
+}
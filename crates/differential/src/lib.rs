@@ -0,0 +1,225 @@
+//! Differential testing of the backends. The same puzzles and candidate
+//! solutions, valid or not, go through every backend, which must accept
+//! exactly the cases a plain Rust check accepts. A backend whose statement
+//! drifts from the others, e.g. by binding fewer public inputs, disagrees
+//! on some case.
+
+pub mod strategy;
+
+use std::panic::{self, AssertUnwindSafe};
+
+use zk_sudoku_core::{
+    rand_core::{CryptoRng, RngCore},
+    sample::{self, SOLVED, SOLVED_UNMATCH, UNSOLVED},
+    Puzzle, Solution, SudokuProver,
+};
+
+/// A solution proved for `puzzle`, the proof being verified for `statement`.
+#[derive(Debug, Clone)]
+pub struct Case {
+    pub name: &'static str,
+    pub puzzle: Puzzle,
+    pub statement: Puzzle,
+    pub solution: Solution,
+}
+
+impl Case {
+    fn new(name: &'static str, solved: [[u8; 9]; 9]) -> Self {
+        let puzzle = sample::puzzle();
+        Self {
+            name,
+            puzzle,
+            statement: puzzle,
+            solution: Solution::new_unchecked(solved),
+        }
+    }
+
+    /// Whether a sound and complete backend accepts the case: the proof is
+    /// verified for its own puzzle, and the solution is valid and keeps the
+    /// givens of the puzzle.
+    pub fn oracle(&self) -> bool {
+        self.statement == self.puzzle
+            && Solution::new(*self.solution.cells()).is_ok()
+            && self.puzzle.check(&self.solution).is_ok()
+    }
+}
+
+/// The valid case, then one case per way to break it.
+pub fn cases() -> Vec<Case> {
+    let mut repeated_in_row = SOLVED;
+    repeated_in_row[0][8] = 1;
+    // swapping two cells of a row keeps the rows and the squares
+    let mut repeated_in_column = SOLVED;
+    repeated_in_column[4][0] = SOLVED[4][1];
+    repeated_in_column[4][1] = SOLVED[4][0];
+    // swapping two rows of different bands keeps the rows and the columns
+    let mut repeated_in_square = SOLVED;
+    repeated_in_square[2] = SOLVED[3];
+    repeated_in_square[3] = SOLVED[2];
+    let mut out_of_range = SOLVED;
+    out_of_range[0][0] = 10;
+    let mut empty = SOLVED;
+    empty[0][0] = 0;
+
+    // the solution also solves the other puzzle, only the statement differs
+    let mut other = UNSOLVED;
+    other[4][4] = 6;
+    let mut other_statement = Case::new("other statement", SOLVED);
+    other_statement.statement = Puzzle::new(other).unwrap();
+
+    vec![
+        Case::new("valid", SOLVED),
+        Case::new("unmatched", SOLVED_UNMATCH),
+        Case::new("repeated in a row", repeated_in_row),
+        Case::new("repeated in a column", repeated_in_column),
+        Case::new("repeated in a square", repeated_in_square),
+        Case::new("out of range", out_of_range),
+        Case::new("empty cell", empty),
+        other_statement,
+    ]
+}
+
+/// Whether `prover` proves and verifies the case. An error or a panic while
+/// proving counts as a rejection, as some backends check the witness there.
+pub fn accepts<P: SudokuProver, R: RngCore + CryptoRng>(
+    prover: &P,
+    pk: &P::ProvingKey,
+    vk: &P::VerifyingKey,
+    case: &Case,
+    rng: &mut R,
+) -> bool {
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        match prover.prove(pk, &case.puzzle, &case.solution, rng) {
+            Ok(proof) => matches!(prover.verify(vk, &case.statement, &proof), Ok(true)),
+            Err(_) => false,
+        }
+    }))
+    .unwrap_or(false)
+}
+
+/// Runs every case through `prover` with one setup, returning the names of
+/// the cases on which it disagrees with `Case::oracle`.
+pub fn disagreements<P: SudokuProver, R: RngCore + CryptoRng>(
+    prover: &P,
+    rng: &mut R,
+) -> Vec<&'static str> {
+    let (pk, vk) = prover.setup(rng).expect("the setup does not fail");
+    cases()
+        .iter()
+        .filter(|case| accepts(prover, &pk, &vk, case, rng) != case.oracle())
+        .map(|case| case.name)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Bls12_381;
+    use ark_ed_on_bls12_381::EdwardsParameters;
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{sample, Solution};
+
+    use super::{cases, disagreements};
+
+    #[test]
+    fn test_oracle() {
+        let cases = cases();
+        assert!(cases[0].oracle());
+        assert!(cases[1..].iter().all(|case| !case.oracle()));
+        // the invalid solutions are rejected for their own reason
+        for case in &cases[2..cases.len() - 1] {
+            assert!(
+                Solution::new(*case.solution.cells()).is_err(),
+                "{}",
+                case.name
+            );
+        }
+        sample::puzzle().check(&sample::solution()).unwrap();
+    }
+
+    #[test]
+    fn test_arkworks() {
+        let prover = zk_sudoku_arkworks::prover::Groth16Prover::<Bls12_381>::default();
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_bellman() {
+        let prover = zk_sudoku_bellman::prover::BellmanProver;
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_bulletproofs() {
+        let prover = zk_sudoku_bulletproofs::prover::BulletproofsProver::default();
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_dusk_plonk() {
+        let prover = zk_sudoku_dusk_plonk::DuskPlonkProver::default();
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_garage_plonk() {
+        let prover = zk_sudoku_garage_plonk::prover::GaragePlonkProver;
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_halo2() {
+        let prover = zk_sudoku_halo2::Halo2Prover::default();
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_halo2_kzg() {
+        let prover = zk_sudoku_halo2_kzg::KzgProver::default();
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_jellyfish() {
+        let prover =
+            zk_sudoku_jellyfish::JellyfishProver::<Bls12_381, EdwardsParameters>::default();
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_nova() {
+        let prover = zk_sudoku_nova::prover::NovaProver;
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_supernova() {
+        let prover = zk_sudoku_nova::supernova::SuperNovaProver;
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_plonky2() {
+        let prover: zk_sudoku_plonky2::prover::Plonky2Prover = Default::default();
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn test_plonky3() {
+        let prover = zk_sudoku_plonky3::config::poseidon2_prover(Default::default());
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    #[ignore = "needs nargo and bb on the PATH"]
+    fn test_noir() {
+        let prover = zk_sudoku_noir::prover::NoirProver;
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+
+    #[test]
+    #[ignore = "needs scarb 2.10 or later on the PATH"]
+    fn test_cairo() {
+        let prover = zk_sudoku_cairo::prover::CairoProver;
+        assert_eq!(disagreements(&prover, &mut OsRng), Vec::<&str>::new());
+    }
+}
@@ -0,0 +1,308 @@
+//! proptest strategies for random solved grids, puzzles keeping some of
+//! their cells, and perturbations of the solutions, so the backends can be
+//! checked against `Case::oracle` beyond the fixed cases.
+
+use std::cell::RefCell;
+
+use proptest::{
+    array::uniform9,
+    bool::weighted,
+    prelude::*,
+    sample::Index,
+    test_runner::{Config, TestError, TestRunner},
+};
+use zk_sudoku_core::{
+    rand_core::{CryptoRng, RngCore},
+    sample::SOLVED,
+    Puzzle, Solution, SudokuProver,
+};
+
+use crate::{accepts, Case};
+
+/// A change to a solved grid, which may or may not keep it a solution of
+/// its puzzle.
+#[derive(Debug, Clone, Copy)]
+pub enum Perturbation {
+    None,
+    /// Swaps two cells, given by their index in 0..81.
+    Swap(usize, usize),
+    /// Adds the offset, in 1..9, modulo 9 to one of the givens.
+    ChangeGiven(Index, u8),
+    /// Writes 0 or 10 into a cell.
+    Inject(usize, u8),
+}
+
+impl Perturbation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Perturbation::None => "none",
+            Perturbation::Swap(..) => "swap",
+            Perturbation::ChangeGiven(..) => "change a given",
+            Perturbation::Inject(..) => "inject",
+        }
+    }
+
+    /// Applies the change to `solved`, the givens being the nonzero cells
+    /// of `unsolved`.
+    pub fn apply(&self, unsolved: &[[u8; 9]; 9], solved: &mut [[u8; 9]; 9]) {
+        match *self {
+            Perturbation::None => {}
+            Perturbation::Swap(a, b) => {
+                let value = solved[a / 9][a % 9];
+                solved[a / 9][a % 9] = solved[b / 9][b % 9];
+                solved[b / 9][b % 9] = value;
+            }
+            Perturbation::ChangeGiven(index, offset) => {
+                let givens: Vec<usize> = (0..81).filter(|i| unsolved[i / 9][i % 9] != 0).collect();
+                if !givens.is_empty() {
+                    let i = givens[index.index(givens.len())];
+                    let cell = &mut solved[i / 9][i % 9];
+                    *cell = (*cell - 1 + offset) % 9 + 1;
+                }
+            }
+            Perturbation::Inject(i, value) => solved[i / 9][i % 9] = value,
+        }
+    }
+}
+
+fn shuffled_three() -> impl Strategy<Value = Vec<usize>> {
+    Just(vec![0, 1, 2]).prop_shuffle()
+}
+
+/// An order of the rows, or of the columns, which keeps them in their band:
+/// the bands are shuffled, then the lines within each band.
+fn line_order() -> impl Strategy<Value = [usize; 9]> {
+    (
+        shuffled_three(),
+        proptest::collection::vec(shuffled_three(), 3),
+    )
+        .prop_map(|(bands, lines)| {
+            let mut order = [0; 9];
+            for (i, band) in bands.iter().enumerate() {
+                for (j, line) in lines[i].iter().enumerate() {
+                    order[3 * i + j] = 3 * band + line;
+                }
+            }
+            order
+        })
+}
+
+/// A solved grid, obtained from `SOLVED` by relabelling the digits,
+/// reordering the lines within their bands, and possibly transposing, all of
+/// which keep a grid solved.
+pub fn solved_grid() -> impl Strategy<Value = [[u8; 9]; 9]> {
+    (
+        Just((1..=9).collect::<Vec<u8>>()).prop_shuffle(),
+        line_order(),
+        line_order(),
+        any::<bool>(),
+    )
+        .prop_map(|(digits, rows, cols, transpose)| {
+            let mut grid = [[0; 9]; 9];
+            for (i, line) in grid.iter_mut().enumerate() {
+                for (j, cell) in line.iter_mut().enumerate() {
+                    let (r, c) = if transpose { (j, i) } else { (i, j) };
+                    *cell = digits[SOLVED[rows[r]][cols[c]] as usize - 1];
+                }
+            }
+            grid
+        })
+}
+
+/// Which cells of a solved grid the puzzle keeps, about a third of them.
+pub fn givens() -> impl Strategy<Value = [[bool; 9]; 9]> {
+    uniform9(uniform9(weighted(0.35)))
+}
+
+pub fn perturbation() -> impl Strategy<Value = Perturbation> {
+    prop_oneof![
+        Just(Perturbation::None),
+        (0..81usize, 0..81usize).prop_map(|(a, b)| Perturbation::Swap(a, b)),
+        (any::<Index>(), 1..9u8).prop_map(|(i, offset)| Perturbation::ChangeGiven(i, offset)),
+        (0..81usize, prop_oneof![Just(0u8), Just(10u8)])
+            .prop_map(|(i, value)| Perturbation::Inject(i, value)),
+    ]
+}
+
+/// A puzzle of a random solved grid, and the grid after a perturbation as
+/// the candidate solution, proved and verified for that puzzle.
+pub fn case() -> impl Strategy<Value = Case> {
+    (solved_grid(), givens(), perturbation())
+        .prop_map(|(grid, givens, perturbation)| perturbed_case(&grid, &givens, perturbation))
+}
+
+/// The puzzle keeping the `givens` of the solved `grid`, and the grid after
+/// `perturbation` as the candidate solution.
+fn perturbed_case(
+    grid: &[[u8; 9]; 9],
+    givens: &[[bool; 9]; 9],
+    perturbation: Perturbation,
+) -> Case {
+    let mut unsolved = [[0; 9]; 9];
+    for i in 0..9 {
+        for j in 0..9 {
+            if givens[i][j] {
+                unsolved[i][j] = grid[i][j];
+            }
+        }
+    }
+    let mut solved = *grid;
+    perturbation.apply(&unsolved, &mut solved);
+    // the givens of a solved grid never repeat
+    let puzzle = Puzzle::new(unsolved).unwrap();
+    Case {
+        name: perturbation.name(),
+        puzzle,
+        statement: puzzle,
+        solution: Solution::new_unchecked(solved),
+    }
+}
+
+/// Runs `cases` random cases through `prover` with one setup, failing with
+/// the smallest case found on which it disagrees with `Case::oracle`, boxed
+/// as the error holds the case twice.
+pub fn check<P: SudokuProver, R: RngCore + CryptoRng>(
+    prover: &P,
+    cases: u32,
+    rng: &mut R,
+) -> Result<(), Box<TestError<Case>>> {
+    let (pk, vk) = prover.setup(rng).expect("the setup does not fail");
+    let rng = RefCell::new(rng);
+    TestRunner::new(Config::with_cases(cases))
+        .run(&case(), |case| {
+            let accepted = accepts(prover, &pk, &vk, &case, &mut **rng.borrow_mut());
+            prop_assert_eq!(accepted, case.oracle(), "{}", case.name);
+            Ok(())
+        })
+        .map_err(Box::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Bls12_381;
+    use ark_ed_on_bls12_381::EdwardsParameters;
+    use proptest::prelude::*;
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{
+        sample::{SOLVED, UNSOLVED},
+        Puzzle, Solution,
+    };
+
+    use super::{case, check, givens, perturbed_case, solved_grid, Perturbation};
+
+    /// Proving is slow, a few cases per backend is already a lot of proofs.
+    const CASES: u32 = 16;
+
+    proptest! {
+        #[test]
+        fn test_solved_grid(grid in solved_grid()) {
+            prop_assert!(Solution::new(grid).is_ok());
+            prop_assert!(Puzzle::new(grid).is_ok());
+        }
+
+        #[test]
+        fn test_oracle(case in case()) {
+            let solution = Solution::new(*case.solution.cells());
+            match case.name {
+                "none" => prop_assert!(case.oracle()),
+                "inject" => prop_assert!(!case.oracle() && solution.is_err()),
+                // changing a given breaks its row, unless there are no givens
+                "change a given" => prop_assert_eq!(
+                    case.oracle(),
+                    case.puzzle.cells().iter().flatten().all(|cell| *cell == 0)
+                ),
+                // a swap is checked against the grid before it in `test_swap`
+                "swap" => {}
+                name => panic!("unknown perturbation {}", name),
+            }
+        }
+
+        #[test]
+        fn test_swap(
+            grid in solved_grid(),
+            givens in givens(),
+            a in 0..81usize,
+            b in 0..81usize,
+        ) {
+            // two different values swapped repeat in the row, or the column,
+            // of one of the two cells
+            let case = perturbed_case(&grid, &givens, Perturbation::Swap(a, b));
+            let same = grid[a / 9][a % 9] == grid[b / 9][b % 9];
+            prop_assert_eq!(case.oracle(), same);
+            prop_assert_eq!(Solution::new(*case.solution.cells()).is_ok(), same);
+        }
+    }
+
+    #[test]
+    fn test_swap_same_cell() {
+        let mut grid = SOLVED;
+        Perturbation::Swap(40, 40).apply(&UNSOLVED, &mut grid);
+        assert_eq!(grid, SOLVED);
+    }
+
+    #[test]
+    fn test_arkworks() {
+        let prover = zk_sudoku_arkworks::prover::Groth16Prover::<Bls12_381>::default();
+        check(&prover, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    fn test_bellman() {
+        check(&zk_sudoku_bellman::prover::BellmanProver, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    fn test_bulletproofs() {
+        let prover = zk_sudoku_bulletproofs::prover::BulletproofsProver::default();
+        check(&prover, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    fn test_dusk_plonk() {
+        let prover = zk_sudoku_dusk_plonk::DuskPlonkProver::default();
+        check(&prover, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    fn test_garage_plonk() {
+        let prover = zk_sudoku_garage_plonk::prover::GaragePlonkProver;
+        check(&prover, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    fn test_halo2() {
+        let prover = zk_sudoku_halo2::Halo2Prover::default();
+        check(&prover, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    fn test_halo2_kzg() {
+        let prover = zk_sudoku_halo2_kzg::KzgProver::default();
+        check(&prover, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    fn test_jellyfish() {
+        let prover =
+            zk_sudoku_jellyfish::JellyfishProver::<Bls12_381, EdwardsParameters>::default();
+        check(&prover, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    fn test_plonky2() {
+        let prover: zk_sudoku_plonky2::prover::Plonky2Prover = Default::default();
+        check(&prover, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs nargo and bb on the PATH"]
+    fn test_noir() {
+        check(&zk_sudoku_noir::prover::NoirProver, CASES, &mut OsRng).unwrap();
+    }
+
+    #[test]
+    #[ignore = "needs scarb 2.10 or later on the PATH"]
+    fn test_cairo() {
+        check(&zk_sudoku_cairo::prover::CairoProver, CASES, &mut OsRng).unwrap();
+    }
+}
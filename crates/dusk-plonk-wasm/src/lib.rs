@@ -0,0 +1,94 @@
+//! Browser bindings of the dusk-plonk backend. The parameters are fetched by
+//! the page as the bytes of `PublicParameters::to_var_bytes`, the circuit
+//! being compiled from them, and grids are passed as 81 cells in row major
+//! order, 0 for an empty cell.
+
+use dusk_bytes::{DeserializableSlice, Serializable};
+use dusk_plonk::prelude::{Proof, Prover, PublicParameters, Verifier};
+use rand_core::OsRng;
+use wasm_bindgen::prelude::*;
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver as _};
+use zk_sudoku_dusk_plonk::{compile, DuskPlonkProver, SudokuCircuit};
+
+fn cells(bytes: &[u8]) -> Result<[[u8; 9]; 9], JsError> {
+    if bytes.len() != 81 {
+        return Err(JsError::new(&format!(
+            "expected 81 cells, got {}",
+            bytes.len()
+        )));
+    }
+    let mut cells = [[0; 9]; 9];
+    for (cell, byte) in cells.iter_mut().flatten().zip(bytes) {
+        *cell = *byte;
+    }
+    Ok(cells)
+}
+
+fn error(err: impl std::fmt::Debug) -> JsError {
+    JsError::new(&format!("{:?}", err))
+}
+
+#[wasm_bindgen]
+pub struct SudokuProver {
+    pk: Prover<SudokuCircuit>,
+    vk: Verifier<SudokuCircuit>,
+}
+
+#[wasm_bindgen]
+impl SudokuProver {
+    /// Compiles the circuit with the parameters, which takes a few seconds.
+    #[wasm_bindgen(constructor)]
+    pub fn new(params: &[u8]) -> Result<SudokuProver, JsError> {
+        let pp = PublicParameters::from_slice(params).map_err(error)?;
+        let (pk, vk) = compile(&pp).map_err(error)?;
+        Ok(Self { pk, vk })
+    }
+
+    /// Proves that `solution` solves `puzzle`, returning the proof bytes.
+    pub fn prove(&self, puzzle: &[u8], solution: &[u8]) -> Result<Vec<u8>, JsError> {
+        let puzzle = Puzzle::new(cells(puzzle)?)?;
+        let solution = Solution::new(cells(solution)?)?;
+        puzzle.check(&solution)?;
+        let proof = DuskPlonkProver::default()
+            .prove(&self.pk, &puzzle, &solution, &mut OsRng)
+            .map_err(error)?;
+        Ok(proof.to_bytes().to_vec())
+    }
+
+    /// Verifies a proof of `prove` for `puzzle`.
+    pub fn verify(&self, proof: &[u8], puzzle: &[u8]) -> Result<bool, JsError> {
+        let puzzle = Puzzle::new(cells(puzzle)?)?;
+        let proof = Proof::from_slice(proof).map_err(error)?;
+        DuskPlonkProver::default()
+            .verify(&self.vk, &puzzle, &proof)
+            .map_err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_plonk::prelude::PublicParameters;
+    use rand_core::OsRng;
+    use zk_sudoku_core::sample;
+    use zk_sudoku_dusk_plonk::DuskPlonkProver;
+
+    use super::SudokuProver;
+
+    #[test]
+    fn test_sudoku_prover() {
+        let capacity = DuskPlonkProver::default().capacity;
+        let params = PublicParameters::setup(capacity, &mut OsRng)
+            .unwrap()
+            .to_var_bytes();
+        let prover = SudokuProver::new(&params).unwrap();
+
+        let puzzle: Vec<u8> = sample::UNSOLVED.concat();
+        let solution: Vec<u8> = sample::SOLVED.concat();
+
+        let proof = prover.prove(&puzzle, &solution).unwrap();
+        assert!(prover.verify(&proof, &puzzle).unwrap());
+        let mut other = puzzle.clone();
+        other[4 * 9 + 4] = 6;
+        assert!(!prover.verify(&proof, &other).unwrap());
+    }
+}
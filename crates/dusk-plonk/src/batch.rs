@@ -0,0 +1,101 @@
+use dusk_plonk::prelude::*;
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::{circuit::SudokuCircuit, prover::public_inputs};
+
+/// `M` puzzles checked by one circuit, the hash of the i-th puzzle being the
+/// i-th public input. The circuit has `M` times the gates of one puzzle but
+/// a single proof, whose size and verification time do not grow with `M`.
+/// The public parameters need `M` times the capacity of one puzzle.
+#[derive(Debug)]
+pub struct BatchSudokuCircuit<const M: usize> {
+    pub circuits: [SudokuCircuit; M],
+}
+
+impl<const M: usize> BatchSudokuCircuit<M> {
+    pub fn new(solved: &[(Puzzle, Solution); M]) -> Self {
+        Self {
+            circuits: std::array::from_fn(|i| SudokuCircuit::new(&solved[i].0, &solved[i].1)),
+        }
+    }
+}
+
+impl<const M: usize> Default for BatchSudokuCircuit<M> {
+    fn default() -> Self {
+        Self {
+            circuits: std::array::from_fn(|_| SudokuCircuit::default()),
+        }
+    }
+}
+
+impl<const M: usize> Circuit for BatchSudokuCircuit<M> {
+    fn circuit<C>(&self, composer: &mut C) -> Result<(), Error>
+    where
+        C: Composer,
+    {
+        for circuit in &self.circuits {
+            circuit.circuit(composer)?;
+        }
+        Ok(())
+    }
+}
+
+/// The public inputs of `BatchSudokuCircuit` for `puzzles` in this order.
+pub fn batch_inputs(puzzles: &[Puzzle]) -> Vec<BlsScalar> {
+    puzzles.iter().flat_map(public_inputs).collect()
+}
+
+/// Proves the `M` puzzles in one proof with a prover compiled from
+/// `BatchSudokuCircuit<M>`.
+pub fn prove_batch<R: RngCore + CryptoRng, const M: usize>(
+    prover: &Prover<BatchSudokuCircuit<M>>,
+    solved: &[(Puzzle, Solution); M],
+    rng: &mut R,
+) -> Result<Proof, Error> {
+    let (proof, _) = prover.prove(rng, &BatchSudokuCircuit::new(solved))?;
+    Ok(proof)
+}
+
+/// Verifies a proof of `prove_batch` for the same puzzles in the same order.
+pub fn verify_batch<const M: usize>(
+    verifier: &Verifier<BatchSudokuCircuit<M>>,
+    puzzles: &[Puzzle; M],
+    proof: &Proof,
+) -> Result<bool, Error> {
+    match verifier.verify(proof, &batch_inputs(puzzles)) {
+        Ok(()) => Ok(true),
+        Err(Error::ProofVerificationError) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_plonk::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use zk_sudoku_core::{sample, Puzzle};
+
+    use super::{prove_batch, verify_batch, BatchSudokuCircuit};
+
+    #[test]
+    fn test_prove_batch() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        // the same solution with one more given
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+
+        let rng = &mut StdRng::seed_from_u64(8349u64);
+        // twice the capacity of one puzzle
+        let pp = PublicParameters::setup(1 << 17, rng).unwrap();
+        let (prover, verifier) =
+            Compiler::compile::<BatchSudokuCircuit<2>>(&pp, b"zk_sudoku_batch").unwrap();
+
+        let proof = prove_batch(&prover, &[(puzzle, solution), (other, solution)], rng).unwrap();
+        assert!(verify_batch(&verifier, &[puzzle, other], &proof).unwrap());
+        assert!(!verify_batch(&verifier, &[other, puzzle], &proof).unwrap());
+    }
+}
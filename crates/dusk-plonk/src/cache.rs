@@ -0,0 +1,164 @@
+//! Files caching the public parameters and the compiled circuit, which take
+//! most of the time of a run. Each file starts with the SHA-256 digest of
+//! the rest, so that a truncated or altered file is detected before it is
+//! deserialized.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use dusk_plonk::prelude::*;
+use rand_core::{CryptoRng, RngCore};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    circuit::SudokuCircuit,
+    prover::{compile, LABEL},
+};
+
+/// Version of the gates of `SudokuCircuit`, to be bumped whenever they
+/// change so that the provers compiled before are not loaded.
+pub const CIRCUIT_VERSION: u32 = 2;
+
+const DIGEST_BYTES: usize = 32;
+
+#[derive(Debug)]
+pub enum CacheError {
+    Io(io::Error),
+    Plonk(Error),
+    /// The digest of the file does not match its content.
+    Corrupted(PathBuf),
+}
+
+impl From<io::Error> for CacheError {
+    fn from(err: io::Error) -> Self {
+        CacheError::Io(err)
+    }
+}
+
+impl From<Error> for CacheError {
+    fn from(err: Error) -> Self {
+        CacheError::Plonk(err)
+    }
+}
+
+/// Writes `bytes` to `path`, preceded by their digest.
+pub fn write_cached(path: impl AsRef<Path>, bytes: &[u8]) -> Result<(), CacheError> {
+    let mut file = Sha256::digest(bytes).to_vec();
+    file.extend_from_slice(bytes);
+    fs::write(path, file)?;
+    Ok(())
+}
+
+/// Reads back the bytes written by `write_cached`, checking their digest.
+pub fn read_cached(path: impl AsRef<Path>) -> Result<Vec<u8>, CacheError> {
+    let path = path.as_ref();
+    let mut file = fs::read(path)?;
+    if file.len() < DIGEST_BYTES {
+        return Err(CacheError::Corrupted(path.to_path_buf()));
+    }
+    let bytes = file.split_off(DIGEST_BYTES);
+    if Sha256::digest(&bytes).as_slice() != file.as_slice() {
+        return Err(CacheError::Corrupted(path.to_path_buf()));
+    }
+    Ok(bytes)
+}
+
+/// The files of the public parameters with `capacity` gates, and of the
+/// prover and verifier compiled from them with `label`.
+#[derive(Debug, Clone)]
+pub struct CachePaths {
+    pub pp: PathBuf,
+    pub prover: PathBuf,
+    pub verifier: PathBuf,
+}
+
+impl CachePaths {
+    pub fn new(dir: impl AsRef<Path>, label: &[u8], capacity: usize) -> Self {
+        let dir = dir.as_ref();
+        let name = format!(
+            "dusk_plonk_{}_v{}_{}",
+            String::from_utf8_lossy(label),
+            CIRCUIT_VERSION,
+            capacity
+        );
+        Self {
+            // the parameters do not depend on the circuit
+            pp: dir.join(format!("dusk_plonk_{}.pp", capacity)),
+            prover: dir.join(format!("{}.pk", name)),
+            verifier: dir.join(format!("{}.vk", name)),
+        }
+    }
+}
+
+/// Reads the prover and verifier of the sudoku circuit from `dir`, or sets
+/// up the parameters and compiles the circuit and writes them there for the
+/// next call. Cached parameters are reused when only the circuit changed.
+pub fn load_or_setup<R: RngCore + CryptoRng>(
+    dir: impl AsRef<Path>,
+    capacity: usize,
+    rng: &mut R,
+) -> Result<(Prover<SudokuCircuit>, Verifier<SudokuCircuit>), CacheError> {
+    let paths = CachePaths::new(dir, LABEL, capacity);
+    if paths.prover.exists() && paths.verifier.exists() {
+        return Ok((
+            Prover::try_from_bytes(&read_cached(&paths.prover)?)?,
+            Verifier::try_from_bytes(&read_cached(&paths.verifier)?)?,
+        ));
+    }
+
+    let pp = if paths.pp.exists() {
+        PublicParameters::from_slice(&read_cached(&paths.pp)?)?
+    } else {
+        let pp = PublicParameters::setup(capacity, rng)?;
+        write_cached(&paths.pp, &pp.to_var_bytes())?;
+        pp
+    };
+    let (prover, verifier) = compile(&pp)?;
+    write_cached(&paths.prover, &prover.to_bytes())?;
+    write_cached(&paths.verifier, &verifier.to_bytes())?;
+    Ok((prover, verifier))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use zk_sudoku_core::{sample, SudokuProver};
+
+    use super::{load_or_setup, CacheError, CachePaths};
+    use crate::prover::{DuskPlonkProver, LABEL};
+
+    #[test]
+    fn test_cache_files() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let rng = &mut StdRng::seed_from_u64(8349u64);
+        let prover = DuskPlonkProver::default();
+        let dir = env::temp_dir().join("zk_sudoku_dusk_plonk_cache");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // the first call writes the files, the second one reads them
+        load_or_setup(&dir, prover.capacity, rng).unwrap();
+        let paths = CachePaths::new(&dir, LABEL, prover.capacity);
+        assert!(paths.pp.exists() && paths.prover.exists() && paths.verifier.exists());
+        let (pk, vk) = load_or_setup(&dir, prover.capacity, rng).unwrap();
+
+        let proof = prover.prove(&pk, &puzzle, &solution, rng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        // a flipped byte is caught by the digest
+        let mut bytes = fs::read(&paths.verifier).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 1;
+        fs::write(&paths.verifier, bytes).unwrap();
+        assert!(matches!(
+            load_or_setup(&dir, prover.capacity, rng),
+            Err(CacheError::Corrupted(_))
+        ));
+    }
+}
@@ -1,35 +1,58 @@
 use dusk_plonk::prelude::*;
+use dusk_poseidon::sponge;
+use zk_sudoku_core::{Puzzle, Solution};
 
 use crate::utils::{is_eq_with_output, is_zero_with_output, range_check};
 
-// Implements a circuit that checks if a sudoku works.
+/// Implements a circuit that checks if a sudoku works. With `DIAGONAL`, it
+/// also checks the rule of X-Sudoku: the cells of each of the two main
+/// diagonals are distinct.
 #[derive(Debug, Default)]
-pub struct SudokuCircuit {
+pub struct SudokuCircuit<const DIAGONAL: bool = false> {
     pub unsolved: [[u64; 9]; 9],
     pub solved: [[u64; 9]; 9],
 }
 
-impl Circuit for SudokuCircuit {
+impl<const DIAGONAL: bool> SudokuCircuit<DIAGONAL> {
+    pub fn new(puzzle: &Puzzle, solution: &Solution) -> Self {
+        Self {
+            unsolved: puzzle.map(u64::from),
+            solved: solution.map(u64::from),
+        }
+    }
+}
+
+/// The Poseidon sponge hash of the cells in row major order, the only public
+/// input of the circuit.
+pub fn puzzle_hash(unsolved: &[[u64; 9]; 9]) -> BlsScalar {
+    let cells = unsolved
+        .iter()
+        .flatten()
+        .map(|x| BlsScalar::from(*x))
+        .collect::<Vec<_>>();
+    sponge::hash(&cells)
+}
+
+impl<const DIAGONAL: bool> Circuit for SudokuCircuit<DIAGONAL> {
     fn circuit<C>(&self, composer: &mut C) -> Result<(), Error>
     where
         C: Composer,
     {
         let one_var = composer.append_constant(BlsScalar::one());
         // new circuit
+        // The unsolved sudoku is private, only its hash is a public input
         let unsolved_vars: Vec<Vec<Witness>> = self
             .unsolved
             .iter()
             .map(|line| {
-                let u: Vec<Witness> = line
-                    .iter()
-                    .map(|x| {
-                        let t = composer.append_public(BlsScalar::from(*x));
-                        t
-                    })
-                    .collect();
-                u
+                line.iter()
+                    .map(|x| composer.append_witness(BlsScalar::from(*x)))
+                    .collect()
             })
             .collect();
+        let hash = sponge::gadget(composer, &unsolved_vars.concat());
+        let public_hash = composer.append_public(puzzle_hash(&self.unsolved));
+        composer.assert_equal(hash, public_hash);
 
         let solved_vars: Vec<Vec<Witness>> = self
             .solved
@@ -94,13 +117,14 @@ impl Circuit for SudokuCircuit {
 
         // Check if each square in solved has all the numbers from 1 to 9, both included
         // For each square and for each element in each square, check that the
-        // element is not equal to previous elements in the same square
+        // element is not equal to the elements of the previous rows of the same
+        // square, except the one in its own column (rows and columns are covered above)
         for i in [0, 3, 6] {
             for j in [0, 3, 6] {
                 for k in i..i + 3 {
                     for l in j..j + 3 {
-                        for m in i..=k {
-                            for n in j..l {
+                        for m in i..k {
+                            for n in (j..j + 3).filter(|n| *n != l) {
                                 let is_equal = is_eq_with_output(
                                     composer,
                                     solved_vars[m][n],
@@ -114,6 +138,21 @@ impl Circuit for SudokuCircuit {
             }
         }
 
+        // Check that each diagonal in solved has all the numbers from 1 to 9,
+        // comparing each element with the previous ones of its diagonal
+        if DIAGONAL {
+            for i in 0..9 {
+                for k in 0..i {
+                    let is_equal =
+                        is_eq_with_output(composer, solved_vars[k][k], solved_vars[i][i]);
+                    composer.assert_equal_constant(is_equal, BlsScalar::zero(), None);
+                    let is_equal =
+                        is_eq_with_output(composer, solved_vars[k][8 - k], solved_vars[i][8 - i]);
+                    composer.assert_equal_constant(is_equal, BlsScalar::zero(), None);
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -122,6 +161,7 @@ impl Circuit for SudokuCircuit {
 mod test {
     use dusk_bytes::Serializable;
     use dusk_plonk::prelude::*;
+    use zk_sudoku_core::sample::{widen, SOLVED, UNSOLVED};
 
     use crate::circuit::SudokuCircuit;
 
@@ -159,28 +199,8 @@ mod test {
             .prove(
                 rng,
                 &SudokuCircuit {
-                    unsolved: [
-                        [0, 0, 0, 0, 0, 6, 0, 0, 0],
-                        [0, 0, 7, 2, 0, 0, 8, 0, 0],
-                        [9, 0, 6, 8, 0, 0, 0, 1, 0],
-                        [3, 0, 0, 7, 0, 0, 0, 2, 9],
-                        [0, 0, 0, 0, 0, 0, 0, 0, 0],
-                        [4, 0, 0, 5, 0, 0, 0, 7, 0],
-                        [6, 5, 0, 1, 0, 0, 0, 0, 0],
-                        [8, 0, 1, 0, 5, 0, 3, 0, 0],
-                        [7, 9, 2, 0, 0, 0, 0, 0, 4],
-                    ],
-                    solved: [
-                        [1, 8, 4, 3, 7, 6, 2, 9, 5],
-                        [5, 3, 7, 2, 9, 1, 8, 4, 6],
-                        [9, 2, 6, 8, 4, 5, 7, 1, 3],
-                        [3, 6, 5, 7, 1, 8, 4, 2, 9],
-                        [2, 7, 8, 4, 6, 9, 5, 3, 1],
-                        [4, 1, 9, 5, 3, 2, 6, 7, 8],
-                        [6, 5, 3, 1, 2, 4, 9, 8, 7],
-                        [8, 4, 1, 9, 5, 7, 3, 6, 2],
-                        [7, 9, 2, 6, 8, 3, 1, 5, 4],
-                    ],
+                    unsolved: widen(UNSOLVED),
+                    solved: widen(SOLVED),
                 },
             )
             .expect("failed to prove");
@@ -190,8 +210,61 @@ mod test {
             proof.to_bytes().len(),
             public_inputs.len()
         );
+        // the hash of the puzzle is the only public input
+        assert_eq!(public_inputs.len(), 1);
         verifier
             .verify(&proof, &public_inputs)
             .expect("failed to verify proof");
     }
+
+    #[test]
+    fn test_diagonal() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let rng = &mut StdRng::seed_from_u64(8349u64);
+        let pp = PublicParameters::setup(1 << 16, rng).expect("failed to create pp");
+        let (prover, verifier) = Compiler::compile::<SudokuCircuit<true>>(&pp, b"demo")
+            .expect("failed to compile circuit");
+
+        // both diagonals of this solution are distinct
+        let circuit = SudokuCircuit::<true> {
+            unsolved: [
+                [0, 0, 0, 2, 0, 5, 4, 0, 0],
+                [4, 0, 0, 8, 0, 7, 3, 0, 0],
+                [0, 9, 5, 0, 0, 0, 0, 0, 2],
+                [0, 0, 2, 0, 0, 0, 0, 3, 0],
+                [0, 4, 6, 0, 0, 0, 0, 0, 1],
+                [0, 0, 8, 0, 7, 4, 0, 0, 0],
+                [0, 0, 0, 7, 0, 0, 0, 0, 3],
+                [0, 1, 9, 0, 0, 8, 0, 7, 0],
+                [0, 6, 0, 9, 0, 3, 1, 0, 0],
+            ],
+            solved: [
+                [6, 8, 3, 2, 9, 5, 4, 1, 7],
+                [4, 2, 1, 8, 6, 7, 3, 5, 9],
+                [7, 9, 5, 3, 4, 1, 8, 6, 2],
+                [5, 7, 2, 1, 8, 9, 6, 3, 4],
+                [9, 4, 6, 5, 3, 2, 7, 8, 1],
+                [1, 3, 8, 6, 7, 4, 2, 9, 5],
+                [8, 5, 4, 7, 1, 6, 9, 2, 3],
+                [3, 1, 9, 4, 2, 8, 5, 7, 6],
+                [2, 6, 7, 9, 5, 3, 1, 4, 8],
+            ],
+        };
+        let (proof, public_inputs) = prover.prove(rng, &circuit).expect("failed to prove");
+        verifier
+            .verify(&proof, &public_inputs)
+            .expect("failed to verify proof");
+
+        // a valid sudoku with a 6 twice on its main diagonal
+        let circuit = SudokuCircuit::<true> {
+            unsolved: widen(UNSOLVED),
+            solved: widen(SOLVED),
+        };
+        let verified = prover
+            .prove(rng, &circuit)
+            .and_then(|(proof, public_inputs)| verifier.verify(&proof, &public_inputs));
+        assert!(verified.is_err());
+    }
 }
@@ -1,2 +1,9 @@
 pub mod circuit;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+pub mod prover;
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod batch;
+
+pub use circuit::SudokuCircuit;
+pub use prover::{compile, prove_sudoku, verify_sudoku, DuskPlonkProver};
@@ -0,0 +1,138 @@
+use dusk_plonk::prelude::*;
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::circuit::{puzzle_hash, SudokuCircuit};
+
+pub(crate) const LABEL: &[u8] = b"zk_sudoku";
+
+/// dusk-plonk over bls12-381, with the Poseidon hash of the puzzle as the
+/// only public input.
+#[derive(Debug, Clone, Copy)]
+pub struct DuskPlonkProver {
+    /// Capacity of the public parameters, in gates.
+    pub capacity: usize,
+}
+
+impl Default for DuskPlonkProver {
+    fn default() -> Self {
+        Self { capacity: 1 << 16 }
+    }
+}
+
+pub(crate) fn public_inputs(puzzle: &Puzzle) -> Vec<BlsScalar> {
+    vec![puzzle_hash(&puzzle.map(u64::from))]
+}
+
+/// Compiles the sudoku circuit with existing parameters, e.g. read back with
+/// `PublicParameters::from_slice`.
+pub fn compile(
+    pp: &PublicParameters,
+) -> Result<(Prover<SudokuCircuit>, Verifier<SudokuCircuit>), Error> {
+    Compiler::compile::<SudokuCircuit>(pp, LABEL)
+}
+
+/// Proves that `solution` solves `puzzle`, compiling the circuit from `pp`.
+/// Returns the proof and its public inputs, the hash of the puzzle.
+pub fn prove_sudoku<R: RngCore + CryptoRng>(
+    pp: &PublicParameters,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+) -> Result<(Proof, Vec<BlsScalar>), Error> {
+    let (prover, _) = compile(pp)?;
+    prover.prove(rng, &SudokuCircuit::new(puzzle, solution))
+}
+
+/// Verifies a proof of `prove_sudoku` with the same parameters, returning
+/// `Ok(false)` for a proof of another puzzle or a forged one.
+pub fn verify_sudoku(pp: &PublicParameters, puzzle: &Puzzle, proof: &Proof) -> Result<bool, Error> {
+    let (_, verifier) = compile(pp)?;
+    verify_with_key(&verifier, puzzle, proof)
+}
+
+fn verify_with_key(
+    vk: &Verifier<SudokuCircuit>,
+    puzzle: &Puzzle,
+    proof: &Proof,
+) -> Result<bool, Error> {
+    match vk.verify(proof, &public_inputs(puzzle)) {
+        Ok(()) => Ok(true),
+        Err(Error::ProofVerificationError) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+impl SudokuProver for DuskPlonkProver {
+    type ProvingKey = Prover<SudokuCircuit>;
+    type VerifyingKey = Verifier<SudokuCircuit>;
+    type Proof = Proof;
+    type Error = Error;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        let pp = PublicParameters::setup(self.capacity, rng)?;
+        compile(&pp)
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        let (proof, _) = pk.prove(rng, &SudokuCircuit::new(puzzle, solution))?;
+        Ok(proof)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        verify_with_key(vk, puzzle, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dusk_plonk::prelude::PublicParameters;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use zk_sudoku_core::{sample, Puzzle, SudokuProver};
+
+    use super::DuskPlonkProver;
+
+    #[test]
+    fn test_dusk_plonk_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let rng = &mut StdRng::seed_from_u64(8349u64);
+        let prover = DuskPlonkProver::default();
+
+        let (pk, vk) = prover.setup(rng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, rng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_library_api() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let rng = &mut StdRng::seed_from_u64(8349u64);
+        let pp = PublicParameters::setup(DuskPlonkProver::default().capacity, rng).unwrap();
+
+        let (proof, public_inputs) = crate::prove_sudoku(&pp, &puzzle, &solution, rng).unwrap();
+        assert_eq!(public_inputs, super::public_inputs(&puzzle));
+        assert!(crate::verify_sudoku(&pp, &puzzle, &proof).unwrap());
+    }
+}
@@ -1,32 +1,23 @@
 use dusk_plonk::prelude::*;
 
+/// Checks 1 <= value <= 9 with the range component of dusk-plonk: value - 1
+/// and 9 - value both fit in 4 bits only if value is in the range, as any
+/// value out of it makes one of them wrap around the modulus.
 pub fn range_check<C: Composer>(composer: &mut C, value: Witness) {
     let s = Constraint::new()
         .left(1)
-        .constant(-(<u64 as Into<BlsScalar>>::into(1)))
+        .constant(-BlsScalar::one())
         .a(value);
-    let res_1 = composer.gate_add(s);
+    let above_one = composer.gate_add(s);
 
     let s = Constraint::new()
-        .left(1)
-        .constant(-(<u64 as Into<BlsScalar>>::into(2)))
+        .left(-BlsScalar::one())
+        .constant(BlsScalar::from(9u64))
         .a(value);
-    let res_2 = composer.gate_add(s);
-
-    let s_mul = Constraint::new().mult(1).a(res_1).b(res_2);
-    let mut res_mul = composer.gate_mul(s_mul);
-
-    for r in 3..=9 {
-        let s = Constraint::new()
-            .left(1)
-            .constant(-(<u64 as Into<BlsScalar>>::into(r)))
-            .a(value);
-        let res = composer.gate_add(s);
-        let s_mul = Constraint::new().mult(1).a(res).b(res_mul);
-        res_mul = composer.gate_mul(s_mul);
-    }
+    let below_nine = composer.gate_add(s);
 
-    composer.assert_equal_constant(res_mul, 0, None)
+    composer.component_range(above_one, 4);
+    composer.component_range(below_nine, 4);
 }
 
 /// A gate which outputs a variable whose value is 1 if
@@ -72,3 +63,78 @@ pub fn is_eq_with_output<C: Composer>(composer: &mut C, a: Witness, b: Witness)
     let difference = composer.gate_add(s);
     is_zero_with_output(composer, difference)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use dusk_plonk::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::range_check;
+
+    /// The check before the range component, asserting that
+    /// (value - 1) * ... * (value - 9) is zero.
+    fn product_range_check<C: Composer>(composer: &mut C, value: Witness) {
+        let s = Constraint::new()
+            .left(1)
+            .constant(-BlsScalar::one())
+            .a(value);
+        let mut product = composer.gate_add(s);
+
+        for r in 2..=9u64 {
+            let s = Constraint::new()
+                .left(1)
+                .constant(-BlsScalar::from(r))
+                .a(value);
+            let factor = composer.gate_add(s);
+            let s = Constraint::new().mult(1).a(factor).b(product);
+            product = composer.gate_mul(s);
+        }
+
+        composer.assert_equal_constant(product, 0, None)
+    }
+
+    static PRODUCT_GATES: AtomicUsize = AtomicUsize::new(0);
+    static COMPONENT_GATES: AtomicUsize = AtomicUsize::new(0);
+
+    /// Range checks one cell both ways, recording the gates each check adds.
+    #[derive(Default)]
+    struct RangeChecks;
+
+    impl Circuit for RangeChecks {
+        #[allow(deprecated)]
+        fn circuit<C>(&self, composer: &mut C) -> Result<(), Error>
+        where
+            C: Composer,
+        {
+            let value = composer.append_witness(BlsScalar::from(5u64));
+
+            let start = composer.constraints();
+            product_range_check(composer, value);
+            let middle = composer.constraints();
+            range_check(composer, value);
+            let end = composer.constraints();
+
+            PRODUCT_GATES.store(middle - start, Ordering::Relaxed);
+            COMPONENT_GATES.store(end - middle, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_range_check_gates() {
+        let rng = &mut StdRng::seed_from_u64(8349u64);
+        let pp = PublicParameters::setup(1 << 8, rng).expect("failed to create pp");
+        Compiler::compile::<RangeChecks>(&pp, b"range").expect("failed to compile circuit");
+
+        let product = PRODUCT_GATES.load(Ordering::Relaxed);
+        let component = COMPONENT_GATES.load(Ordering::Relaxed);
+        println!(
+            "range check of a cell: {} gates, {} with the product",
+            component, product
+        );
+        assert!(component < product);
+    }
+}
@@ -1,10 +1,27 @@
-use std::marker::PhantomData;
+use std::{
+    any::TypeId,
+    collections::HashMap,
+    marker::PhantomData,
+    sync::{Mutex, OnceLock},
+};
 
 use ark_ec::TEModelParameters;
 use ark_ff::PrimeField;
-use plonk::prelude::{Circuit, Variable};
+use plonk::prelude::{Circuit, Error, StandardComposer, Variable};
+use zk_sudoku_core::{Puzzle, Solution};
 
-use crate::utils::range_check;
+use crate::{
+    hash::{puzzle_hash, puzzle_hash_gadget},
+    utils::range_check,
+};
+
+/// Gates kept free above the ones of the gadget when padding, for the ones
+/// the composer adds itself.
+pub const HEADROOM: usize = 16;
+
+// The gates of the gadget counted once per field and curve, as counting
+// them synthesizes the whole circuit
+static CIRCUIT_SIZES: OnceLock<Mutex<HashMap<(TypeId, TypeId), usize>>> = OnceLock::new();
 
 // Implements a circuit that checks if a sudoku works.
 #[derive(derivative::Derivative, Clone)]
@@ -16,34 +33,62 @@ pub struct SudokuCircuit<F, P> {
     pub _marker2: PhantomData<P>,
 }
 
-impl<F, P> Circuit<F, P> for SudokuCircuit<F, P>
+impl<F, P> SudokuCircuit<F, P> {
+    pub fn new(puzzle: &Puzzle, solution: &Solution) -> Self {
+        Self {
+            unsolved: *puzzle.cells(),
+            solved: *solution.cells(),
+            _marker1: PhantomData,
+            _marker2: PhantomData,
+        }
+    }
+}
+
+impl<F, P> SudokuCircuit<F, P>
 where
     F: PrimeField,
     P: TEModelParameters<BaseField = F>,
 {
-    const CIRCUIT_ID: [u8; 32] = [0xff; 32];
+    /// The number of gates of the gadget, which only depends on the shape of
+    /// the circuit. It is counted on the first call only.
+    pub fn circuit_size(&self) -> usize {
+        let mut sizes = CIRCUIT_SIZES.get_or_init(Default::default).lock().unwrap();
+        *sizes
+            .entry((TypeId::of::<F>(), TypeId::of::<P>()))
+            .or_insert_with(|| {
+                let mut composer = StandardComposer::<F, P>::new();
+                self.synthesize(&mut composer, F::one())
+                    .expect("the sudoku gadget does not fail");
+                composer.circuit_size()
+            })
+    }
 
-    fn gadget(
-        &mut self,
-        composer: &mut plonk::prelude::StandardComposer<F, P>,
-    ) -> Result<(), plonk::prelude::Error> {
-        let one_var = composer.add_input(F::one());
+    /// The gates of the circuit, `one` being the witness of the variable
+    /// constrained to one. Anything but one is a forged witness, only used
+    /// by the tests to check that it is rejected.
+    pub(crate) fn synthesize(
+        &self,
+        composer: &mut StandardComposer<F, P>,
+        one: F,
+    ) -> Result<(), Error> {
+        let one_var = composer.add_input(one);
+        // The check of the givens below selects and compares against it
+        composer.constrain_to_constant(one_var, F::one(), None);
         let zero_var = composer.zero_var();
         // new circuit
+        // The unsolved sudoku is private, only its hash is a public input
         let unsolved_vars: Vec<Vec<Variable>> = self
             .unsolved
             .iter()
             .map(|line| {
-                let u: Vec<Variable> = line
-                    .iter()
-                    .map(|x| {
-                        let t = composer.add_input(F::from(*x));
-                        t
-                    })
-                    .collect();
-                u
+                line.iter()
+                    .map(|x| composer.add_input(F::from(*x)))
+                    .collect()
             })
             .collect();
+        // The cells are bytes as each one is 0 or the range checked solved cell
+        let hash = puzzle_hash_gadget(composer, &unsolved_vars.concat());
+        composer.constrain_to_constant(hash, F::zero(), Some(-puzzle_hash::<F>(&self.unsolved)));
 
         let solved_vars: Vec<Vec<Variable>> = self
             .solved
@@ -106,13 +151,14 @@ where
 
         // Check if each square in solved has all the numbers from 1 to 9, both included
         // For each square and for each element in each square, check that the
-        // element is not equal to previous elements in the same square
+        // element is not equal to the elements of the previous rows of the same
+        // square, except the one in its own column (rows and columns are covered above)
         for i in [0, 3, 6] {
             for j in [0, 3, 6] {
                 for k in i..i + 3 {
                     for l in j..j + 3 {
-                        for m in i..=k {
-                            for n in j..l {
+                        for m in i..k {
+                            for n in (j..j + 3).filter(|n| *n != l) {
                                 let is_equal = composer
                                     .is_eq_with_output(solved_vars[m][n], solved_vars[k][l]);
                                 composer.assert_equal(is_equal, zero_var)
@@ -125,9 +171,21 @@ where
 
         Ok(())
     }
+}
+
+impl<F, P> Circuit<F, P> for SudokuCircuit<F, P>
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    const CIRCUIT_ID: [u8; 32] = [0xff; 32];
+
+    fn gadget(&mut self, composer: &mut StandardComposer<F, P>) -> Result<(), Error> {
+        self.synthesize(composer, F::one())
+    }
 
     fn padded_circuit_size(&self) -> usize {
-        1 << 16
+        (self.circuit_size() + HEADROOM).next_power_of_two()
     }
 }
 
@@ -137,6 +195,7 @@ mod test {
 
     use ark_bls12_381::{Bls12_381, Fr as BlsScalar};
     use ark_ed_on_bls12_381::EdwardsParameters as JubJubParameters;
+    use ark_ff::One;
     use ark_poly::polynomial::univariate::DensePolynomial;
     use ark_poly_commit::{sonic_pc::SonicKZG10, PolynomialCommitment};
     use ark_serialize::CanonicalSerialize;
@@ -145,6 +204,7 @@ mod test {
     use plonk_core::circuit::{verify_proof, Circuit};
     use plonk_core::prelude::*;
     use rand_core::OsRng;
+    use zk_sudoku_core::sample::{SOLVED, UNSOLVED};
 
     use crate::circuit::SudokuCircuit;
 
@@ -152,37 +212,26 @@ mod test {
     fn test_circuit() -> Result<(), Error> {
         // Generate CRS
         type PC = SonicKZG10<Bls12_381, DensePolynomial<BlsScalar>>;
-        let pp = PC::setup(1 << 16, None, &mut OsRng).map_err(to_pc_error::<BlsScalar, PC>)?;
-
         let mut circuit = SudokuCircuit::<BlsScalar, JubJubParameters>::default();
+        let padded_size = circuit.padded_circuit_size();
+        println!(
+            "circuit size: {}, padded to {}",
+            circuit.circuit_size(),
+            padded_size
+        );
+        assert!(circuit.circuit_size() < padded_size);
+        let pp = PC::setup(padded_size, None, &mut OsRng).map_err(to_pc_error::<BlsScalar, PC>)?;
+
         // Compile the circuit
-        let (pk_p, (vk, _pi_pos)) = circuit.compile::<PC>(&pp)?;
+        let (pk_p, (vk, pi_pos)) = circuit.compile::<PC>(&pp)?;
+        // the hash of the puzzle is the only public input
+        assert_eq!(pi_pos.len(), 1);
 
         // Prover POV
         let (proof, pi) = {
             let mut circuit: SudokuCircuit<BlsScalar, JubJubParameters> = SudokuCircuit {
-                unsolved: [
-                    [0, 0, 0, 0, 0, 6, 0, 0, 0],
-                    [0, 0, 7, 2, 0, 0, 8, 0, 0],
-                    [9, 0, 6, 8, 0, 0, 0, 1, 0],
-                    [3, 0, 0, 7, 0, 0, 0, 2, 9],
-                    [0, 0, 0, 0, 0, 0, 0, 0, 0],
-                    [4, 0, 0, 5, 0, 0, 0, 7, 0],
-                    [6, 5, 0, 1, 0, 0, 0, 0, 0],
-                    [8, 0, 1, 0, 5, 0, 3, 0, 0],
-                    [7, 9, 2, 0, 0, 0, 0, 0, 4],
-                ],
-                solved: [
-                    [1, 8, 4, 3, 7, 6, 2, 9, 5],
-                    [5, 3, 7, 2, 9, 1, 8, 4, 6],
-                    [9, 2, 6, 8, 4, 5, 7, 1, 3],
-                    [3, 6, 5, 7, 1, 8, 4, 2, 9],
-                    [2, 7, 8, 4, 6, 9, 5, 3, 1],
-                    [4, 1, 9, 5, 3, 2, 6, 7, 8],
-                    [6, 5, 3, 1, 2, 4, 9, 8, 7],
-                    [8, 4, 1, 9, 5, 7, 3, 6, 2],
-                    [7, 9, 2, 6, 8, 3, 1, 5, 4],
-                ],
+                unsolved: UNSOLVED,
+                solved: SOLVED,
                 _marker1: PhantomData,
                 _marker2: PhantomData,
             };
@@ -202,4 +251,20 @@ mod test {
             b"Test",
         )
     }
+
+    #[test]
+    fn test_circuit_size() {
+        let circuit = SudokuCircuit::<BlsScalar, JubJubParameters>::default();
+        let mut composer = StandardComposer::<BlsScalar, JubJubParameters>::new();
+        circuit.synthesize(&mut composer, BlsScalar::one()).unwrap();
+        // the count cached by the first call is the one of the gadget, and
+        // is not counted again for another witness
+        assert_eq!(circuit.circuit_size(), composer.circuit_size());
+        let solved = SudokuCircuit::<BlsScalar, JubJubParameters> {
+            unsolved: UNSOLVED,
+            solved: SOLVED,
+            ..Default::default()
+        };
+        assert_eq!(solved.circuit_size(), composer.circuit_size());
+    }
 }
@@ -0,0 +1,92 @@
+//! Poseidon hash of the puzzle, the only public input of the circuit. As in
+//! the arkworks crate, the 81 cells are packed into 3 field elements of 31
+//! bytes, which one permutation of width 4 absorbs at once.
+
+use ark_ec::TEModelParameters;
+use ark_ff::PrimeField;
+use plonk::prelude::{StandardComposer, Variable};
+use plonk_hashing::poseidon::{
+    constants::PoseidonConstants,
+    poseidon::{NativeSpec, PlonkSpec, Poseidon},
+};
+use zk_sudoku_core::public_input::preimage;
+
+/// Bytes packed into one field element, which keeps it below the modulus.
+const PACKED_BYTES: usize = 31;
+/// The width of the permutation, one more than the 3 packed elements.
+pub const WIDTH: usize = 4;
+
+/// The hash of the unsolved cells, in row major order.
+pub fn puzzle_hash<F: PrimeField>(unsolved: &[[u8; 9]; 9]) -> F {
+    let constants = PoseidonConstants::<F>::generate::<WIDTH>();
+    let mut poseidon = Poseidon::<(), NativeSpec<F, WIDTH>, WIDTH>::new(&mut (), constants);
+    for chunk in preimage(unsolved, None).chunks(PACKED_BYTES) {
+        poseidon
+            .input(F::from_le_bytes_mod_order(chunk))
+            .expect("3 elements fit in the state");
+    }
+    poseidon.output_hash(&mut ())
+}
+
+/// `puzzle_hash` of the variables of the 81 cells, which must be bytes for
+/// the packing to be injective. Returns the variable of the hash.
+pub fn puzzle_hash_gadget<F, P>(
+    composer: &mut StandardComposer<F, P>,
+    cells: &[Variable],
+) -> Variable
+where
+    F: PrimeField,
+    P: TEModelParameters<BaseField = F>,
+{
+    let packed = cells
+        .chunks(PACKED_BYTES)
+        .map(|chunk| {
+            let mut acc = composer.zero_var();
+            let mut coeff = F::one();
+            for cell in chunk {
+                acc = composer
+                    .arithmetic_gate(|gate| gate.witness(acc, *cell, None).add(F::one(), coeff));
+                coeff *= F::from(256u32);
+            }
+            acc
+        })
+        .collect::<Vec<_>>();
+
+    let constants = PoseidonConstants::<F>::generate::<WIDTH>();
+    let mut poseidon = Poseidon::<_, PlonkSpec<WIDTH>, WIDTH>::new(composer, constants);
+    for element in packed {
+        poseidon
+            .input(element)
+            .expect("3 elements fit in the state");
+    }
+    poseidon.output_hash(composer)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr as BlsScalar;
+    use ark_ed_on_bls12_381::EdwardsParameters as JubJubParameters;
+    use plonk::prelude::StandardComposer;
+    use zk_sudoku_core::sample::UNSOLVED;
+
+    use super::{puzzle_hash, puzzle_hash_gadget};
+
+    #[test]
+    fn test_puzzle_hash() {
+        let unsolved = UNSOLVED;
+        let hash = puzzle_hash::<BlsScalar>(&unsolved);
+
+        let mut composer = StandardComposer::<BlsScalar, JubJubParameters>::new();
+        let cells = unsolved
+            .iter()
+            .flatten()
+            .map(|cell| composer.add_input(BlsScalar::from(*cell)))
+            .collect::<Vec<_>>();
+        let digest = puzzle_hash_gadget(&mut composer, &cells);
+        assert_eq!(composer.value_of_var(digest), hash);
+
+        let mut other = unsolved;
+        other[4][4] = 6;
+        assert_ne!(puzzle_hash::<BlsScalar>(&other), hash);
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+
+use ark_bls12_381::Fr as BlsScalar;
+use ark_ed_on_bls12_381::EdwardsParameters as JubJubParameters;
+use ark_poly_commit::PolynomialCommitment;
+use plonk::error::to_pc_error;
+use plonk_core::{circuit::Circuit, prelude::Error};
+use rand_core::{CryptoRng, RngCore};
+pub use zk_sudoku_core::keys::{read_key, write_key};
+
+use crate::{
+    circuit::SudokuCircuit,
+    prover::{compile, GarageProvingKey, GarageVerifyingKey, UniversalParams, PC},
+};
+
+pub type KeyError = zk_sudoku_core::keys::KeyError<Error>;
+
+/// Version of the gates of `SudokuCircuit`. The key files are named after
+/// it, so that bumping it when the gates change leaves the keys compiled for
+/// the old ones unread.
+pub const CIRCUIT_VERSION: u32 = 2;
+
+/// The files of the setup of a circuit padded to `padded_size` gates: the
+/// public parameters, the prover key, the verifier key and the positions of
+/// the public inputs. The parameters only depend on the size.
+#[derive(Debug, Clone)]
+pub struct KeyPaths {
+    pub pp: PathBuf,
+    pub pk: PathBuf,
+    pub vk: PathBuf,
+    pub pi_pos: PathBuf,
+}
+
+impl KeyPaths {
+    pub fn new(dir: impl AsRef<Path>, padded_size: usize) -> Self {
+        let name = format!("garage_plonk_v{}_{}", CIRCUIT_VERSION, padded_size);
+        let dir = dir.as_ref();
+        Self {
+            pp: dir.join(format!("garage_plonk_{}.pp", padded_size)),
+            pk: dir.join(format!("{}.pk", name)),
+            vk: dir.join(format!("{}.vk", name)),
+            pi_pos: dir.join(format!("{}.pi", name)),
+        }
+    }
+
+    /// The files of the sudoku circuit.
+    pub fn sudoku(dir: impl AsRef<Path>) -> Self {
+        let circuit = SudokuCircuit::<BlsScalar, JubJubParameters>::default();
+        Self::new(dir, circuit.padded_circuit_size())
+    }
+}
+
+/// Writes the keys of `compile` to `paths`, the parameters shared by both
+/// keys being written once.
+pub fn write_keys(
+    paths: &KeyPaths,
+    pk: &GarageProvingKey,
+    vk: &GarageVerifyingKey,
+) -> Result<(), KeyError> {
+    write_key(&vk.pp, &paths.pp)?;
+    write_key(&pk.key, &paths.pk)?;
+    write_key(&vk.key, &paths.vk)?;
+    write_key(&vk.pi_pos, &paths.pi_pos)
+}
+
+/// Reads back the keys written by `write_keys`.
+pub fn read_keys(paths: &KeyPaths) -> Result<(GarageProvingKey, GarageVerifyingKey), KeyError> {
+    let pp: UniversalParams = read_key(&paths.pp)?;
+    Ok((
+        GarageProvingKey {
+            pp: pp.clone(),
+            key: read_key(&paths.pk)?,
+        },
+        GarageVerifyingKey {
+            pp,
+            key: read_key(&paths.vk)?,
+            pi_pos: read_key(&paths.pi_pos)?,
+        },
+    ))
+}
+
+/// Reads the keys of the sudoku circuit from `dir`, or sets up and compiles
+/// the circuit and writes them there for the next call. Existing public
+/// parameters are reused when only the keys are missing.
+pub fn load_or_setup<R: RngCore + CryptoRng>(
+    dir: impl AsRef<Path>,
+    rng: &mut R,
+) -> Result<(GarageProvingKey, GarageVerifyingKey), KeyError> {
+    let paths = KeyPaths::sudoku(dir);
+    if [&paths.pp, &paths.pk, &paths.vk, &paths.pi_pos]
+        .iter()
+        .all(|path| path.exists())
+    {
+        return read_keys(&paths);
+    }
+
+    let pp = if paths.pp.exists() {
+        read_key(&paths.pp)?
+    } else {
+        let circuit = SudokuCircuit::<BlsScalar, JubJubParameters>::default();
+        PC::setup(circuit.padded_circuit_size(), None, rng)
+            .map_err(|err| KeyError::Backend(to_pc_error::<BlsScalar, PC>(err)))?
+    };
+    let (pk, vk) = compile(pp).map_err(KeyError::Backend)?;
+    write_keys(&paths, &pk, &vk)?;
+    Ok((pk, vk))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use rand_core::OsRng;
+    use zk_sudoku_core::{sample, SudokuProver};
+
+    use super::{load_or_setup, KeyPaths};
+    use crate::prover::GaragePlonkProver;
+
+    #[test]
+    fn test_key_files() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let dir = env::temp_dir().join("zk_sudoku_garage_plonk_keys");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // the first call writes the files, the second one reads them
+        load_or_setup(&dir, &mut OsRng).unwrap();
+        let paths = KeyPaths::sudoku(&dir);
+        assert!(paths.pp.exists() && paths.pk.exists());
+        assert!(paths.vk.exists() && paths.pi_pos.exists());
+        let (pk, vk) = load_or_setup(&dir, &mut OsRng).unwrap();
+
+        let prover = GaragePlonkProver;
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+    }
+}
@@ -1,2 +1,6 @@
 pub mod circuit;
-pub mod utils;
\ No newline at end of file
+pub mod hash;
+pub mod keys;
+pub mod utils;
+pub mod prover;
+pub mod report;
\ No newline at end of file
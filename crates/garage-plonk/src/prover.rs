@@ -0,0 +1,259 @@
+use ark_bls12_381::{Bls12_381, Fr as BlsScalar, G1Affine};
+use ark_ed_on_bls12_381::EdwardsParameters as JubJubParameters;
+use ark_poly::polynomial::univariate::DensePolynomial;
+use ark_poly_commit::{ipa_pc::InnerProductArgPC, sonic_pc::SonicKZG10, PolynomialCommitment};
+use blake2::Blake2b;
+use plonk::error::to_pc_error;
+use plonk_core::{
+    circuit::{verify_proof, Circuit},
+    commitment::HomomorphicCommitment,
+    prelude::*,
+    proof_system::pi::PublicInputs,
+};
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::{circuit::SudokuCircuit, hash::puzzle_hash};
+
+/// The KZG commitment, with a trusted setup and constant size openings.
+pub type PC = SonicKZG10<Bls12_381, DensePolynomial<BlsScalar>>;
+/// The inner product argument, with a transparent setup but openings and a
+/// verifier growing with the log and the size of the circuit.
+pub type IpaPC = InnerProductArgPC<G1Affine, Blake2b, DensePolynomial<BlsScalar>>;
+pub type UniversalParams<P = PC> =
+    <P as PolynomialCommitment<BlsScalar, DensePolynomial<BlsScalar>>>::UniversalParams;
+
+const LABEL: &[u8] = b"zk_sudoku";
+
+/// ZK-Garage plonk over bls12-381 with the Sonic KZG10 commitment. The free
+/// functions ending in `_with` take any other commitment, such as `IpaPC`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GaragePlonkProver;
+
+pub struct GarageProvingKey<P: HomomorphicCommitment<BlsScalar> = PC> {
+    pub pp: UniversalParams<P>,
+    pub key: ProverKey<BlsScalar>,
+}
+
+pub struct GarageVerifyingKey<P: HomomorphicCommitment<BlsScalar> = PC> {
+    pub pp: UniversalParams<P>,
+    pub key: VerifierKey<BlsScalar, P>,
+    /// Position of the public input, the hash of the puzzle.
+    pub pi_pos: Vec<usize>,
+}
+
+/// Compiles the sudoku circuit with existing parameters, e.g. read with
+/// `keys::read_key`.
+pub fn compile(pp: UniversalParams) -> Result<(GarageProvingKey, GarageVerifyingKey), Error> {
+    compile_with::<PC>(pp)
+}
+
+/// `compile` with the commitment `P`.
+pub fn compile_with<P: HomomorphicCommitment<BlsScalar>>(
+    pp: UniversalParams<P>,
+) -> Result<(GarageProvingKey<P>, GarageVerifyingKey<P>), Error> {
+    let mut circuit = SudokuCircuit::<BlsScalar, JubJubParameters>::default();
+    let (pk, (vk, pi_pos)) = circuit.compile::<P>(&pp)?;
+    Ok((
+        GarageProvingKey {
+            pp: pp.clone(),
+            key: pk,
+        },
+        GarageVerifyingKey {
+            pp,
+            key: vk,
+            pi_pos,
+        },
+    ))
+}
+
+/// Sets up the parameters of the commitment `P` for the padded sudoku
+/// circuit and compiles it.
+pub fn setup_with<P: HomomorphicCommitment<BlsScalar>, R: RngCore + CryptoRng>(
+    rng: &mut R,
+) -> Result<(GarageProvingKey<P>, GarageVerifyingKey<P>), Error> {
+    let circuit = SudokuCircuit::<BlsScalar, JubJubParameters>::default();
+    let pp =
+        P::setup(circuit.padded_circuit_size(), None, rng).map_err(to_pc_error::<BlsScalar, P>)?;
+    compile_with::<P>(pp)
+}
+
+/// Proves that `solution` solves `puzzle` with the commitment `P`.
+pub fn prove_with<P: HomomorphicCommitment<BlsScalar>>(
+    pk: &GarageProvingKey<P>,
+    puzzle: &Puzzle,
+    solution: &Solution,
+) -> Result<Proof<BlsScalar, P>, Error> {
+    let mut circuit = SudokuCircuit::<BlsScalar, JubJubParameters>::new(puzzle, solution);
+    let (proof, _) = circuit.gen_proof::<P>(&pk.pp, pk.key.clone(), LABEL)?;
+    Ok(proof)
+}
+
+/// Verifies a proof of `prove_with`, returning `Ok(false)` for a proof of
+/// another puzzle or a forged one.
+pub fn verify_with<P: HomomorphicCommitment<BlsScalar>>(
+    vk: &GarageVerifyingKey<P>,
+    puzzle: &Puzzle,
+    proof: &Proof<BlsScalar, P>,
+) -> Result<bool, Error> {
+    // The circuit binds the hash with a gate `hash + pi = 0`
+    let mut pi = PublicInputs::new();
+    pi.add_input(vk.pi_pos[0], &-puzzle_hash::<BlsScalar>(puzzle.cells()))?;
+
+    match verify_proof::<BlsScalar, JubJubParameters, P>(&vk.pp, vk.key.clone(), proof, &pi, LABEL)
+    {
+        Ok(()) => Ok(true),
+        Err(Error::ProofVerificationError) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+impl SudokuProver for GaragePlonkProver {
+    type ProvingKey = GarageProvingKey;
+    type VerifyingKey = GarageVerifyingKey;
+    type Proof = Proof<BlsScalar, PC>;
+    type Error = Error;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        setup_with::<PC, _>(rng)
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        _rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        prove_with(pk, puzzle, solution)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        verify_with(vk, puzzle, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Fr as BlsScalar;
+    use ark_ed_on_bls12_381::EdwardsParameters as JubJubParameters;
+    use ark_ff::Zero;
+    use plonk_core::{
+        circuit::Circuit,
+        prelude::{Error, StandardComposer},
+    };
+    use rand_core::OsRng;
+    use zk_sudoku_core::{
+        sample::{
+            self, SOLVED, SOLVED_REPEATED_IN_COLUMN, SOLVED_REPEATED_IN_ROW,
+            SOLVED_REPEATED_IN_SQUARE, SOLVED_UNMATCH, UNSOLVED,
+        },
+        Puzzle, Solution, SudokuProver,
+    };
+
+    use super::{GaragePlonkProver, LABEL, PC};
+    use crate::circuit::SudokuCircuit;
+
+    #[test]
+    fn test_garage_plonk_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let prover = GaragePlonkProver;
+
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_solutions() {
+        let prover = GaragePlonkProver;
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let puzzle = Puzzle::new(UNSOLVED).unwrap();
+
+        let mut out_of_bound = SOLVED;
+        out_of_bound[0][8] = 10;
+        // The puzzle is a private witness too, bound by its hash only. The
+        // last two cases are valid solutions of puzzles other than the public
+        // one, the empty one and the one with its digits shifted
+        let shift =
+            |grid: [[u8; 9]; 9]| grid.map(|row| row.map(|x| if x == 0 { 0 } else { x % 9 + 1 }));
+        let invalid = [
+            ("out of bound", UNSOLVED, out_of_bound),
+            ("unmatched", UNSOLVED, SOLVED_UNMATCH),
+            ("repeated in a row", UNSOLVED, SOLVED_REPEATED_IN_ROW),
+            ("repeated in a column", UNSOLVED, SOLVED_REPEATED_IN_COLUMN),
+            ("repeated in a square", UNSOLVED, SOLVED_REPEATED_IN_SQUARE),
+            ("empty puzzle", [[0; 9]; 9], SOLVED),
+            ("shifted puzzle", shift(UNSOLVED), shift(SOLVED)),
+        ];
+        for (name, unsolved, solved) in invalid {
+            // bypass `Solution::new`, which rejects these grids
+            let mut circuit = SudokuCircuit::<BlsScalar, JubJubParameters> {
+                unsolved,
+                solved,
+                ..Default::default()
+            };
+            // the prover does not check the gates, so the proof is computed
+            // but must not verify
+            let verified = circuit
+                .gen_proof::<PC>(&pk.pp, pk.key.clone(), LABEL)
+                .and_then(|(proof, _)| prover.verify(&vk, &puzzle, &proof));
+            assert!(!matches!(verified, Ok(true)), "{} solution verified", name);
+        }
+    }
+
+    // The sudoku circuit proven with 0 as the witness of the variable
+    // constrained to one
+    #[derive(Clone, Default)]
+    struct ForgedOne(SudokuCircuit<BlsScalar, JubJubParameters>);
+
+    impl Circuit<BlsScalar, JubJubParameters> for ForgedOne {
+        const CIRCUIT_ID: [u8; 32] = SudokuCircuit::<BlsScalar, JubJubParameters>::CIRCUIT_ID;
+
+        fn gadget(
+            &mut self,
+            composer: &mut StandardComposer<BlsScalar, JubJubParameters>,
+        ) -> Result<(), Error> {
+            self.0.synthesize(composer, BlsScalar::zero())
+        }
+
+        fn padded_circuit_size(&self) -> usize {
+            self.0.padded_circuit_size()
+        }
+    }
+
+    #[test]
+    fn test_forged_one() {
+        let prover = GaragePlonkProver;
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let puzzle = Puzzle::new(UNSOLVED).unwrap();
+
+        // With one forged to 0, the check of the givens would turn around
+        // and only accept a solution differing from every given, such as
+        // the sample one with its digits shifted
+        let shifted = SOLVED.map(|row| row.map(|x| x % 9 + 1));
+        let mut circuit = ForgedOne(SudokuCircuit {
+            unsolved: UNSOLVED,
+            solved: shifted,
+            ..Default::default()
+        });
+        let verified = circuit
+            .gen_proof::<PC>(&pk.pp, pk.key.clone(), LABEL)
+            .and_then(|(proof, _)| prover.verify(&vk, &puzzle, &proof));
+        assert!(!matches!(verified, Ok(true)), "forged one verified");
+    }
+}
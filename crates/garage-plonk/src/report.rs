@@ -0,0 +1,89 @@
+//! Timings and sizes of the garage-plonk prover on the sample sudoku with
+//! each polynomial commitment, as the reports of the other backends.
+
+use std::time::Instant;
+
+use ark_bls12_381::Fr as BlsScalar;
+use ark_ed_on_bls12_381::EdwardsParameters as JubJubParameters;
+use ark_serialize::CanonicalSerialize;
+use plonk_core::{circuit::Circuit, commitment::HomomorphicCommitment, prelude::Error};
+use rand_core::{CryptoRng, OsRng, RngCore};
+use serde_json::json;
+use zk_sudoku_core::{
+    report::{print_table, Report},
+    sample,
+};
+
+use crate::{
+    circuit::SudokuCircuit,
+    prover::{prove_with, setup_with, verify_with, IpaPC, PC},
+};
+
+/// Sets up, proves the sample sudoku and verifies the proof with the
+/// commitment `P`, timing each step. The gates are reported as the
+/// constraints and the commitment, "kzg" or "ipa", as the config. A rejected
+/// proof is an `Error::ProofVerificationError`.
+pub fn run<P, R>(commitment: &'static str, rng: &mut R) -> Result<Report, Error>
+where
+    P: HomomorphicCommitment<BlsScalar>,
+    R: RngCore + CryptoRng,
+{
+    let puzzle = sample::puzzle();
+    let solution = sample::solution();
+    let circuit = SudokuCircuit::<BlsScalar, JubJubParameters>::new(&puzzle, &solution);
+
+    let setup_start = Instant::now();
+    let (pk, vk) = setup_with::<P, _>(rng)?;
+    let setup_time = setup_start.elapsed();
+
+    let prove_start = Instant::now();
+    let proof = prove_with(&pk, &puzzle, &solution)?;
+    let prove_time = prove_start.elapsed();
+
+    let verify_start = Instant::now();
+    let valid_proof = verify_with(&vk, &puzzle, &proof)?;
+    let verify_time = verify_start.elapsed();
+    if !valid_proof {
+        return Err(Error::ProofVerificationError);
+    }
+
+    Ok(Report {
+        backend: "garage-plonk",
+        curve: "bls12_381",
+        config: Some(commitment),
+        num_constraints: circuit.circuit_size(),
+        setup_time,
+        prove_time,
+        verify_time,
+        proof_len: proof.serialized_size(),
+        extra: vec![("padded_size", json!(circuit.padded_circuit_size()))],
+    })
+}
+
+/// Runs the sudoku circuit with the KZG and the IPA commitments.
+pub fn test_garage_plonk() -> Vec<Report> {
+    let reports = vec![
+        run::<PC, _>("kzg", &mut OsRng).unwrap(),
+        run::<IpaPC, _>("ipa", &mut OsRng).unwrap(),
+    ];
+    print_table(&reports);
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use rand_core::OsRng;
+
+    use super::run;
+    use crate::prover::{IpaPC, PC};
+
+    #[test]
+    fn test_commitments() {
+        let kzg = run::<PC, _>("kzg", &mut OsRng).unwrap();
+        let ipa = run::<IpaPC, _>("ipa", &mut OsRng).unwrap();
+        println!("{}\n{}", kzg.to_json(), ipa.to_json());
+        assert_eq!(kzg.num_constraints, ipa.num_constraints);
+        // each opening of the inner product argument takes log(n) points
+        assert!(ipa.proof_len > kzg.proof_len);
+    }
+}
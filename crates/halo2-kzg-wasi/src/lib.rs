@@ -0,0 +1,45 @@
+//! The verify-only path of the halo2 KZG backend as a WebAssembly component
+//! of the WASI preview 2, for any host of the component model rather than a
+//! browser. It exports the `verify` of wit/verifier.wit, which takes the
+//! bytes of a `zk_sudoku_halo2_kzg::Manifest`.
+
+use zk_sudoku_halo2_kzg::manifest;
+
+wit_bindgen::generate!({ world: "verifier" });
+
+pub struct Verifier;
+
+impl Guest for Verifier {
+    /// Generates the verifying key from the parameters of the manifest,
+    /// which takes a few seconds, and verifies its proof.
+    fn verify(manifest: Vec<u8>) -> Result<bool, String> {
+        manifest::verify(&manifest).map_err(|err| format!("{:?}", err))
+    }
+}
+
+export!(Verifier);
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::ThreadRng;
+    use zk_sudoku_core::sample;
+    use zk_sudoku_halo2_kzg::{prove, setup, Manifest, PuzzleHash};
+
+    use super::{Guest, Verifier};
+
+    #[test]
+    fn test_verify() {
+        let puzzle = sample::puzzle();
+        let mut rng = ThreadRng::default();
+        let hash = PuzzleHash::default();
+        let (pk, vk) = setup(hash.k(), hash, &mut rng).unwrap();
+        let proof = prove(&pk, &puzzle, &sample::solution(), &mut rng).unwrap();
+
+        let manifest = Manifest::new(&vk, puzzle, proof).to_bytes();
+        assert_eq!(Verifier::verify(manifest.clone()), Ok(true));
+        let mut other = manifest;
+        other[1 + 4 * 9 + 4] = 6;
+        assert_eq!(Verifier::verify(other), Ok(false));
+        assert!(Verifier::verify(vec![2]).is_err());
+    }
+}
@@ -0,0 +1,422 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Constraints, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::gadgets::{
+    fit_degree,
+    keccak::{self, KeccakConfig},
+    permutation::PermutationConfig,
+    poseidon::{self, PoseidonConfig},
+    range_check, CircuitField, Word,
+};
+use zk_sudoku_core::{public_input::keccak256_input, Puzzle, Solution};
+
+/// log2 of the rows used by the circuit, most of them by the Poseidon
+/// permutations hashing the unsolved sudoku, one round per row. At least
+/// `minimum_k`.
+pub const K: u32 = 12;
+/// log2 of the rows used by the circuit with the keccak256 puzzle hash.
+pub const KECCAK_K: u32 = 13;
+
+/// Rows of the cells, one per cell.
+pub const CELL_ROWS: usize = 81;
+/// Rows of the permutation checks, 9 per row, column and box.
+pub const PERMUTATION_ROWS: usize = 27 * 9;
+
+/// The hash committing to the unsolved sudoku, the only public input of the
+/// circuit, as the `PuzzleHash` of `zk_sudoku_arkworks`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PuzzleHash {
+    /// Poseidon (x^5, width 3) of the 81 cells, see `unsolved_hash`.
+    #[default]
+    Poseidon,
+    /// keccak256 of the 81 cells, its first 31 bytes read as a big endian
+    /// integer, so that a Solidity verifier gets the public input from the
+    /// `keccak256` opcode. Takes about twice the rows of Poseidon and 64
+    /// more advice columns, hence `KECCAK_K`.
+    Keccak256,
+}
+
+impl PuzzleHash {
+    pub fn hash<F: CircuitField>(&self, unsolved: &[[u64; 9]; 9]) -> F {
+        match self {
+            PuzzleHash::Poseidon => unsolved_hash(unsolved),
+            PuzzleHash::Keccak256 => {
+                let cells = unsolved.map(|row| row.map(|cell| cell as u8));
+                keccak256_input(&cells, None)
+                    .iter()
+                    .rev()
+                    .fold(F::ZERO, |acc, byte| {
+                        acc * F::from(256) + F::from(*byte as u64)
+                    })
+            }
+        }
+    }
+
+    /// Rows taken by the hash of the 81 cells.
+    pub const fn rows(&self) -> usize {
+        match self {
+            PuzzleHash::Poseidon => poseidon::rows(81),
+            PuzzleHash::Keccak256 => keccak::ROWS,
+        }
+    }
+
+    /// The `k` of the circuit with this hash, `K` or `KECCAK_K`.
+    pub const fn k(&self) -> u32 {
+        match self {
+            PuzzleHash::Poseidon => K,
+            PuzzleHash::Keccak256 => KECCAK_K,
+        }
+    }
+
+    pub const fn name(&self) -> &'static str {
+        match self {
+            PuzzleHash::Poseidon => "poseidon",
+            PuzzleHash::Keccak256 => "keccak256",
+        }
+    }
+}
+
+/// Rows of the circuit, laid out in a single region: the cells, then the
+/// permutation checks, then the hash of the unsolved cells.
+pub const fn rows(hash: PuzzleHash) -> usize {
+    CELL_ROWS + PERMUTATION_ROWS + hash.rows()
+}
+
+/// Rows of the circuit with the default Poseidon hash.
+pub const ROWS: usize = rows(PuzzleHash::Poseidon);
+
+/// The smallest `k` whose `2^k` rows fit the rows and the blinding rows of
+/// the circuit over `F` with `hash`.
+pub fn minimum_k<F: CircuitField>(hash: PuzzleHash) -> u32 {
+    let mut meta = ConstraintSystem::<F>::default();
+    SudokuCircuit::<F>::configure_with_params(&mut meta, hash);
+    let rows = (rows(hash) + meta.blinding_factors() + 1).max(meta.minimum_rows());
+    (1..32)
+        .find(|k| 1 << k >= rows)
+        .expect("the circuit does not fit in 2^31 rows")
+}
+
+/// Poseidon hash of the unsolved sudoku, the only public input of the circuit.
+pub fn unsolved_hash<F: CircuitField>(unsolved: &[[u64; 9]; 9]) -> F {
+    let message: [F; 81] = unsolved
+        .iter()
+        .flatten()
+        .map(|x| F::from(*x))
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    poseidon::hash(message)
+}
+
+#[derive(Debug, Clone)]
+pub struct SudokuConfig<F> {
+    pub advices: [Column<Advice>; 2],
+    pub instance: Column<Instance>,
+    /// Enables the checks of one cell per row of the first region: the range
+    /// of both cells, 1..=9 when solved and 0..=9 when unsolved, and the
+    /// solved cell matching the unsolved one if given.
+    pub q_cell: Selector,
+    pub permutation: PermutationConfig<F>,
+    /// The chip of the puzzle hash the circuit is configured with, the other
+    /// one being `None`.
+    pub poseidon: Option<PoseidonConfig<F>>,
+    pub keccak: Option<KeccakConfig<F>>,
+}
+
+/// The sudoku circuit of `zk_sudoku_halo2` in its default configuration: the
+/// ranges checked by the cell gate, each row, column and box checked to be a
+/// permutation of 1..=9, and the hash of the unsolved sudoku as the instance,
+/// Poseidon unless `hash` is set otherwise.
+///
+/// It is generic over the field, so the same gates run on the scalar field
+/// of bn254 with KZG and on the one of Grumpkin with IPA.
+pub struct SudokuCircuit<F> {
+    pub unsolved: [[u64; 9]; 9],
+    pub solved: [[u64; 9]; 9],
+    pub hash: PuzzleHash,
+    pub _marker: PhantomData<F>,
+}
+
+impl<F> Default for SudokuCircuit<F> {
+    fn default() -> Self {
+        Self {
+            unsolved: [[0; 9]; 9],
+            solved: [[0; 9]; 9],
+            hash: PuzzleHash::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F> Clone for SudokuCircuit<F> {
+    fn clone(&self) -> Self {
+        Self {
+            unsolved: self.unsolved,
+            solved: self.solved,
+            hash: self.hash,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F> SudokuCircuit<F> {
+    /// The circuit proving that `solution` solves `puzzle`, both validated
+    /// when built from their cells, with the Poseidon puzzle hash.
+    pub fn new(puzzle: &Puzzle, solution: &Solution) -> Self {
+        Self {
+            unsolved: puzzle.map(u64::from),
+            solved: solution.map(u64::from),
+            hash: PuzzleHash::Poseidon,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn with_hash(self, hash: PuzzleHash) -> Self {
+        Self { hash, ..self }
+    }
+}
+
+impl<F: CircuitField> Circuit<F> for SudokuCircuit<F> {
+    type Config = SudokuConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = PuzzleHash;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default().with_hash(self.hash)
+    }
+
+    fn params(&self) -> PuzzleHash {
+        self.hash
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Self::configure_with_params(meta, PuzzleHash::default())
+    }
+
+    fn configure_with_params(meta: &mut ConstraintSystem<F>, hash: PuzzleHash) -> Self::Config {
+        let advices = [meta.advice_column(), meta.advice_column()];
+
+        // Instance column used for public inputs
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        // Permutation over all advice columns.
+        for advice in advices {
+            meta.enable_equality(advice);
+        }
+
+        let q_cell = meta.selector();
+        meta.create_gate("cell check", |meta| {
+            let q = meta.query_selector(q_cell);
+            let unsolved = meta.query_advice(advices[0], Rotation::cur());
+            let solved = meta.query_advice(advices[1], Rotation::cur());
+
+            Constraints::with_selector(
+                q,
+                [
+                    ("range check", range_check(1, 9, solved.clone())),
+                    ("unsolved range check", range_check(0, 9, unsolved.clone())),
+                    // an empty cell is 0, a given one is equal to the solved cell
+                    ("given cell", unsolved.clone() * (unsolved - solved)),
+                ],
+            )
+        });
+
+        // used for the permutation checks, on copies of the solved cells
+        let permutation = PermutationConfig::configure(meta, advices[1]);
+
+        // used for hashing the unsolved sudoku, the message being copied to
+        // the cell columns by Poseidon and next to its lanes by keccak256
+        let (poseidon, keccak) = match hash {
+            PuzzleHash::Poseidon => {
+                let state = [(); poseidon::WIDTH].map(|_| meta.advice_column());
+                (Some(PoseidonConfig::configure(meta, state, advices)), None)
+            }
+            PuzzleHash::Keccak256 => (None, Some(KeccakConfig::configure(meta))),
+        };
+        fit_degree(meta);
+
+        SudokuConfig {
+            advices,
+            instance,
+            q_cell,
+            permutation,
+            poseidon,
+            keccak,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let digest = layouter.assign_region(
+            || "sudoku",
+            |mut region| {
+                // Check if the numbers of the unsolved sudoku are >=0 and <=9,
+                // and the ones of the solved sudoku >=1 and <=9
+                // If unsolved[i][j] is not zero, solved[i][j] is equal to unsolved[i][j]
+                let mut unsolved_cells = vec![];
+                let mut solved_cells = vec![];
+                for (offset, (unsolved, solved)) in self
+                    .unsolved
+                    .iter()
+                    .flatten()
+                    .zip(self.solved.iter().flatten())
+                    .enumerate()
+                {
+                    config.q_cell.enable(&mut region, offset)?;
+                    unsolved_cells.push(Word::assign(
+                        &mut region,
+                        config.advices[0],
+                        offset,
+                        Value::known(F::from(*unsolved)),
+                    ));
+                    solved_cells.push(Word::assign(
+                        &mut region,
+                        config.advices[1],
+                        offset,
+                        Value::known(F::from(*solved)),
+                    ));
+                }
+
+                let rows = (0..9).map(|i| (0..9).map(|j| (i, j)).collect::<Vec<_>>());
+                let columns = (0..9).map(|j| (0..9).map(|i| (i, j)).collect::<Vec<_>>());
+                let squares = (0..9).map(|s| {
+                    (0..9)
+                        .map(|c| (s / 3 * 3 + c / 3, s % 3 * 3 + c % 3))
+                        .collect::<Vec<_>>()
+                });
+                for (g, group) in rows.chain(columns).chain(squares).enumerate() {
+                    let cells = group
+                        .iter()
+                        .map(|&(i, j)| solved_cells[i * 9 + j])
+                        .collect::<Vec<_>>();
+                    config
+                        .permutation
+                        .assign_group(&mut region, CELL_ROWS + g * 9, &cells)?;
+                }
+
+                // the hash of the unsolved sudoku, exposed as the public input
+                let offset = CELL_ROWS + PERMUTATION_ROWS;
+                match (&config.poseidon, &config.keccak) {
+                    (Some(poseidon), _) => {
+                        let message: [Word<F>; 81] = unsolved_cells.try_into().unwrap();
+                        poseidon.hash(&mut region, offset, &message)
+                    }
+                    (_, Some(keccak)) => keccak.hash(&mut region, offset, &unsolved_cells),
+                    (None, None) => unreachable!("the circuit is configured with a hash"),
+                }
+            },
+        )?;
+        layouter.constrain_instance(digest.cell, config.instance, 0);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use halo2_proofs::{
+        dev::{MockProver, VerifyFailure},
+        halo2curves::bn256::Fr,
+        plonk::Any,
+    };
+    use zk_sudoku_core::sample::{widen, SOLVED, UNSOLVED};
+
+    use super::{minimum_k, rows, unsolved_hash, PuzzleHash, SudokuCircuit, K, KECCAK_K, ROWS};
+
+    fn sample_circuit() -> SudokuCircuit<Fr> {
+        SudokuCircuit {
+            unsolved: widen(UNSOLVED),
+            solved: widen(SOLVED),
+            hash: PuzzleHash::Poseidon,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    #[test]
+    fn test_circuit() {
+        let mut circuit = sample_circuit();
+        let public_inputs = vec![unsolved_hash::<Fr>(&circuit.unsolved)];
+        let prover = MockProver::run(K, &circuit, vec![public_inputs.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        // the hash of another puzzle
+        let mut other = circuit.unsolved;
+        other[4][4] = 6;
+        let prover = MockProver::run(K, &circuit, vec![vec![unsolved_hash::<Fr>(&other)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let mut repeated = circuit.clone();
+        repeated.solved[8][7] = 1;
+        let prover = MockProver::run(K, &repeated, vec![public_inputs]).unwrap();
+        assert!(prover.verify().is_err());
+
+        circuit.solved[0][0] = 10;
+        let prover = MockProver::run(
+            K,
+            &circuit,
+            vec![vec![unsolved_hash::<Fr>(&circuit.unsolved)]],
+        )
+        .unwrap();
+        let failures = prover.verify().unwrap_err();
+        let range_check = vec![(((Any::advice(), 1).into(), 0).into(), "0xa".to_string())];
+        assert!(failures.iter().any(|failure| matches!(
+            failure,
+            VerifyFailure::ConstraintNotSatisfied { cell_values, .. } if *cell_values == range_check
+        )));
+    }
+
+    #[test]
+    fn test_minimum_k() {
+        let k = minimum_k::<Fr>(PuzzleHash::Poseidon);
+        println!("rows: {}, minimum k: {}", ROWS, k);
+        assert!(k <= K);
+        MockProver::run(
+            k,
+            &sample_circuit(),
+            vec![vec![unsolved_hash(&widen(UNSOLVED))]],
+        )
+        .unwrap()
+        .assert_satisfied();
+        // the mock prover panics on the rows past the end
+        assert!(panic::catch_unwind(|| {
+            MockProver::run(k - 1, &sample_circuit(), vec![vec![Fr::from(0)]])
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn test_keccak_circuit() {
+        let circuit = sample_circuit().with_hash(PuzzleHash::Keccak256);
+        let public_inputs = vec![PuzzleHash::Keccak256.hash::<Fr>(&circuit.unsolved)];
+        MockProver::run(KECCAK_K, &circuit, vec![public_inputs])
+            .unwrap()
+            .assert_satisfied();
+
+        let mut other = circuit.unsolved;
+        other[4][4] = 6;
+        let public_inputs = vec![PuzzleHash::Keccak256.hash::<Fr>(&other)];
+        let prover = MockProver::run(KECCAK_K, &circuit, vec![public_inputs]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // the Poseidon hash is not accepted either
+        let public_inputs = vec![unsolved_hash::<Fr>(&circuit.unsolved)];
+        let prover = MockProver::run(KECCAK_K, &circuit, vec![public_inputs]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let rows = rows(PuzzleHash::Keccak256);
+        let k = minimum_k::<Fr>(PuzzleHash::Keccak256);
+        println!("rows: {}, minimum k: {}", rows, k);
+        assert!(PuzzleHash::Keccak256.rows() > 2 * PuzzleHash::Poseidon.rows());
+        assert_eq!(k, KECCAK_K);
+    }
+}
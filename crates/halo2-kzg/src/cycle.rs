@@ -0,0 +1,96 @@
+//! The sudoku circuit on both halves of the bn254/Grumpkin cycle.
+//!
+//! The scalar field of Grumpkin is the base field of bn254 and the other way
+//! around, so the points of each curve are pairs of native values in a
+//! circuit over the scalar field of the other. `SudokuCircuit` is generic
+//! over the field and is instantiated on both.
+//!
+//! Only the bn254 half can be proved with halo2: `q - 1` of the bn254 base
+//! field has a 2-adicity of 1, so there is no evaluation domain of `2^k`
+//! rows over `grumpkin::Fr` and keygen fails before any commitment is made.
+//! The Grumpkin instantiation is checked with the mock prover, and the
+//! recursion on bn254 verifies a proof of `KzgProver` in a circuit over
+//! `bn256::Fr` itself, deferring the pairings of its openings to the last
+//! layer.
+
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::{bn256, grumpkin},
+};
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::{
+    circuit::{minimum_k, unsolved_hash, PuzzleHash, SudokuCircuit},
+    gadgets::CircuitField,
+};
+
+/// The sudoku circuit over the scalar field of bn254, proved by `KzgProver`.
+pub type Bn254Circuit = SudokuCircuit<bn256::Fr>;
+/// The sudoku circuit over the scalar field of Grumpkin, the base field of
+/// bn254.
+pub type GrumpkinCircuit = SudokuCircuit<grumpkin::Fr>;
+
+/// Checks that `solution` solves `puzzle` in the circuit over `F`, without a
+/// proof, returning the constraints it fails.
+pub fn check<F: CircuitField>(
+    puzzle: &Puzzle,
+    solution: &Solution,
+) -> Result<(), Vec<VerifyFailure>> {
+    let circuit = SudokuCircuit::<F>::new(puzzle, solution);
+    let public_inputs = vec![unsolved_hash::<F>(&puzzle.map(u64::from))];
+    MockProver::run(
+        minimum_k::<F>(PuzzleHash::Poseidon),
+        &circuit,
+        vec![public_inputs],
+    )
+    .expect("the circuit is synthesized")
+    .verify()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic;
+
+    use halo2_proofs::{
+        arithmetic::CurveAffine,
+        halo2curves::{bn256, grumpkin},
+        plonk::keygen_vk,
+        poly::{commitment::ParamsProver, ipa::commitment::ParamsIPA},
+    };
+    use zk_sudoku_core::{sample, Solution};
+
+    use super::{check, GrumpkinCircuit};
+    use crate::circuit::K;
+
+    #[test]
+    fn test_cycle() {
+        // the coordinates of each curve are scalars of the other one
+        let p = bn256::G1Affine::generator().coordinates().unwrap();
+        let (x, y): (grumpkin::Fr, grumpkin::Fr) = (*p.x(), *p.y());
+        assert_eq!(y.square(), x.square() * x + bn256::G1Affine::b());
+        let q = grumpkin::G1Affine::generator().coordinates().unwrap();
+        let (x, y): (bn256::Fr, bn256::Fr) = (*q.x(), *q.y());
+        assert_eq!(y.square(), x.square() * x + grumpkin::G1Affine::b());
+    }
+
+    #[test]
+    fn test_check() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        check::<bn256::Fr>(&puzzle, &solution).unwrap();
+        check::<grumpkin::Fr>(&puzzle, &solution).unwrap();
+
+        // swapping two cells of a row keeps the rows valid but not the columns
+        let mut cells = *solution.cells();
+        cells[0].swap(0, 1);
+        let swapped = Solution::new_unchecked(cells);
+        assert!(check::<grumpkin::Fr>(&puzzle, &swapped).is_err());
+    }
+
+    #[test]
+    fn test_grumpkin_keygen() {
+        // no evaluation domain over the scalar field of Grumpkin
+        let params = ParamsIPA::<grumpkin::G1Affine>::new(K);
+        assert!(panic::catch_unwind(|| keygen_vk(&params, &GrumpkinCircuit::default())).is_err());
+    }
+}
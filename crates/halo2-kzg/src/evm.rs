@@ -0,0 +1,178 @@
+//! Verifying sudoku proofs on the EVM, with the verifier contract generated
+//! by snark-verifier for the verifying key.
+//!
+//! The contract draws its challenges with Keccak, so the proofs it accepts
+//! are made by `prove` here rather than by `crate::prove`, whose transcript
+//! is Blake2b. The calldata is the instance, the hash of the puzzle,
+//! followed by the proof. With `PuzzleHash::Keccak256` the instance is
+//! `uint256(keccak256(cells)) >> 8`, which a contract computes from the
+//! cells themselves.
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{create_proof, verify_proof, Error},
+    poly::kzg::{
+        commitment::KZGCommitmentScheme,
+        multiopen::{ProverSHPLONK, VerifierSHPLONK},
+        strategy::SingleStrategy,
+    },
+};
+use rand_core::{CryptoRng, RngCore};
+use snark_verifier_sdk::{
+    evm::{encode_calldata, gen_evm_verifier_sol_code},
+    snark_verifier::{
+        loader::{evm::compile_solidity, native::NativeLoader},
+        system::halo2::transcript::evm::EvmTranscript,
+    },
+    CircuitExt, SHPLONK,
+};
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::{
+    circuit::{PuzzleHash, SudokuCircuit},
+    prover::{public_inputs, KzgProvingKey, KzgVerifyingKey},
+};
+
+pub mod aggregation;
+
+impl CircuitExt<Fr> for SudokuCircuit<Fr> {
+    fn num_instance(&self) -> Vec<usize> {
+        vec![1]
+    }
+
+    fn instances(&self) -> Vec<Vec<Fr>> {
+        vec![vec![self.hash.hash(&self.unsolved)]]
+    }
+}
+
+/// The Solidity source of the verifier contract of `vk`, whose fallback
+/// function takes the calldata of `calldata` and reverts on a rejected
+/// proof.
+pub fn verifier_code(vk: &KzgVerifyingKey) -> String {
+    gen_evm_verifier_sol_code::<SudokuCircuit<Fr>, SHPLONK>(&vk.params, &vk.key, vec![1])
+}
+
+/// The deployment bytecode of `verifier_code`, compiled by the `solc` found
+/// on the `PATH`.
+pub fn deployment_code(vk: &KzgVerifyingKey) -> Vec<u8> {
+    compile_solidity(&verifier_code(vk))
+}
+
+/// Proves that `solution` solves `puzzle` for the verifier contract.
+pub fn prove<R: RngCore + CryptoRng>(
+    pk: &KzgProvingKey,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+) -> Result<Vec<u8>, Error> {
+    let circuit = SudokuCircuit::<Fr>::new(puzzle, solution).with_hash(pk.hash);
+    let public_inputs = public_inputs(pk.hash, puzzle);
+
+    let mut transcript = EvmTranscript::<G1Affine, NativeLoader, _, _>::new(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+        &pk.params,
+        &pk.key,
+        &[circuit],
+        &[&[&public_inputs]],
+        rng,
+        &mut transcript,
+    )?;
+    Ok(transcript.finalize())
+}
+
+/// Verifies a proof of `prove` out of the EVM, as the contract does.
+pub fn verify(vk: &KzgVerifyingKey, puzzle: &Puzzle, proof: &[u8]) -> Result<bool, Error> {
+    let public_inputs = public_inputs(vk.hash, puzzle);
+
+    let mut transcript = EvmTranscript::<G1Affine, NativeLoader, _, _>::new(proof);
+    let result = verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+        &vk.params,
+        &vk.key,
+        SingleStrategy::new(&vk.params),
+        &[&[&public_inputs]],
+        &mut transcript,
+    );
+    match result {
+        Ok(()) => Ok(true),
+        Err(Error::ConstraintSystemFailure) | Err(Error::Opening) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// The calldata of the verifier contract for a proof of `prove` with the
+/// keys of `hash`.
+pub fn calldata(hash: PuzzleHash, puzzle: &Puzzle, proof: &[u8]) -> Vec<u8> {
+    encode_calldata(&[public_inputs(hash, puzzle)], proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::ThreadRng;
+    use sha3::{Digest, Keccak256};
+    use snark_verifier_sdk::evm::evm_verify;
+    use zk_sudoku_core::{public_input::cell_bytes, sample, Puzzle};
+
+    use super::{calldata, deployment_code, prove, verifier_code, verify};
+    use crate::{
+        circuit::{unsolved_hash, PuzzleHash, KECCAK_K},
+        prover::setup,
+        KzgProver,
+    };
+
+    #[test]
+    fn test_evm_proof() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ThreadRng::default();
+        let (pk, vk) = setup(KzgProver::default().k, PuzzleHash::Poseidon, &mut rng).unwrap();
+
+        let proof = prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        assert!(verify(&vk, &puzzle, &proof).unwrap());
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        assert!(!verify(&vk, &Puzzle::new(other).unwrap(), &proof).unwrap());
+
+        // the hash as a big endian word, then the proof
+        let calldata = calldata(PuzzleHash::Poseidon, &puzzle, &proof);
+        let mut hash =
+            unsolved_hash::<halo2_proofs::halo2curves::bn256::Fr>(&puzzle.map(u64::from))
+                .to_bytes();
+        hash.reverse();
+        assert_eq!(calldata[..32], hash);
+        assert_eq!(calldata[32..], proof);
+
+        let code = verifier_code(&vk);
+        assert!(code.contains("contract Halo2Verifier"));
+    }
+
+    #[test]
+    fn test_evm_keccak() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ThreadRng::default();
+        let (pk, vk) = setup(KECCAK_K, PuzzleHash::Keccak256, &mut rng).unwrap();
+
+        let proof = prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        assert!(verify(&vk, &puzzle, &proof).unwrap());
+
+        // uint256(keccak256(cells)) >> 8, as a contract computes it
+        let calldata = calldata(PuzzleHash::Keccak256, &puzzle, &proof);
+        let digest = Keccak256::digest(&cell_bytes(puzzle.cells()));
+        assert_eq!(calldata[0], 0);
+        assert_eq!(calldata[1..32], digest[..31]);
+    }
+
+    #[test]
+    #[ignore = "needs solc on the PATH"]
+    fn test_evm_verify() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ThreadRng::default();
+        let (pk, vk) = setup(KzgProver::default().k, PuzzleHash::Poseidon, &mut rng).unwrap();
+
+        let proof = prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        let instances = vec![vec![unsolved_hash(&puzzle.map(u64::from))]];
+        let gas = evm_verify(deployment_code(&vk), instances, proof).unwrap();
+        println!("gas: {}", gas);
+    }
+}
@@ -0,0 +1,286 @@
+//! Aggregating sudoku proofs into one proof for the EVM.
+//!
+//! The aggregation circuit of snark-verifier verifies the sudoku proofs over
+//! `bn256::Fr`, deferring their pairings to an accumulator of 12 limbs, the
+//! first instance values of its proof. The hashes of the puzzles follow, one
+//! per aggregated proof, so the contract knows which puzzles were solved.
+//! The sudoku proofs hash their transcript with Poseidon, which is cheaper
+//! in-circuit, and must be made with parameters of the same setup, e.g.
+//! `sudoku_params`. The puzzle hash of the sudoku circuit is kept, so the
+//! hashes are keccak256 ones with `PuzzleHash::Keccak256`.
+
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::bn256::{Bn256, Fq, Fr, G1Affine},
+    plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Error, ProvingKey},
+    poly::{
+        commitment::{Params, ParamsProver},
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy,
+        },
+    },
+};
+use rand_core::{CryptoRng, RngCore};
+use snark_verifier_sdk::{
+    evm::{encode_calldata, gen_evm_verifier_sol_code},
+    halo2::{
+        aggregation::{AggregationCircuit, AggregationConfigParams, VerifierUniversality},
+        gen_dummy_snark_from_vk, PoseidonTranscript, POSEIDON_SPEC,
+    },
+    snark_verifier::{
+        halo2_base::gates::{circuit::CircuitBuilderStage, flex_gate::MultiPhaseThreadBreakPoints},
+        loader::native::NativeLoader,
+        pcs::{
+            kzg::{KzgAccumulator, KzgDecidingKey},
+            AccumulationDecider,
+        },
+        system::halo2::{compile, transcript::evm::EvmTranscript, Config},
+        util::arithmetic::fe_from_limbs,
+    },
+    CircuitExt, Snark, BITS, LIMBS, SHPLONK,
+};
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::{
+    circuit::{PuzzleHash, SudokuCircuit},
+    prover::{public_inputs, KzgProvingKey, KzgVerifyingKey},
+};
+
+/// log2 of the rows of the aggregation circuit, enough for one sudoku
+/// proof, the parameters of the setup being of this size.
+pub const AGGREGATION_K: u32 = 20;
+
+/// The parameters of the sudoku circuit with `hash`, the first `2^k` points
+/// of the parameters of the aggregation circuit.
+pub fn sudoku_params(params: &ParamsKZG<Bn256>, hash: PuzzleHash) -> ParamsKZG<Bn256> {
+    let mut params = params.clone();
+    params.downsize(hash.k());
+    params
+}
+
+/// A sudoku proof to aggregate, with the protocol of its verifying key.
+pub fn snark<R: RngCore + CryptoRng>(
+    pk: &KzgProvingKey,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+) -> Result<Snark, Error> {
+    let circuit = SudokuCircuit::<Fr>::new(puzzle, solution).with_hash(pk.hash);
+    let instances = circuit.instances();
+    let protocol = compile(
+        &pk.params,
+        pk.key.get_vk(),
+        Config::kzg().with_num_instance(circuit.num_instance()),
+    );
+
+    let mut transcript =
+        PoseidonTranscript::<NativeLoader, Vec<u8>>::from_spec(vec![], POSEIDON_SPEC.clone());
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+        &pk.params,
+        &pk.key,
+        &[circuit],
+        &[&[&public_inputs(pk.hash, puzzle)]],
+        rng,
+        &mut transcript,
+    )?;
+    Ok(Snark::new(protocol, instances, transcript.finalize()))
+}
+
+/// The keys of the aggregation circuit for a number of sudoku proofs, with
+/// the layout found at keygen, which the prover must follow.
+#[derive(Debug, Clone)]
+pub struct AggregationKeys {
+    pub params: ParamsKZG<Bn256>,
+    pub config: AggregationConfigParams,
+    pub break_points: MultiPhaseThreadBreakPoints,
+    pub pk: ProvingKey<G1Affine>,
+    pub num_snarks: usize,
+}
+
+fn circuit(
+    stage: CircuitBuilderStage,
+    config: AggregationConfigParams,
+    params: &ParamsKZG<Bn256>,
+    snarks: Vec<Snark>,
+) -> AggregationCircuit {
+    let mut circuit = AggregationCircuit::new::<SHPLONK>(
+        stage,
+        config,
+        params,
+        snarks,
+        VerifierUniversality::None,
+    );
+    // the hashes of the puzzles after the accumulator
+    circuit.expose_previous_instances(false);
+    circuit
+}
+
+/// Generates the keys of the circuit aggregating `num_snarks` proofs of the
+/// sudoku circuit of `vk`, whose parameters are
+/// `sudoku_params(&params, vk.hash)`.
+pub fn keygen(
+    params: ParamsKZG<Bn256>,
+    vk: &KzgVerifyingKey,
+    num_snarks: usize,
+) -> Result<AggregationKeys, Error> {
+    let snarks = (0..num_snarks)
+        .map(|_| gen_dummy_snark_from_vk::<SHPLONK>(&vk.params, &vk.key, vec![1], None))
+        .collect();
+    let config = AggregationConfigParams {
+        degree: params.k(),
+        lookup_bits: params.k() as usize - 1,
+        ..Default::default()
+    };
+    let mut circuit = circuit(CircuitBuilderStage::Keygen, config, &params, snarks);
+    let config = circuit.calculate_params(Some(10));
+
+    let vk = keygen_vk(&params, &circuit)?;
+    let pk = keygen_pk(&params, vk, &circuit)?;
+    Ok(AggregationKeys {
+        break_points: circuit.break_points(),
+        params,
+        config,
+        pk,
+        num_snarks,
+    })
+}
+
+/// Aggregates `snarks` into a proof for the contract of `verifier_code`,
+/// returning its instance values with the proof.
+pub fn prove<R: RngCore + CryptoRng>(
+    keys: &AggregationKeys,
+    snarks: Vec<Snark>,
+    rng: &mut R,
+) -> Result<(Vec<Fr>, Vec<u8>), Error> {
+    assert_eq!(snarks.len(), keys.num_snarks, "wrong number of proofs");
+    let circuit = circuit(
+        CircuitBuilderStage::Prover,
+        keys.config,
+        &keys.params,
+        snarks,
+    )
+    .use_break_points(keys.break_points.clone());
+    let instances = circuit.instances().remove(0);
+
+    let mut transcript = EvmTranscript::<G1Affine, NativeLoader, _, _>::new(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+        &keys.params,
+        &keys.pk,
+        &[circuit],
+        &[&[&instances]],
+        rng,
+        &mut transcript,
+    )?;
+    Ok((instances, transcript.finalize()))
+}
+
+/// Verifies a proof of `prove` out of the EVM, as the contract does: the
+/// proof of the aggregation circuit, then the pairing of the accumulator in
+/// the first instance values.
+pub fn verify(keys: &AggregationKeys, instances: &[Fr], proof: &[u8]) -> Result<bool, Error> {
+    let mut transcript = EvmTranscript::<G1Affine, NativeLoader, _, _>::new(proof);
+    let result = verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+        &keys.params,
+        keys.pk.get_vk(),
+        SingleStrategy::new(&keys.params),
+        &[&[instances]],
+        &mut transcript,
+    );
+    match result {
+        Ok(()) => {}
+        Err(Error::ConstraintSystemFailure) | Err(Error::Opening) => return Ok(false),
+        Err(err) => return Err(err),
+    }
+
+    // the coordinates of both points, in limbs
+    let [lhs_x, lhs_y, rhs_x, rhs_y] = [0, 1, 2, 3].map(|i| {
+        let limbs = instances[i * LIMBS..(i + 1) * LIMBS].try_into().unwrap();
+        fe_from_limbs::<Fr, Fq, LIMBS, BITS>(limbs)
+    });
+    let (Some(lhs), Some(rhs)) = (
+        Option::<G1Affine>::from(G1Affine::from_xy(lhs_x, lhs_y)),
+        Option::<G1Affine>::from(G1Affine::from_xy(rhs_x, rhs_y)),
+    ) else {
+        return Ok(false);
+    };
+    let dk: KzgDecidingKey<Bn256> =
+        (keys.params.get_g()[0], keys.params.g2(), keys.params.s_g2()).into();
+    Ok(SHPLONK::decide(&dk, KzgAccumulator::<_, NativeLoader>::new(lhs, rhs)).is_ok())
+}
+
+/// The Solidity source of the contract verifying the aggregated proofs,
+/// including the pairing of the accumulator.
+pub fn verifier_code(keys: &AggregationKeys) -> String {
+    gen_evm_verifier_sol_code::<AggregationCircuit, SHPLONK>(
+        &keys.params,
+        keys.pk.get_vk(),
+        vec![AggregationCircuit::accumulator_indices().unwrap().len() + keys.num_snarks],
+    )
+}
+
+/// The calldata of the contract of `verifier_code` for a proof of `prove`.
+pub fn calldata(instances: &[Fr], proof: &[u8]) -> Vec<u8> {
+    encode_calldata(&[instances.to_vec()], proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        halo2curves::bn256::{Bn256, Fr},
+        poly::kzg::commitment::ParamsKZG,
+    };
+    use rand::rngs::ThreadRng;
+    use snark_verifier_sdk::{evm::evm_verify, snark_verifier::loader::evm::compile_solidity};
+    use zk_sudoku_core::sample;
+
+    use super::{
+        calldata, keygen, prove, snark, sudoku_params, verifier_code, verify, AggregationKeys,
+        AGGREGATION_K,
+    };
+    use crate::{
+        circuit::{unsolved_hash, PuzzleHash},
+        prover::keygen as sudoku_keygen,
+    };
+
+    fn aggregate() -> (AggregationKeys, Vec<Fr>, Vec<u8>) {
+        let mut rng = ThreadRng::default();
+        let params = ParamsKZG::<Bn256>::setup(AGGREGATION_K, &mut rng);
+        let hash = PuzzleHash::Poseidon;
+        let (pk, vk) = sudoku_keygen(sudoku_params(&params, hash), hash).unwrap();
+        let keys = keygen(params, &vk, 1).unwrap();
+
+        let snarks = vec![snark(&pk, &sample::puzzle(), &sample::solution(), &mut rng).unwrap()];
+        let (instances, proof) = prove(&keys, snarks, &mut rng).unwrap();
+        (keys, instances, proof)
+    }
+
+    #[test]
+    #[ignore = "takes minutes and gigabytes at AGGREGATION_K"]
+    fn test_aggregation() {
+        let (keys, instances, proof) = aggregate();
+        assert!(verify(&keys, &instances, &proof).unwrap());
+        let mut forged = instances.clone();
+        forged.swap(0, 6);
+        assert!(!verify(&keys, &forged, &proof).unwrap());
+
+        // the accumulator, then the hash of the puzzle
+        assert_eq!(instances.len(), 13);
+        assert_eq!(
+            instances[12],
+            unsolved_hash(&sample::puzzle().map(u64::from))
+        );
+        assert_eq!(calldata(&instances, &proof).len(), 13 * 32 + proof.len());
+        assert!(verifier_code(&keys).contains("contract Halo2Verifier"));
+    }
+
+    #[test]
+    #[ignore = "takes minutes and gigabytes at AGGREGATION_K, and needs solc on the PATH"]
+    fn test_aggregation_evm_verify() {
+        let (keys, instances, proof) = aggregate();
+        let deployment_code = compile_solidity(&verifier_code(&keys));
+        let gas = evm_verify(deployment_code, vec![instances], proof).unwrap();
+        println!("gas: {}", gas);
+    }
+}
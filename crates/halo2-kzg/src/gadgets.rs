@@ -0,0 +1,71 @@
+use halo2_proofs::{
+    circuit::{Cell, Region, Value},
+    halo2curves::ff::{FromUniformBytes, PrimeField},
+    plonk::{Advice, Column, ConstraintSystem, Expression},
+};
+
+pub mod keccak;
+pub mod permutation;
+pub mod poseidon;
+
+/// The fields the sudoku circuit is built over, those of the curves with
+/// Poseidon constants generated at runtime.
+pub trait CircuitField: PrimeField + FromUniformBytes<64> + Ord {}
+
+impl<F: PrimeField + FromUniformBytes<64> + Ord> CircuitField for F {}
+
+/// An assigned advice cell with its value.
+///
+/// halo2-axiom's `AssignedCell` borrows its value from the region it was
+/// assigned in, this keeps a copy so cells can be passed between regions.
+#[derive(Debug, Clone, Copy)]
+pub struct Word<F> {
+    pub cell: Cell,
+    pub value: Value<F>,
+}
+
+impl<F: PrimeField> Word<F> {
+    pub fn assign(
+        region: &mut Region<'_, F>,
+        column: Column<Advice>,
+        offset: usize,
+        value: Value<F>,
+    ) -> Self {
+        let cell = region.assign_advice(column, offset, value).cell();
+        Self { cell, value }
+    }
+
+    /// Assigns the value of this word at `offset` of `column`, constrained to
+    /// be equal to it.
+    pub fn copy(&self, region: &mut Region<'_, F>, column: Column<Advice>, offset: usize) -> Self {
+        let word = Self::assign(region, column, offset, self.value);
+        region.constrain_equal(word.cell, self.cell);
+        word
+    }
+}
+
+/// Given a start, a end, and a value v, returns the expression
+/// (start - v) * (start + 1 - v) * ... * (end - v)
+pub fn range_check<F: PrimeField>(start: usize, end: usize, value: Expression<F>) -> Expression<F> {
+    assert!(end > start);
+    (start + 1..=end).fold(
+        value.clone() - Expression::Constant(F::from(start as u64)),
+        |expr, i| expr * (Expression::Constant(F::from(i as u64)) - value.clone()),
+    )
+}
+
+/// Raises the degree of the constraint system to the one of its gates.
+///
+/// halo2-axiom caps the degree at `MAX_DEGREE`, 5 unless set in the
+/// environment, which is too low for the range checks of degree 11 and the
+/// S-boxes of degree 6. Called once all the gates are created.
+pub fn fit_degree<F: PrimeField>(meta: &mut ConstraintSystem<F>) {
+    let degree = meta
+        .gates()
+        .iter()
+        .flat_map(|gate| gate.polynomials())
+        .map(|poly| poly.degree())
+        .max()
+        .unwrap_or(1);
+    meta.set_minimum_degree(degree);
+}
@@ -0,0 +1,513 @@
+use std::{array, marker::PhantomData};
+
+use halo2_proofs::{
+    circuit::{Region, Value},
+    halo2curves::ff::PrimeField,
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::Word;
+
+// Rotation offsets of rho, indexed by x + 5 * y
+const RHO: [usize; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Bytes absorbed per permutation by keccak256.
+pub const RATE: usize = 136;
+/// Bytes of the digest returned by `KeccakConfig::hash`, as many as
+/// `zk_sudoku_core::public_input::keccak256_input` keeps.
+pub const DIGEST_BYTES: usize = 31;
+
+/// Rows of each round: 9 per column parity of theta, 3 per xor of theta,
+/// 4 per lane of chi and 3 for iota.
+pub const ROWS_PER_ROUND: usize = 5 * 9 + 5 * 3 + 25 * 3 + 25 * 4 + 3;
+/// Rows taken by the hash of a message: 2 per lane of the padded block, the
+/// rounds, then the 4 lanes of the digest and its word.
+pub const ROWS: usize = 25 * 2 + ROUND_CONSTANTS.len() * ROWS_PER_ROUND + 5;
+
+/// A lane of the state, its 64 bits on a row, least significant first.
+pub type Lane<F> = [Word<F>; 64];
+
+fn xor<F: PrimeField>(a: Expression<F>, b: Expression<F>) -> Expression<F> {
+    a.clone() + b.clone() - Expression::Constant(F::from(2)) * a * b
+}
+
+fn xor_value<F: PrimeField>(a: Value<F>, b: Value<F>) -> Value<F> {
+    a.zip(b).map(|(a, b)| a + b - (a * b).double())
+}
+
+/// The weight of bit `i` of lane `lane` in the digest word, the first
+/// `DIGEST_BYTES` bytes of the state read as a big endian integer.
+fn digest_weight<F: PrimeField>(lane: usize, i: usize) -> Option<F> {
+    let byte = lane * 8 + i / 8;
+    (byte < DIGEST_BYTES)
+        .then(|| F::from(2).pow_vartime([(i % 8 + 8 * (DIGEST_BYTES - 1 - byte)) as u64]))
+}
+
+/// Keccak256 of fewer than `RATE` bytes, a single permutation with the bits
+/// of each lane on a row, one xor, chi or packing per gate.
+///
+/// The lanes of theta and chi are copied next to each other before each
+/// gate, the rotations of theta and rho being the order of the copies, and
+/// the round constants of iota are lanes of constants. The message bytes are
+/// copied after the lane they are packed into, with the padding of the EVM
+/// opcode.
+///
+/// ```text
+///     bits[0..8]       bits[8..64]  | q_pack | q_xor | q_chi | q_digest
+///  -----------------------------------------------------------------------
+///   bits of lane 0 ...              |   1    |   0   |   0   |    0
+///   m0 .. m7                        |   0    |   0   |   0   |    0
+///     ...
+///   a                               |   0    |   1   |   0   |    0
+///   b                               |   0    |   0   |   0   |    0
+///   a ^ b                           |   0    |   0   |   0   |    0
+///     ...
+///   a                               |   0    |   0   |   1   |    0
+///   b                               |   0    |   0   |   0   |    0
+///   c                               |   0    |   0   |   0   |    0
+///   a ^ (!b & c)                    |   0    |   0   |   0   |    0
+///     ...
+///   lanes 0 to 3 on 4 rows          |   0    |   0   |   0   |    1
+///   digest                          |   0    |   0   |   0   |    0
+/// ```
+///
+/// About twice as many rows as the Poseidon sponge and 64 advice columns
+/// where it takes 5, the price of a digest the EVM computes natively.
+#[derive(Debug, Clone)]
+pub struct KeccakConfig<F> {
+    pub bits: [Column<Advice>; 64],
+    pub q_pack: Selector,
+    pub q_xor: Selector,
+    pub q_chi: Selector,
+    pub q_digest: Selector,
+    _marker: PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakConfig<F> {
+    /// Configures the permutation over 64 advice columns of its own, with the
+    /// padding and the round constants assigned from a column of constants
+    /// of its own.
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> Self {
+        let bits = [(); 64].map(|_| meta.advice_column());
+        for column in bits {
+            meta.enable_equality(column);
+        }
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let q_pack = meta.selector();
+        let q_xor = meta.selector();
+        let q_chi = meta.selector();
+        let q_digest = meta.selector();
+
+        meta.create_gate("pack", |meta| {
+            let q = meta.query_selector(q_pack);
+            let lane = bits.map(|bit| meta.query_advice(bit, Rotation::cur()));
+            let one = Expression::Constant(F::ONE);
+            let two = Expression::Constant(F::from(2));
+
+            let mut constraints = lane
+                .iter()
+                .map(|bit| bit.clone() * (one.clone() - bit.clone()))
+                .collect::<Vec<_>>();
+            for (k, byte_bits) in lane.chunks(8).enumerate() {
+                let byte = meta.query_advice(bits[k], Rotation::next());
+                let packed = byte_bits
+                    .iter()
+                    .rev()
+                    .fold(Expression::Constant(F::ZERO), |acc, bit| {
+                        acc * two.clone() + bit.clone()
+                    });
+                constraints.push(packed - byte);
+            }
+            Constraints::with_selector(q, constraints)
+        });
+
+        meta.create_gate("xor", |meta| {
+            let q = meta.query_selector(q_xor);
+            let constraints = bits.map(|bit| {
+                let a = meta.query_advice(bit, Rotation::cur());
+                let b = meta.query_advice(bit, Rotation::next());
+                let out = meta.query_advice(bit, Rotation(2));
+                xor(a, b) - out
+            });
+            Constraints::with_selector(q, constraints)
+        });
+
+        meta.create_gate("chi", |meta| {
+            let q = meta.query_selector(q_chi);
+            let constraints = bits.map(|bit| {
+                let a = meta.query_advice(bit, Rotation::cur());
+                let b = meta.query_advice(bit, Rotation::next());
+                let c = meta.query_advice(bit, Rotation(2));
+                let out = meta.query_advice(bit, Rotation(3));
+                xor(a, (Expression::Constant(F::ONE) - b) * c) - out
+            });
+            Constraints::with_selector(q, constraints)
+        });
+
+        meta.create_gate("digest", |meta| {
+            let q = meta.query_selector(q_digest);
+            let mut word = Expression::Constant(F::ZERO);
+            for lane in 0..4 {
+                for (i, bit) in bits.iter().enumerate() {
+                    if let Some(weight) = digest_weight::<F>(lane, i) {
+                        word = word
+                            + meta.query_advice(*bit, Rotation(lane as i32))
+                                * Expression::Constant(weight);
+                    }
+                }
+            }
+            let digest = meta.query_advice(bits[0], Rotation(4));
+            Constraints::with_selector(q, [word - digest])
+        });
+
+        Self {
+            bits,
+            q_pack,
+            q_xor,
+            q_chi,
+            q_digest,
+            _marker: PhantomData,
+        }
+    }
+
+    fn assign_lane(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lane: [Value<F>; 64],
+    ) -> Lane<F> {
+        array::from_fn(|i| Word::assign(region, self.bits[i], offset, lane[i]))
+    }
+
+    /// Copies `lane` rotated left by `rotation` bits to the row at `offset`.
+    fn copy_lane(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        lane: &Lane<F>,
+        rotation: usize,
+    ) -> Lane<F> {
+        array::from_fn(|i| lane[(i + 64 - rotation) % 64].copy(region, self.bits[i], offset))
+    }
+
+    fn constant_lane(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        value: u64,
+    ) -> Result<Lane<F>, Error> {
+        let mut lane = vec![];
+        for (i, column) in self.bits.iter().enumerate() {
+            let bit = F::from((value >> i) & 1);
+            let cell = region.assign_advice_from_constant(|| "constant", *column, offset, bit)?;
+            lane.push(Word {
+                cell: cell.cell(),
+                value: Value::known(bit),
+            });
+        }
+        Ok(lane.try_into().unwrap())
+    }
+
+    /// Xors the lanes at `offset` and the next row into the row after.
+    fn xor(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        a: &Lane<F>,
+        b: &Lane<F>,
+    ) -> Result<Lane<F>, Error> {
+        self.q_xor.enable(region, offset)?;
+        let out = array::from_fn(|i| xor_value(a[i].value, b[i].value));
+        Ok(self.assign_lane(region, offset + 2, out))
+    }
+
+    /// Copies both lanes rotated by their offsets to the rows at `offset`
+    /// and xors them on the third row.
+    fn xor_copies(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        (a, a_rotation): (&Lane<F>, usize),
+        (b, b_rotation): (&Lane<F>, usize),
+    ) -> Result<Lane<F>, Error> {
+        let a = self.copy_lane(region, offset, a, a_rotation);
+        let b = self.copy_lane(region, offset + 1, b, b_rotation);
+        self.xor(region, offset, &a, &b)
+    }
+
+    /// Packs the bytes of lane `index` of the padded block on the 2 rows at
+    /// `offset`.
+    fn pack(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        message: &[Word<F>],
+        index: usize,
+    ) -> Result<Lane<F>, Error> {
+        self.q_pack.enable(region, offset)?;
+        let mut bytes = vec![];
+        for (k, column) in self.bits[..8].iter().enumerate() {
+            let position = index * 8 + k;
+            let byte = match message.get(position) {
+                Some(word) => word.copy(region, *column, offset + 1).value,
+                None => {
+                    let mut padding = 0;
+                    if position == message.len() {
+                        padding ^= 0x01;
+                    }
+                    if position == RATE - 1 {
+                        padding ^= 0x80;
+                    }
+                    let padding = F::from(padding);
+                    region.assign_advice_from_constant(
+                        || "padding",
+                        *column,
+                        offset + 1,
+                        padding,
+                    )?;
+                    Value::known(padding)
+                }
+            };
+            bytes.push(byte);
+        }
+        // the bytes are below 256, the first byte of their little endian
+        // representation
+        let lane = array::from_fn(|i| {
+            bytes[i / 8].map(|byte| F::from(((byte.to_repr().as_ref()[0] >> (i % 8)) & 1) as u64))
+        });
+        Ok(self.assign_lane(region, offset, lane))
+    }
+
+    /// Hashes `message`, bytes checked to be below 256, on the `ROWS` rows of
+    /// `region` from `offset`, returning the first `DIGEST_BYTES` bytes of the
+    /// digest as a big endian integer.
+    pub fn hash(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        message: &[Word<F>],
+    ) -> Result<Word<F>, Error> {
+        assert!(
+            message.len() < RATE,
+            "the keccak256 chip only absorbs one block"
+        );
+
+        let mut offset = offset;
+        let mut state = vec![];
+        for index in 0..25 {
+            state.push(self.pack(region, offset, message, index)?);
+            offset += 2;
+        }
+
+        for round_constant in ROUND_CONSTANTS {
+            // theta
+            let mut parities = vec![];
+            for x in 0..5 {
+                let mut parity = self.copy_lane(region, offset, &state[x], 0);
+                for y in 1..5 {
+                    let lane = self.copy_lane(region, offset + 1, &state[x + 5 * y], 0);
+                    parity = self.xor(region, offset, &parity, &lane)?;
+                    offset += 2;
+                }
+                parities.push(parity);
+                offset += 1;
+            }
+            let mut d = vec![];
+            for x in 0..5 {
+                d.push(self.xor_copies(
+                    region,
+                    offset,
+                    (&parities[(x + 4) % 5], 0),
+                    (&parities[(x + 1) % 5], 1),
+                )?);
+                offset += 3;
+            }
+            for (i, lane) in state.iter_mut().enumerate() {
+                *lane = self.xor_copies(region, offset, (lane, 0), (&d[i % 5], 0))?;
+                offset += 3;
+            }
+
+            // rho and pi, the lanes being rotated as they are copied for chi
+            let mut rotated = [(0, 0); 25];
+            for x in 0..5 {
+                for y in 0..5 {
+                    rotated[y + 5 * ((2 * x + 3 * y) % 5)] = (x + 5 * y, RHO[x + 5 * y]);
+                }
+            }
+
+            // chi
+            let mut next = vec![];
+            for y in 0..5 {
+                for x in 0..5 {
+                    self.q_chi.enable(region, offset)?;
+                    let [a, b, c] = [x, (x + 1) % 5, (x + 2) % 5].map(|x| {
+                        let (lane, rotation) = rotated[x + 5 * y];
+                        offset += 1;
+                        self.copy_lane(region, offset - 1, &state[lane], rotation)
+                    });
+                    let out = array::from_fn(|i| {
+                        let masked = b[i].value.zip(c[i].value).map(|(b, c)| (F::ONE - b) * c);
+                        xor_value(a[i].value, masked)
+                    });
+                    next.push(self.assign_lane(region, offset, out));
+                    offset += 1;
+                }
+            }
+            state = next;
+
+            // iota
+            let a = self.copy_lane(region, offset, &state[0], 0);
+            let constant = self.constant_lane(region, offset + 1, round_constant)?;
+            state[0] = self.xor(region, offset, &a, &constant)?;
+            offset += 3;
+        }
+
+        self.q_digest.enable(region, offset)?;
+        let mut digest = Value::known(F::ZERO);
+        for (index, lane) in state[..4].iter().enumerate() {
+            let lane = self.copy_lane(region, offset + index, lane, 0);
+            for (i, bit) in lane.iter().enumerate() {
+                if let Some(weight) = digest_weight::<F>(index, i) {
+                    digest = digest.zip(bit.value).map(|(acc, bit)| acc + bit * weight);
+                }
+            }
+        }
+        Ok(Word::assign(region, self.bits[0], offset + 4, digest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        arithmetic::Field,
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        halo2curves::bn256::Fr,
+        plonk::{Circuit, Instance},
+    };
+    use sha3::{Digest, Keccak256};
+
+    use super::*;
+    use crate::gadgets::fit_degree;
+
+    struct HashCircuit<F> {
+        message: Vec<u64>,
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: PrimeField> Circuit<F> for HashCircuit<F> {
+        type Config = (KeccakConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                message: vec![0; self.message.len()],
+                _marker: PhantomData,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let config = KeccakConfig::configure(meta);
+            fit_degree(meta);
+            (config, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let digest = layouter.assign_region(
+                || "hash",
+                |mut region| {
+                    let message = self
+                        .message
+                        .iter()
+                        .enumerate()
+                        .map(|(i, byte)| {
+                            Word::assign(
+                                &mut region,
+                                config.bits[i % 64],
+                                i / 64,
+                                Value::known(F::from(*byte)),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    config.hash(&mut region, 3, &message)
+                },
+            )?;
+            layouter.constrain_instance(digest.cell, instance, 0);
+            Ok(())
+        }
+    }
+
+    fn digest(message: &[u8]) -> Fr {
+        Keccak256::digest(message)[..DIGEST_BYTES]
+            .iter()
+            .fold(Fr::ZERO, |acc, byte| {
+                acc * Fr::from(256) + Fr::from(*byte as u64)
+            })
+    }
+
+    #[test]
+    fn test_keccak256() {
+        for len in [0, 81, 135] {
+            let message = (0..len).map(|i| (i * 7 % 10) as u8).collect::<Vec<u8>>();
+            let circuit = HashCircuit::<Fr> {
+                message: message.iter().map(|byte| *byte as u64).collect(),
+                _marker: PhantomData,
+            };
+            MockProver::run(13, &circuit, vec![vec![digest(&message)]])
+                .unwrap()
+                .assert_satisfied();
+
+            let prover =
+                MockProver::run(13, &circuit, vec![vec![digest(&message) + Fr::ONE]]).unwrap();
+            assert!(prover.verify().is_err());
+        }
+
+        // 256 has the bits of 0 in its first byte
+        let mut message = vec![0; 81];
+        message[0] = 256;
+        let circuit = HashCircuit::<Fr> {
+            message,
+            _marker: PhantomData,
+        };
+        let prover = MockProver::run(13, &circuit, vec![vec![digest(&[0; 81])]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
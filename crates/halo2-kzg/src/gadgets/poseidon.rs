@@ -0,0 +1,351 @@
+use std::{array, marker::PhantomData};
+
+use halo2_proofs::{
+    circuit::{Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Constraints, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+use poseidon_primitives::poseidon::primitives::{ConstantLength, Domain, Hash, Spec};
+
+use super::{CircuitField, Word};
+
+pub const WIDTH: usize = 3;
+pub const RATE: usize = 2;
+
+/// Poseidon with the x^5 S-box and a width of 3, with the rounds for 128 bits
+/// of security over 254 bit fields, R_F = 8 and R_P = 57. The round constants
+/// and the MDS matrix are generated by the Grain LFSR of the reference
+/// implementation, for the field of the circuit.
+#[derive(Debug)]
+pub struct P128Pow5T3<F>(PhantomData<F>);
+
+impl<F: CircuitField> Spec<F, WIDTH, RATE> for P128Pow5T3<F> {
+    fn full_rounds() -> usize {
+        8
+    }
+
+    fn partial_rounds() -> usize {
+        57
+    }
+
+    fn sbox(val: F) -> F {
+        val.pow_vartime([5])
+    }
+
+    fn secure_mds() -> usize {
+        0
+    }
+}
+
+/// The Poseidon hash of `message` out of the circuit, the one `PoseidonConfig`
+/// lays out.
+pub fn hash<F: CircuitField, const L: usize>(message: [F; L]) -> F {
+    Hash::<_, P128Pow5T3<F>, ConstantLength<L>, WIDTH, RATE>::init().hash(message)
+}
+
+/// Rows of each permutation, one per round and one absorbing the message.
+pub const ROWS_PER_BLOCK: usize = 66;
+
+/// Rows taken by the hash of a message of `len` words, the last one holding
+/// the digest.
+pub const fn rows(len: usize) -> usize {
+    len.div_ceil(RATE) * ROWS_PER_BLOCK + 1
+}
+
+/// The Poseidon sponge of `hash`, one round per row.
+///
+/// A row holds the state before the round and its round constants, the next
+/// row the state after it. The message is added to the state on the rows of
+/// `s_absorb`, 2 words per permutation, the last one padded with 0.
+///
+/// ```text
+///   state[0..3] | message[0..2] |  rc[0..3]  | s_absorb | s_full | s_partial
+///  ---------------------------------------------------------------------------
+///    0  0  cap  |    m0   m1    |            |    1     |   0    |    0
+///     s + m     |               |  rc of 0   |    0     |   1    |    0
+///     ...
+///               |               |  rc of 4   |    0     |   0    |    1
+///     ...
+///   digest ..   |    m2   m3    |            |    1     |   0    |    0
+/// ```
+///
+/// This is simpler than the `Pow5Chip` of halo2_gadgets, which takes 2 rounds
+/// per row, at the cost of about twice as many rows. The rows are given by
+/// the caller, as the simple floor planner of halo2-axiom starts every region
+/// at the first row.
+#[derive(Debug, Clone)]
+pub struct PoseidonConfig<F> {
+    pub state: [Column<Advice>; WIDTH],
+    pub message: [Column<Advice>; RATE],
+    pub rc: [Column<Fixed>; WIDTH],
+    pub s_absorb: Selector,
+    pub s_full: Selector,
+    pub s_partial: Selector,
+    round_constants: Vec<[F; WIDTH]>,
+    mds: [[F; WIDTH]; WIDTH],
+}
+
+fn pow5<F: CircuitField>(x: Expression<F>) -> Expression<F> {
+    let x2 = x.clone() * x.clone();
+    x2.clone() * x2 * x
+}
+
+/// Row `row` of the MDS matrix times `words`.
+fn mix<F: CircuitField>(row: &[F; WIDTH], words: &[Expression<F>; WIDTH]) -> Expression<F> {
+    row.iter()
+        .zip(words)
+        .fold(Expression::Constant(F::ZERO), |acc, (m, word)| {
+            acc + Expression::Constant(*m) * word.clone()
+        })
+}
+
+impl<F: CircuitField> PoseidonConfig<F> {
+    /// Configures the sponge over `state` and `message`, which get equality
+    /// enabled. The initial capacity and the padding are assigned from a
+    /// column of constants of its own, the floor planner placing them from
+    /// its first row.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        state: [Column<Advice>; WIDTH],
+        message: [Column<Advice>; RATE],
+    ) -> Self {
+        let rc = [(); WIDTH].map(|_| meta.fixed_column());
+        let s_absorb = meta.selector();
+        let s_full = meta.selector();
+        let s_partial = meta.selector();
+        for column in state.into_iter().chain(message) {
+            meta.enable_equality(column);
+        }
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+
+        let (round_constants, mds, _) = P128Pow5T3::<F>::constants();
+
+        for (name, selector, full) in [
+            ("full round", s_full, true),
+            ("partial round", s_partial, false),
+        ] {
+            meta.create_gate(name, |meta| {
+                let s = meta.query_selector(selector);
+                let words: [Expression<F>; WIDTH] = array::from_fn(|j| {
+                    let word = meta.query_advice(state[j], Rotation::cur())
+                        + meta.query_fixed(rc[j], Rotation::cur());
+                    // the S-box is only applied to the first word in a partial round
+                    if full || j == 0 {
+                        pow5(word)
+                    } else {
+                        word
+                    }
+                });
+                let next = state.map(|column| meta.query_advice(column, Rotation::next()));
+                Constraints::with_selector(
+                    s,
+                    mds.iter()
+                        .zip(next)
+                        .map(|(row, next)| mix(row, &words) - next)
+                        .collect::<Vec<_>>(),
+                )
+            });
+        }
+
+        meta.create_gate("absorb", |meta| {
+            let s = meta.query_selector(s_absorb);
+            let constraints = (0..WIDTH)
+                .map(|i| {
+                    let cur = meta.query_advice(state[i], Rotation::cur());
+                    let next = meta.query_advice(state[i], Rotation::next());
+                    match message.get(i) {
+                        Some(column) => cur + meta.query_advice(*column, Rotation::cur()) - next,
+                        None => cur - next,
+                    }
+                })
+                .collect::<Vec<_>>();
+            Constraints::with_selector(s, constraints)
+        });
+
+        Self {
+            state,
+            message,
+            rc,
+            s_absorb,
+            s_full,
+            s_partial,
+            round_constants,
+            mds,
+        }
+    }
+
+    fn assign_state(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        state: [Value<F>; WIDTH],
+    ) -> [Word<F>; WIDTH] {
+        array::from_fn(|i| Word::assign(region, self.state[i], offset, state[i]))
+    }
+
+    /// Hashes `message` on the `rows(L)` rows of `region` from `offset`,
+    /// returning the digest as `hash` computes it.
+    pub fn hash<const L: usize>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        message: &[Word<F>; L],
+    ) -> Result<Word<F>, Error> {
+        let full_rounds = P128Pow5T3::<F>::full_rounds() / 2;
+        let partial_rounds = P128Pow5T3::<F>::partial_rounds();
+
+        // the initial state is fixed by the length of the message
+        let capacity = <ConstantLength<L> as Domain<F, RATE>>::initial_capacity_element();
+        let initial = [F::ZERO, F::ZERO, capacity];
+        for (column, value) in self.state.iter().zip(initial) {
+            region.assign_advice_from_constant(|| "initial state", *column, offset, value)?;
+        }
+        let mut state = initial.map(Value::known);
+        let mut digest = None;
+
+        for (block, chunk) in message.chunks(RATE).enumerate() {
+            let mut offset = offset + block * ROWS_PER_BLOCK;
+            self.s_absorb.enable(region, offset)?;
+            for (i, column) in self.message.iter().enumerate() {
+                let value = match chunk.get(i) {
+                    Some(word) => word.copy(region, *column, offset).value,
+                    None => {
+                        region.assign_advice_from_constant(
+                            || "padding",
+                            *column,
+                            offset,
+                            F::ZERO,
+                        )?;
+                        Value::known(F::ZERO)
+                    }
+                };
+                state[i] = state[i].zip(value).map(|(s, m)| s + m);
+            }
+            offset += 1;
+            let mut words = self.assign_state(region, offset, state);
+
+            for (round, rc) in self.round_constants.iter().enumerate() {
+                let full = round < full_rounds || round >= full_rounds + partial_rounds;
+                if full {
+                    self.s_full.enable(region, offset)?;
+                } else {
+                    self.s_partial.enable(region, offset)?;
+                }
+                for (column, rc) in self.rc.iter().zip(rc) {
+                    region.assign_fixed(*column, offset, *rc);
+                }
+
+                let sboxed: [Value<F>; WIDTH] = array::from_fn(|j| {
+                    state[j].map(|word| {
+                        let word = word + rc[j];
+                        if full || j == 0 {
+                            word.pow_vartime([5])
+                        } else {
+                            word
+                        }
+                    })
+                });
+                state = self.mds.map(|row| {
+                    row.iter()
+                        .zip(sboxed)
+                        .fold(Value::known(F::ZERO), |acc, (m, word)| {
+                            acc.zip(word).map(|(acc, word)| acc + *m * word)
+                        })
+                });
+                offset += 1;
+                words = self.assign_state(region, offset, state);
+            }
+            digest = Some(words[0]);
+        }
+
+        Ok(digest.expect("the message is not empty"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner},
+        dev::MockProver,
+        halo2curves::{bn256, grumpkin},
+        plonk::{Circuit, Instance},
+    };
+
+    use super::*;
+    use crate::gadgets::fit_degree;
+
+    struct HashCircuit<F, const L: usize> {
+        message: [u64; L],
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: CircuitField, const L: usize> Circuit<F> for HashCircuit<F, L> {
+        type Config = (PoseidonConfig<F>, Column<Instance>);
+        type FloorPlanner = SimpleFloorPlanner;
+        type Params = ();
+
+        fn without_witnesses(&self) -> Self {
+            Self {
+                message: [0; L],
+                _marker: PhantomData,
+            }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let state = [(); WIDTH].map(|_| meta.advice_column());
+            let message = [(); RATE].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(instance);
+            let config = PoseidonConfig::configure(meta, state, message);
+            fit_degree(meta);
+            (config, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let digest = layouter.assign_region(
+                || "hash",
+                |mut region| {
+                    let message: [Word<F>; L] = array::from_fn(|i| {
+                        Word::assign(
+                            &mut region,
+                            config.message[0],
+                            i,
+                            Value::known(F::from(self.message[i])),
+                        )
+                    });
+                    config.hash(&mut region, L, &message)
+                },
+            )?;
+            layouter.constrain_instance(digest.cell, instance, 0);
+            Ok(())
+        }
+    }
+
+    fn check_hash<F: CircuitField, const L: usize>() {
+        let message: [u64; L] = array::from_fn(|i| i as u64);
+        let circuit = HashCircuit::<F, L> {
+            message,
+            _marker: PhantomData,
+        };
+        let digest = hash(message.map(F::from));
+        MockProver::run(10, &circuit, vec![vec![digest]])
+            .unwrap()
+            .assert_satisfied();
+
+        let prover = MockProver::run(10, &circuit, vec![vec![digest + F::ONE]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_hash() {
+        // with and without padding
+        check_hash::<bn256::Fr, 4>();
+        check_hash::<bn256::Fr, 5>();
+        check_hash::<grumpkin::Fr, 5>();
+    }
+}
@@ -0,0 +1,16 @@
+pub use halo2_proofs;
+
+pub mod gadgets;
+pub mod circuit;
+pub mod cycle;
+#[cfg(feature = "evm")]
+pub mod evm;
+pub mod manifest;
+pub mod prover;
+pub mod report;
+
+pub use circuit::{PuzzleHash, SudokuCircuit};
+pub use manifest::Manifest;
+pub use prover::{
+    keygen, prove, setup, verify, verifying_key, KzgKeys, KzgProver, KzgProvingKey, KzgVerifyingKey,
+};
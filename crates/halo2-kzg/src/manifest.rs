@@ -0,0 +1,175 @@
+//! A proof that carries what its verifier needs: the puzzle hash of the
+//! circuit, the puzzle, the KZG parameters and the proof of `crate::prove`.
+//! This is what the `verify` export of crates/halo2-kzg-wasi takes.
+//!
+//! The bytes are the hash, 0 for Poseidon and 1 for keccak256, the 81 cells
+//! row by row, the parameters in the compressed `SerdeFormat::Processed`,
+//! whose points are checked on reading, then the proof up to the end.
+//!
+//! The verifying key is generated from the parameters, so a manifest cannot
+//! bring the key of another circuit. The parameters are taken as they come
+//! though, and whoever knows their randomness can forge proofs: a verifier
+//! relying on a ceremony compares them with its own copy first.
+
+use std::io::{self, Read};
+
+use halo2_proofs::{
+    halo2curves::bn256::Bn256, plonk::Error, poly::kzg::commitment::ParamsKZG, SerdeFormat,
+};
+use zk_sudoku_core::{GridError, Puzzle};
+
+use crate::{
+    circuit::PuzzleHash,
+    prover::{self, KzgVerifyingKey},
+};
+
+/// The largest `k` of the parameters of a manifest, so that a forged one
+/// cannot have the verifier read `2^k` points for any `k`.
+pub const MAX_K: u32 = 16;
+
+#[derive(Debug)]
+pub enum ManifestError {
+    /// The manifest ends early or its parameters are not valid points.
+    Io(io::Error),
+    /// The hash byte is neither 0 nor 1.
+    Hash(u8),
+    /// The `k` of the parameters is below the one of the circuit or above
+    /// `MAX_K`.
+    K(u32),
+    Puzzle(GridError),
+    Plonk(Error),
+}
+
+impl From<io::Error> for ManifestError {
+    fn from(err: io::Error) -> Self {
+        ManifestError::Io(err)
+    }
+}
+
+impl From<GridError> for ManifestError {
+    fn from(err: GridError) -> Self {
+        ManifestError::Puzzle(err)
+    }
+}
+
+impl From<Error> for ManifestError {
+    fn from(err: Error) -> Self {
+        ManifestError::Plonk(err)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub hash: PuzzleHash,
+    pub puzzle: Puzzle,
+    pub params: ParamsKZG<Bn256>,
+    pub proof: Vec<u8>,
+}
+
+impl Manifest {
+    /// The manifest of `proof`, made with the proving key of `vk`.
+    pub fn new(vk: &KzgVerifyingKey, puzzle: Puzzle, proof: Vec<u8>) -> Self {
+        Self {
+            hash: vk.hash,
+            puzzle,
+            params: vk.params.clone(),
+            proof,
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![match self.hash {
+            PuzzleHash::Poseidon => 0,
+            PuzzleHash::Keccak256 => 1,
+        }];
+        bytes.extend(self.puzzle.cells().iter().flatten());
+        self.params
+            .write_custom(&mut bytes, SerdeFormat::Processed)
+            .expect("writing to a vec does not fail");
+        bytes.extend(&self.proof);
+        bytes
+    }
+
+    pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, ManifestError> {
+        let mut hash = [0];
+        bytes.read_exact(&mut hash)?;
+        let hash = match hash[0] {
+            0 => PuzzleHash::Poseidon,
+            1 => PuzzleHash::Keccak256,
+            byte => return Err(ManifestError::Hash(byte)),
+        };
+
+        let mut cells = [[0; 9]; 9];
+        for row in cells.iter_mut() {
+            bytes.read_exact(row)?;
+        }
+        let puzzle = Puzzle::new(cells)?;
+
+        // the parameters start with their k, checked before reading them
+        let mut k = [0; 4];
+        let mut peek = bytes;
+        peek.read_exact(&mut k)?;
+        let k = u32::from_le_bytes(k);
+        if k < hash.k() || k > MAX_K {
+            return Err(ManifestError::K(k));
+        }
+        let params = ParamsKZG::read_custom(&mut bytes, SerdeFormat::Processed)?;
+
+        Ok(Self {
+            hash,
+            puzzle,
+            params,
+            proof: bytes.to_vec(),
+        })
+    }
+
+    /// Generates the verifying key from the parameters and verifies the
+    /// proof, `Ok(false)` meaning a rejected proof as with `crate::verify`.
+    pub fn verify(self) -> Result<bool, ManifestError> {
+        let vk = prover::verifying_key(self.params, self.hash)?;
+        Ok(prover::verify(&vk, &self.puzzle, &self.proof)?)
+    }
+}
+
+/// Reads a manifest from `bytes` and verifies it.
+pub fn verify(bytes: &[u8]) -> Result<bool, ManifestError> {
+    Manifest::from_bytes(bytes)?.verify()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::ThreadRng;
+    use zk_sudoku_core::sample;
+
+    use super::{verify, Manifest, ManifestError, MAX_K};
+    use crate::{circuit::PuzzleHash, prover};
+
+    #[test]
+    fn test_manifest() {
+        let puzzle = sample::puzzle();
+        let mut rng = ThreadRng::default();
+        let (pk, vk) =
+            prover::setup(PuzzleHash::Poseidon.k(), PuzzleHash::Poseidon, &mut rng).unwrap();
+        let proof = prover::prove(&pk, &puzzle, &sample::solution(), &mut rng).unwrap();
+
+        let bytes = Manifest::new(&vk, puzzle, proof).to_bytes();
+        assert!(verify(&bytes).unwrap());
+
+        // another puzzle with the same proof
+        let mut other = bytes.clone();
+        other[1 + 4 * 9 + 4] = 6;
+        assert!(!verify(&other).unwrap());
+
+        let mut keccak = bytes.clone();
+        keccak[0] = 1;
+        assert!(matches!(verify(&keccak), Err(ManifestError::K(_))));
+        keccak[0] = 2;
+        assert!(matches!(verify(&keccak), Err(ManifestError::Hash(2))));
+
+        let mut large = bytes.clone();
+        large[82..86].copy_from_slice(&(MAX_K + 1).to_le_bytes());
+        assert!(matches!(verify(&large), Err(ManifestError::K(_))));
+
+        assert!(matches!(verify(&bytes[..500]), Err(ManifestError::Io(_))));
+    }
+}
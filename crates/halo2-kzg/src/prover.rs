@@ -0,0 +1,200 @@
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Error, ProvingKey, VerifyingKey},
+    poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::{ProverSHPLONK, VerifierSHPLONK},
+        strategy::SingleStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::circuit::{PuzzleHash, SudokuCircuit};
+
+/// Halo2 with the KZG commitment over bn254 and the SHPLONK multiopen, the
+/// hash of the puzzle being the only instance value.
+#[derive(Debug, Clone, Copy)]
+pub struct KzgProver {
+    /// log2 of the number of rows, at least `hash.k()`.
+    pub k: u32,
+    pub hash: PuzzleHash,
+}
+
+impl KzgProver {
+    pub fn new(hash: PuzzleHash) -> Self {
+        Self { k: hash.k(), hash }
+    }
+}
+
+impl Default for KzgProver {
+    fn default() -> Self {
+        Self::new(PuzzleHash::default())
+    }
+}
+
+/// The KZG parameters are needed by both sides, so each key carries them,
+/// with the puzzle hash of the circuit.
+#[derive(Debug, Clone)]
+pub struct KzgKeys<K> {
+    pub params: ParamsKZG<Bn256>,
+    pub key: K,
+    pub hash: PuzzleHash,
+}
+
+pub type KzgProvingKey = KzgKeys<ProvingKey<G1Affine>>;
+pub type KzgVerifyingKey = KzgKeys<VerifyingKey<G1Affine>>;
+
+pub(crate) fn public_inputs(hash: PuzzleHash, puzzle: &Puzzle) -> Vec<Fr> {
+    vec![hash.hash(&puzzle.map(u64::from))]
+}
+
+/// Generates parameters for `2^k` rows from `rng` and the keys of the sudoku
+/// circuit with `hash`.
+///
+/// Whoever knows the randomness of the parameters can forge proofs, so this
+/// is for tests and local runs only. Parameters of a ceremony, read with
+/// `ParamsKZG::read`, go to `keygen` instead.
+pub fn setup<R: RngCore>(
+    k: u32,
+    hash: PuzzleHash,
+    rng: &mut R,
+) -> Result<(KzgProvingKey, KzgVerifyingKey), Error> {
+    keygen(ParamsKZG::setup(k, rng), hash)
+}
+
+/// Generates the keys of the sudoku circuit with `hash` for existing
+/// parameters.
+pub fn keygen(
+    params: ParamsKZG<Bn256>,
+    hash: PuzzleHash,
+) -> Result<(KzgProvingKey, KzgVerifyingKey), Error> {
+    let circuit = SudokuCircuit::<Fr>::default().with_hash(hash);
+    let vk = keygen_vk(&params, &circuit)?;
+    let pk = keygen_pk(&params, vk.clone(), &circuit)?;
+    Ok((
+        KzgKeys {
+            params: params.clone(),
+            key: pk,
+            hash,
+        },
+        KzgKeys {
+            params,
+            key: vk,
+            hash,
+        },
+    ))
+}
+
+/// Generates the verifying key only, for a verifier that has the parameters
+/// and no use for the proving key.
+pub fn verifying_key(params: ParamsKZG<Bn256>, hash: PuzzleHash) -> Result<KzgVerifyingKey, Error> {
+    let circuit = SudokuCircuit::<Fr>::default().with_hash(hash);
+    let key = keygen_vk(&params, &circuit)?;
+    Ok(KzgKeys { params, key, hash })
+}
+
+/// Proves that `solution` solves `puzzle`, the proof being bound to the hash
+/// of the puzzle only.
+pub fn prove<R: RngCore + CryptoRng>(
+    pk: &KzgProvingKey,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+) -> Result<Vec<u8>, Error> {
+    let circuit = SudokuCircuit::<Fr>::new(puzzle, solution).with_hash(pk.hash);
+    let public_inputs = public_inputs(pk.hash, puzzle);
+
+    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+        &pk.params,
+        &pk.key,
+        &[circuit],
+        &[&[&public_inputs]],
+        rng,
+        &mut transcript,
+    )?;
+    Ok(transcript.finalize())
+}
+
+/// Verifies a proof of `prove`, returning `Ok(false)` for a proof of another
+/// puzzle or a forged one, and an error if the proof cannot be read.
+pub fn verify(vk: &KzgVerifyingKey, puzzle: &Puzzle, proof: &[u8]) -> Result<bool, Error> {
+    let public_inputs = public_inputs(vk.hash, puzzle);
+
+    let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof);
+    let result = verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+        &vk.params,
+        &vk.key,
+        SingleStrategy::new(&vk.params),
+        &[&[&public_inputs]],
+        &mut transcript,
+    );
+    match result {
+        Ok(()) => Ok(true),
+        Err(Error::ConstraintSystemFailure) | Err(Error::Opening) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+impl SudokuProver for KzgProver {
+    type ProvingKey = KzgProvingKey;
+    type VerifyingKey = KzgVerifyingKey;
+    type Proof = Vec<u8>;
+    type Error = Error;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        setup(self.k, self.hash, rng)
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        prove(pk, puzzle, solution, rng)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        verify(vk, puzzle, proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::ThreadRng;
+    use zk_sudoku_core::{sample, Puzzle, SudokuProver};
+
+    use super::KzgProver;
+    use crate::circuit::PuzzleHash;
+
+    #[test]
+    fn test_kzg_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ThreadRng::default();
+        for prover in [KzgProver::default(), KzgProver::new(PuzzleHash::Keccak256)] {
+            let (pk, vk) = prover.setup(&mut rng).unwrap();
+            let proof = prover.prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+            assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+            let mut other = *puzzle.cells();
+            other[4][4] = 6;
+            let other = Puzzle::new(other).unwrap();
+            assert!(!prover.verify(&vk, &other, &proof).unwrap());
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! Timings and sizes of the KZG prover on the sample sudoku, as the reports
+//! of the other backends.
+
+use std::time::Instant;
+
+use halo2_proofs::{plonk::Error, poly::commitment::Params};
+use rand_core::{CryptoRng, RngCore};
+use serde_json::json;
+use zk_sudoku_core::{report::Report, sample, SudokuProver};
+
+use crate::{circuit::rows, prover::KzgProver};
+
+/// Sets up, proves the sample sudoku and verifies the proof with the KZG
+/// commitment over bn254, timing each step. The setup includes generating
+/// the parameters. The rows used are reported as the constraints, the ones
+/// of the puzzle hash as `hash_rows` and the size of the serialized
+/// parameters as `params_len`. A rejected proof is an
+/// `Error::ConstraintSystemFailure`.
+pub fn run<R: RngCore + CryptoRng>(prover: &KzgProver, rng: &mut R) -> Result<Report, Error> {
+    let puzzle = sample::puzzle();
+    let solution = sample::solution();
+
+    let setup_start = Instant::now();
+    let (pk, vk) = prover.setup(rng)?;
+    let setup_time = setup_start.elapsed();
+
+    let prove_start = Instant::now();
+    let proof = prover.prove(&pk, &puzzle, &solution, rng)?;
+    let prove_time = prove_start.elapsed();
+
+    let verify_start = Instant::now();
+    let valid_proof = prover.verify(&vk, &puzzle, &proof)?;
+    let verify_time = verify_start.elapsed();
+    if !valid_proof {
+        return Err(Error::ConstraintSystemFailure);
+    }
+
+    let mut params = vec![];
+    vk.params.write(&mut params)?;
+    Ok(Report {
+        backend: "halo2-kzg",
+        curve: "bn254",
+        config: Some("kzg"),
+        num_constraints: rows(prover.hash),
+        setup_time,
+        prove_time,
+        verify_time,
+        proof_len: proof.len(),
+        extra: vec![
+            ("k", json!(prover.k)),
+            ("hash", json!(prover.hash.name())),
+            ("hash_rows", json!(prover.hash.rows())),
+            ("params_len", json!(params.len())),
+        ],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::ThreadRng;
+
+    use super::run;
+    use crate::prover::KzgProver;
+
+    #[test]
+    fn test_run() {
+        let report = run(&KzgProver::default(), &mut ThreadRng::default()).unwrap();
+        println!("{}", report.to_json());
+        // two G1 points per row, in the monomial and the Lagrange bases
+        assert!(
+            report.extra("params_len").unwrap().as_u64().unwrap()
+                > 64 << report.extra("k").unwrap().as_u64().unwrap()
+        );
+        assert!(report.proof_len > 0);
+    }
+}
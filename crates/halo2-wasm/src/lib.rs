@@ -0,0 +1,89 @@
+//! Browser bindings of the halo2 backend. The parameters are fetched by the
+//! page as the bytes of `zk_sudoku_halo2::keys::write_params`, the keys
+//! being derived from them, and grids are passed as 81 cells in row major
+//! order, 0 for an empty cell.
+
+use rand_core::OsRng;
+use wasm_bindgen::prelude::*;
+use zk_sudoku_core::{Puzzle, Solution};
+use zk_sudoku_halo2::{
+    halo2_proofs::{
+        pasta::vesta,
+        plonk::{ProvingKey, VerifyingKey},
+    },
+    keygen,
+    keys::params_from_bytes,
+    prove, verify, Halo2Keys,
+};
+
+fn cells(bytes: &[u8]) -> Result<[[u8; 9]; 9], JsError> {
+    if bytes.len() != 81 {
+        return Err(JsError::new(&format!(
+            "expected 81 cells, got {}",
+            bytes.len()
+        )));
+    }
+    let mut cells = [[0; 9]; 9];
+    for (cell, byte) in cells.iter_mut().flatten().zip(bytes) {
+        *cell = *byte;
+    }
+    Ok(cells)
+}
+
+fn error(err: impl std::fmt::Debug) -> JsError {
+    JsError::new(&format!("{:?}", err))
+}
+
+#[wasm_bindgen]
+pub struct SudokuProver {
+    pk: Halo2Keys<ProvingKey<vesta::Affine>>,
+    vk: Halo2Keys<VerifyingKey<vesta::Affine>>,
+}
+
+#[wasm_bindgen]
+impl SudokuProver {
+    /// Derives the keys from the parameters, which takes a few seconds.
+    #[wasm_bindgen(constructor)]
+    pub fn new(params: &[u8]) -> Result<SudokuProver, JsError> {
+        let params = params_from_bytes(params).map_err(error)?;
+        let (pk, vk) = keygen(params).map_err(error)?;
+        Ok(Self { pk, vk })
+    }
+
+    /// Proves that `solution` solves `puzzle`, returning the proof bytes.
+    pub fn prove(&self, puzzle: &[u8], solution: &[u8]) -> Result<Vec<u8>, JsError> {
+        let puzzle = Puzzle::new(cells(puzzle)?)?;
+        let solution = Solution::new(cells(solution)?)?;
+        puzzle.check(&solution)?;
+        prove(&self.pk, &puzzle, &solution, &mut OsRng).map_err(error)
+    }
+
+    /// Verifies a proof of `prove` for `puzzle`.
+    pub fn verify(&self, proof: &[u8], puzzle: &[u8]) -> Result<bool, JsError> {
+        let puzzle = Puzzle::new(cells(puzzle)?)?;
+        verify(&self.vk, &puzzle, proof).map_err(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zk_sudoku_core::sample;
+    use zk_sudoku_halo2::{circuit::K, halo2_proofs::poly::commitment::Params, keys};
+
+    use super::SudokuProver;
+
+    #[test]
+    fn test_sudoku_prover() {
+        let params = keys::params_to_bytes(&Params::new(K)).unwrap();
+        let prover = SudokuProver::new(&params).unwrap();
+
+        let puzzle: Vec<u8> = sample::UNSOLVED.concat();
+        let solution: Vec<u8> = sample::SOLVED.concat();
+
+        let proof = prover.prove(&puzzle, &solution).unwrap();
+        assert!(prover.verify(&proof, &puzzle).unwrap());
+        let mut other = puzzle.clone();
+        other[4 * 9 + 4] = 6;
+        assert!(!prover.verify(&proof, &other).unwrap());
+    }
+}
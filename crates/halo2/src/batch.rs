@@ -0,0 +1,281 @@
+//! Batching of sudoku proofs, not recursive aggregation: a verifier still
+//! checks every puzzle itself, only with less work than one proof at a time.
+//! Aggregating inner proofs into an outer one needs an in-circuit verifier
+//! of halo2's IPA proofs, which this revision of halo2 doesn't have.
+//!
+//! - `prove_batch` and `verify_batch` prove several puzzles in a single
+//!   `create_proof` call, one copy of the circuit per puzzle.
+//! - `verify_proofs` checks separate proofs with halo2's `BatchVerifier`.
+//! - `BatchSudokuCircuit` checks several puzzles in one circuit.
+
+use halo2_gadgets::poseidon::primitives::{P128Pow5T3, Spec};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    pasta::{vesta, Fp},
+    plonk::{
+        create_proof, verify_proof, BatchVerifier, Circuit, ConstraintSystem, Error, ProvingKey,
+        SingleVerifier, VerifyingKey,
+    },
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::{
+    circuit::{SudokuCircuit, SudokuConfig},
+    prover::{public_inputs, Halo2Keys},
+};
+
+/// `M` puzzles checked by one circuit, the hash of the i-th puzzle being the
+/// i-th instance value. Unlike `prove_batch`, the puzzles share the columns,
+/// the tables and the keys of a single circuit, so the proof and its
+/// verification cost as much as those of one puzzle with `M` times the rows.
+#[derive(Clone)]
+pub struct BatchSudokuCircuit<F, const M: usize> {
+    pub circuits: [SudokuCircuit<F>; M],
+}
+
+impl<F, const M: usize> BatchSudokuCircuit<F, M> {
+    pub fn new(solved: &[(Puzzle, Solution); M]) -> Self {
+        Self {
+            circuits: std::array::from_fn(|i| SudokuCircuit::new(&solved[i].0, &solved[i].1)),
+        }
+    }
+}
+
+impl<F, const M: usize> Default for BatchSudokuCircuit<F, M> {
+    fn default() -> Self {
+        Self {
+            circuits: std::array::from_fn(|_| SudokuCircuit::default()),
+        }
+    }
+}
+
+impl<F: FieldExt, const M: usize> Circuit<F> for BatchSudokuCircuit<F, M>
+where
+    P128Pow5T3: Spec<F, 3, 2>,
+{
+    type Config = SudokuConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SudokuCircuit::<F>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.load_tables(&mut layouter)?;
+        for (i, circuit) in self.circuits.iter().enumerate() {
+            circuit.assign_puzzle(
+                config.clone(),
+                &mut layouter.namespace(|| format!("puzzle {}", i)),
+                i,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// The instance values of `BatchSudokuCircuit` for `puzzles` in this order.
+pub fn batch_inputs(puzzles: &[Puzzle]) -> Vec<Fp> {
+    puzzles.iter().flat_map(public_inputs).collect()
+}
+
+/// Proves several puzzles in one proof. halo2 lays out one copy of the
+/// circuit per puzzle and opens all of their commitments with a single
+/// inner product argument, so the proof grows with the advice commitments
+/// and evaluations of each copy only, and is checked by `verify_batch`.
+pub fn prove_batch<R: RngCore + CryptoRng>(
+    pk: &Halo2Keys<ProvingKey<vesta::Affine>>,
+    solved: &[(Puzzle, Solution)],
+    rng: &mut R,
+) -> Result<Vec<u8>, Error> {
+    let circuits = solved
+        .iter()
+        .map(|(puzzle, solution)| SudokuCircuit::<Fp>::new(puzzle, solution))
+        .collect::<Vec<_>>();
+    let inputs = solved
+        .iter()
+        .map(|(puzzle, _)| public_inputs(puzzle))
+        .collect::<Vec<_>>();
+    let columns = inputs
+        .iter()
+        .map(|inputs| [&inputs[..]])
+        .collect::<Vec<_>>();
+    let instances = columns
+        .iter()
+        .map(|columns| &columns[..])
+        .collect::<Vec<_>>();
+
+    let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+    create_proof(
+        &pk.params,
+        &pk.key,
+        &circuits,
+        &instances,
+        rng,
+        &mut transcript,
+    )?;
+    Ok(transcript.finalize())
+}
+
+/// Verifies a proof of `prove_batch` for the same puzzles in the same order.
+pub fn verify_batch(
+    vk: &Halo2Keys<VerifyingKey<vesta::Affine>>,
+    puzzles: &[Puzzle],
+    proof: &[u8],
+) -> Result<bool, Error> {
+    let inputs = puzzles.iter().map(public_inputs).collect::<Vec<_>>();
+    let columns = inputs
+        .iter()
+        .map(|inputs| [&inputs[..]])
+        .collect::<Vec<_>>();
+    let instances = columns
+        .iter()
+        .map(|columns| &columns[..])
+        .collect::<Vec<_>>();
+
+    let strategy = SingleVerifier::new(&vk.params);
+    let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(proof);
+    match verify_proof(&vk.params, &vk.key, strategy, &instances, &mut transcript) {
+        Ok(()) => Ok(true),
+        Err(Error::ConstraintSystemFailure) | Err(Error::Opening) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Verifies separate proofs of `prove` at once. The final multiscalar
+/// multiplications of their inner product arguments are combined with
+/// random factors and computed as one, the most expensive part of verifying
+/// an IPA proof. Returns false if any proof is invalid.
+pub fn verify_proofs(
+    vk: &Halo2Keys<VerifyingKey<vesta::Affine>>,
+    proofs: &[(Puzzle, Vec<u8>)],
+) -> bool {
+    let mut batch = BatchVerifier::new();
+    for (puzzle, proof) in proofs {
+        batch.add(vec![vec![public_inputs(puzzle)]], proof.clone());
+    }
+    batch.finalize(&vk.params, &vk.key)
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        dev::MockProver,
+        pasta::{vesta, Fp},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, SingleVerifier},
+        poly::commitment::Params,
+        transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+    };
+    use rand::rngs::ThreadRng;
+    use zk_sudoku_core::{sample, Puzzle, Solution};
+
+    use super::{batch_inputs, prove_batch, verify_batch, verify_proofs, BatchSudokuCircuit};
+    use crate::{
+        circuit::{minimum_k, SudokuCircuit, K},
+        prove, setup,
+    };
+
+    fn solved() -> Vec<(Puzzle, Solution)> {
+        let solution = sample::solution();
+        let puzzle = sample::puzzle();
+        // the same solution with one more given
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        vec![(puzzle, solution), (other, solution)]
+    }
+
+    #[test]
+    fn test_prove_batch() {
+        let mut rng = ThreadRng::default();
+        let (pk, vk) = setup(K).unwrap();
+        let solved = solved();
+        let puzzles = solved.iter().map(|(puzzle, _)| *puzzle).collect::<Vec<_>>();
+
+        let proof = prove_batch(&pk, &solved, &mut rng).unwrap();
+        println!("proof size for {} puzzles: {}", solved.len(), proof.len());
+        assert!(verify_batch(&vk, &puzzles, &proof).unwrap());
+        let swapped = [puzzles[1], puzzles[0]];
+        assert!(!verify_batch(&vk, &swapped, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_verify_proofs() {
+        let mut rng = ThreadRng::default();
+        let (pk, vk) = setup(K).unwrap();
+        let solved = solved();
+
+        let mut proofs = solved
+            .iter()
+            .map(|(puzzle, solution)| (*puzzle, prove(&pk, puzzle, solution, &mut rng).unwrap()))
+            .collect::<Vec<_>>();
+        assert!(verify_proofs(&vk, &proofs));
+        proofs[0].0 = proofs[1].0;
+        assert!(!verify_proofs(&vk, &proofs));
+    }
+
+    #[test]
+    fn test_batch_circuit() {
+        let solved = solved();
+        let solved: [(Puzzle, Solution); 2] = [solved[0], solved[1]];
+        let puzzles = solved.map(|(puzzle, _)| puzzle);
+        let circuit = BatchSudokuCircuit::<Fp, 2>::new(&solved);
+        let inputs = batch_inputs(&puzzles);
+
+        let k = minimum_k(&circuit, 2);
+        println!(
+            "minimum k: {} for 2 puzzles, {} for one",
+            k,
+            minimum_k(&SudokuCircuit::<Fp>::default(), 1)
+        );
+        MockProver::run(k, &circuit, vec![inputs.clone()])
+            .unwrap()
+            .assert_satisfied();
+        let swapped = batch_inputs(&[puzzles[1], puzzles[0]]);
+        assert!(MockProver::run(k, &circuit, vec![swapped.clone()])
+            .unwrap()
+            .verify()
+            .is_err());
+
+        let params: Params<vesta::Affine> = Params::new(k);
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk, &circuit).unwrap();
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(
+            &params,
+            &pk,
+            &[circuit],
+            &[&[&inputs]],
+            ThreadRng::default(),
+            &mut transcript,
+        )
+        .unwrap();
+        let proof = transcript.finalize();
+        println!("proof size for 2 puzzles in one circuit: {}", proof.len());
+
+        let verify = |inputs: &[Fp]| {
+            let strategy = SingleVerifier::new(&params);
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            verify_proof(
+                &params,
+                pk.get_vk(),
+                strategy,
+                &[&[inputs]],
+                &mut transcript,
+            )
+        };
+        assert!(verify(&inputs).is_ok());
+        assert!(verify(&swapped).is_err());
+    }
+}
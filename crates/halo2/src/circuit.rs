@@ -1,42 +1,173 @@
 use std::marker::PhantomData;
 
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3, Spec},
+    Hash, Pow5Chip, Pow5Config,
+};
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, Expression, Instance, Selector},
+    circuit::{floor_planner::V1, AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    plonk::{Advice, Circuit, Column, Constraints, Error, FloorPlanner, Instance, Selector},
     poly::Rotation,
 };
 
 use crate::gadgets::{
-    is_zero::{IsZeroChip, IsZeroConfig},
     not_equal::{IsEqualChip, IsEqualConfig},
-    range_check::custom_gate::RangeCheckConfig,
+    permutation::PermutationConfig,
+    range_check::{custom_gate::range_check, lookup_table},
 };
+use zk_sudoku_core::{Puzzle, Solution};
+
+/// log2 of the rows used by the circuit, most of them by the Poseidon
+/// permutations hashing the unsolved sudoku. At least `minimum_k` of the
+/// circuit, with some room so the keys stay the same as the layout changes.
+pub const K: u32 = 12;
+
+/// The smallest `k` whose `2^k` rows fit the circuit with `num_instances`
+/// instance values, found by laying it out with the mock prover for growing
+/// `k`, which fails as long as a region or the blinding rows run past the end.
+pub fn minimum_k<F: FieldExt, C: Circuit<F>>(circuit: &C, num_instances: usize) -> u32 {
+    (1..32)
+        .find(|k| MockProver::run(*k, circuit, vec![vec![F::zero(); num_instances]]).is_ok())
+        .expect("the circuit does not fit in 2^31 rows")
+}
+
+/// Poseidon hash of the unsolved sudoku, the only public input of the circuit.
+pub fn unsolved_hash<F: FieldExt>(unsolved: &[[u64; 9]; 9]) -> F
+where
+    P128Pow5T3: Spec<F, 3, 2>,
+{
+    let message: [F; 81] = unsolved
+        .iter()
+        .flatten()
+        .map(|x| F::from(*x))
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    poseidon::Hash::<_, P128Pow5T3, ConstantLength<81>, 3, 2>::init().hash(message)
+}
 
 #[derive(Debug, Clone)]
-pub struct SudoukuConfig<F: FieldExt> {
+pub struct SudokuConfig<F: FieldExt> {
     pub advices: [Column<Advice>; 2],
     pub instance: Column<Instance>,
-    pub q_zero_or_equal: Selector,
+    /// Enables the checks of one cell per row of the first region: the range
+    /// of both cells, 1..=9 when solved and 0..=9 when unsolved, and the
+    /// solved cell matching the unsolved one if given.
+    pub q_cell: Selector,
     pub q_not_equal: Selector,
-    pub q_is_zero: Selector,
-    pub q_is_equal: Selector,
-    pub range_check: RangeCheckConfig<F, 1, 9>,
-    pub is_zero: IsZeroConfig<F>,
-    pub is_equal: IsEqualConfig<F>,
+    /// The tables of the solved and unsolved ranges, looked up on the rows of
+    /// `q_cell` if `LOOKUP` is set. The ranges are checked by the cell gate
+    /// otherwise.
+    pub range_tables: Option<(
+        lookup_table::RangeCheckConfig<F, 1, 9>,
+        lookup_table::RangeCheckConfig<F, 0, 9>,
+    )>,
+    /// One is_equal check per pair of cells on a row of the not equal
+    /// regions, the first one over the cells of the first region.
+    pub not_equal: Vec<IsEqualConfig<F>>,
+    /// The permutation check replacing the not equal regions, if enabled.
+    pub permutation: Option<PermutationConfig<F>>,
+    pub poseidon: Pow5Config<F, 3, 2>,
+}
+
+impl<F: FieldExt> SudokuConfig<F> {
+    /// Loads the tables of the range checks, once per circuit.
+    pub fn load_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        if let Some((range_check, unsolved_range_check)) = &self.range_tables {
+            range_check.load(layouter)?;
+            unsolved_range_check.load(layouter)?;
+        }
+        Ok(())
+    }
 }
 
-#[derive(Default, Clone)]
-struct SudoukuCircuit<F> {
+/// Rows of the not equal regions with `lanes` pairs of cells per row, out of
+/// the 810 pairs of the rows, columns and boxes.
+pub const fn not_equal_rows(lanes: usize) -> usize {
+    (324 + lanes - 1) / lanes * 2 + (162 + lanes - 1) / lanes
+}
+
+/// The sudoku circuit, checking the range of the solved cells with a lookup
+/// table if `LOOKUP` is set, with a custom gate otherwise.
+///
+/// With `PERMUTATION` set, each row, column and box is checked to be a
+/// permutation of 1..=9 on 9 rows, 243 rows in all and 9 more advice columns.
+/// Otherwise the 810 pairs of cells which must differ are checked `LANES` per
+/// row, each lane taking 4 more advice columns, the first one 2.
+///
+/// The regions are placed by `P`, one after the other by default. `V1`
+/// measures them first and places each one at the first rows where its
+/// columns are free, see `V1SudokuCircuit`.
+pub struct SudokuCircuit<
+    F,
+    const LOOKUP: bool = false,
+    const LANES: usize = 1,
+    const PERMUTATION: bool = true,
+    P = SimpleFloorPlanner,
+> {
     pub unsolved: [[u64; 9]; 9],
     pub solved: [[u64; 9]; 9],
-    pub _marker: PhantomData<F>,
+    pub _marker: PhantomData<(F, P)>,
+}
+
+/// The default configuration of the sudoku circuit laid out by the `V1`
+/// floor planner.
+pub type V1SudokuCircuit<F> = SudokuCircuit<F, false, 1, true, V1>;
+
+// implemented by hand as the floor planners are neither `Default` nor `Clone`
+impl<F, const LOOKUP: bool, const LANES: usize, const PERMUTATION: bool, P> Default
+    for SudokuCircuit<F, LOOKUP, LANES, PERMUTATION, P>
+{
+    fn default() -> Self {
+        Self {
+            unsolved: [[0; 9]; 9],
+            solved: [[0; 9]; 9],
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F, const LOOKUP: bool, const LANES: usize, const PERMUTATION: bool, P> Clone
+    for SudokuCircuit<F, LOOKUP, LANES, PERMUTATION, P>
+{
+    fn clone(&self) -> Self {
+        Self {
+            unsolved: self.unsolved,
+            solved: self.solved,
+            _marker: PhantomData,
+        }
+    }
 }
 
-impl<F: FieldExt> Circuit<F> for SudoukuCircuit<F> {
-    type Config = SudoukuConfig<F>;
+impl<F, const LOOKUP: bool, const LANES: usize, const PERMUTATION: bool, P>
+    SudokuCircuit<F, LOOKUP, LANES, PERMUTATION, P>
+{
+    /// The circuit proving that `solution` solves `puzzle`, both validated
+    /// when built from their cells.
+    pub fn new(puzzle: &Puzzle, solution: &Solution) -> Self {
+        Self {
+            unsolved: puzzle.map(u64::from),
+            solved: solution.map(u64::from),
+            _marker: PhantomData,
+        }
+    }
+}
 
-    type FloorPlanner = SimpleFloorPlanner;
+impl<
+        F: FieldExt,
+        const LOOKUP: bool,
+        const LANES: usize,
+        const PERMUTATION: bool,
+        P: FloorPlanner,
+    > Circuit<F> for SudokuCircuit<F, LOOKUP, LANES, PERMUTATION, P>
+where
+    P128Pow5T3: Spec<F, 3, 2>,
+{
+    type Config = SudokuConfig<F>;
+
+    type FloorPlanner = P;
 
     fn without_witnesses(&self) -> Self {
         Self::default()
@@ -54,70 +185,125 @@ impl<F: FieldExt> Circuit<F> for SudoukuCircuit<F> {
             meta.enable_equality(advice);
         }
 
-        // used for range check, if use lookup, there should be complex_selector
-        let q_range_check = meta.selector();
-        let range_check = RangeCheckConfig::configure(meta, q_range_check, advices[1]);
-
-        // used for is_zero check
-        let q_is_zero = meta.selector();
-        let value_inv = meta.advice_column();
-        let is_zero = IsZeroChip::configure(
-            meta,
-            |meta| meta.query_selector(q_is_zero),
-            |meta| meta.query_advice(advices[0], Rotation::cur()),
-            value_inv,
-        );
-
-        // used for is_equal check
-        let q_is_equal = meta.selector();
-        let is_equal_col = meta.advice_column();
-        let is_equal =
-            IsEqualChip::configure(meta, q_is_equal, advices[0], advices[1], is_equal_col);
-
-        let q_zero_or_equal = meta.selector();
-        meta.create_gate("enforce is_zero or equal", |meta| {
-            let q_enable = meta.query_selector(q_zero_or_equal);
-            let is_zero = is_zero.expr();
-            let is_equal = is_equal.expr();
-            vec![
-                q_enable
-                    * (Expression::Constant(F::one()) - is_zero)
-                    * (Expression::Constant(F::one()) - is_equal),
-            ]
+        // A single gate checks each cell, the ranges being looked up over the
+        // same selector if LOOKUP is set
+        let q_cell = if LOOKUP {
+            meta.complex_selector()
+        } else {
+            meta.selector()
+        };
+        let range_tables = LOOKUP.then(|| {
+            (
+                lookup_table::RangeCheckConfig::configure(meta, q_cell, advices[1]),
+                lookup_table::RangeCheckConfig::configure(meta, q_cell, advices[0]),
+            )
+        });
+        meta.create_gate("cell check", |meta| {
+            let q = meta.query_selector(q_cell);
+            let unsolved = meta.query_advice(advices[0], Rotation::cur());
+            let solved = meta.query_advice(advices[1], Rotation::cur());
+
+            let mut constraints = vec![];
+            if !LOOKUP {
+                constraints.push(("range check", range_check(1, 9, solved.clone())));
+                constraints.push(("unsolved range check", range_check(0, 9, unsolved.clone())));
+            }
+            // an empty cell is 0, a given one is equal to the solved cell
+            constraints.push(("given cell", unsolved.clone() * (unsolved - solved)));
+            Constraints::with_selector(q, constraints)
         });
 
+        // used for the not equal checks, LANES pairs per row
         let q_not_equal = meta.selector();
-        meta.create_gate("enforce not_equal", |meta| {
-            let q_enable = meta.query_selector(q_not_equal);
-            let is_equal = is_equal.expr();
+        let lanes = if PERMUTATION { 0 } else { LANES };
+        let not_equal = (0..lanes)
+            .map(|lane| {
+                let (a, b) = if lane == 0 {
+                    (advices[0], advices[1])
+                } else {
+                    let (a, b) = (meta.advice_column(), meta.advice_column());
+                    meta.enable_equality(a);
+                    meta.enable_equality(b);
+                    (a, b)
+                };
+                let (diff_inv, is_equal_col) = (meta.advice_column(), meta.advice_column());
+                IsEqualChip::configure(meta, q_not_equal, a, b, diff_inv, is_equal_col)
+            })
+            .collect::<Vec<_>>();
+        // a gate must have constraints
+        if !not_equal.is_empty() {
+            meta.create_gate("enforce not_equal", |meta| {
+                let q_enable = meta.query_selector(q_not_equal);
+                not_equal
+                    .iter()
+                    .map(|is_equal| q_enable.clone() * is_equal.expr())
+                    .collect::<Vec<_>>()
+            });
+        }
 
-            vec![q_enable * is_equal]
-        });
+        // used for the permutation checks, on copies of the solved cells
+        let permutation = PERMUTATION.then(|| PermutationConfig::configure(meta, advices[1]));
+
+        // used for hashing the unsolved sudoku
+        let state = [0; 3].map(|_| meta.advice_column());
+        let partial_sbox = meta.advice_column();
+        let rc_a = [0; 3].map(|_| meta.fixed_column());
+        let rc_b = [0; 3].map(|_| meta.fixed_column());
+        for column in state {
+            meta.enable_equality(column);
+        }
+        // The hasher loads its initial capacity element as a constant
+        meta.enable_constant(rc_b[0]);
+        let poseidon = Pow5Chip::configure::<P128Pow5T3>(meta, state, partial_sbox, rc_a, rc_b);
 
-        return SudoukuConfig {
+        return SudokuConfig {
             advices,
             instance: primary,
-            range_check,
-            q_zero_or_equal,
+            poseidon,
+            q_cell,
             q_not_equal,
-            q_is_zero,
-            q_is_equal,
-            is_zero,
-            is_equal,
+            range_tables,
+            not_equal,
+            permutation,
         };
     }
 
     fn synthesize(
         &self,
         config: Self::Config,
-        mut layouter: impl halo2_proofs::circuit::Layouter<F>,
-    ) -> Result<(), halo2_proofs::plonk::Error> {
-        let is_equal = IsEqualChip::construct(config.is_equal);
-        let is_zero = IsZeroChip::construct(config.is_zero);
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        self.assign(config, &mut layouter).map(|_| ())
+    }
+}
 
-        // if use lookup, uncomment this line
-        // config.range_check.load(&mut layouter)?;
+impl<F: FieldExt, const LOOKUP: bool, const LANES: usize, const PERMUTATION: bool, P>
+    SudokuCircuit<F, LOOKUP, LANES, PERMUTATION, P>
+where
+    P128Pow5T3: Spec<F, 3, 2>,
+{
+    /// Assigns every rule of the circuit and returns the solved cells, for
+    /// circuits extending this one.
+    pub(crate) fn assign(
+        &self,
+        config: SudokuConfig<F>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error> {
+        config.load_tables(layouter)?;
+        self.assign_puzzle(config, layouter, 0)
+    }
 
+    /// Assigns the rules of one puzzle, the hash of the unsolved sudoku being
+    /// the instance value at `row`. The tables of the range checks are loaded
+    /// by the caller, once per circuit.
+    pub(crate) fn assign_puzzle(
+        &self,
+        config: SudokuConfig<F>,
+        layouter: &mut impl Layouter<F>,
+        row: usize,
+    ) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error> {
+        // Check if the numbers of the unsolved sudoku are >=0 and <=9, without
+        // relying on the match check below
         // Check if the numbers of the solved sudoku are >=1 and <=9
         // Each number in the solved sudoku is checked to see if it is >=1 and <=9
         // Check if unsolved is the initial state of solved
@@ -138,9 +324,7 @@ impl<F: FieldExt> Circuit<F> for SudoukuCircuit<F> {
                         let unsolved_value = F::from(self.unsolved[i][j]);
                         let solved_value = F::from(self.solved[i][j]);
 
-                        config.q_zero_or_equal.enable(&mut region, offset)?;
-                        config.q_is_equal.enable(&mut region, offset)?;
-                        config.q_is_zero.enable(&mut region, offset)?;
+                        config.q_cell.enable(&mut region, offset)?;
 
                         unsolved_cells[i].push(region.assign_advice(
                             || "unsolved",
@@ -148,13 +332,6 @@ impl<F: FieldExt> Circuit<F> for SudoukuCircuit<F> {
                             offset,
                             || Value::known(unsolved_value),
                         )?);
-                        // if use lookup, uncomment this line
-                        // config.range_check.q_lookup.enable(&mut region, offset)?;
-                        config
-                            .range_check
-                            .q_range_check
-                            .enable(&mut region, offset)?;
-
                         solved_cells[i].push(region.assign_advice(
                             || "solved",
                             config.advices[1],
@@ -162,14 +339,6 @@ impl<F: FieldExt> Circuit<F> for SudoukuCircuit<F> {
                             || Value::known(solved_value),
                         )?);
 
-                        is_zero.assign(&mut region, offset, Value::known(unsolved_value))?;
-                        is_equal.assign(
-                            &mut region,
-                            offset,
-                            Value::known(unsolved_value),
-                            Value::known(solved_value),
-                        )?;
-
                         offset += 1;
                     }
                 }
@@ -178,189 +347,179 @@ impl<F: FieldExt> Circuit<F> for SudoukuCircuit<F> {
             },
         )?;
 
-        // expose public inputs
-        unsolved_cells
+        // expose the hash of the unsolved sudoku as the public input
+        let message: [AssignedCell<F, F>; 81] = unsolved_cells
             .into_iter()
             .flatten()
-            .enumerate()
-            .try_for_each(|(i, unsolved)| {
-                layouter.constrain_instance(unsolved.cell(), config.instance, i)?;
-                Result::<(), halo2_proofs::plonk::Error>::Ok(())
-            })?;
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<81>, 3, 2>::init(
+            Pow5Chip::construct(config.poseidon.clone()),
+            layouter.namespace(|| "init hasher"),
+        )?;
+        let digest = hasher.hash(layouter.namespace(|| "hash unsolved"), message)?;
+        layouter.constrain_instance(digest.cell(), config.instance, row)?;
+
+        if let Some(permutation) = &config.permutation {
+            let rows = (0..9).map(|i| (0..9).map(|j| (i, j)).collect::<Vec<_>>());
+            let columns = (0..9).map(|j| (0..9).map(|i| (i, j)).collect::<Vec<_>>());
+            let squares = (0..9).map(|s| {
+                (0..9)
+                    .map(|c| (s / 3 * 3 + c / 3, s % 3 * 3 + c % 3))
+                    .collect::<Vec<_>>()
+            });
+            let groups = rows.chain(columns).chain(squares).collect::<Vec<_>>();
+            layouter.assign_region(
+                || "permutation of rows, columns and squares",
+                |mut region| {
+                    for (g, group) in groups.iter().enumerate() {
+                        let cells = group
+                            .iter()
+                            .map(|&(i, j)| solved_cells[i][j].clone())
+                            .collect::<Vec<_>>();
+                        permutation.assign_group(&mut region, g * 9, &cells)?;
+                    }
+                    Ok(())
+                },
+            )?;
+            return Ok(solved_cells);
+        }
 
         // Check if each row in solved has all the numbers from 1 to 9, both included
         // For each element in solved, check that this element is not equal
         // to previous elements in the same row
-        layouter.assign_region(
-            || "diff in same row",
-            |mut region| {
-                let mut offset = 0;
-                for i in 0..9 {
-                    for j in 0..9 {
-                        for k in 0..j {
-                            config.q_not_equal.enable(&mut region, offset)?;
-                            config.q_is_equal.enable(&mut region, offset)?;
-                            solved_cells[i][k].copy_advice(
-                                || "i k",
-                                &mut region,
-                                config.advices[0],
-                                offset,
-                            )?;
-                            solved_cells[i][j].copy_advice(
-                                || "i j",
-                                &mut region,
-                                config.advices[1],
-                                offset,
-                            )?;
-                            is_equal.assign(
-                                &mut region,
-                                offset,
-                                solved_cells[i][k].value().copied(),
-                                solved_cells[i][j].value().copied(),
-                            )?;
-                            offset += 1;
-                        }
-                    }
-                }
-                Ok(())
-            },
-        )?;
+        let pairs = (0..9)
+            .flat_map(|i| (0..9).flat_map(move |j| (0..j).map(move |k| ((i, k), (i, j)))))
+            .collect::<Vec<_>>();
+        assign_not_equal(&config, layouter, "diff in same row", &solved_cells, &pairs)?;
 
         // Check if each column in solved has all the numbers from 1 to 9, both included
         // For each element in solved, check that this element is not equal
         // to previous elements in the same column
-        layouter.assign_region(
-            || "diff in same column",
-            |mut region| {
-                let mut offset = 0;
-                for i in 0..9 {
-                    for j in 0..9 {
-                        for k in 0..i {
-                            config.q_not_equal.enable(&mut region, offset)?;
-                            config.q_is_equal.enable(&mut region, offset)?;
-                            solved_cells[k][j].copy_advice(
-                                || "k j",
-                                &mut region,
-                                config.advices[0],
-                                offset,
-                            )?;
-                            solved_cells[i][j].copy_advice(
-                                || "i j",
-                                &mut region,
-                                config.advices[1],
-                                offset,
-                            )?;
-                            is_equal.assign(
-                                &mut region,
-                                offset,
-                                solved_cells[k][j].value().copied(),
-                                solved_cells[i][j].value().copied(),
-                            )?;
-                            offset += 1;
-                        }
-                    }
-                }
-                Ok(())
-            },
+        let pairs = (0..9)
+            .flat_map(|i| (0..9).flat_map(move |j| (0..i).map(move |k| ((k, j), (i, j)))))
+            .collect::<Vec<_>>();
+        assign_not_equal(
+            &config,
+            layouter,
+            "diff in same column",
+            &solved_cells,
+            &pairs,
         )?;
 
         // Check if each square in solved has all the numbers from 1 to 9, both included
         // For each square and for each element in each square, check that the
-        // element is not equal to previous elements in the same square
-        layouter.assign_region(
-            || "diff in same square",
-            |mut region| {
-                let mut offset = 0;
-                for i in [0, 3, 6] {
-                    for j in [0, 3, 6] {
-                        for k in i..i + 3 {
-                            for l in j..j + 3 {
-                                for m in i..=k {
-                                    for n in j..l {
-                                        config.q_not_equal.enable(&mut region, offset)?;
-                                        config.q_is_equal.enable(&mut region, offset)?;
-                                        solved_cells[m][n].copy_advice(
-                                            || "m n",
-                                            &mut region,
-                                            config.advices[0],
-                                            offset,
-                                        )?;
-                                        solved_cells[k][l].copy_advice(
-                                            || "k l",
-                                            &mut region,
-                                            config.advices[1],
-                                            offset,
-                                        )?;
-                                        is_equal.assign(
-                                            &mut region,
-                                            offset,
-                                            solved_cells[m][n].value().copied(),
-                                            solved_cells[k][l].value().copied(),
-                                        )?;
-                                        offset += 1;
-                                    }
-                                }
+        // element is not equal to the elements of the previous rows of the same
+        // square, except the one in its own column (rows and columns are covered above)
+        let mut pairs = vec![];
+        for i in [0, 3, 6] {
+            for j in [0, 3, 6] {
+                for k in i..i + 3 {
+                    for l in j..j + 3 {
+                        for m in i..k {
+                            for n in (j..j + 3).filter(|n| *n != l) {
+                                pairs.push(((m, n), (k, l)));
                             }
                         }
                     }
                 }
-                Ok(())
-            },
+            }
+        }
+        assign_not_equal(
+            &config,
+            layouter,
+            "diff in same square",
+            &solved_cells,
+            &pairs,
         )?;
-        Ok(())
+        Ok(solved_cells)
     }
 }
 
+/// Checks that the cells of each pair differ, one pair per lane of a row.
+/// The lanes left on the last row compare 0 with 1.
+fn assign_not_equal<F: FieldExt>(
+    config: &SudokuConfig<F>,
+    layouter: &mut impl Layouter<F>,
+    name: &'static str,
+    cells: &[Vec<AssignedCell<F, F>>],
+    pairs: &[((usize, usize), (usize, usize))],
+) -> Result<(), Error> {
+    layouter.assign_region(
+        || name,
+        |mut region| {
+            for (offset, row) in pairs.chunks(config.not_equal.len()).enumerate() {
+                config.q_not_equal.enable(&mut region, offset)?;
+                for (lane, is_equal) in config.not_equal.iter().enumerate() {
+                    let (a, b) = match row.get(lane) {
+                        Some(&((i, j), (k, l))) => (
+                            cells[i][j].copy_advice(|| "a", &mut region, is_equal.a, offset)?,
+                            cells[k][l].copy_advice(|| "b", &mut region, is_equal.b, offset)?,
+                        ),
+                        None => (
+                            region.assign_advice(
+                                || "padding a",
+                                is_equal.a,
+                                offset,
+                                || Value::known(F::zero()),
+                            )?,
+                            region.assign_advice(
+                                || "padding b",
+                                is_equal.b,
+                                offset,
+                                || Value::known(F::one()),
+                            )?,
+                        ),
+                    };
+                    IsEqualChip::construct(is_equal.clone()).assign(
+                        &mut region,
+                        offset,
+                        a.value().copied(),
+                        b.value().copied(),
+                    )?;
+                }
+            }
+            Ok(())
+        },
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use std::time::Instant;
+    use std::{
+        marker::PhantomData,
+        time::{Duration, Instant},
+    };
 
     use halo2_proofs::{
         dev::{FailureLocation, MockProver, VerifyFailure},
         pasta::{vesta, Fp},
-        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Any, SingleVerifier},
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Any, Circuit, SingleVerifier},
         poly::commitment::Params,
         transcript::{Blake2bRead, Blake2bWrite, Challenge255},
     };
     use rand::rngs::ThreadRng;
+    use zk_sudoku_core::sample::{widen, SOLVED, UNSOLVED};
 
-    use super::SudoukuCircuit;
+    use super::{minimum_k, not_equal_rows, unsolved_hash, SudokuCircuit, V1SudokuCircuit, K};
+    use crate::layout::LayoutStats;
+
+    fn sample_circuit<const LOOKUP: bool, const LANES: usize, const PERMUTATION: bool>(
+    ) -> SudokuCircuit<Fp, LOOKUP, LANES, PERMUTATION> {
+        SudokuCircuit {
+            unsolved: widen(UNSOLVED),
+            solved: widen(SOLVED),
+            _marker: std::marker::PhantomData,
+        }
+    }
 
     #[test]
     fn test_circuit() {
-        let k = 10;
-        let mut circuit = SudoukuCircuit::<Fp> {
-            unsolved: [
-                [0, 0, 0, 0, 0, 6, 0, 0, 0],
-                [0, 0, 7, 2, 0, 0, 8, 0, 0],
-                [9, 0, 6, 8, 0, 0, 0, 1, 0],
-                [3, 0, 0, 7, 0, 0, 0, 2, 9],
-                [0, 0, 0, 0, 0, 0, 0, 0, 0],
-                [4, 0, 0, 5, 0, 0, 0, 7, 0],
-                [6, 5, 0, 1, 0, 0, 0, 0, 0],
-                [8, 0, 1, 0, 5, 0, 3, 0, 0],
-                [7, 9, 2, 0, 0, 0, 0, 0, 4],
-            ],
-            solved: [
-                [1, 8, 4, 3, 7, 6, 2, 9, 5],
-                [5, 3, 7, 2, 9, 1, 8, 4, 6],
-                [9, 2, 6, 8, 4, 5, 7, 1, 3],
-                [3, 6, 5, 7, 1, 8, 4, 2, 9],
-                [2, 7, 8, 4, 6, 9, 5, 3, 1],
-                [4, 1, 9, 5, 3, 2, 6, 7, 8],
-                [6, 5, 3, 1, 2, 4, 9, 8, 7],
-                [8, 4, 1, 9, 5, 7, 3, 6, 2],
-                [7, 9, 2, 6, 8, 3, 1, 5, 4],
-            ],
-            _marker: std::marker::PhantomData,
-        };
+        let k = K;
+        let mut circuit = sample_circuit::<false, 1, false>();
 
-        let public_inputs = circuit
-            .unsolved
-            .iter()
-            .flatten()
-            .map(|x| Fp::from(*x))
-            .collect::<Vec<_>>();
+        let public_inputs = vec![unsolved_hash::<Fp>(&circuit.unsolved)];
         let prover = MockProver::run(k, &circuit, vec![public_inputs.clone()]).unwrap();
         prover.assert_satisfied();
 
@@ -419,18 +578,13 @@ mod tests {
             let prover = MockProver::run(
                 k,
                 &circuit,
-                vec![circuit
-                    .unsolved
-                    .iter()
-                    .flatten()
-                    .map(|x| Fp::from(*x))
-                    .collect::<Vec<_>>()],
+                vec![vec![unsolved_hash::<Fp>(&circuit.unsolved)]],
             )
             .unwrap();
             assert_eq!(
                 prover.verify(),
                 Err(vec![VerifyFailure::ConstraintNotSatisfied {
-                    constraint: ((0, "range check").into(), 0, "range check").into(),
+                    constraint: ((0, "cell check").into(), 0, "range check").into(),
                     location: FailureLocation::InRegion {
                         region: (0, "range check and match check").into(),
                         offset: 0
@@ -441,44 +595,222 @@ mod tests {
         }
     }
 
-    #[cfg(feature = "dev-graph")]
     #[test]
-    fn print_range_check_1() {
-        use plotters::prelude::*;
+    fn test_minimum_k() {
+        let k = minimum_k(&sample_circuit::<false, 1, false>(), 1);
+        println!("minimum k: {}", k);
+        assert!(k <= K);
+        assert!(MockProver::run(
+            k - 1,
+            &sample_circuit::<false, 1, false>(),
+            vec![vec![Fp::from(0)]]
+        )
+        .is_err());
+        assert!(minimum_k(&sample_circuit::<true, 1, false>(), 1) <= K);
+    }
 
-        let root = BitMapBackend::new("range-check-1-layout.png", (1024, 3096)).into_drawing_area();
-        root.fill(&WHITE).unwrap();
-        let root = root
-            .titled("Range Check 1 Layout", ("sans-serif", 60))
-            .unwrap();
+    fn check_lanes<const LANES: usize>() -> u32 {
+        let circuit = sample_circuit::<false, LANES, false>();
+        let public_inputs = vec![unsolved_hash::<Fp>(&circuit.unsolved)];
+        let prover = MockProver::run(K, &circuit, vec![public_inputs.clone()]).unwrap();
+        prover.assert_satisfied();
 
-        let circuit = SudoukuCircuit::<Fp> {
-            unsolved: [
-                [0, 0, 0, 0, 0, 6, 0, 0, 0],
-                [0, 0, 7, 2, 0, 0, 8, 0, 0],
-                [9, 0, 6, 8, 0, 0, 0, 1, 0],
-                [3, 0, 0, 7, 0, 0, 0, 2, 9],
-                [0, 0, 0, 0, 0, 0, 0, 0, 0],
-                [4, 0, 0, 5, 0, 0, 0, 7, 0],
-                [6, 5, 0, 1, 0, 0, 0, 0, 0],
-                [8, 0, 1, 0, 5, 0, 3, 0, 0],
-                [7, 9, 2, 0, 0, 0, 0, 0, 4],
-            ],
-            solved: [
-                [1, 8, 4, 3, 7, 6, 2, 9, 5],
-                [5, 3, 7, 2, 9, 1, 8, 4, 6],
-                [9, 2, 6, 8, 4, 5, 7, 1, 3],
-                [3, 6, 5, 7, 1, 8, 4, 2, 9],
-                [2, 7, 8, 4, 6, 9, 5, 3, 1],
-                [4, 1, 9, 5, 3, 2, 6, 7, 8],
-                [6, 5, 3, 1, 2, 4, 9, 8, 7],
-                [8, 4, 1, 9, 5, 7, 3, 6, 2],
-                [7, 9, 2, 6, 8, 3, 1, 5, 4],
-            ],
-            _marker: std::marker::PhantomData,
+        // the 1 of the last row is repeated, in a lane depending on LANES
+        let mut repeated = circuit.clone();
+        repeated.solved[8][7] = 1;
+        let prover = MockProver::run(K, &repeated, vec![public_inputs]).unwrap();
+        assert!(prover.verify().is_err());
+
+        let k = minimum_k(&circuit, 1);
+        println!(
+            "lanes: {}, not equal rows: {}, minimum k: {}",
+            LANES,
+            not_equal_rows(LANES),
+            k
+        );
+        k
+    }
+
+    #[test]
+    fn test_not_equal_lanes() {
+        let k1 = check_lanes::<1>();
+        let k2 = check_lanes::<2>();
+        let k4 = check_lanes::<4>();
+        // the Poseidon permutations may take more rows, so k does not always
+        // go down
+        assert!(k4 <= k2 && k2 <= k1);
+        assert_eq!(not_equal_rows(1), 810);
+        assert_eq!(not_equal_rows(4), 81 * 2 + 41);
+    }
+
+    /// Times the keygen and the proof of `circuit`, returned in this order.
+    fn prove_time<C: Circuit<Fp> + Clone>(
+        circuit: &C,
+        unsolved: &[[u64; 9]; 9],
+    ) -> (Duration, Duration) {
+        let public_inputs = vec![unsolved_hash::<Fp>(unsolved)];
+        let params: Params<vesta::Affine> = Params::new(K);
+        let keygen_start = Instant::now();
+        let vk = keygen_vk(&params, circuit).unwrap();
+        let pk = keygen_pk(&params, vk, circuit).unwrap();
+        let keygen_time = keygen_start.elapsed();
+
+        let prove_start = Instant::now();
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(
+            &params,
+            &pk,
+            &[circuit.clone()],
+            &[&[&public_inputs]],
+            ThreadRng::default(),
+            &mut transcript,
+        )
+        .unwrap();
+        (keygen_time, prove_start.elapsed())
+    }
+
+    #[test]
+    fn test_permutation() {
+        let circuit = sample_circuit::<false, 1, true>();
+        let public_inputs = vec![unsolved_hash::<Fp>(&circuit.unsolved)];
+        let prover = MockProver::run(K, &circuit, vec![public_inputs.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        let mut repeated = circuit.clone();
+        repeated.solved[8][7] = 1;
+        let prover = MockProver::run(K, &repeated, vec![public_inputs]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 243 rows instead of 810
+        let pairwise = sample_circuit::<false, 1, false>();
+        assert!(minimum_k(&circuit, 1) <= minimum_k(&pairwise, 1));
+        for (permutation, k, (_, prove)) in [
+            (
+                false,
+                minimum_k(&pairwise, 1),
+                prove_time(&pairwise, &pairwise.unsolved),
+            ),
+            (
+                true,
+                minimum_k(&circuit, 1),
+                prove_time(&circuit, &circuit.unsolved),
+            ),
+        ] {
+            println!(
+                "permutation: {}, prove time {}ms, minimum k: {}",
+                permutation,
+                prove.as_millis(),
+                k
+            );
+        }
+    }
+
+    #[test]
+    fn test_floor_planners() {
+        let simple = sample_circuit::<false, 1, true>();
+        let v1 = V1SudokuCircuit::<Fp> {
+            unsolved: simple.unsolved,
+            solved: simple.solved,
+            _marker: PhantomData,
         };
-        halo2_proofs::dev::CircuitLayout::default()
-            .render(10, &circuit, &root)
-            .unwrap();
+        let public_inputs = vec![unsolved_hash::<Fp>(&v1.unsolved)];
+        MockProver::run(K, &v1, vec![public_inputs])
+            .unwrap()
+            .assert_satisfied();
+
+        let simple_rows = LayoutStats::collect(&simple).unwrap().rows;
+        let v1_rows = LayoutStats::collect(&v1).unwrap().rows;
+        for (name, rows, k, (keygen, prove)) in [
+            (
+                "simple",
+                simple_rows,
+                minimum_k(&simple, 1),
+                prove_time(&simple, &simple.unsolved),
+            ),
+            (
+                "v1",
+                v1_rows,
+                minimum_k(&v1, 1),
+                prove_time(&v1, &v1.unsolved),
+            ),
+        ] {
+            println!(
+                "floor planner: {}, rows: {}, minimum k: {}, keygen {}ms, prove {}ms",
+                name,
+                rows,
+                k,
+                keygen.as_millis(),
+                prove.as_millis()
+            );
+        }
+        // V1 moves regions up next to the ones over other columns
+        assert!(v1_rows <= simple_rows);
+    }
+
+    #[test]
+    fn test_unsolved_range_check() {
+        let mut circuit = sample_circuit::<false, 1, true>();
+        circuit.unsolved[0][0] = 10;
+        let public_inputs = vec![unsolved_hash::<Fp>(&circuit.unsolved)];
+        let prover = MockProver::run(K, &circuit, vec![public_inputs]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        let range_check = vec![(((Any::Advice, 0).into(), 0).into(), "0xa".to_string())];
+        assert!(failures.iter().any(|failure| matches!(
+            failure,
+            VerifyFailure::ConstraintNotSatisfied { cell_values, .. } if *cell_values == range_check
+        )));
+
+        let mut circuit = sample_circuit::<true, 1, true>();
+        circuit.unsolved[0][0] = 10;
+        let public_inputs = vec![unsolved_hash::<Fp>(&circuit.unsolved)];
+        let prover = MockProver::run(K, &circuit, vec![public_inputs]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(failures.iter().any(|failure| matches!(
+            failure,
+            VerifyFailure::Lookup {
+                lookup_index: 1,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_lookup_range_check() {
+        let mut circuit = sample_circuit::<true, 1, false>();
+        let public_inputs = vec![unsolved_hash::<Fp>(&circuit.unsolved)];
+        let prover = MockProver::run(K, &circuit, vec![public_inputs.clone()]).unwrap();
+        prover.assert_satisfied();
+
+        circuit.solved[0][0] = 10;
+        let prover = MockProver::run(K, &circuit, vec![public_inputs]).unwrap();
+        let failures = prover.verify().unwrap_err();
+        assert!(matches!(
+            failures[..],
+            [VerifyFailure::Lookup {
+                lookup_index: 0,
+                ..
+            }]
+        ));
+    }
+
+    #[cfg(feature = "dev-graph")]
+    #[test]
+    fn print_range_check_1() {
+        use crate::layout::render_layout;
+
+        render_layout(
+            &sample_circuit::<false, 1, true>(),
+            K,
+            "Range Check 1 Layout",
+            "range-check-1-layout.png".as_ref(),
+        )
+        .unwrap();
+        render_layout(
+            &sample_circuit::<true, 1, true>(),
+            K,
+            "Lookup Range Check Layout",
+            "lookup-range-check-layout.svg".as_ref(),
+        )
+        .unwrap();
     }
 }
@@ -0,0 +1,121 @@
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, P128Pow5T3, Spec},
+    Hash, Pow5Chip,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::circuit::{SudokuCircuit, SudokuConfig};
+
+/// log2 of the rows used by the circuit with the commitment, whose Poseidon
+/// permutations do not fit next to those of the puzzle hash in `K`.
+pub const COMMITTED_K: u32 = 13;
+
+/// Poseidon hash of the 81 solved cells followed by the salt, the second
+/// instance value of `CommittedSudokuCircuit`. The salt keeps the commitment
+/// hiding, so it is revealed together with the solution.
+pub fn solution_commitment<F: FieldExt>(solved: &[[u64; 9]; 9], salt: F) -> F
+where
+    P128Pow5T3: Spec<F, 3, 2>,
+{
+    let message: [F; 82] = solved
+        .iter()
+        .flatten()
+        .map(|x| F::from(*x))
+        .chain([salt])
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    poseidon::Hash::<_, P128Pow5T3, ConstantLength<82>, 3, 2>::init().hash(message)
+}
+
+/// The sudoku circuit exposing a commitment to its solution after the hash
+/// of the puzzle, for protocols selling the solution and revealing it later.
+#[derive(Default, Clone)]
+pub(crate) struct CommittedSudokuCircuit<F, const LOOKUP: bool = false> {
+    pub circuit: SudokuCircuit<F, LOOKUP>,
+    pub salt: F,
+}
+
+impl<F: FieldExt, const LOOKUP: bool> Circuit<F> for CommittedSudokuCircuit<F, LOOKUP>
+where
+    P128Pow5T3: Spec<F, 3, 2>,
+{
+    type Config = SudokuConfig<F>;
+
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        SudokuCircuit::<F, LOOKUP>::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let solved_cells = self.circuit.assign(config.clone(), &mut layouter)?;
+
+        let salt = layouter.assign_region(
+            || "salt",
+            |mut region| {
+                region.assign_advice(|| "salt", config.advices[0], 0, || Value::known(self.salt))
+            },
+        )?;
+
+        let message: [AssignedCell<F, F>; 82] = solved_cells
+            .into_iter()
+            .flatten()
+            .chain([salt])
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        let hasher = Hash::<_, _, P128Pow5T3, ConstantLength<82>, 3, 2>::init(
+            Pow5Chip::construct(config.poseidon.clone()),
+            layouter.namespace(|| "init commitment hasher"),
+        )?;
+        let commitment = hasher.hash(layouter.namespace(|| "commit solved"), message)?;
+        layouter.constrain_instance(commitment.cell(), config.instance, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{dev::MockProver, pasta::Fp};
+    use zk_sudoku_core::sample::{widen, SOLVED, UNSOLVED};
+
+    use super::{solution_commitment, CommittedSudokuCircuit, COMMITTED_K};
+    use crate::circuit::{minimum_k, unsolved_hash, SudokuCircuit};
+
+    #[test]
+    fn test_solution_commitment() {
+        let circuit = CommittedSudokuCircuit::<Fp> {
+            circuit: SudokuCircuit {
+                unsolved: widen(UNSOLVED),
+                solved: widen(SOLVED),
+                _marker: std::marker::PhantomData,
+            },
+            salt: Fp::from(42),
+        };
+        let unsolved_hash = unsolved_hash::<Fp>(&circuit.circuit.unsolved);
+        assert!(minimum_k(&circuit, 2) <= COMMITTED_K);
+
+        let commitment = solution_commitment(&circuit.circuit.solved, circuit.salt);
+        let prover =
+            MockProver::run(COMMITTED_K, &circuit, vec![vec![unsolved_hash, commitment]]).unwrap();
+        prover.assert_satisfied();
+
+        // committed with another salt
+        let commitment = solution_commitment(&circuit.circuit.solved, Fp::from(43));
+        let prover =
+            MockProver::run(COMMITTED_K, &circuit, vec![vec![unsolved_hash, commitment]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,96 @@
+//! Costs of a circuit estimated from its layout, to size `k` and compare the
+//! configurations of the sudoku circuit without proving.
+
+use halo2_proofs::{
+    dev::CircuitCost,
+    pasta::{vesta, Fp},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+use serde_json::{json, Value as Json};
+
+use crate::{
+    circuit::{minimum_k, SudokuCircuit},
+    layout::LayoutStats,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CostEstimate {
+    /// The smallest `k` fitting the circuit, see `minimum_k`.
+    pub k: u32,
+    /// Rows used by the regions and tables, without the blinding rows.
+    pub rows: usize,
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub lookups: usize,
+    /// Bytes of a proof over vesta at `k`.
+    pub proof_size: usize,
+}
+
+impl CostEstimate {
+    /// Estimates the costs of `circuit` with `num_instances` instance values.
+    pub fn estimate<C: Circuit<Fp>>(circuit: &C, num_instances: usize) -> Result<Self, Error> {
+        let k = minimum_k(circuit, num_instances);
+        let stats = LayoutStats::collect(circuit)?;
+        let mut cs = ConstraintSystem::<Fp>::default();
+        C::configure(&mut cs);
+        let cost = CircuitCost::<vesta::Point, C>::measure(k as usize, circuit);
+
+        Ok(Self {
+            k,
+            rows: stats.rows,
+            advice_columns: stats.advice_columns,
+            fixed_columns: stats.fixed_columns,
+            instance_columns: stats.instance_columns,
+            lookups: cs.lookups().len(),
+            proof_size: cost.proof_size(num_instances).into(),
+        })
+    }
+
+    pub fn to_json(&self) -> Json {
+        json!({
+            "k": self.k,
+            "rows": self.rows,
+            "advice_columns": self.advice_columns,
+            "fixed_columns": self.fixed_columns,
+            "instance_columns": self.instance_columns,
+            "lookups": self.lookups,
+            "proof_size": self.proof_size,
+        })
+    }
+}
+
+/// Costs of the sudoku circuit in the given configuration, see
+/// `SudokuCircuit`. Only the 9x9 grid is supported by the circuit.
+pub fn sudoku_cost<const LOOKUP: bool, const LANES: usize, const PERMUTATION: bool>(
+) -> Result<CostEstimate, Error> {
+    CostEstimate::estimate(
+        &SudokuCircuit::<Fp, LOOKUP, LANES, PERMUTATION>::default(),
+        1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::sudoku_cost;
+    use crate::circuit::K;
+
+    #[test]
+    fn test_sudoku_cost() {
+        let permutation = sudoku_cost::<false, 1, true>().unwrap();
+        let pairwise = sudoku_cost::<false, 1, false>().unwrap();
+        let lookup = sudoku_cost::<true, 1, true>().unwrap();
+        println!("permutation: {}", permutation.to_json());
+        println!("pairwise: {}", pairwise.to_json());
+        println!("lookup: {}", lookup.to_json());
+
+        assert!(permutation.k <= K);
+        assert!(permutation.rows < pairwise.rows);
+        assert!(permutation.advice_columns > pairwise.advice_columns);
+        assert_eq!(permutation.instance_columns, 1);
+        assert_eq!(permutation.lookups, 0);
+        assert_eq!(lookup.lookups, 2);
+        // every lookup adds commitments and evaluations
+        assert!(lookup.proof_size > permutation.proof_size);
+    }
+}
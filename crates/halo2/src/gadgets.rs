@@ -2,6 +2,7 @@ use halo2_proofs::{arithmetic::FieldExt, plonk::Expression};
 
 pub mod is_zero;
 pub mod not_equal;
+pub mod permutation;
 pub mod range_check;
 
 /// Check that an expression is in the small range [0..range),
@@ -3,6 +3,9 @@ use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
 #[derive(Clone, Debug)]
 pub struct IsZeroConfig<F> {
     pub value_inv: Column<Advice>,
+    /// The output, 1 if the value is zero and 0 otherwise.
+    pub is_zero: Column<Advice>,
+    /// The output queried at the current row.
     pub is_zero_expr: Expression<F>,
 }
 
@@ -26,6 +29,7 @@ impl<F: FieldExt> IsZeroChip<F> {
         q_enable: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
         value: impl FnOnce(&mut VirtualCells<'_, F>) -> Expression<F>,
         value_inv: Column<Advice>,
+        is_zero: Column<Advice>,
     ) -> IsZeroConfig<F> {
         let mut is_zero_expr = Expression::Constant(F::zero());
 
@@ -42,24 +46,38 @@ impl<F: FieldExt> IsZeroChip<F> {
             let q_enable = q_enable(meta);
             let value_inv = meta.query_advice(value_inv, Rotation::cur());
 
-            is_zero_expr = Expression::Constant(F::one()) - value.clone() * value_inv;
-            vec![q_enable * value * is_zero_expr.clone()]
+            let is_zero_value = Expression::Constant(F::one()) - value.clone() * value_inv;
+            is_zero_expr = meta.query_advice(is_zero, Rotation::cur());
+            vec![
+                q_enable.clone() * value * is_zero_value.clone(),
+                // the output is the expression above, 0 or 1
+                q_enable * (is_zero_expr.clone() - is_zero_value),
+            ]
         });
 
         IsZeroConfig {
             value_inv,
+            is_zero,
             is_zero_expr,
         }
     }
 
+    /// Assigns the inverse hint and returns the constrained output.
     pub fn assign(
         &self,
         region: &mut Region<'_, F>,
         offset: usize,
         value: Value<F>,
-    ) -> Result<(), Error> {
+    ) -> Result<AssignedCell<F, F>, Error> {
         let value_inv = value.map(|value| value.invert().unwrap_or(F::zero()));
         region.assign_advice(|| "value inv", self.config.value_inv, offset, || value_inv)?;
-        Ok(())
+        let is_zero = value.map(|value| {
+            if value == F::zero() {
+                F::one()
+            } else {
+                F::zero()
+            }
+        });
+        region.assign_advice(|| "is zero", self.config.is_zero, offset, || is_zero)
     }
 }
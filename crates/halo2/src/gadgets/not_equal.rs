@@ -30,6 +30,7 @@ impl<F: FieldExt> IsEqualChip<F> {
         q_enable: Selector,
         a: Column<Advice>,
         b: Column<Advice>,
+        diff_inv: Column<Advice>,
         is_equal: Column<Advice>,
     ) -> IsEqualConfig<F> {
         let is_zero_config = IsZeroChip::configure(
@@ -40,6 +41,7 @@ impl<F: FieldExt> IsEqualChip<F> {
                 let b = meta.query_advice(b, Rotation::cur());
                 a - b
             },
+            diff_inv,
             is_equal,
         );
 
@@ -51,22 +53,16 @@ impl<F: FieldExt> IsEqualChip<F> {
         }
     }
 
+    /// Assigns the inverse hint of `a - b` and returns the constrained
+    /// output, 1 if `a == b` and 0 otherwise.
     pub fn assign(
         &self,
         region: &mut Region<'_, F>,
         offset: usize,
         a: Value<F>,
         b: Value<F>,
-    ) -> Result<(), Error> {
-        let value = a - b;
-        let value_inv = value.map(|value| value.invert().unwrap_or(F::zero()));
-        region.assign_advice(
-            || "value inv",
-            self.config.is_zero_config.value_inv,
-            offset,
-            || value_inv,
-        )?;
-        Ok(())
+    ) -> Result<AssignedCell<F, F>, Error> {
+        IsZeroChip::construct(self.config.is_zero_config.clone()).assign(region, offset, a - b)
     }
 }
 
@@ -99,8 +95,9 @@ mod tests {
             let q_enable = meta.selector();
             let a_col = meta.advice_column();
             let b_col = meta.advice_column();
+            let diff_inv = meta.advice_column();
             let is_equal = meta.advice_column();
-            let config = IsEqualChip::configure(meta, q_enable, a_col, b_col, is_equal);
+            let config = IsEqualChip::configure(meta, q_enable, a_col, b_col, diff_inv, is_equal);
 
             meta.create_gate("chech is_not_equal", |meta| {
                 let q_enable = meta.query_selector(q_enable);
@@ -126,7 +123,9 @@ mod tests {
                     region.assign_advice(|| "a", is_equal.config.a, 0, || Value::known(self.a))?;
                     region.assign_advice(|| "b", is_equal.config.b, 0, || Value::known(self.b))?;
 
-                    is_equal.assign(&mut region, 0, Value::known(self.a), Value::known(self.b))
+                    is_equal
+                        .assign(&mut region, 0, Value::known(self.a), Value::known(self.b))
+                        .map(|_| ())
                 },
             )?;
 
@@ -167,4 +166,72 @@ mod tests {
             );
         }
     }
+
+    #[derive(Default)]
+    struct IsEqualCircuit<F: FieldExt> {
+        a: F,
+        b: F,
+    }
+
+    impl<F: FieldExt> Circuit<F> for IsEqualCircuit<F> {
+        type Config = (IsEqualConfig<F>, Column<Instance>);
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let q_enable = meta.selector();
+            let a_col = meta.advice_column();
+            let b_col = meta.advice_column();
+            let diff_inv = meta.advice_column();
+            let is_equal = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(is_equal);
+            meta.enable_equality(instance);
+            let config = IsEqualChip::configure(meta, q_enable, a_col, b_col, diff_inv, is_equal);
+            (config, instance)
+        }
+
+        fn synthesize(
+            &self,
+            (config, instance): Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let is_equal = IsEqualChip::construct(config);
+
+            let output = layouter.assign_region(
+                || "a == b",
+                |mut region| {
+                    is_equal.config.q_enable.enable(&mut region, 0)?;
+                    region.assign_advice(|| "a", is_equal.config.a, 0, || Value::known(self.a))?;
+                    region.assign_advice(|| "b", is_equal.config.b, 0, || Value::known(self.b))?;
+
+                    is_equal.assign(&mut region, 0, Value::known(self.a), Value::known(self.b))
+                },
+            )?;
+            layouter.constrain_instance(output.cell(), instance, 0)
+        }
+    }
+
+    #[test]
+    fn test_is_equal_output() {
+        let k = 4;
+        let run = |a: u64, b: u64, output: u64| {
+            let circuit = IsEqualCircuit::<Fp> {
+                a: Fp::from(a),
+                b: Fp::from(b),
+            };
+            MockProver::run(k, &circuit, vec![vec![Fp::from(output)]])
+                .unwrap()
+                .verify()
+        };
+
+        assert!(run(1, 1, 1).is_ok());
+        assert!(run(1, 2, 0).is_ok());
+        // the output is constrained, not only hinted
+        assert!(run(1, 2, 1).is_err());
+        assert!(run(1, 1, 0).is_err());
+    }
 }
@@ -0,0 +1,178 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::*, plonk::*, poly::Rotation};
+
+/// Checks that 9 consecutive rows of `value` hold a permutation of 1..=9.
+///
+/// Each row decomposes its value into one-hot bits, one per digit, and on the
+/// first row of a group the bits of each digit must sum to 1 over the 9 rows.
+/// This needs no random challenge, and takes 9 rows per row, column or box
+/// where the pairwise not equal checks take 36.
+///
+///    value  | bits[0] ... bits[8] | q_cell | q_group
+///   --------------------------------------------------
+///      3    |    0    0 1 ...  0  |   1    |    1
+///      7    |    0    ...  1 0 0  |   1    |    0
+///     ...
+#[derive(Debug, Clone)]
+pub struct PermutationConfig<F> {
+    pub q_cell: Selector,
+    pub q_group: Selector,
+    pub value: Column<Advice>,
+    pub bits: [Column<Advice>; 9],
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> PermutationConfig<F> {
+    pub fn configure(meta: &mut ConstraintSystem<F>, value: Column<Advice>) -> Self {
+        let q_cell = meta.selector();
+        let q_group = meta.selector();
+        let bits = [(); 9].map(|_| meta.advice_column());
+
+        meta.create_gate("one hot digit", |meta| {
+            let q = meta.query_selector(q_cell);
+            let value = meta.query_advice(value, Rotation::cur());
+            let bits = bits.map(|bit| meta.query_advice(bit, Rotation::cur()));
+            let one = Expression::Constant(F::one());
+
+            let mut constraints = bits
+                .iter()
+                .map(|bit| q.clone() * bit.clone() * (one.clone() - bit.clone()))
+                .collect::<Vec<_>>();
+            let sum = bits
+                .iter()
+                .fold(Expression::Constant(F::zero()), |acc, bit| {
+                    acc + bit.clone()
+                });
+            constraints.push(q.clone() * (sum - one));
+            let digit = bits
+                .iter()
+                .enumerate()
+                .fold(Expression::Constant(F::zero()), |acc, (i, bit)| {
+                    acc + bit.clone() * Expression::Constant(F::from(i as u64 + 1))
+                });
+            constraints.push(q * (digit - value));
+            constraints
+        });
+
+        meta.create_gate("permutation", |meta| {
+            let q = meta.query_selector(q_group);
+            bits.iter()
+                .map(|bit| {
+                    let count = (0..9).fold(Expression::Constant(F::zero()), |acc, row| {
+                        acc + meta.query_advice(*bit, Rotation(row))
+                    });
+                    q.clone() * (count - Expression::Constant(F::one()))
+                })
+                .collect::<Vec<_>>()
+        });
+
+        Self {
+            q_cell,
+            q_group,
+            value,
+            bits,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Copies the 9 cells of a group to the rows starting at `offset`.
+    pub fn assign_group(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        self.q_group.enable(region, offset)?;
+        for (row, cell) in cells.iter().enumerate() {
+            self.q_cell.enable(region, offset + row)?;
+            cell.copy_advice(|| "cell", region, self.value, offset + row)?;
+            for (i, bit) in self.bits.iter().enumerate() {
+                let is_digit = cell.value().map(|value| {
+                    if *value == F::from(i as u64 + 1) {
+                        F::one()
+                    } else {
+                        F::zero()
+                    }
+                });
+                region.assign_advice(|| "bit", *bit, offset + row, || is_digit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{circuit::floor_planner::V1, dev::MockProver, pasta::Fp, plonk::Circuit};
+
+    use super::*;
+
+    #[derive(Default)]
+    struct GroupCircuit<F: FieldExt> {
+        values: [u64; 9],
+        _marker: PhantomData<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for GroupCircuit<F> {
+        type Config = PermutationConfig<F>;
+        type FloorPlanner = V1;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let value = meta.advice_column();
+            meta.enable_equality(value);
+            PermutationConfig::configure(meta, value)
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            layouter.assign_region(
+                || "group",
+                |mut region| {
+                    // the cells as the sudoku circuit would have assigned them
+                    let cells = self
+                        .values
+                        .iter()
+                        .enumerate()
+                        .map(|(row, value)| {
+                            region.assign_advice(
+                                || "value",
+                                config.value,
+                                9 + row,
+                                || Value::known(F::from(*value)),
+                            )
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    config.assign_group(&mut region, 0, &cells)
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn test_permutation() {
+        let k = 5;
+
+        let circuit = GroupCircuit::<Fp> {
+            values: [5, 3, 7, 2, 9, 1, 8, 4, 6],
+            _marker: PhantomData,
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        prover.assert_satisfied();
+
+        // same sum, but 4 is missing and 3 repeated
+        let circuit = GroupCircuit::<Fp> {
+            values: [5, 3, 7, 2, 9, 1, 8, 3, 7],
+            _marker: PhantomData,
+        };
+        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -27,6 +27,16 @@ pub struct RangeCheckConfig<F: FieldExt, const START: usize, const END: usize> {
     _marker: PhantomData<F>,
 }
 
+/// Given a start, a end, and a value v, returns the expression
+/// (start - v) * (start + 1 - v) * ... * (end - v)
+pub fn range_check<F: FieldExt>(start: usize, end: usize, value: Expression<F>) -> Expression<F> {
+    assert!(end > start);
+    (start + 1..=end).fold(
+        value.clone() - Expression::Constant(F::from(start as u64)),
+        |expr, i| expr * (Expression::Constant(F::from(i as u64)) - value.clone()),
+    )
+}
+
 impl<F: FieldExt, const START: usize, const END: usize> RangeCheckConfig<F, START, END> {
     pub fn configure(
         meta: &mut ConstraintSystem<F>,
@@ -41,16 +51,6 @@ impl<F: FieldExt, const START: usize, const END: usize> RangeCheckConfig<F, STAR
             let q = meta.query_selector(q_range_check);
             let value = meta.query_advice(value, Rotation::cur());
 
-            // Given a start, a end, and a value v, returns the expression
-            // (start - v) * (start + 1 - v) * ... * (end - v)
-            let range_check = |start: usize, end: usize, value: Expression<F>| {
-                assert!(start > 0 && end > start);
-                (start + 1..=end).fold(
-                    value.clone() - Expression::Constant(F::from(start as u64)),
-                    |expr, i| expr * (Expression::Constant(F::from(i as u64)) - value.clone()),
-                )
-            };
-
             Constraints::with_selector(q, [("range check", range_check(START, END, value))])
         });
 
@@ -0,0 +1,202 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use halo2_proofs::{
+    pasta::{vesta, Fp},
+    plonk::{keygen_vk, Error, VerifyingKey},
+    poly::commitment::Params,
+};
+
+use crate::{circuit::SudokuCircuit, prover::Halo2Keys};
+
+const MAGIC: &[u8; 8] = b"ZKSUDOKU";
+/// Bumped whenever the layout of a file or the circuit changes.
+pub const VERSION: u32 = 5;
+
+/// What a file holds, written after the version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Params = 0,
+    VerifyingKey = 1,
+    Proof = 2,
+}
+
+#[derive(Debug)]
+pub enum KeyError {
+    Io(io::Error),
+    Halo2(Error),
+    /// The file was not written by this crate.
+    Magic,
+    /// The file was written by another version of this crate.
+    Version(u32),
+    /// The file holds something else, e.g. a proof read as a key.
+    Kind(u8),
+}
+
+impl From<io::Error> for KeyError {
+    fn from(err: io::Error) -> Self {
+        KeyError::Io(err)
+    }
+}
+
+impl From<Error> for KeyError {
+    fn from(err: Error) -> Self {
+        KeyError::Halo2(err)
+    }
+}
+
+fn write_header<W: Write>(writer: &mut W, kind: FileKind) -> io::Result<()> {
+    writer.write_all(MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    writer.write_all(&[kind as u8])
+}
+
+fn read_header<R: Read>(reader: &mut R, kind: FileKind) -> Result<(), KeyError> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(KeyError::Magic);
+    }
+    let mut version = [0; 4];
+    reader.read_exact(&mut version)?;
+    let version = u32::from_le_bytes(version);
+    if version != VERSION {
+        return Err(KeyError::Version(version));
+    }
+    let mut found = [0];
+    reader.read_exact(&mut found)?;
+    if found[0] != kind as u8 {
+        return Err(KeyError::Kind(found[0]));
+    }
+    Ok(())
+}
+
+/// Writes the IPA parameters to `path`.
+pub fn write_params(
+    params: &Params<vesta::Affine>,
+    path: impl AsRef<Path>,
+) -> Result<(), KeyError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_header(&mut writer, FileKind::Params)?;
+    params.write(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads back the parameters written by `write_params`.
+pub fn read_params(path: impl AsRef<Path>) -> Result<Params<vesta::Affine>, KeyError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    read_header(&mut reader, FileKind::Params)?;
+    Ok(Params::read(&mut reader)?)
+}
+
+/// The content of the file written by `write_params`, for callers without a
+/// file system such as a browser.
+pub fn params_to_bytes(params: &Params<vesta::Affine>) -> Result<Vec<u8>, KeyError> {
+    let mut bytes = vec![];
+    write_header(&mut bytes, FileKind::Params)?;
+    params.write(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads the parameters from the content of a file written by `write_params`.
+pub fn params_from_bytes(mut bytes: &[u8]) -> Result<Params<vesta::Affine>, KeyError> {
+    read_header(&mut bytes, FileKind::Params)?;
+    Ok(Params::read(&mut bytes)?)
+}
+
+/// Writes the verifying key to `path`. halo2 does not serialize verifying
+/// keys, so only the parameters are written and `read_verifying_key` derives
+/// the key again, which is deterministic for the sudoku circuit.
+pub fn write_verifying_key(
+    vk: &Halo2Keys<VerifyingKey<vesta::Affine>>,
+    path: impl AsRef<Path>,
+) -> Result<(), KeyError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_header(&mut writer, FileKind::VerifyingKey)?;
+    vk.params.write(&mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads back the verifying key written by `write_verifying_key`.
+pub fn read_verifying_key(
+    path: impl AsRef<Path>,
+) -> Result<Halo2Keys<VerifyingKey<vesta::Affine>>, KeyError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    read_header(&mut reader, FileKind::VerifyingKey)?;
+    let params = Params::read(&mut reader)?;
+    let key = keygen_vk(&params, &SudokuCircuit::<Fp>::default())?;
+    Ok(Halo2Keys { params, key })
+}
+
+pub fn write_proof(proof: &[u8], path: impl AsRef<Path>) -> Result<(), KeyError> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_header(&mut writer, FileKind::Proof)?;
+    writer.write_all(proof)?;
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn read_proof(path: impl AsRef<Path>) -> Result<Vec<u8>, KeyError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    read_header(&mut reader, FileKind::Proof)?;
+    let mut proof = vec![];
+    reader.read_to_end(&mut proof)?;
+    Ok(proof)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use rand::rngs::ThreadRng;
+    use zk_sudoku_core::{sample, SudokuProver};
+
+    use super::{
+        params_from_bytes, params_to_bytes, read_params, read_proof, read_verifying_key,
+        write_params, write_proof, write_verifying_key, KeyError, VERSION,
+    };
+    use crate::prover::Halo2Prover;
+
+    #[test]
+    fn test_keys_round_trip() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ThreadRng::default();
+        let prover = Halo2Prover::default();
+        let (pk, vk) = prover.setup(&mut rng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+
+        let dir = env::temp_dir().join("zk_sudoku_halo2_keys_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        write_params(&pk.params, dir.join("params.bin")).unwrap();
+        write_verifying_key(&vk, dir.join("vk.bin")).unwrap();
+        write_proof(&proof, dir.join("proof.bin")).unwrap();
+
+        // as another process would
+        let params = read_params(dir.join("params.bin")).unwrap();
+        assert_eq!(params.get_g(), pk.params.get_g());
+        let bytes = params_to_bytes(&params).unwrap();
+        assert_eq!(bytes, std::fs::read(dir.join("params.bin")).unwrap());
+        assert_eq!(params_from_bytes(&bytes).unwrap().get_g(), params.get_g());
+        let vk = read_verifying_key(dir.join("vk.bin")).unwrap();
+        let proof = read_proof(dir.join("proof.bin")).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        assert!(matches!(
+            read_verifying_key(dir.join("proof.bin")),
+            Err(KeyError::Kind(2))
+        ));
+        let mut bytes = std::fs::read(dir.join("proof.bin")).unwrap();
+        bytes[8] += 1;
+        std::fs::write(dir.join("proof.bin"), bytes).unwrap();
+        assert!(matches!(
+            read_proof(dir.join("proof.bin")),
+            Err(KeyError::Version(v)) if v == VERSION + 1
+        ));
+    }
+}
@@ -0,0 +1,320 @@
+//! Statistics of the layout of a circuit, and its rendering with the
+//! `dev-graph` feature.
+
+use std::collections::HashSet;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Value,
+    pasta::Fp,
+    plonk::{
+        Advice, Any, Assigned, Assignment, Circuit, Column, ConstraintSystem, Error, Fixed,
+        FloorPlanner, Instance, Selector,
+    },
+};
+use serde_json::{json, Value as Json};
+
+use crate::circuit::SudokuCircuit;
+
+/// The rows and columns used by one region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionStats {
+    pub name: String,
+    /// First row of the region, as placed by the floor planner.
+    pub offset: usize,
+    pub rows: usize,
+    /// Advice and fixed columns assigned by the region.
+    pub columns: usize,
+}
+
+/// How a circuit is laid out, without the blinding rows.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutStats {
+    pub regions: Vec<RegionStats>,
+    /// Rows up to the last one assigned, including the lookup tables.
+    pub rows: usize,
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+}
+
+impl LayoutStats {
+    /// Lays out `circuit` as the floor planner of the prover would.
+    pub fn collect<F: FieldExt, C: Circuit<F>>(circuit: &C) -> Result<Self, Error> {
+        let mut cs = ConstraintSystem::default();
+        let config = C::configure(&mut cs);
+        let mut layout = Layout::default();
+        C::FloorPlanner::synthesize(&mut layout, circuit, config, cs.constants().clone())?;
+
+        Ok(Self {
+            regions: layout.regions.into_iter().map(RegionStats::from).collect(),
+            rows: layout.rows,
+            advice_columns: cs.num_advice_columns(),
+            fixed_columns: cs.num_fixed_columns(),
+            instance_columns: cs.num_instance_columns(),
+            selectors: cs.num_selectors(),
+        })
+    }
+
+    pub fn to_json(&self) -> Json {
+        json!({
+            "rows": self.rows,
+            "advice_columns": self.advice_columns,
+            "fixed_columns": self.fixed_columns,
+            "instance_columns": self.instance_columns,
+            "selectors": self.selectors,
+            "regions": self.regions.iter().map(|region| json!({
+                "name": region.name,
+                "offset": region.offset,
+                "rows": region.rows,
+                "columns": region.columns,
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Region {
+    name: String,
+    rows: Option<(usize, usize)>,
+    columns: HashSet<Column<Any>>,
+}
+
+impl From<Region> for RegionStats {
+    fn from(region: Region) -> Self {
+        let (offset, rows) = region
+            .rows
+            .map_or((0, 0), |(start, end)| (start, end - start + 1));
+        Self {
+            name: region.name,
+            offset,
+            rows,
+            columns: region.columns.len(),
+        }
+    }
+}
+
+/// Records the regions, as `halo2_proofs::dev::CircuitLayout` does to draw
+/// them.
+#[derive(Debug, Default)]
+struct Layout {
+    regions: Vec<Region>,
+    current: Option<Region>,
+    rows: usize,
+}
+
+impl Layout {
+    fn update(&mut self, column: Column<Any>, row: usize) {
+        self.rows = self.rows.max(row + 1);
+        if let Some(region) = &mut self.current {
+            region.columns.insert(column);
+            region.rows = Some(match region.rows {
+                Some((start, end)) => (start.min(row), end.max(row)),
+                None => (row, row),
+            });
+        }
+    }
+}
+
+impl<F: FieldExt> Assignment<F> for Layout {
+    fn enter_region<NR, N>(&mut self, name_fn: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.current = Some(Region {
+            name: name_fn().into(),
+            ..Default::default()
+        });
+    }
+
+    fn exit_region(&mut self) {
+        self.regions.extend(self.current.take());
+    }
+
+    fn enable_selector<A, AR>(&mut self, _: A, _: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.rows = self.rows.max(row + 1);
+        Ok(())
+    }
+
+    fn query_instance(&self, _: Column<Instance>, _: usize) -> Result<Value<F>, Error> {
+        Ok(Value::unknown())
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Advice>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.update(column.into(), row);
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Fixed>,
+        row: usize,
+        _: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.update(column.into(), row);
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        _: Column<Fixed>,
+        _: usize,
+        _: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
+
+/// Draws every region of `circuit` with `2^k` rows to `path`, as SVG if the
+/// extension is `svg`, as PNG otherwise.
+#[cfg(feature = "dev-graph")]
+pub fn render_layout<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    k: u32,
+    title: &str,
+    path: &std::path::Path,
+) -> Result<(), String> {
+    use plotters::prelude::*;
+
+    fn render<F: FieldExt, C: Circuit<F>, DB: DrawingBackend>(
+        root: DrawingArea<DB, plotters::coord::Shift>,
+        circuit: &C,
+        k: u32,
+        title: &str,
+    ) -> Result<(), String> {
+        root.fill(&WHITE).map_err(|err| err.to_string())?;
+        let root = root
+            .titled(title, ("sans-serif", 60))
+            .map_err(|err| err.to_string())?;
+        halo2_proofs::dev::CircuitLayout::default()
+            .render(k, circuit, &root)
+            .map_err(|err| err.to_string())?;
+        root.present().map_err(|err| err.to_string())
+    }
+
+    let size = (1024, 3096);
+    if path
+        .extension()
+        .map_or(false, |extension| extension == "svg")
+    {
+        render(
+            SVGBackend::new(path, size).into_drawing_area(),
+            circuit,
+            k,
+            title,
+        )
+    } else {
+        render(
+            BitMapBackend::new(path, size).into_drawing_area(),
+            circuit,
+            k,
+            title,
+        )
+    }
+}
+
+/// Statistics of the sudoku circuit over Pasta, with the range check by
+/// lookup if `lookup` is set, by custom gate otherwise.
+pub fn sudoku_stats(lookup: bool) -> Result<LayoutStats, Error> {
+    if lookup {
+        LayoutStats::collect(&SudokuCircuit::<Fp, true>::default())
+    } else {
+        LayoutStats::collect(&SudokuCircuit::<Fp, false>::default())
+    }
+}
+
+/// Draws the sudoku circuit to `path`, see `sudoku_stats`.
+#[cfg(feature = "dev-graph")]
+pub fn render_sudoku(lookup: bool, path: &std::path::Path) -> Result<(), String> {
+    if lookup {
+        let circuit = SudokuCircuit::<Fp, true>::default();
+        render_layout(
+            &circuit,
+            crate::circuit::K,
+            "Sudoku Layout, Lookup Range Check",
+            path,
+        )
+    } else {
+        let circuit = SudokuCircuit::<Fp, false>::default();
+        render_layout(
+            &circuit,
+            crate::circuit::K,
+            "Sudoku Layout, Custom Gate Range Check",
+            path,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::Fp;
+
+    use super::{sudoku_stats, LayoutStats};
+    use crate::circuit::SudokuCircuit;
+
+    #[test]
+    fn test_layout_stats() {
+        let stats = LayoutStats::collect(&SudokuCircuit::<Fp>::default()).unwrap();
+        println!("{}", stats.to_json());
+
+        let region = stats
+            .regions
+            .iter()
+            .find(|region| region.name == "range check and match check")
+            .unwrap();
+        assert_eq!(region.rows, 81);
+        // the cells only, checked by a single gate
+        assert_eq!(region.columns, 2);
+        let region = stats
+            .regions
+            .iter()
+            .find(|region| region.name == "permutation of rows, columns and squares")
+            .unwrap();
+        assert_eq!(region.rows, 243);
+        assert!(stats.rows >= 243);
+        assert_eq!(stats.instance_columns, 1);
+
+        // the tables of the lookups are fixed columns
+        let lookup = sudoku_stats(true).unwrap();
+        assert_eq!(lookup.advice_columns, stats.advice_columns);
+        assert_eq!(lookup.fixed_columns, stats.fixed_columns + 2);
+    }
+}
@@ -1,2 +1,19 @@
+pub use halo2_proofs;
+
 pub mod gadgets;
-pub mod circuit;
\ No newline at end of file
+pub mod batch;
+pub mod circuit;
+pub mod commitment;
+pub mod cost;
+pub mod keys;
+pub mod layout;
+pub mod prover;
+pub mod report;
+pub mod transcript;
+
+#[cfg(test)]
+mod negative_tests;
+
+pub use circuit::SudokuCircuit;
+pub use prover::{keygen, prove, prove_with, setup, verify, verify_with, Halo2Keys, Halo2Prover};
+pub use transcript::TranscriptHash;
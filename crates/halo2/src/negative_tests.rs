@@ -0,0 +1,233 @@
+//! Mutations of a valid witness, one category of rule at a time, each with
+//! the constraint it must break, as the negative tests of the arkworks crate
+//! do with fixed grids.
+
+use halo2_proofs::{
+    dev::{FailureLocation, MockProver, VerifyFailure},
+    pasta::Fp,
+};
+use zk_sudoku_core::sample;
+
+use crate::circuit::{unsolved_hash, SudokuCircuit, K};
+
+const UNSOLVED: [[u64; 9]; 9] = sample::widen(sample::UNSOLVED);
+const SOLVED: [[u64; 9]; 9] = sample::widen(sample::SOLVED);
+
+const MATCH_REGION: &str = "range check and match check";
+const PERMUTATION_REGION: &str = "permutation of rows, columns and squares";
+
+/// A failure of the mock prover, the constraint and region being read from
+/// their names as their indexes change with the configuration.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Failure {
+    Gate {
+        /// The name of the constraint, or of its gate if it has none.
+        constraint: String,
+        region: String,
+        offset: usize,
+    },
+    Lookup,
+    Instance,
+    Other(String),
+}
+
+/// The first name in `Constraint 0 ('name') in gate 3 ('name')`, that of
+/// the gate if the constraint has none, or in `Region 3 ('name')`.
+fn quoted_name(display: String) -> String {
+    let start = display.find("('").map_or(0, |i| i + 2);
+    let end = display[start..]
+        .find("')")
+        .map_or(display.len(), |i| start + i);
+    display[start..end].to_string()
+}
+
+fn failures<const LOOKUP: bool, const PERMUTATION: bool>(
+    circuit: &SudokuCircuit<Fp, LOOKUP, 1, PERMUTATION>,
+    instance: Fp,
+) -> Vec<Failure> {
+    let prover = MockProver::run(K, circuit, vec![vec![instance]]).unwrap();
+    prover
+        .verify()
+        .err()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|failure| match failure {
+            VerifyFailure::ConstraintNotSatisfied {
+                constraint,
+                location: FailureLocation::InRegion { region, offset },
+                ..
+            } => Failure::Gate {
+                constraint: quoted_name(constraint.to_string()),
+                region: quoted_name(region.to_string()),
+                offset,
+            },
+            VerifyFailure::Lookup { .. } => Failure::Lookup,
+            VerifyFailure::Permutation { .. } => Failure::Instance,
+            failure => Failure::Other(failure.to_string()),
+        })
+        .collect()
+}
+
+fn gate(constraint: &str, region: &str, offset: usize) -> Failure {
+    Failure::Gate {
+        constraint: constraint.to_string(),
+        region: region.to_string(),
+        offset,
+    }
+}
+
+/// A mutated solution, and what must fail in the permutation and pairwise
+/// layouts. The pairwise regions are only checked by name, their offsets
+/// depending on the order of the pairs.
+struct Mutation {
+    name: String,
+    solved: [[u64; 9]; 9],
+    permutation: Failure,
+    pairwise: (&'static str, &'static str),
+}
+
+/// The first cell of `cells` to fill, so that a mutation breaks one rule only
+/// and not the match with the puzzle.
+fn empty_cell(cells: impl Iterator<Item = (usize, usize)>) -> (usize, usize) {
+    cells.find(|&(i, j)| UNSOLVED[i][j] == 0).unwrap()
+}
+
+fn mutations() -> Vec<Mutation> {
+    let mut mutations = vec![];
+    for i in 0..9 {
+        // a digit out of range in each row
+        let j = (i * 4) % 9;
+        let mut solved = SOLVED;
+        solved[i][j] = 10;
+        mutations.push(Mutation {
+            name: format!("out of range at ({}, {})", i, j),
+            solved,
+            permutation: gate("range check", MATCH_REGION, i * 9 + j),
+            pairwise: ("range check", MATCH_REGION),
+        });
+
+        // a given digit changed in each row with one
+        if let Some(j) = (0..9).find(|&j| UNSOLVED[i][j] != 0) {
+            let mut solved = SOLVED;
+            solved[i][j] = solved[i][j] % 9 + 1;
+            mutations.push(Mutation {
+                name: format!("given changed at ({}, {})", i, j),
+                solved,
+                permutation: gate("given cell", MATCH_REGION, i * 9 + j),
+                pairwise: ("given cell", MATCH_REGION),
+            });
+        }
+
+        // a digit repeated in each row, column and box
+        let (row, j) = empty_cell((0..9).map(|j| (i, j)));
+        let mut solved = SOLVED;
+        solved[row][j] = solved[row][(j + 1) % 9];
+        mutations.push(Mutation {
+            name: format!("repeated in row {}", i),
+            solved,
+            permutation: gate("permutation", PERMUTATION_REGION, i * 9),
+            pairwise: ("enforce not_equal", "diff in same row"),
+        });
+
+        let (k, column) = empty_cell((0..9).map(|k| (k, i)));
+        let mut solved = SOLVED;
+        solved[k][column] = solved[(k + 1) % 9][column];
+        mutations.push(Mutation {
+            name: format!("repeated in column {}", i),
+            solved,
+            permutation: gate("permutation", PERMUTATION_REGION, (9 + i) * 9),
+            pairwise: ("enforce not_equal", "diff in same column"),
+        });
+
+        let (top, left) = (i / 3 * 3, i % 3 * 3);
+        let (k, l) = empty_cell((0..9).map(|c| (top + c / 3, left + c % 3)));
+        // from another row and column of the box
+        let mut solved = SOLVED;
+        solved[k][l] = solved[top + (k - top + 1) % 3][left + (l - left + 1) % 3];
+        mutations.push(Mutation {
+            name: format!("repeated in box {}", i),
+            solved,
+            permutation: gate("permutation", PERMUTATION_REGION, (18 + i) * 9),
+            pairwise: ("enforce not_equal", "diff in same square"),
+        });
+    }
+    mutations
+}
+
+fn circuit<const LOOKUP: bool, const PERMUTATION: bool>(
+    solved: [[u64; 9]; 9],
+) -> SudokuCircuit<Fp, LOOKUP, 1, PERMUTATION> {
+    SudokuCircuit {
+        unsolved: UNSOLVED,
+        solved,
+        _marker: std::marker::PhantomData,
+    }
+}
+
+#[test]
+fn test_mutations_permutation() {
+    let instance = unsolved_hash::<Fp>(&UNSOLVED);
+    assert!(failures(&circuit::<false, true>(SOLVED), instance).is_empty());
+    for mutation in mutations() {
+        let failures = failures(&circuit::<false, true>(mutation.solved), instance);
+        assert!(
+            failures.contains(&mutation.permutation),
+            "{}: {:?} not in {:?}",
+            mutation.name,
+            mutation.permutation,
+            failures
+        );
+    }
+}
+
+#[test]
+fn test_mutations_pairwise() {
+    let instance = unsolved_hash::<Fp>(&UNSOLVED);
+    assert!(failures(&circuit::<false, false>(SOLVED), instance).is_empty());
+    for mutation in mutations() {
+        let failures = failures(&circuit::<false, false>(mutation.solved), instance);
+        let (constraint, region) = mutation.pairwise;
+        assert!(
+            failures.iter().any(|failure| matches!(
+                failure,
+                Failure::Gate { constraint: c, region: r, .. } if c == constraint && r == region
+            )),
+            "{}: {} in {} not in {:?}",
+            mutation.name,
+            constraint,
+            region,
+            failures
+        );
+    }
+}
+
+#[test]
+fn test_mutations_lookup() {
+    let instance = unsolved_hash::<Fp>(&UNSOLVED);
+    let mut solved = SOLVED;
+    solved[4][4] = 10;
+    assert!(failures(&circuit::<true, true>(solved), instance).contains(&Failure::Lookup));
+
+    // the unsolved cells are range checked too
+    let mut circuit = circuit::<true, true>(SOLVED);
+    circuit.unsolved[4][4] = 10;
+    let instance = unsolved_hash::<Fp>(&circuit.unsolved);
+    assert!(failures(&circuit, instance).contains(&Failure::Lookup));
+}
+
+#[test]
+fn test_wrong_instance() {
+    // the cells copied to the instance are all reported
+    let instance = unsolved_hash::<Fp>(&UNSOLVED) + Fp::from(1);
+    let wrong = failures(&circuit::<false, true>(SOLVED), instance);
+    assert!(!wrong.is_empty());
+    assert!(wrong.iter().all(|failure| *failure == Failure::Instance));
+
+    // the hash of another puzzle
+    let mut unsolved = UNSOLVED;
+    unsolved[4][4] = SOLVED[4][4];
+    let instance = unsolved_hash::<Fp>(&unsolved);
+    let wrong = failures(&circuit::<false, true>(SOLVED), instance);
+    assert!(!wrong.is_empty());
+    assert!(wrong.iter().all(|failure| *failure == Failure::Instance));
+}
@@ -0,0 +1,248 @@
+use halo2_proofs::{
+    pasta::{vesta, Fp},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Error, ProvingKey, SingleVerifier,
+        VerifyingKey,
+    },
+    poly::commitment::Params,
+    transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+};
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::{
+    circuit::{unsolved_hash, SudokuCircuit, K},
+    transcript::{Keccak256Read, Keccak256Write, TranscriptHash},
+};
+
+/// Halo2 with the IPA commitment over vesta, the Poseidon hash of the puzzle
+/// being the only instance value.
+#[derive(Debug, Clone, Copy)]
+pub struct Halo2Prover {
+    /// log2 of the number of rows, at least `K`.
+    pub k: u32,
+    pub transcript: TranscriptHash,
+}
+
+impl Default for Halo2Prover {
+    fn default() -> Self {
+        Self {
+            k: K,
+            transcript: TranscriptHash::default(),
+        }
+    }
+}
+
+/// The IPA parameters are needed by both sides, so each key carries them.
+#[derive(Debug, Clone)]
+pub struct Halo2Keys<K> {
+    pub params: Params<vesta::Affine>,
+    pub key: K,
+}
+
+pub(crate) fn public_inputs(puzzle: &Puzzle) -> Vec<Fp> {
+    vec![unsolved_hash(&puzzle.map(u64::from))]
+}
+
+/// Generates the parameters and keys of the sudoku circuit with `2^k` rows.
+pub fn setup(
+    k: u32,
+) -> Result<
+    (
+        Halo2Keys<ProvingKey<vesta::Affine>>,
+        Halo2Keys<VerifyingKey<vesta::Affine>>,
+    ),
+    Error,
+> {
+    keygen(Params::new(k))
+}
+
+/// Generates the keys of the sudoku circuit for existing parameters, e.g.
+/// read with `keys::read_params`.
+pub fn keygen(
+    params: Params<vesta::Affine>,
+) -> Result<
+    (
+        Halo2Keys<ProvingKey<vesta::Affine>>,
+        Halo2Keys<VerifyingKey<vesta::Affine>>,
+    ),
+    Error,
+> {
+    let circuit = SudokuCircuit::<Fp>::default();
+    let vk = keygen_vk(&params, &circuit)?;
+    let pk = keygen_pk(&params, vk.clone(), &circuit)?;
+    Ok((
+        Halo2Keys {
+            params: params.clone(),
+            key: pk,
+        },
+        Halo2Keys { params, key: vk },
+    ))
+}
+
+/// Proves that `solution` solves `puzzle`, the proof being bound to the hash
+/// of the puzzle only.
+pub fn prove<R: RngCore + CryptoRng>(
+    pk: &Halo2Keys<ProvingKey<vesta::Affine>>,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+) -> Result<Vec<u8>, Error> {
+    prove_with(pk, puzzle, solution, rng, TranscriptHash::Blake2b)
+}
+
+/// Verifies a proof of `prove`, returning `Ok(false)` for a proof of another
+/// puzzle or a forged one, and an error if the proof cannot be read.
+pub fn verify(
+    vk: &Halo2Keys<VerifyingKey<vesta::Affine>>,
+    puzzle: &Puzzle,
+    proof: &[u8],
+) -> Result<bool, Error> {
+    verify_with(vk, puzzle, proof, TranscriptHash::Blake2b)
+}
+
+/// `prove` with the challenges drawn from `transcript`.
+pub fn prove_with<R: RngCore + CryptoRng>(
+    pk: &Halo2Keys<ProvingKey<vesta::Affine>>,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+    transcript: TranscriptHash,
+) -> Result<Vec<u8>, Error> {
+    let circuit = SudokuCircuit::<Fp>::new(puzzle, solution);
+    let public_inputs = public_inputs(puzzle);
+
+    match transcript {
+        TranscriptHash::Blake2b => {
+            let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+            create_proof(
+                &pk.params,
+                &pk.key,
+                &[circuit],
+                &[&[&public_inputs]],
+                rng,
+                &mut transcript,
+            )?;
+            Ok(transcript.finalize())
+        }
+        TranscriptHash::Keccak256 => {
+            let mut transcript = Keccak256Write::<_, _, Challenge255<_>>::init(vec![]);
+            create_proof(
+                &pk.params,
+                &pk.key,
+                &[circuit],
+                &[&[&public_inputs]],
+                rng,
+                &mut transcript,
+            )?;
+            Ok(transcript.finalize())
+        }
+    }
+}
+
+/// `verify` of a proof of `prove_with` and the same transcript.
+pub fn verify_with(
+    vk: &Halo2Keys<VerifyingKey<vesta::Affine>>,
+    puzzle: &Puzzle,
+    proof: &[u8],
+    transcript: TranscriptHash,
+) -> Result<bool, Error> {
+    let public_inputs = public_inputs(puzzle);
+
+    let strategy = SingleVerifier::new(&vk.params);
+    let result = match transcript {
+        TranscriptHash::Blake2b => verify_proof(
+            &vk.params,
+            &vk.key,
+            strategy,
+            &[&[&public_inputs]],
+            &mut Blake2bRead::<_, _, Challenge255<_>>::init(proof),
+        ),
+        TranscriptHash::Keccak256 => verify_proof(
+            &vk.params,
+            &vk.key,
+            strategy,
+            &[&[&public_inputs]],
+            &mut Keccak256Read::<_, _, Challenge255<_>>::init(proof),
+        ),
+    };
+    match result {
+        Ok(()) => Ok(true),
+        Err(Error::ConstraintSystemFailure) | Err(Error::Opening) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+impl SudokuProver for Halo2Prover {
+    type ProvingKey = Halo2Keys<ProvingKey<vesta::Affine>>;
+    type VerifyingKey = Halo2Keys<VerifyingKey<vesta::Affine>>;
+    type Proof = Vec<u8>;
+    type Error = Error;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        _rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        setup(self.k)
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        prove_with(pk, puzzle, solution, rng, self.transcript)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        verify_with(vk, puzzle, proof, self.transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::pasta::Fp;
+    use rand::rngs::ThreadRng;
+    use zk_sudoku_core::{sample, Puzzle, SudokuProver};
+
+    use super::Halo2Prover;
+    use crate::circuit::K;
+
+    #[test]
+    fn test_halo2_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ThreadRng::default();
+        let prover = Halo2Prover::default();
+
+        let (pk, vk) = prover.setup(&mut rng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_library_api() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+
+        let circuit = crate::SudokuCircuit::<Fp>::new(&puzzle, &solution);
+        assert_eq!(circuit.solved[0][0], 1);
+
+        let mut rng = ThreadRng::default();
+        let (pk, vk) = crate::setup(K).unwrap();
+        let proof = crate::prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        assert!(crate::verify(&vk, &puzzle, &proof).unwrap());
+    }
+}
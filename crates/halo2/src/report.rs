@@ -0,0 +1,50 @@
+//! Timings and sizes of the halo2 prover on the sample sudoku, as the
+//! reports of the other backends.
+
+use std::time::Instant;
+
+use halo2_proofs::plonk::Error;
+use rand_core::{CryptoRng, RngCore};
+use serde_json::json;
+use zk_sudoku_core::{report::Report, sample, SudokuProver};
+
+use crate::{keys, layout::sudoku_stats, prover::Halo2Prover};
+
+/// Sets up, proves the sample sudoku and verifies the proof with the IPA
+/// commitment over vesta, timing each step. The rows used are reported as
+/// the constraints and the size of the parameters file as `params_len`. A
+/// rejected proof is an `Error::ConstraintSystemFailure`.
+pub fn run<R: RngCore + CryptoRng>(prover: &Halo2Prover, rng: &mut R) -> Result<Report, Error> {
+    let puzzle = sample::puzzle();
+    let solution = sample::solution();
+
+    let setup_start = Instant::now();
+    let (pk, vk) = prover.setup(rng)?;
+    let setup_time = setup_start.elapsed();
+
+    let prove_start = Instant::now();
+    let proof = prover.prove(&pk, &puzzle, &solution, rng)?;
+    let prove_time = prove_start.elapsed();
+
+    let verify_start = Instant::now();
+    let valid_proof = prover.verify(&vk, &puzzle, &proof)?;
+    let verify_time = verify_start.elapsed();
+    if !valid_proof {
+        return Err(Error::ConstraintSystemFailure);
+    }
+
+    let params_len = keys::params_to_bytes(&vk.params)
+        .expect("the parameters are written to memory")
+        .len();
+    Ok(Report {
+        backend: "halo2",
+        curve: "pasta",
+        config: Some("ipa"),
+        num_constraints: sudoku_stats(false)?.rows,
+        setup_time,
+        prove_time,
+        verify_time,
+        proof_len: proof.len(),
+        extra: vec![("k", json!(prover.k)), ("params_len", json!(params_len))],
+    })
+}
@@ -0,0 +1,247 @@
+//! Fiat-Shamir transcripts of the prover and verifier. halo2 only ships
+//! Blake2b, which an EVM verifier would have to compute without a
+//! precompile, so the challenges can be drawn from Keccak256 instead.
+
+use std::{
+    io::{self, Read, Write},
+    marker::PhantomData,
+};
+
+use halo2_proofs::{
+    arithmetic::{Coordinates, CurveAffine},
+    pasta::group::ff::PrimeField,
+    transcript::{Challenge255, EncodedChallenge, Transcript, TranscriptRead, TranscriptWrite},
+};
+use sha3::{Digest, Keccak256};
+
+/// The hash of the transcript, which the prover and verifier must agree on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TranscriptHash {
+    #[default]
+    Blake2b,
+    Keccak256,
+}
+
+const KECCAK256_PREFIX_CHALLENGE: u8 = 0;
+const KECCAK256_PREFIX_POINT: u8 = 1;
+const KECCAK256_PREFIX_SCALAR: u8 = 2;
+// a challenge takes 64 bytes, the two halves of which are hashed apart
+const KECCAK256_PREFIX_CHALLENGE_LO: u8 = 10;
+const KECCAK256_PREFIX_CHALLENGE_HI: u8 = 11;
+
+/// The Keccak256 state shared by both sides, absorbing the same prefixes as
+/// the Blake2b transcript.
+#[derive(Debug, Clone)]
+struct KeccakState(Keccak256);
+
+impl KeccakState {
+    fn new() -> Self {
+        let mut state = Keccak256::new();
+        state.update(b"Halo2-Transcript");
+        Self(state)
+    }
+
+    fn squeeze<C: CurveAffine>(&mut self) -> Challenge255<C> {
+        self.0.update([KECCAK256_PREFIX_CHALLENGE]);
+        let mut lo = self.0.clone();
+        let mut hi = self.0.clone();
+        lo.update([KECCAK256_PREFIX_CHALLENGE_LO]);
+        hi.update([KECCAK256_PREFIX_CHALLENGE_HI]);
+
+        let mut result = [0; 64];
+        result[..32].copy_from_slice(&lo.finalize());
+        result[32..].copy_from_slice(&hi.finalize());
+        Challenge255::new(&result)
+    }
+
+    fn point<C: CurveAffine>(&mut self, point: C) -> io::Result<()> {
+        self.0.update([KECCAK256_PREFIX_POINT]);
+        let coords: Coordinates<C> = Option::from(point.coordinates()).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "cannot write points at infinity to the transcript",
+            )
+        })?;
+        self.0.update(coords.x().to_repr().as_ref());
+        self.0.update(coords.y().to_repr().as_ref());
+        Ok(())
+    }
+
+    fn scalar<C: CurveAffine>(&mut self, scalar: C::Scalar) {
+        self.0.update([KECCAK256_PREFIX_SCALAR]);
+        self.0.update(scalar.to_repr().as_ref());
+    }
+}
+
+/// The Keccak256 transcript of the verifier, reading the proof.
+#[derive(Debug, Clone)]
+pub struct Keccak256Read<R: Read, C: CurveAffine, E: EncodedChallenge<C>> {
+    state: KeccakState,
+    reader: R,
+    _marker: PhantomData<(C, E)>,
+}
+
+impl<R: Read, C: CurveAffine, E: EncodedChallenge<C>> Keccak256Read<R, C, E> {
+    pub fn init(reader: R) -> Self {
+        Self {
+            state: KeccakState::new(),
+            reader,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<R: Read, C: CurveAffine> Transcript<C, Challenge255<C>>
+    for Keccak256Read<R, C, Challenge255<C>>
+{
+    fn squeeze_challenge(&mut self) -> Challenge255<C> {
+        self.state.squeeze()
+    }
+
+    fn common_point(&mut self, point: C) -> io::Result<()> {
+        self.state.point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.state.scalar::<C>(scalar);
+        Ok(())
+    }
+}
+
+impl<R: Read, C: CurveAffine> TranscriptRead<C, Challenge255<C>>
+    for Keccak256Read<R, C, Challenge255<C>>
+{
+    fn read_point(&mut self) -> io::Result<C> {
+        let mut compressed = C::Repr::default();
+        self.reader.read_exact(compressed.as_mut())?;
+        let point: C = Option::from(C::from_bytes(&compressed)).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Other, "invalid point encoding in proof")
+        })?;
+        self.common_point(point)?;
+        Ok(point)
+    }
+
+    fn read_scalar(&mut self) -> io::Result<C::Scalar> {
+        let mut data = <C::Scalar as PrimeField>::Repr::default();
+        self.reader.read_exact(data.as_mut())?;
+        let scalar: C::Scalar = Option::from(C::Scalar::from_repr(data)).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::Other,
+                "invalid field element encoding in proof",
+            )
+        })?;
+        self.common_scalar(scalar)?;
+        Ok(scalar)
+    }
+}
+
+/// The Keccak256 transcript of the prover, writing the proof.
+#[derive(Debug, Clone)]
+pub struct Keccak256Write<W: Write, C: CurveAffine, E: EncodedChallenge<C>> {
+    state: KeccakState,
+    writer: W,
+    _marker: PhantomData<(C, E)>,
+}
+
+impl<W: Write, C: CurveAffine, E: EncodedChallenge<C>> Keccak256Write<W, C, E> {
+    pub fn init(writer: W) -> Self {
+        Self {
+            state: KeccakState::new(),
+            writer,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the proof written so far.
+    pub fn finalize(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write, C: CurveAffine> Transcript<C, Challenge255<C>>
+    for Keccak256Write<W, C, Challenge255<C>>
+{
+    fn squeeze_challenge(&mut self) -> Challenge255<C> {
+        self.state.squeeze()
+    }
+
+    fn common_point(&mut self, point: C) -> io::Result<()> {
+        self.state.point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.state.scalar::<C>(scalar);
+        Ok(())
+    }
+}
+
+impl<W: Write, C: CurveAffine> TranscriptWrite<C, Challenge255<C>>
+    for Keccak256Write<W, C, Challenge255<C>>
+{
+    fn write_point(&mut self, point: C) -> io::Result<()> {
+        self.common_point(point)?;
+        self.writer.write_all(point.to_bytes().as_ref())
+    }
+
+    fn write_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.common_scalar(scalar)?;
+        self.writer.write_all(scalar.to_repr().as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use halo2_proofs::{
+        pasta::{group::prime::PrimeCurveAffine, vesta, Fq},
+        transcript::{Challenge255, Transcript, TranscriptRead, TranscriptWrite},
+    };
+    use rand::rngs::ThreadRng;
+    use zk_sudoku_core::sample;
+
+    use super::{Keccak256Read, Keccak256Write, TranscriptHash};
+    use crate::{
+        circuit::K,
+        prover::{prove_with, setup, verify_with},
+    };
+
+    #[test]
+    fn test_keccak_transcript() {
+        let point = vesta::Affine::generator();
+        let scalar = Fq::from(42);
+
+        let mut write = Keccak256Write::<_, vesta::Affine, Challenge255<_>>::init(vec![]);
+        write.write_point(point).unwrap();
+        write.write_scalar(scalar).unwrap();
+        let challenge = write.squeeze_challenge_scalar::<()>();
+        let proof = write.finalize();
+
+        let mut read = Keccak256Read::<_, vesta::Affine, Challenge255<_>>::init(&proof[..]);
+        assert_eq!(read.read_point().unwrap(), point);
+        assert_eq!(read.read_scalar().unwrap(), scalar);
+        assert_eq!(*read.squeeze_challenge_scalar::<()>(), *challenge);
+    }
+
+    #[test]
+    fn test_prove_with_transcripts() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ThreadRng::default();
+        let (pk, vk) = setup(K).unwrap();
+
+        let hashes = [TranscriptHash::Blake2b, TranscriptHash::Keccak256];
+        for prover in hashes {
+            let proof = prove_with(&pk, &puzzle, &solution, &mut rng, prover).unwrap();
+            for verifier in hashes {
+                // the challenges differ with the other hash
+                let verified = verify_with(&vk, &puzzle, &proof, verifier);
+                assert_eq!(
+                    matches!(verified, Ok(true)),
+                    prover == verifier,
+                    "{:?} proof, {:?} verifier",
+                    prover,
+                    verifier
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Instant;
+
+use ark_ec::{ModelParameters, PairingEngine};
+use ark_std::rand::{CryptoRng, RngCore};
+use jf_plonk::{errors::PlonkError, proof_system::PlonkKzgSnark, transcript::StandardTranscript};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::{
+    keys::VerifyingKey,
+    prover::{JellyfishProver, Proof, TranscriptKind},
+};
+
+/// Verifies proofs of `JellyfishProver` for several puzzles with the same
+/// verifying key. jf-plonk aggregates the polynomial commitment openings
+/// of all the proofs with random powers, so a single pairing check is done
+/// for the batch. Returns false if any proof is invalid.
+pub fn verify_batch<E: PairingEngine>(
+    vk: &VerifyingKey<E>,
+    proofs: &[(Puzzle, Proof<E>)],
+) -> Result<bool, PlonkError> {
+    verify_batch_with(vk, proofs, TranscriptKind::Standard)
+}
+
+/// `verify_batch` for proofs made with `transcript`.
+pub fn verify_batch_with<E: PairingEngine>(
+    vk: &VerifyingKey<E>,
+    proofs: &[(Puzzle, Proof<E>)],
+    transcript: TranscriptKind,
+) -> Result<bool, PlonkError> {
+    let public_inputs = proofs
+        .iter()
+        .map(|(puzzle, _)| puzzle.iter().map(E::Fr::from).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let public_inputs = public_inputs.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let proofs = proofs.iter().map(|(_, proof)| proof).collect::<Vec<_>>();
+    let verify_keys = vec![vk; proofs.len()];
+    let extra_transcript_init_msgs = vec![None; proofs.len()];
+
+    let verified = match transcript {
+        TranscriptKind::Standard => PlonkKzgSnark::<E>::batch_verify::<StandardTranscript>(
+            &verify_keys,
+            &public_inputs,
+            &proofs,
+            &extra_transcript_init_msgs,
+        ),
+        TranscriptKind::Solidity => PlonkKzgSnark::<E>::batch_verify::<SolidityTranscript>(
+            &verify_keys,
+            &public_inputs,
+            &proofs,
+            &extra_transcript_init_msgs,
+        ),
+    };
+    match verified {
+        Ok(()) => Ok(true),
+        Err(PlonkError::WrongProof) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+/// Proves the sudoku `batch_size` times and compares verifying the proofs
+/// one by one with `verify_batch`.
+pub fn run_batch_verify<E, P, R>(
+    puzzle: &Puzzle,
+    solution: &Solution,
+    batch_size: usize,
+    rng: &mut R,
+) where
+    E: PairingEngine,
+    P: ModelParameters<BaseField = E::Fr>,
+    R: RngCore + CryptoRng,
+{
+    let prover = JellyfishProver::<E, P>::default();
+    let (pk, vk) = prover.setup(rng).unwrap();
+    let proofs = (0..batch_size)
+        .map(|_| (*puzzle, prover.prove(&pk, puzzle, solution, rng).unwrap()))
+        .collect::<Vec<_>>();
+
+    let single_start = Instant::now();
+    for (puzzle, proof) in &proofs {
+        assert!(prover.verify(&vk, puzzle, proof).unwrap());
+    }
+    let single_time = single_start.elapsed();
+    println!(
+        "verify {} proofs one by one {}ms, {}s",
+        batch_size,
+        single_time.as_millis(),
+        single_time.as_secs()
+    );
+
+    let batch_start = Instant::now();
+    assert!(verify_batch::<E>(&vk, &proofs).unwrap());
+    let batch_time = batch_start.elapsed();
+    println!(
+        "verify {} proofs in a batch {}ms, {}s",
+        batch_size,
+        batch_time.as_millis(),
+        batch_time.as_secs()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Bls12_381;
+    use ark_ed_on_bls12_381::EdwardsParameters;
+    use ark_std::rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use zk_sudoku_core::{sample, Puzzle, Solution, SudokuProver};
+
+    use super::{run_batch_verify, verify_batch};
+    use crate::prover::JellyfishProver;
+
+    fn solved() -> (Puzzle, Solution) {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        (puzzle, solution)
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        let (puzzle, solution) = solved();
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let prover = JellyfishProver::<Bls12_381, EdwardsParameters>::default();
+        let (pk, vk) = prover.setup(&mut rng).unwrap();
+
+        let mut proofs = [puzzle, other, puzzle]
+            .iter()
+            .map(|puzzle| {
+                (
+                    *puzzle,
+                    prover.prove(&pk, puzzle, &solution, &mut rng).unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert!(verify_batch::<Bls12_381>(&vk, &proofs).unwrap());
+        proofs[0].0 = other;
+        assert!(!verify_batch::<Bls12_381>(&vk, &proofs).unwrap());
+    }
+
+    #[test]
+    fn test_run_batch_verify() {
+        let (puzzle, solution) = solved();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        run_batch_verify::<Bls12_381, EdwardsParameters, _>(&puzzle, &solution, 8, &mut rng);
+    }
+}
@@ -9,50 +9,103 @@ use std::marker::PhantomData;
 
 use ark_ec::ModelParameters;
 use ark_ff::PrimeField;
-use jf_relation::{errors::CircuitError, Circuit, PlonkCircuit};
+use jf_relation::{errors::CircuitError, Circuit, PlonkCircuit, Variable};
+use zk_sudoku_core::{Puzzle, Solution};
 
-use crate::utils::range_check;
+use crate::utils::{lookup_range_check, range_check};
 
-pub struct SudokuCircuit<F, P> {
+/// The range table that comes with an UltraPlonk circuit, unused by the
+/// sudoku so it is kept to two rows.
+pub const ULTRA_RANGE_BIT_LEN: usize = 1;
+
+/// The variables of the cells of both grids.
+#[derive(Debug, Clone, Copy)]
+pub struct SudokuVariables {
+    pub unsolved: [[Variable; 9]; 9],
+    pub solved: [[Variable; 9]; 9],
+}
+
+/// With `LOOKUP`, the cells of the solution are looked up in a table of
+/// 1..=9 in an UltraPlonk circuit, instead of checking that the product of
+/// their differences with 1..=9 is zero in a TurboPlonk circuit.
+pub struct SudokuCircuit<F, P, const LOOKUP: bool = false> {
     pub unsolved: [[u8; 9]; 9],
     pub solved: [[u8; 9]; 9],
     pub _marker1: PhantomData<F>,
     pub _marker2: PhantomData<P>,
 }
 
-impl<F: PrimeField, P: ModelParameters<BaseField = F>> SudokuCircuit<F, P> {
+impl<F: PrimeField, P: ModelParameters<BaseField = F>, const LOOKUP: bool>
+    SudokuCircuit<F, P, LOOKUP>
+{
+    pub fn new(puzzle: &Puzzle, solution: &Solution) -> Self {
+        Self {
+            unsolved: *puzzle.cells(),
+            solved: *solution.cells(),
+            _marker1: PhantomData,
+            _marker2: PhantomData,
+        }
+    }
+
     pub fn synthesize(&self) -> Result<PlonkCircuit<F>, CircuitError> {
+        let mut circuit = Self::new_circuit();
+        let vars = self.assign(&mut circuit)?;
+        Self::constrain(&mut circuit, &vars)?;
+        Ok(circuit)
+    }
+
+    /// An empty circuit supporting the gates of `constrain`.
+    pub fn new_circuit() -> PlonkCircuit<F> {
         // Step 1:
         // We instantiate a turbo plonk circuit.
         //
-        // Here we only need turbo plonk since we are not using plookups.
-        let mut circuit = PlonkCircuit::<F>::new_turbo_plonk();
-
-        let mut unsolved_vars = Vec::with_capacity(9);
-        self.unsolved.iter().enumerate().try_for_each(|(i, x)| {
-            unsolved_vars.push(Vec::with_capacity(9));
-            x.iter().try_for_each(|y| {
-                unsolved_vars[i].push(circuit.create_variable((*y).into())?);
-                <Result<(), CircuitError>>::Ok(())
-            })?;
-            <Result<(), CircuitError>>::Ok(())
-        })?;
-
-        let mut solved_vars = Vec::with_capacity(9);
-        self.solved.iter().enumerate().try_for_each(|(i, x)| {
-            solved_vars.push(Vec::with_capacity(9));
-            x.iter().try_for_each(|y| {
-                solved_vars[i].push(circuit.create_variable((*y).into())?);
-                <Result<(), CircuitError>>::Ok(())
-            })?;
-            <Result<(), CircuitError>>::Ok(())
-        })?;
+        // Here we only need turbo plonk unless the range checks use plookups.
+        if LOOKUP {
+            PlonkCircuit::<F>::new_ultra_plonk(ULTRA_RANGE_BIT_LEN)
+        } else {
+            PlonkCircuit::<F>::new_turbo_plonk()
+        }
+    }
 
-        // Check if the numbers of the solved sudoku are >=1 and <=9
-        // Each number in the solved sudoku is checked to see if it is >=1 and <=9
+    /// Creates the variables of both grids with the cells as their witness.
+    pub fn assign(&self, circuit: &mut PlonkCircuit<F>) -> Result<SudokuVariables, CircuitError> {
+        let mut vars = SudokuVariables {
+            unsolved: [[circuit.zero(); 9]; 9],
+            solved: [[circuit.zero(); 9]; 9],
+        };
+        // The unsolved sudoku is the public input, the solved one stays private
         for i in 0..9 {
             for j in 0..9 {
-                range_check(&mut circuit, solved_vars[i][j])?;
+                vars.unsolved[i][j] = circuit.create_public_variable(self.unsolved[i][j].into())?;
+            }
+        }
+        for i in 0..9 {
+            for j in 0..9 {
+                vars.solved[i][j] = circuit.create_variable(self.solved[i][j].into())?;
+            }
+        }
+        Ok(vars)
+    }
+
+    /// Adds the rules of the sudoku on variables of `assign`, or of another
+    /// circuit embedding the sudoku. jellyfish computes the witness of each
+    /// gate as it is added, so the cells must be assigned beforehand.
+    pub fn constrain(
+        circuit: &mut PlonkCircuit<F>,
+        vars: &SudokuVariables,
+    ) -> Result<(), CircuitError> {
+        let unsolved_vars = &vars.unsolved;
+        let solved_vars = &vars.solved;
+
+        // Check if the numbers of the solved sudoku are >=1 and <=9
+        // Each number in the solved sudoku is checked to see if it is >=1 and <=9
+        if LOOKUP {
+            lookup_range_check(circuit, &solved_vars.concat())?;
+        } else {
+            for i in 0..9 {
+                for j in 0..9 {
+                    range_check(circuit, solved_vars[i][j])?;
+                }
             }
         }
 
@@ -94,13 +147,14 @@ impl<F: PrimeField, P: ModelParameters<BaseField = F>> SudokuCircuit<F, P> {
 
         // Check if each square in solved has all the numbers from 1 to 9, both included
         // For each square and for each element in each square, check that the
-        // element is not equal to previous elements in the same square
+        // element is not equal to the elements of the previous rows of the same
+        // square, except the one in its own column (rows and columns are covered above)
         for i in [0, 3, 6] {
             for j in [0, 3, 6] {
                 for k in i..i + 3 {
                     for l in j..j + 3 {
-                        for m in i..=k {
-                            for n in j..l {
+                        for m in i..k {
+                            for n in (j..j + 3).filter(|n| *n != l) {
                                 let is_equal =
                                     circuit.is_equal(solved_vars[m][n], solved_vars[k][l])?;
                                 circuit.enforce_false(is_equal.into())?;
@@ -111,13 +165,13 @@ impl<F: PrimeField, P: ModelParameters<BaseField = F>> SudokuCircuit<F, P> {
             }
         }
 
-        Ok(circuit)
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::marker::PhantomData;
+    use std::{marker::PhantomData, time::Instant};
 
     use ark_bls12_381::{Bls12_381, Fr};
     use ark_ed_on_bls12_381::EdwardsParameters;
@@ -128,41 +182,28 @@ mod tests {
     };
     use jf_relation::{Arithmetization, Circuit};
     use rand_chacha::ChaCha20Rng;
+    use zk_sudoku_core::sample;
 
     use super::SudokuCircuit;
 
     #[test]
     fn test_circuit() {
         let circuit: SudokuCircuit<Fr, EdwardsParameters> = SudokuCircuit {
-            unsolved: [
-                [0, 0, 0, 0, 0, 6, 0, 0, 0],
-                [0, 0, 7, 2, 0, 0, 8, 0, 0],
-                [9, 0, 6, 8, 0, 0, 0, 1, 0],
-                [3, 0, 0, 7, 0, 0, 0, 2, 9],
-                [0, 0, 0, 0, 0, 0, 0, 0, 0],
-                [4, 0, 0, 5, 0, 0, 0, 7, 0],
-                [6, 5, 0, 1, 0, 0, 0, 0, 0],
-                [8, 0, 1, 0, 5, 0, 3, 0, 0],
-                [7, 9, 2, 0, 0, 0, 0, 0, 4],
-            ],
-            solved: [
-                [1, 8, 4, 3, 7, 6, 2, 9, 5],
-                [5, 3, 7, 2, 9, 1, 8, 4, 6],
-                [9, 2, 6, 8, 4, 5, 7, 1, 3],
-                [3, 6, 5, 7, 1, 8, 4, 2, 9],
-                [2, 7, 8, 4, 6, 9, 5, 3, 1],
-                [4, 1, 9, 5, 3, 2, 6, 7, 8],
-                [6, 5, 3, 1, 2, 4, 9, 8, 7],
-                [8, 4, 1, 9, 5, 7, 3, 6, 2],
-                [7, 9, 2, 6, 8, 3, 1, 5, 4],
-            ],
+            unsolved: sample::UNSOLVED,
+            solved: sample::SOLVED,
             _marker1: PhantomData,
             _marker2: PhantomData,
         };
 
+        let public_inputs = circuit
+            .unsolved
+            .iter()
+            .flatten()
+            .map(|x| Fr::from(*x))
+            .collect::<Vec<_>>();
         let mut circuit = circuit.synthesize().unwrap();
         // Sanity check: the circuit must be satisfied.
-        assert!(circuit.check_circuit_satisfiability(&[]).is_ok());
+        assert!(circuit.check_circuit_satisfiability(&public_inputs).is_ok());
 
         // And we are done!
         circuit.finalize_for_arithmetization().unwrap();
@@ -191,8 +232,9 @@ mod tests {
         )
         .unwrap();
 
-        // Last step, verify the proof against the public inputs.
-        let public_inputs = circuit.public_input().unwrap();
+        // Last step, verify the proof against the public inputs, which are the
+        // cells of the puzzle in row major order.
+        assert_eq!(circuit.public_input().unwrap(), public_inputs);
         // extra messages to bound to proof by appending in its transcripts, not used
         // here.
         let extra_transcript_init_msg = None;
@@ -203,5 +245,61 @@ mod tests {
             extra_transcript_init_msg,
         )
         .is_ok());
+
+        // the proof is bound to this puzzle
+        let mut other = public_inputs.clone();
+        other[4 * 9 + 4] = Fr::from(6u8);
+        assert!(PlonkKzgSnark::<Bls12_381>::verify::<StandardTranscript>(
+            &vk, &other, &proof, None,
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_lookup_circuit() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let public_inputs = puzzle.iter().map(Fr::from).collect::<Vec<_>>();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let turbo = SudokuCircuit::<Fr, EdwardsParameters, false>::new(&puzzle, &solution)
+            .synthesize()
+            .unwrap();
+        let ultra = SudokuCircuit::<Fr, EdwardsParameters, true>::new(&puzzle, &solution)
+            .synthesize()
+            .unwrap();
+        // a lookup replaces the subtractions and products of each range check
+        assert!(ultra.num_gates() < turbo.num_gates());
+
+        for (name, mut circuit) in [("turbo", turbo), ("ultra", ultra)] {
+            assert!(circuit.check_circuit_satisfiability(&public_inputs).is_ok());
+            circuit.finalize_for_arithmetization().unwrap();
+
+            let srs_size = circuit.srs_size().unwrap();
+            let srs = PlonkKzgSnark::<Bls12_381>::universal_setup(srs_size, &mut rng).unwrap();
+            let (pk, vk) = PlonkKzgSnark::<Bls12_381>::preprocess(&srs, &circuit).unwrap();
+
+            let prove_start = Instant::now();
+            let proof = PlonkKzgSnark::<Bls12_381>::prove::<_, _, StandardTranscript>(
+                &mut rng, &circuit, &pk, None,
+            )
+            .unwrap();
+            let prove_time = prove_start.elapsed();
+            println!(
+                "{}: {} gates, srs_size {}, prove time {}ms",
+                name,
+                circuit.num_gates(),
+                srs_size,
+                prove_time.as_millis()
+            );
+
+            assert!(PlonkKzgSnark::<Bls12_381>::verify::<StandardTranscript>(
+                &vk,
+                &public_inputs,
+                &proof,
+                None,
+            )
+            .is_ok());
+        }
     }
 }
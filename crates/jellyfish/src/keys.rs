@@ -0,0 +1,129 @@
+use std::path::{Path, PathBuf};
+
+use ark_ec::{ModelParameters, PairingEngine};
+use ark_std::rand::{CryptoRng, RngCore};
+use jf_plonk::{
+    errors::PlonkError,
+    proof_system::{PlonkKzgSnark, UniversalSNARK},
+};
+use jf_relation::Arithmetization;
+pub use zk_sudoku_core::keys::{read_key, write_key};
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::prover::circuit;
+
+pub type Srs<E> = <PlonkKzgSnark<E> as UniversalSNARK<E>>::UniversalSRS;
+pub type ProvingKey<E> = <PlonkKzgSnark<E> as UniversalSNARK<E>>::ProvingKey;
+pub type VerifyingKey<E> = <PlonkKzgSnark<E> as UniversalSNARK<E>>::VerifyingKey;
+
+pub type KeyError = zk_sudoku_core::keys::KeyError<PlonkError>;
+
+/// Version of the gates of `SudokuCircuit`, part of the names of the key
+/// files. Bump it with any change to the gates, or the keys preprocessed for
+/// the previous ones would still be loaded.
+pub const CIRCUIT_VERSION: u32 = 1;
+
+/// The file of the universal SRS supporting circuits up to `srs_size`.
+pub fn srs_path(dir: impl AsRef<Path>, srs_size: usize) -> PathBuf {
+    dir.as_ref().join(format!("jellyfish_srs_{}.bin", srs_size))
+}
+
+/// The files of the proving and verifying keys of the sudoku circuit, whose
+/// TurboPlonk and UltraPlonk variants may need the same SRS size. The SRS
+/// doesn't depend on the circuit, only the keys are named after its version.
+pub fn key_paths<const LOOKUP: bool>(dir: impl AsRef<Path>, srs_size: usize) -> (PathBuf, PathBuf) {
+    let plonk = if LOOKUP { "ultra" } else { "turbo" };
+    let name = format!("jellyfish_{}_v{}_{}", plonk, CIRCUIT_VERSION, srs_size);
+    (
+        dir.as_ref().join(format!("{}.pk", name)),
+        dir.as_ref().join(format!("{}.vk", name)),
+    )
+}
+
+/// Reads the SRS of `srs_size` from `dir`, or runs the universal setup and
+/// writes it there for the next call.
+pub fn load_or_setup_srs<E: PairingEngine, R: RngCore + CryptoRng>(
+    dir: impl AsRef<Path>,
+    srs_size: usize,
+    rng: &mut R,
+) -> Result<Srs<E>, KeyError> {
+    let path = srs_path(dir, srs_size);
+    if path.exists() {
+        return read_key(path);
+    }
+    let srs = PlonkKzgSnark::<E>::universal_setup(srs_size, rng).map_err(KeyError::Backend)?;
+    write_key(&srs, path)?;
+    Ok(srs)
+}
+
+/// Reads the keys of the sudoku circuit from `dir`, or preprocesses the
+/// circuit with the SRS of `load_or_setup_srs` and writes them there. The
+/// keys are the ones `prover::setup` would return.
+pub fn load_or_preprocess<E, P, const LOOKUP: bool, R>(
+    dir: impl AsRef<Path>,
+    rng: &mut R,
+) -> Result<(ProvingKey<E>, VerifyingKey<E>), KeyError>
+where
+    E: PairingEngine,
+    P: ModelParameters<BaseField = E::Fr>,
+    R: RngCore + CryptoRng,
+{
+    // Only the shape of the circuit matters here
+    let solution = Solution::new_unchecked(Default::default());
+    let circuit =
+        circuit::<E, P, LOOKUP>(&Puzzle::default(), &solution).map_err(KeyError::Backend)?;
+    let srs_size = circuit
+        .srs_size()
+        .map_err(|err| KeyError::Backend(err.into()))?;
+
+    let (pk_path, vk_path) = key_paths::<LOOKUP>(&dir, srs_size);
+    if pk_path.exists() && vk_path.exists() {
+        return Ok((read_key(pk_path)?, read_key(vk_path)?));
+    }
+    let srs = load_or_setup_srs::<E, _>(&dir, srs_size, rng)?;
+    let (pk, vk) = PlonkKzgSnark::<E>::preprocess(&srs, &circuit).map_err(KeyError::Backend)?;
+    write_key(&pk, pk_path)?;
+    write_key(&vk, vk_path)?;
+    Ok((pk, vk))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use ark_bls12_381::Bls12_381;
+    use ark_ed_on_bls12_381::EdwardsParameters;
+    use ark_std::rand::SeedableRng;
+    use jf_relation::Arithmetization;
+    use rand_chacha::ChaCha20Rng;
+    use zk_sudoku_core::{sample, SudokuProver};
+
+    use super::{key_paths, load_or_preprocess, srs_path};
+    use crate::prover::{circuit, JellyfishProver};
+
+    #[test]
+    fn test_key_files() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let dir = env::temp_dir().join("zk_sudoku_jellyfish_keys");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // the first call writes the files, the second one reads them
+        load_or_preprocess::<Bls12_381, EdwardsParameters, false, _>(&dir, &mut rng).unwrap();
+        let srs_size = circuit::<Bls12_381, EdwardsParameters, false>(&puzzle, &solution)
+            .unwrap()
+            .srs_size()
+            .unwrap();
+        assert!(srs_path(&dir, srs_size).exists());
+        let (pk_path, vk_path) = key_paths::<false>(&dir, srs_size);
+        assert!(pk_path.exists() && vk_path.exists());
+        let (pk, vk) =
+            load_or_preprocess::<Bls12_381, EdwardsParameters, false, _>(&dir, &mut rng).unwrap();
+
+        let prover = JellyfishProver::<Bls12_381, EdwardsParameters>::default();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+    }
+}
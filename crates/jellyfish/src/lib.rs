@@ -1,2 +1,9 @@
 pub mod utils;
-pub mod circuit;
\ No newline at end of file
+pub mod batch;
+pub mod circuit;
+pub mod keys;
+pub mod prover;
+pub mod report;
+
+pub use circuit::SudokuCircuit;
+pub use prover::{prove, prove_with, setup, verify, verify_with, JellyfishProver, TranscriptKind};
@@ -0,0 +1,302 @@
+use std::marker::PhantomData;
+
+use ark_ec::{ModelParameters, PairingEngine};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::rand::{CryptoRng, RngCore};
+use jf_plonk::{
+    errors::PlonkError,
+    proof_system::{PlonkKzgSnark, UniversalSNARK},
+    transcript::{SolidityTranscript, StandardTranscript},
+};
+use jf_relation::{Arithmetization, PlonkCircuit};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::{
+    circuit::SudokuCircuit,
+    keys::{ProvingKey, VerifyingKey},
+};
+
+pub type Proof<E> = <PlonkKzgSnark<E> as UniversalSNARK<E>>::Proof;
+
+/// The first byte of `proof_to_bytes`, bumped whenever the layout of the
+/// proof changes.
+pub const PROOF_VERSION: u8 = 1;
+
+/// The proof as its version followed by its compressed canonical
+/// serialization.
+pub fn proof_to_bytes<E: PairingEngine>(proof: &Proof<E>) -> Result<Vec<u8>, SerializationError> {
+    let mut bytes = vec![PROOF_VERSION];
+    proof.serialize(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Reads back a proof of `proof_to_bytes`, rejecting other versions.
+pub fn proof_from_bytes<E: PairingEngine>(bytes: &[u8]) -> Result<Proof<E>, SerializationError> {
+    match bytes.split_first() {
+        Some((&PROOF_VERSION, proof)) => Proof::<E>::deserialize(proof),
+        _ => Err(SerializationError::InvalidData),
+    }
+}
+
+/// The Fiat-Shamir transcript of the prover and verifier, which must agree
+/// on it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TranscriptKind {
+    /// The Merlin transcript of jf-plonk.
+    #[default]
+    Standard,
+    /// Keccak256 challenges, as computed by the on-chain Plonk verifiers.
+    Solidity,
+}
+
+/// Jellyfish TurboPlonk with KZG over the pairing `E`, the puzzle being the
+/// public input. With `LOOKUP`, UltraPlonk with the range checks of the
+/// solution looked up in a table.
+pub struct JellyfishProver<E, P, const LOOKUP: bool = false> {
+    pub transcript: TranscriptKind,
+    _marker: PhantomData<(E, P)>,
+}
+
+impl<E, P, const LOOKUP: bool> Default for JellyfishProver<E, P, LOOKUP> {
+    fn default() -> Self {
+        Self::with_transcript(TranscriptKind::default())
+    }
+}
+
+impl<E, P, const LOOKUP: bool> JellyfishProver<E, P, LOOKUP> {
+    pub fn with_transcript(transcript: TranscriptKind) -> Self {
+        Self {
+            transcript,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// The sudoku circuit of `puzzle` and `solution`, finalized so that it can
+/// be preprocessed or proven.
+pub fn circuit<E, P, const LOOKUP: bool>(
+    puzzle: &Puzzle,
+    solution: &Solution,
+) -> Result<PlonkCircuit<E::Fr>, PlonkError>
+where
+    E: PairingEngine,
+    P: ModelParameters<BaseField = E::Fr>,
+{
+    let mut circuit = SudokuCircuit::<E::Fr, P, LOOKUP>::new(puzzle, solution).synthesize()?;
+    circuit.finalize_for_arithmetization()?;
+    Ok(circuit)
+}
+
+/// Runs a universal setup just large enough for the sudoku circuit and
+/// preprocesses the circuit, see `keys::load_or_preprocess` to reuse them.
+pub fn setup<E, P, const LOOKUP: bool, R>(
+    rng: &mut R,
+) -> Result<(ProvingKey<E>, VerifyingKey<E>), PlonkError>
+where
+    E: PairingEngine,
+    P: ModelParameters<BaseField = E::Fr>,
+    R: RngCore + CryptoRng,
+{
+    // Only the shape of the circuit matters here
+    let solution = Solution::new_unchecked(Default::default());
+    let circuit = circuit::<E, P, LOOKUP>(&Puzzle::default(), &solution)?;
+    let srs = PlonkKzgSnark::<E>::universal_setup(circuit.srs_size()?, rng)?;
+    PlonkKzgSnark::<E>::preprocess(&srs, &circuit)
+}
+
+/// Proves that `solution` solves `puzzle`, whose cells are the public
+/// inputs.
+pub fn prove<E, P, const LOOKUP: bool, R>(
+    pk: &ProvingKey<E>,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+) -> Result<Proof<E>, PlonkError>
+where
+    E: PairingEngine,
+    P: ModelParameters<BaseField = E::Fr>,
+    R: RngCore + CryptoRng,
+{
+    prove_with::<E, P, LOOKUP, R>(pk, puzzle, solution, rng, TranscriptKind::Standard)
+}
+
+/// Verifies a proof of `prove`, returning `Ok(false)` for a proof of another
+/// puzzle or a forged one.
+pub fn verify<E: PairingEngine>(
+    vk: &VerifyingKey<E>,
+    puzzle: &Puzzle,
+    proof: &Proof<E>,
+) -> Result<bool, PlonkError> {
+    verify_with(vk, puzzle, proof, TranscriptKind::Standard)
+}
+
+/// `prove` with the challenges drawn from `transcript`.
+pub fn prove_with<E, P, const LOOKUP: bool, R>(
+    pk: &ProvingKey<E>,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+    transcript: TranscriptKind,
+) -> Result<Proof<E>, PlonkError>
+where
+    E: PairingEngine,
+    P: ModelParameters<BaseField = E::Fr>,
+    R: RngCore + CryptoRng,
+{
+    let circuit = circuit::<E, P, LOOKUP>(puzzle, solution)?;
+    match transcript {
+        TranscriptKind::Standard => {
+            PlonkKzgSnark::<E>::prove::<_, _, StandardTranscript>(rng, &circuit, pk, None)
+        }
+        TranscriptKind::Solidity => {
+            PlonkKzgSnark::<E>::prove::<_, _, SolidityTranscript>(rng, &circuit, pk, None)
+        }
+    }
+}
+
+/// `verify` of a proof of `prove_with` and the same transcript.
+pub fn verify_with<E: PairingEngine>(
+    vk: &VerifyingKey<E>,
+    puzzle: &Puzzle,
+    proof: &Proof<E>,
+    transcript: TranscriptKind,
+) -> Result<bool, PlonkError> {
+    let public_inputs = puzzle.iter().map(E::Fr::from).collect::<Vec<_>>();
+    let verified = match transcript {
+        TranscriptKind::Standard => {
+            PlonkKzgSnark::<E>::verify::<StandardTranscript>(vk, &public_inputs, proof, None)
+        }
+        TranscriptKind::Solidity => {
+            PlonkKzgSnark::<E>::verify::<SolidityTranscript>(vk, &public_inputs, proof, None)
+        }
+    };
+    match verified {
+        Ok(()) => Ok(true),
+        Err(PlonkError::WrongProof) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+impl<E, P, const LOOKUP: bool> SudokuProver for JellyfishProver<E, P, LOOKUP>
+where
+    E: PairingEngine,
+    P: ModelParameters<BaseField = E::Fr>,
+{
+    type ProvingKey = ProvingKey<E>;
+    type VerifyingKey = VerifyingKey<E>;
+    type Proof = Proof<E>;
+    type Error = PlonkError;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        setup::<E, P, LOOKUP, R>(rng)
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        prove_with::<E, P, LOOKUP, R>(pk, puzzle, solution, rng, self.transcript)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        verify_with(vk, puzzle, proof, self.transcript)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Bls12_381;
+    use ark_ed_on_bls12_381::EdwardsParameters;
+    use ark_std::rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use zk_sudoku_core::{sample, Puzzle, SudokuProver};
+
+    use super::{
+        proof_from_bytes, proof_to_bytes, prove, setup, verify, JellyfishProver, TranscriptKind,
+    };
+
+    #[test]
+    fn test_jellyfish_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let prover = JellyfishProver::<Bls12_381, EdwardsParameters>::default();
+
+        let (pk, vk) = prover.setup(&mut rng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let bytes = proof_to_bytes::<Bls12_381>(&proof).unwrap();
+        let proof = proof_from_bytes::<Bls12_381>(&bytes).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+        assert!(proof_from_bytes::<Bls12_381>(&bytes[1..]).is_err());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+
+        let prover = JellyfishProver::<Bls12_381, EdwardsParameters, true>::default();
+        let (pk, vk) = prover.setup(&mut rng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut rng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_prove() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+
+        let (pk, vk) = setup::<Bls12_381, EdwardsParameters, true, _>(&mut rng).unwrap();
+        let proof =
+            prove::<Bls12_381, EdwardsParameters, true, _>(&pk, &puzzle, &solution, &mut rng)
+                .unwrap();
+        assert!(verify(&vk, &puzzle, &proof).unwrap());
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        assert!(!verify(&vk, &Puzzle::new(other).unwrap(), &proof).unwrap());
+    }
+
+    #[test]
+    fn test_transcripts() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let (pk, vk) = JellyfishProver::<Bls12_381, EdwardsParameters>::default()
+            .setup(&mut rng)
+            .unwrap();
+
+        let transcripts = [TranscriptKind::Standard, TranscriptKind::Solidity];
+        for prover in transcripts {
+            let proof = JellyfishProver::<Bls12_381, EdwardsParameters>::with_transcript(prover)
+                .prove(&pk, &puzzle, &solution, &mut rng)
+                .unwrap();
+            for verifier in transcripts {
+                // the challenges differ with the other transcript
+                let verified =
+                    JellyfishProver::<Bls12_381, EdwardsParameters>::with_transcript(verifier)
+                        .verify(&vk, &puzzle, &proof);
+                assert_eq!(
+                    matches!(verified, Ok(true)),
+                    prover == verifier,
+                    "{:?} proof, {:?} verifier",
+                    prover,
+                    verifier
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! Timings and sizes of the jellyfish prover on the sample sudoku, as the
+//! reports of the other backends.
+
+use std::time::Instant;
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::{ModelParameters, PairingEngine};
+use ark_ed_on_bls12_381::EdwardsParameters;
+use ark_std::rand::{CryptoRng, RngCore, SeedableRng};
+use jf_plonk::errors::PlonkError;
+use jf_relation::Arithmetization;
+use rand_chacha::ChaCha20Rng;
+use serde_json::json;
+use zk_sudoku_core::{
+    report::{print_table, Report},
+    sample, SudokuProver,
+};
+
+use crate::prover::{circuit, proof_to_bytes, JellyfishProver};
+
+/// Sets up, proves the sample sudoku and verifies the proof, timing each
+/// step. The setup includes the universal setup of the SRS. The gates are
+/// reported as the constraints, "turbo" for the product range checks and
+/// "ultra" for the lookups as the config. A rejected proof is a
+/// `PlonkError::WrongProof`.
+pub fn run<E, P, const LOOKUP: bool, R>(
+    curve: &'static str,
+    rng: &mut R,
+) -> Result<Report, PlonkError>
+where
+    E: PairingEngine,
+    P: ModelParameters<BaseField = E::Fr>,
+    R: RngCore + CryptoRng,
+{
+    let puzzle = sample::puzzle();
+    let solution = sample::solution();
+    let prover = JellyfishProver::<E, P, LOOKUP>::default();
+    let circuit = circuit::<E, P, LOOKUP>(&puzzle, &solution)?;
+
+    let setup_start = Instant::now();
+    let (pk, vk) = prover.setup(rng)?;
+    let setup_time = setup_start.elapsed();
+
+    let prove_start = Instant::now();
+    let proof = prover.prove(&pk, &puzzle, &solution, rng)?;
+    let prove_time = prove_start.elapsed();
+
+    let verify_start = Instant::now();
+    let valid_proof = prover.verify(&vk, &puzzle, &proof)?;
+    let verify_time = verify_start.elapsed();
+    if !valid_proof {
+        return Err(PlonkError::WrongProof);
+    }
+
+    Ok(Report {
+        backend: "jellyfish",
+        curve,
+        config: Some(if LOOKUP { "ultra" } else { "turbo" }),
+        num_constraints: circuit.num_gates(),
+        setup_time,
+        prove_time,
+        verify_time,
+        proof_len: proof_to_bytes::<E>(&proof).unwrap().len(),
+        extra: vec![("srs_size", json!(circuit.srs_size()?))],
+    })
+}
+
+/// Runs the TurboPlonk and UltraPlonk circuits over BLS12-381.
+pub fn test_jellyfish() -> Vec<Report> {
+    // a fixed seed, the setup is not meant to be trusted here
+    let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+    let reports = vec![
+        run::<Bls12_381, EdwardsParameters, false, _>("bls12_381", &mut rng).unwrap(),
+        run::<Bls12_381, EdwardsParameters, true, _>("bls12_381", &mut rng).unwrap(),
+    ];
+    print_table(&reports);
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Bls12_381;
+    use ark_ed_on_bls12_381::EdwardsParameters;
+    use ark_std::rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+    use serde_json::Value;
+
+    use super::run;
+
+    #[test]
+    fn test_report() {
+        let mut rng = ChaCha20Rng::from_seed([0u8; 32]);
+        let report = run::<Bls12_381, EdwardsParameters, false, _>("bls12_381", &mut rng).unwrap();
+        println!("{}", report.to_json());
+        let srs_size = report.extra("srs_size").and_then(Value::as_u64).unwrap();
+        assert!(report.num_constraints as u64 <= srs_size);
+        assert!(report.proof_len > 0);
+    }
+}
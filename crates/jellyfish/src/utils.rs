@@ -18,3 +18,21 @@ pub fn range_check<F: FftField>(
 
     circuit.enforce_equal(res_mul, circuit.zero())
 }
+
+/// Looks every value up in a table of 1..=9, which needs an UltraPlonk
+/// circuit. The keys of the table are the row indices, so each lookup takes
+/// `value - 1` as its key.
+pub fn lookup_range_check<F: FftField>(
+    circuit: &mut PlonkCircuit<F>,
+    values: &[Variable],
+) -> Result<(), CircuitError> {
+    let table_vars = (1..=9u32)
+        .map(|r| Ok((circuit.create_constant_variable(r.into())?, circuit.zero())))
+        .collect::<Result<Vec<_>, CircuitError>>()?;
+    let lookup_vars = values
+        .iter()
+        .map(|value| Ok((circuit.sub(*value, circuit.one())?, *value, circuit.zero())))
+        .collect::<Result<Vec<_>, CircuitError>>()?;
+
+    circuit.create_table_and_lookup_variables(&lookup_vars, &table_vars)
+}
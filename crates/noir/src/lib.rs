@@ -0,0 +1,5 @@
+pub use crate::nargo::test_noir;
+
+pub mod nargo;
+pub mod project;
+pub mod prover;
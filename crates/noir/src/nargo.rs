@@ -0,0 +1,53 @@
+use std::time::Instant;
+
+use zk_sudoku_core::{
+    report::{print_table, Report},
+    sample::{SOLVED, UNSOLVED},
+};
+
+use crate::project::{NoirError, NoirProject};
+
+/// Proves and verifies the sample sudoku through nargo and bb. The compile
+/// is reported as the setup and the gates counted by bb as the constraints.
+/// The timings include spawning nargo and bb and the file round trips,
+/// which the native backends don't pay. A rejected proof is an error.
+pub fn run_nargo() -> Result<Report, NoirError> {
+    let project = NoirProject::create()?;
+
+    let compile_start = Instant::now();
+    project.compile()?;
+    let compile_time = compile_start.elapsed();
+    let num_gates = project.gates()?;
+
+    let prove_start = Instant::now();
+    let proof = project.prove(&UNSOLVED, &SOLVED)?;
+    let prove_time = prove_start.elapsed();
+
+    let verify_start = Instant::now();
+    let valid_proof = project.verify(&UNSOLVED, &proof)?;
+    let verify_time = verify_start.elapsed();
+    if !valid_proof {
+        return Err(NoirError::Nargo {
+            command: "bb verify".into(),
+            stderr: "the proof of the sample sudoku is rejected".into(),
+        });
+    }
+
+    Ok(Report {
+        backend: "noir",
+        curve: "bn254",
+        config: None,
+        num_constraints: num_gates,
+        setup_time: compile_time,
+        prove_time,
+        verify_time,
+        proof_len: proof.len(),
+        extra: vec![],
+    })
+}
+
+pub fn test_noir() -> Vec<Report> {
+    let reports = vec![run_nargo().unwrap()];
+    print_table(&reports);
+    reports
+}
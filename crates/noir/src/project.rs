@@ -0,0 +1,278 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    sync::Arc,
+};
+
+use tempfile::TempDir;
+
+const NARGO_TOML: &str = include_str!("../circuit/Nargo.toml");
+const MAIN_NR: &str = include_str!("../circuit/src/main.nr");
+const PACKAGE: &str = "sudoku";
+
+#[derive(Debug)]
+pub enum NoirError {
+    Io(io::Error),
+    /// nargo or bb ran but exited with an error, other than bb rejecting a
+    /// proof.
+    Nargo {
+        command: String,
+        stderr: String,
+    },
+    /// The gates printed by `bb gates` are not JSON.
+    Gates(serde_json::Error),
+}
+
+impl From<io::Error> for NoirError {
+    fn from(err: io::Error) -> Self {
+        NoirError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for NoirError {
+    fn from(err: serde_json::Error) -> Self {
+        NoirError::Gates(err)
+    }
+}
+
+// The grid as a TOML array of arrays, the way nargo writes its inputs
+fn toml_grid(name: &str, cells: &[[u8; 9]; 9]) -> String {
+    let rows = cells
+        .iter()
+        .map(|row| {
+            let row = row
+                .iter()
+                .map(|x| format!("\"{}\"", x))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("[{}]", row)
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{} = [{}]\n", name, rows)
+}
+
+// The public inputs the way bb writes them, one big endian 32 byte field
+// element per cell, row by row
+fn public_inputs(unsolved: &[[u8; 9]; 9]) -> Vec<u8> {
+    unsolved
+        .iter()
+        .flatten()
+        .flat_map(|&x| {
+            let mut word = [0u8; 32];
+            word[31] = x;
+            word
+        })
+        .collect()
+}
+
+// bb exits with 1 both on a rejected proof and on errors, only its log tells
+// them apart
+fn is_rejection(output: &Output) -> bool {
+    let log = String::from_utf8_lossy(&output.stderr).to_lowercase()
+        + &String::from_utf8_lossy(&output.stdout).to_lowercase();
+    output.status.code() == Some(1)
+        && (log.contains("verified: 0")
+            || log.contains("verified: false")
+            || log.contains("verification failed"))
+}
+
+/// The Noir sudoku program written out as a nargo package. nargo compiles
+/// and executes it, and the barretenberg `bb` binary proves and verifies
+/// with UltraHonk, both found on the `PATH`, so no proving stack is linked
+/// in.
+///
+/// `nargo prove` and `nargo verify` are gone from nargo 1.0, the package
+/// asks for `compiler_version >= 1.0.0-beta.6`, whose `bb` (0.84 and later)
+/// writes the proof and the public inputs to separate files.
+///
+/// The package lives in a temporary directory shared by the clones, removed
+/// once the last of them is dropped.
+#[derive(Debug, Clone)]
+pub struct NoirProject {
+    dir: Arc<TempDir>,
+}
+
+impl NoirProject {
+    /// Writes the embedded package in a new temporary directory.
+    pub fn create() -> Result<Self, NoirError> {
+        let dir = tempfile::Builder::new()
+            .prefix("zk_sudoku_noir")
+            .tempdir()?;
+        fs::create_dir_all(dir.path().join("src"))?;
+        fs::write(dir.path().join("Nargo.toml"), NARGO_TOML)?;
+        fs::write(dir.path().join("src").join("main.nr"), MAIN_NR)?;
+        Ok(Self { dir: Arc::new(dir) })
+    }
+
+    pub fn dir(&self) -> &Path {
+        self.dir.path()
+    }
+
+    fn target(&self) -> PathBuf {
+        self.dir().join("target")
+    }
+
+    fn bytecode_path(&self) -> PathBuf {
+        self.target().join(format!("{}.json", PACKAGE))
+    }
+
+    fn witness_path(&self) -> PathBuf {
+        self.target().join(format!("{}.gz", PACKAGE))
+    }
+
+    fn spawn(&self, program: &str, args: &[&str]) -> Result<Output, NoirError> {
+        Ok(Command::new(program)
+            .args(args)
+            .current_dir(self.dir())
+            .output()?)
+    }
+
+    fn run(&self, program: &str, args: &[&str]) -> Result<(), NoirError> {
+        let output = self.spawn(program, args)?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(nargo_error(program, args, &output))
+        }
+    }
+
+    /// Compiles the program and writes its verifying key next to it.
+    pub fn compile(&self) -> Result<(), NoirError> {
+        self.run("nargo", &["compile"])?;
+        let bytecode = self.bytecode_path();
+        let target = self.target();
+        self.run(
+            "bb",
+            &[
+                "write_vk",
+                "-b",
+                &bytecode.to_string_lossy(),
+                "-o",
+                &target.to_string_lossy(),
+            ],
+        )
+    }
+
+    /// The gates of the compiled program, as counted by `bb gates`.
+    pub fn gates(&self) -> Result<usize, NoirError> {
+        let bytecode = self.bytecode_path();
+        let args = ["gates", "-b", &bytecode.to_string_lossy()];
+        let output = self.spawn("bb", &args)?;
+        if !output.status.success() {
+            return Err(nargo_error("bb", &args, &output));
+        }
+        let gates = serde_json::from_slice::<serde_json::Value>(&output.stdout)?;
+        Ok(gates["functions"][0]["circuit_size"]
+            .as_u64()
+            .unwrap_or_default() as usize)
+    }
+
+    pub fn prove(
+        &self,
+        unsolved: &[[u8; 9]; 9],
+        solved: &[[u8; 9]; 9],
+    ) -> Result<Vec<u8>, NoirError> {
+        let inputs = toml_grid("solved", solved) + &toml_grid("unsolved", unsolved);
+        fs::write(self.dir().join("Prover.toml"), inputs)?;
+        self.run("nargo", &["execute"])?;
+
+        let bytecode = self.bytecode_path();
+        let witness = self.witness_path();
+        let target = self.target();
+        self.run(
+            "bb",
+            &[
+                "prove",
+                "-b",
+                &bytecode.to_string_lossy(),
+                "-w",
+                &witness.to_string_lossy(),
+                "-o",
+                &target.to_string_lossy(),
+            ],
+        )?;
+        Ok(fs::read(target.join("proof"))?)
+    }
+
+    /// `Ok(false)` when bb rejects the proof, an error when bb fails for
+    /// any other reason.
+    pub fn verify(&self, unsolved: &[[u8; 9]; 9], proof: &[u8]) -> Result<bool, NoirError> {
+        let dir = self.dir().join("verify");
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join("proof"), proof)?;
+        fs::write(dir.join("public_inputs"), public_inputs(unsolved))?;
+
+        let vk = self.target().join("vk");
+        let proof = dir.join("proof");
+        let public_inputs = dir.join("public_inputs");
+        let args = [
+            "verify",
+            "-k",
+            &vk.to_string_lossy(),
+            "-p",
+            &proof.to_string_lossy(),
+            "-i",
+            &public_inputs.to_string_lossy(),
+        ];
+        let output = self.spawn("bb", &args)?;
+        if output.status.success() {
+            Ok(true)
+        } else if is_rejection(&output) {
+            Ok(false)
+        } else {
+            Err(nargo_error("bb", &args, &output))
+        }
+    }
+}
+
+fn nargo_error(program: &str, args: &[&str], output: &Output) -> NoirError {
+    NoirError::Nargo {
+        command: format!("{} {}", program, args.join(" ")),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        os::unix::process::ExitStatusExt,
+        process::{ExitStatus, Output},
+    };
+
+    use super::{is_rejection, public_inputs, toml_grid};
+
+    #[test]
+    fn test_toml_grid() {
+        let mut cells = [[0u8; 9]; 9];
+        cells[0][1] = 6;
+        let toml = toml_grid("unsolved", &cells);
+        assert!(toml.starts_with("unsolved = [[\"0\", \"6\", \"0\","));
+        assert_eq!(toml.matches('[').count(), 10);
+        assert!(toml.ends_with("]]\n"));
+    }
+
+    #[test]
+    fn test_public_inputs() {
+        let mut cells = [[0u8; 9]; 9];
+        cells[0][1] = 6;
+        let inputs = public_inputs(&cells);
+        assert_eq!(inputs.len(), 81 * 32);
+        assert_eq!(inputs[63], 6);
+        assert_eq!(inputs.iter().filter(|&&x| x != 0).count(), 1);
+    }
+
+    #[test]
+    fn test_is_rejection() {
+        let output = |code: i32, stderr: &str| Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: vec![],
+            stderr: stderr.as_bytes().to_vec(),
+        };
+        assert!(is_rejection(&output(1, "Proof verification failed")));
+        assert!(is_rejection(&output(1, "verified: 0")));
+        assert!(!is_rejection(&output(1, "Unable to open file: vk")));
+        assert!(!is_rejection(&output(134, "verification failed")));
+    }
+}
@@ -0,0 +1,71 @@
+use zk_sudoku_core::{
+    rand_core::{CryptoRng, RngCore},
+    Puzzle, Solution, SudokuProver,
+};
+
+use crate::project::{NoirError, NoirProject};
+
+/// The Noir program proved by nargo with its default barretenberg backend.
+/// barretenberg draws its own randomness, so the rng passed in is unused.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoirProver;
+
+impl SudokuProver for NoirProver {
+    type ProvingKey = NoirProject;
+    type VerifyingKey = NoirProject;
+    type Proof = Vec<u8>;
+    type Error = NoirError;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        _rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        let project = NoirProject::create()?;
+        project.compile()?;
+        Ok((project.clone(), project))
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        _rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        pk.prove(puzzle.cells(), solution.cells())
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        vk.verify(puzzle.cells(), proof)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{sample, Puzzle, SudokuProver};
+
+    use super::NoirProver;
+
+    #[test]
+    #[ignore = "needs nargo and bb on the PATH"]
+    fn test_noir_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let prover = NoirProver;
+
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+    }
+}
@@ -0,0 +1,225 @@
+use bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
+use ff::PrimeField;
+use nova_snark::traits::circuit::StepCircuit;
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::gadgets::{all_digits, alloc_cell, enforce_all_digits, packed_sum};
+
+/// Rows checked by one folding step, i.e. a band of three squares.
+pub const BAND: usize = 3;
+/// Folding steps of a proof, one per band.
+pub const NUM_STEPS: usize = 9 / BAND;
+/// Length of the state folded from step to step: the 81 cells of the puzzle,
+/// then the digits counted so far in each column.
+pub const ARITY: usize = 81 + 9;
+
+/// One folding step, checking a band of three rows of the solution.
+///
+/// The step checks the rows and the squares of its band, and the givens of
+/// the puzzle rows at the front of the state. It then rotates these rows to
+/// the back, so the next step finds its own at the front, and adds the digits
+/// of the band to the column counts. After the last step the state is the
+/// puzzle again, followed by the counts of the columns, which are all
+/// `all_digits` iff every column holds each digit once.
+#[derive(Debug, Default, Clone)]
+pub struct SudokuStep {
+    pub band: [[u64; 9]; BAND],
+}
+
+impl SudokuStep {
+    /// The steps proving `solution`, band by band.
+    pub fn steps(solution: &Solution) -> Vec<Self> {
+        let solved = solution.map(u64::from);
+        solved
+            .chunks(BAND)
+            .map(|rows| {
+                let mut band = [[0; 9]; BAND];
+                band.copy_from_slice(rows);
+                Self { band }
+            })
+            .collect()
+    }
+}
+
+/// The state the first step starts from: the puzzle and no digit counted.
+pub fn initial_state<F: PrimeField>(puzzle: &Puzzle) -> Vec<F> {
+    puzzle
+        .iter()
+        .map(|x| F::from(x as u64))
+        .chain(std::iter::repeat_n(F::ZERO, 9))
+        .collect()
+}
+
+/// The state after the last step if the solution is valid for `puzzle`.
+pub fn final_state<F: PrimeField>(puzzle: &Puzzle) -> Vec<F> {
+    puzzle
+        .iter()
+        .map(|x| F::from(x as u64))
+        .chain(std::iter::repeat_n(all_digits(), 9))
+        .collect()
+}
+
+impl<F: PrimeField> StepCircuit<F> for SudokuStep {
+    fn arity(&self) -> usize {
+        ARITY
+    }
+
+    fn synthesize<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        z: &[AllocatedNum<F>],
+    ) -> Result<Vec<AllocatedNum<F>>, SynthesisError> {
+        let (givens, counts) = z.split_at(81);
+
+        let mut cells = Vec::with_capacity(BAND);
+        for (i, row) in self.band.iter().enumerate() {
+            let mut line = Vec::with_capacity(9);
+            for (j, value) in row.iter().enumerate() {
+                line.push(alloc_cell(
+                    cs.namespace(|| format!("cell {} {}", i, j)),
+                    *value,
+                )?);
+            }
+            cells.push(line);
+        }
+
+        // Check if the band keeps the givens of its rows of the puzzle
+        // given * (solved - given) = 0, so if the given is not zero, the
+        // solved cell is equal to it
+        for (i, line) in cells.iter().enumerate() {
+            for (j, cell) in line.iter().enumerate() {
+                let given = givens[i * 9 + j].get_variable();
+                cs.enforce(
+                    || format!("given {} {}", i, j),
+                    |lc| lc + given,
+                    |lc| lc + &cell.digit - given,
+                    |lc| lc,
+                );
+            }
+        }
+
+        // Check if each row of the band has all the numbers from 1 to 9
+        for (i, line) in cells.iter().enumerate() {
+            enforce_all_digits(cs, format!("row {}", i), line);
+        }
+
+        // Check if each square of the band has all the numbers from 1 to 9
+        for j in [0, 3, 6] {
+            let square = cells.iter().flat_map(|line| &line[j..j + 3]);
+            enforce_all_digits(cs, format!("square {}", j / 3), square);
+        }
+
+        // Count the digits of the band in each column, the verifier checking
+        // the counts of the last state
+        let mut next_counts = Vec::with_capacity(9);
+        for (j, count) in counts.iter().enumerate() {
+            let column = cells.iter().map(|line| &line[j]);
+            let added = column.clone().map(|cell| cell.packed_value).sum::<F>();
+            let next = AllocatedNum::alloc(cs.namespace(|| format!("count {}", j)), || {
+                count
+                    .get_value()
+                    .map(|count| count + added)
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let sum = packed_sum(column);
+            cs.enforce(
+                || format!("column {}", j),
+                |lc| lc + count.get_variable() + &sum - next.get_variable(),
+                |lc| lc + CS::one(),
+                |lc| lc,
+            );
+            next_counts.push(next);
+        }
+
+        Ok(givens[BAND * 9..]
+            .iter()
+            .chain(&givens[..BAND * 9])
+            .cloned()
+            .chain(next_counts)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bellpepper_core::{num::AllocatedNum, test_cs::TestConstraintSystem, ConstraintSystem};
+    use nova_snark::{
+        provider::PallasEngine,
+        traits::{circuit::StepCircuit, Engine},
+    };
+    use zk_sudoku_core::{
+        sample::{self, SOLVED},
+        Puzzle, Solution,
+    };
+
+    use super::{final_state, initial_state, SudokuStep};
+
+    type F = <PallasEngine as Engine>::Scalar;
+
+    // Runs the steps one after the other, the state of each feeding the next
+    fn run(puzzle: &Puzzle, solved: [[u8; 9]; 9]) -> (bool, Vec<F>) {
+        let mut cs = TestConstraintSystem::<F>::new();
+        let mut z = initial_state::<F>(puzzle)
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| AllocatedNum::alloc(cs.namespace(|| format!("z0 {}", i)), || Ok(x)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        for (i, step) in SudokuStep::steps(&Solution::new_unchecked(solved))
+            .iter()
+            .enumerate()
+        {
+            z = step
+                .synthesize(&mut cs.namespace(|| format!("step {}", i)), &z)
+                .unwrap();
+        }
+        let state = z.iter().map(|x| x.get_value().unwrap()).collect();
+        (cs.is_satisfied(), state)
+    }
+
+    #[test]
+    fn test_sudoku_step() {
+        let puzzle = sample::puzzle();
+        let (satisfied, state) = run(&puzzle, SOLVED);
+        assert!(satisfied);
+        assert_eq!(state, final_state::<F>(&puzzle));
+
+        // in the first band, then in the last one, checked once rotated
+        let mut unmatch = *puzzle.cells();
+        unmatch[0][0] = 2;
+        assert!(!run(&Puzzle::new(unmatch).unwrap(), SOLVED).0);
+        let mut unmatch = *puzzle.cells();
+        unmatch[8][8] = 5;
+        assert!(!run(&Puzzle::new(unmatch).unwrap(), SOLVED).0);
+
+        // no givens, so that each case breaks one check only
+        let empty = Puzzle::new([[0; 9]; 9]).unwrap();
+        assert!(run(&empty, SOLVED).0);
+
+        let mut out_of_range = SOLVED;
+        out_of_range[0][8] = 10;
+        assert!(!run(&empty, out_of_range).0);
+
+        let mut repeated_in_row = SOLVED;
+        repeated_in_row[0][8] = 1;
+        assert!(!run(&empty, repeated_in_row).0);
+
+        // swapping two rows of different bands keeps the rows and the columns
+        let mut repeated_in_square = SOLVED;
+        repeated_in_square[2] = SOLVED[3];
+        repeated_in_square[3] = SOLVED[2];
+        assert!(!run(&empty, repeated_in_square).0);
+    }
+
+    #[test]
+    fn test_columns_in_final_state() {
+        // swapping two cells of a row keeps the rows and the squares, the
+        // broken columns only show in the counts of the last state
+        let mut repeated_in_column = SOLVED;
+        repeated_in_column[4].swap(0, 1);
+        let empty = Puzzle::new([[0; 9]; 9]).unwrap();
+        let (satisfied, state) = run(&empty, repeated_in_column);
+        assert!(satisfied);
+        assert_ne!(state, final_state::<F>(&empty));
+    }
+}
@@ -0,0 +1,88 @@
+//! The cell gadgets shared by the uniform and the non-uniform steps.
+
+use bellpepper_core::{ConstraintSystem, LinearCombination, SynthesisError};
+use ff::PrimeField;
+
+// Counts of the digits 1 to 9 are packed as the base 16 digits of a field
+// element. A set of at most 9 cells never counts a digit 16 times, so summing
+// packed counts never carries over to the next digit.
+fn weight<F: PrimeField>(digit: u64) -> F {
+    F::from(1u64 << (4 * (digit - 1)))
+}
+
+/// The packed counts of nine cells holding each digit once.
+pub(crate) fn all_digits<F: PrimeField>() -> F {
+    (1..=9).map(weight::<F>).sum()
+}
+
+/// A cell of the solution allocated as one boolean per digit, exactly one of
+/// which is set, so the cell is in range by construction.
+pub(crate) struct Cell<F: PrimeField> {
+    pub(crate) digit: LinearCombination<F>,
+    pub(crate) packed: LinearCombination<F>,
+    pub(crate) packed_value: F,
+}
+
+pub(crate) fn alloc_cell<F: PrimeField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    value: u64,
+) -> Result<Cell<F>, SynthesisError> {
+    let mut digit = LinearCombination::zero();
+    let mut packed = LinearCombination::zero();
+    let mut count = LinearCombination::zero();
+    for d in 1..=9u64 {
+        let bit = cs.alloc(
+            || format!("is {}", d),
+            || Ok(if value == d { F::ONE } else { F::ZERO }),
+        )?;
+        cs.enforce(
+            || format!("boolean {}", d),
+            |lc| lc + bit,
+            |lc| lc + CS::one() - bit,
+            |lc| lc,
+        );
+        digit = digit + (F::from(d), bit);
+        packed = packed + (weight::<F>(d), bit);
+        count = count + bit;
+    }
+    cs.enforce(
+        || "one digit",
+        |_| count,
+        |lc| lc + CS::one(),
+        |lc| lc + CS::one(),
+    );
+
+    let packed_value = if (1..=9).contains(&value) {
+        weight(value)
+    } else {
+        F::ZERO
+    };
+    Ok(Cell {
+        digit,
+        packed,
+        packed_value,
+    })
+}
+
+pub(crate) fn packed_sum<'a, F: PrimeField>(
+    cells: impl IntoIterator<Item = &'a Cell<F>>,
+) -> LinearCombination<F> {
+    cells
+        .into_iter()
+        .fold(LinearCombination::zero(), |lc, cell| lc + &cell.packed)
+}
+
+// The cells hold each digit once iff their packed counts sum to `all_digits`
+pub(crate) fn enforce_all_digits<'a, F: PrimeField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    name: String,
+    cells: impl IntoIterator<Item = &'a Cell<F>>,
+) {
+    let sum = packed_sum(cells);
+    cs.enforce(
+        || name,
+        |_| sum,
+        |lc| lc + CS::one(),
+        |lc| lc + (all_digits::<F>(), CS::one()),
+    );
+}
@@ -0,0 +1,25 @@
+pub use arecibo;
+pub use nova_snark;
+
+use crate::{nova::run_nova, supernova::run_supernova};
+
+pub mod circuit;
+mod gadgets;
+pub mod non_uniform;
+pub mod nova;
+pub mod prover;
+pub mod supernova;
+
+pub fn test_nova() {
+    println!("-----------------------------");
+    println!("Run Nova with pallas/vesta...");
+    println!("-----------------------------");
+    run_nova();
+}
+
+pub fn test_supernova() {
+    println!("-----------------------------");
+    println!("Run SuperNova with pallas/vesta...");
+    println!("-----------------------------");
+    run_supernova();
+}
\ No newline at end of file
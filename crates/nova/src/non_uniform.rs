@@ -0,0 +1,426 @@
+use arecibo::{
+    supernova::NonUniformCircuit,
+    traits::{
+        circuit_supernova::{StepCircuit, TrivialSecondaryCircuit},
+        Engine,
+    },
+};
+use bellpepper_core::{num::AllocatedNum, ConstraintSystem, SynthesisError};
+use ff::PrimeField;
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::gadgets::{all_digits, alloc_cell, enforce_all_digits, packed_sum};
+
+// The state folded from step to step: the 81 cells of the puzzle, rotated by
+// a row on each row step, the digits counted in each column and in each square
+// of the current band, the position of the next row in its band and the band
+// as one boolean each, then how many times the columns were checked.
+const PUZZLE: usize = 0;
+const COLUMNS: usize = 81;
+const SQUARES: usize = COLUMNS + 9;
+const POSITION: usize = SQUARES + 3;
+const BAND: usize = POSITION + 3;
+const CHECKED: usize = BAND + 3;
+/// Length of the state, the same for the three circuits.
+pub const ARITY: usize = CHECKED + 1;
+
+/// Circuit index of `RowStep`, which the folding starts with.
+pub const ROW_STEP: usize = 0;
+/// Circuit index of `SquareCheck`.
+pub const SQUARE_CHECK: usize = 1;
+/// Circuit index of `ColumnCheck`.
+pub const COLUMN_CHECK: usize = 2;
+
+// The program counter and the state after a step, as SuperNova takes them
+type StepOutput<F> = (Option<AllocatedNum<F>>, Vec<AllocatedNum<F>>);
+
+fn alloc_constant<F: PrimeField, CS: ConstraintSystem<F>>(
+    mut cs: CS,
+    value: F,
+) -> Result<AllocatedNum<F>, SynthesisError> {
+    let num = AllocatedNum::alloc(cs.namespace(|| "value"), || Ok(value))?;
+    cs.enforce(
+        || "constant",
+        |lc| lc + num.get_variable(),
+        |lc| lc + CS::one(),
+        |lc| lc + (value, CS::one()),
+    );
+    Ok(num)
+}
+
+// Checks the packed counts hold each digit once, then starts them over
+fn check_counts<F: PrimeField, CS: ConstraintSystem<F>>(
+    cs: &mut CS,
+    counts: &[AllocatedNum<F>],
+) -> Result<Vec<AllocatedNum<F>>, SynthesisError> {
+    let mut next_counts = Vec::with_capacity(counts.len());
+    for (i, count) in counts.iter().enumerate() {
+        cs.enforce(
+            || format!("all digits {}", i),
+            |lc| lc + count.get_variable(),
+            |lc| lc + CS::one(),
+            |lc| lc + (all_digits::<F>(), CS::one()),
+        );
+        next_counts.push(alloc_constant(
+            cs.namespace(|| format!("reset {}", i)),
+            F::ZERO,
+        )?);
+    }
+    Ok(next_counts)
+}
+
+// The booleans of a position rotated by one, (a, b, c) becoming (c, a, b)
+fn rotate<F: PrimeField>(
+    booleans: &[AllocatedNum<F>],
+) -> impl Iterator<Item = AllocatedNum<F>> + '_ {
+    booleans[2..].iter().chain(&booleans[..2]).cloned()
+}
+
+/// Checks the row at the front of the puzzle: its cells, its givens and the
+/// row itself. Its digits are added to the counts of the columns and of the
+/// squares, which the checks below read. The next step checks the squares if
+/// the row ends its band, the next row otherwise.
+#[derive(Debug, Default, Clone)]
+pub struct RowStep {
+    pub row: [u64; 9],
+}
+
+impl RowStep {
+    fn synthesize<F: PrimeField, CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        z: &[AllocatedNum<F>],
+    ) -> Result<StepOutput<F>, SynthesisError> {
+        let mut cells = Vec::with_capacity(9);
+        for (j, value) in self.row.iter().enumerate() {
+            cells.push(alloc_cell(cs.namespace(|| format!("cell {}", j)), *value)?);
+        }
+
+        // Check if the row keeps the givens of the puzzle
+        // given * (solved - given) = 0, so if the given is not zero, the
+        // solved cell is equal to it
+        for (j, cell) in cells.iter().enumerate() {
+            let given = z[PUZZLE + j].get_variable();
+            cs.enforce(
+                || format!("given {}", j),
+                |lc| lc + given,
+                |lc| lc + &cell.digit - given,
+                |lc| lc,
+            );
+        }
+
+        // Check if the row has all the numbers from 1 to 9
+        enforce_all_digits(cs, "row".to_string(), &cells);
+
+        // Count the digits of the row in its column and in its square
+        let groups = (0..9)
+            .map(|j| (COLUMNS + j, j..j + 1))
+            .chain((0..3).map(|s| (SQUARES + s, 3 * s..3 * s + 3)));
+        let mut counts = Vec::with_capacity(12);
+        for (i, cols) in groups {
+            let added = cells[cols.clone()]
+                .iter()
+                .map(|cell| cell.packed_value)
+                .sum::<F>();
+            let next = AllocatedNum::alloc(cs.namespace(|| format!("count {}", i)), || {
+                z[i].get_value()
+                    .map(|count| count + added)
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+            let sum = packed_sum(&cells[cols]);
+            cs.enforce(
+                || format!("add {}", i),
+                |lc| lc + z[i].get_variable() + &sum - next.get_variable(),
+                |lc| lc + CS::one(),
+                |lc| lc,
+            );
+            counts.push(next);
+        }
+
+        // The row at the end of its band is the one last in position
+        let pc = z[POSITION + 2].clone();
+        let next = z[PUZZLE + 9..PUZZLE + 81]
+            .iter()
+            .chain(&z[PUZZLE..PUZZLE + 9])
+            .cloned()
+            .chain(counts)
+            .chain(rotate(&z[POSITION..BAND]))
+            .chain(z[BAND..].iter().cloned())
+            .collect();
+        Ok((Some(pc), next))
+    }
+}
+
+/// Checks the squares of the band just counted, then moves to the next band.
+/// The next step checks the columns after the last band, the next row
+/// otherwise.
+#[derive(Debug, Default, Clone)]
+pub struct SquareCheck;
+
+impl SquareCheck {
+    fn synthesize<F: PrimeField, CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        z: &[AllocatedNum<F>],
+    ) -> Result<StepOutput<F>, SynthesisError> {
+        let squares = check_counts(cs, &z[SQUARES..POSITION])?;
+
+        // COLUMN_CHECK after the last band, ROW_STEP otherwise
+        let last_band = &z[BAND + 2];
+        let pc = AllocatedNum::alloc(cs.namespace(|| "pc"), || {
+            last_band
+                .get_value()
+                .map(|last| last * F::from(COLUMN_CHECK as u64))
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        cs.enforce(
+            || "next circuit",
+            |lc| lc + (F::from(COLUMN_CHECK as u64), last_band.get_variable()),
+            |lc| lc + CS::one(),
+            |lc| lc + pc.get_variable(),
+        );
+
+        let next = z[..SQUARES]
+            .iter()
+            .cloned()
+            .chain(squares)
+            .chain(z[POSITION..BAND].iter().cloned())
+            .chain(rotate(&z[BAND..CHECKED]))
+            .chain(z[CHECKED..].iter().cloned())
+            .collect();
+        Ok((Some(pc), next))
+    }
+}
+
+/// Checks the columns once every row is counted, and records it in the
+/// state so that the verifier knows the check ran.
+#[derive(Debug, Default, Clone)]
+pub struct ColumnCheck;
+
+impl ColumnCheck {
+    fn synthesize<F: PrimeField, CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        z: &[AllocatedNum<F>],
+    ) -> Result<StepOutput<F>, SynthesisError> {
+        let columns = check_counts(cs, &z[COLUMNS..SQUARES])?;
+
+        let checked = &z[CHECKED];
+        let next_checked = AllocatedNum::alloc(cs.namespace(|| "checked"), || {
+            checked
+                .get_value()
+                .map(|checked| checked + F::ONE)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        cs.enforce(
+            || "count the check",
+            |lc| lc + checked.get_variable() + CS::one() - next_checked.get_variable(),
+            |lc| lc + CS::one(),
+            |lc| lc,
+        );
+
+        let pc = alloc_constant(cs.namespace(|| "pc"), F::from(ROW_STEP as u64))?;
+        let next = z[..COLUMNS]
+            .iter()
+            .cloned()
+            .chain(columns)
+            .chain(z[SQUARES..CHECKED].iter().cloned())
+            .chain(std::iter::once(next_checked))
+            .collect();
+        Ok((Some(pc), next))
+    }
+}
+
+/// One step of the non-uniform folding, the circuit run being picked by the
+/// program counter the previous step returned.
+#[derive(Debug, Clone)]
+pub enum SudokuStep {
+    Row(RowStep),
+    Squares(SquareCheck),
+    Columns(ColumnCheck),
+}
+
+impl SudokuStep {
+    /// The steps proving `solution`: three rows then their squares, for each
+    /// band, then the columns.
+    pub fn steps(solution: &Solution) -> Vec<Self> {
+        let solved = solution.map(u64::from);
+        let mut steps = Vec::with_capacity(13);
+        for band in solved.chunks(3) {
+            for row in band {
+                steps.push(SudokuStep::Row(RowStep { row: *row }));
+            }
+            steps.push(SudokuStep::Squares(SquareCheck));
+        }
+        steps.push(SudokuStep::Columns(ColumnCheck));
+        steps
+    }
+}
+
+impl<F: PrimeField> StepCircuit<F> for SudokuStep {
+    fn arity(&self) -> usize {
+        ARITY
+    }
+
+    fn circuit_index(&self) -> usize {
+        match self {
+            SudokuStep::Row(_) => ROW_STEP,
+            SudokuStep::Squares(_) => SQUARE_CHECK,
+            SudokuStep::Columns(_) => COLUMN_CHECK,
+        }
+    }
+
+    fn synthesize<CS: ConstraintSystem<F>>(
+        &self,
+        cs: &mut CS,
+        _pc: Option<&AllocatedNum<F>>,
+        z: &[AllocatedNum<F>],
+    ) -> Result<StepOutput<F>, SynthesisError> {
+        match self {
+            SudokuStep::Row(step) => step.synthesize(cs, z),
+            SudokuStep::Squares(step) => step.synthesize(cs, z),
+            SudokuStep::Columns(step) => step.synthesize(cs, z),
+        }
+    }
+}
+
+/// The three circuits of the folding, as SuperNova builds its parameters
+/// from.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SudokuProgram;
+
+impl<E1, E2> NonUniformCircuit<E1, E2, SudokuStep, TrivialSecondaryCircuit<E2::Scalar>>
+    for SudokuProgram
+where
+    E1: Engine<Base = <E2 as Engine>::Scalar>,
+    E2: Engine<Base = <E1 as Engine>::Scalar>,
+{
+    fn num_circuits(&self) -> usize {
+        3
+    }
+
+    fn primary_circuit(&self, circuit_index: usize) -> SudokuStep {
+        match circuit_index {
+            ROW_STEP => SudokuStep::Row(RowStep::default()),
+            SQUARE_CHECK => SudokuStep::Squares(SquareCheck),
+            COLUMN_CHECK => SudokuStep::Columns(ColumnCheck),
+            _ => panic!("no circuit {}", circuit_index),
+        }
+    }
+
+    fn secondary_circuit(&self) -> TrivialSecondaryCircuit<E2::Scalar> {
+        Default::default()
+    }
+}
+
+fn state<F: PrimeField>(puzzle: &Puzzle, checked: u64) -> Vec<F> {
+    let first = [F::ONE, F::ZERO, F::ZERO];
+    puzzle
+        .iter()
+        .map(|x| F::from(x as u64))
+        .chain(std::iter::repeat_n(F::ZERO, 9 + 3))
+        .chain(first)
+        .chain(first)
+        .chain(std::iter::once(F::from(checked)))
+        .collect()
+}
+
+/// The state the first step starts from: the puzzle, no digit counted, the
+/// first row of the first band next.
+pub fn initial_state<F: PrimeField>(puzzle: &Puzzle) -> Vec<F> {
+    state(puzzle, 0)
+}
+
+/// The state after the last step if the solution is valid for `puzzle`: the
+/// same but for the columns checked once. Every count is back to zero, so the
+/// state tells nothing about the solution.
+pub fn final_state<F: PrimeField>(puzzle: &Puzzle) -> Vec<F> {
+    state(puzzle, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use arecibo::{
+        provider::PallasEngine,
+        traits::{circuit_supernova::StepCircuit, Engine},
+    };
+    use bellpepper_core::{num::AllocatedNum, test_cs::TestConstraintSystem, ConstraintSystem};
+    use ff::Field;
+    use zk_sudoku_core::{
+        sample::{self, SOLVED},
+        Puzzle, Solution,
+    };
+
+    use super::{final_state, initial_state, SudokuStep, ROW_STEP};
+
+    type F = <PallasEngine as Engine>::Scalar;
+
+    // Runs the steps one after the other, checking each one is the circuit
+    // the previous one asked for
+    fn run(puzzle: &Puzzle, solved: [[u8; 9]; 9]) -> (bool, Vec<F>) {
+        let mut cs = TestConstraintSystem::<F>::new();
+        let mut z = initial_state::<F>(puzzle)
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| AllocatedNum::alloc(cs.namespace(|| format!("z0 {}", i)), || Ok(x)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let mut pc = ROW_STEP;
+        for (i, step) in SudokuStep::steps(&Solution::new_unchecked(solved))
+            .iter()
+            .enumerate()
+        {
+            assert_eq!(StepCircuit::<F>::circuit_index(step), pc);
+            let (next_pc, next_z) = step
+                .synthesize(&mut cs.namespace(|| format!("step {}", i)), None, &z)
+                .unwrap();
+            let next_pc = next_pc.unwrap().get_value().unwrap();
+            pc = (0..3).find(|pc| F::from(*pc as u64) == next_pc).unwrap();
+            z = next_z;
+        }
+        let state = z.iter().map(|x| x.get_value().unwrap()).collect();
+        (cs.is_satisfied(), state)
+    }
+
+    #[test]
+    fn test_sudoku_steps() {
+        let puzzle = sample::puzzle();
+        let (satisfied, state) = run(&puzzle, SOLVED);
+        assert!(satisfied);
+        assert_eq!(state, final_state::<F>(&puzzle));
+
+        let mut unmatch = *puzzle.cells();
+        unmatch[8][8] = 5;
+        assert!(!run(&Puzzle::new(unmatch).unwrap(), SOLVED).0);
+
+        // no givens, so that each case breaks one check only
+        let empty = Puzzle::new([[0; 9]; 9]).unwrap();
+        let mut out_of_range = SOLVED;
+        out_of_range[0][8] = 10;
+        assert!(!run(&empty, out_of_range).0);
+
+        let mut repeated_in_row = SOLVED;
+        repeated_in_row[0][8] = 1;
+        assert!(!run(&empty, repeated_in_row).0);
+
+        // swapping two rows of different bands keeps the rows and the columns
+        let mut repeated_in_square = SOLVED;
+        repeated_in_square[2] = SOLVED[3];
+        repeated_in_square[3] = SOLVED[2];
+        assert!(!run(&empty, repeated_in_square).0);
+
+        // swapping two cells of a row keeps the rows and the squares
+        let mut repeated_in_column = SOLVED;
+        repeated_in_column[4].swap(0, 1);
+        assert!(!run(&empty, repeated_in_column).0);
+    }
+
+    #[test]
+    fn test_final_state_hides_the_solution() {
+        let empty = Puzzle::new([[0; 9]; 9]).unwrap();
+        let (_, state) = run(&empty, SOLVED);
+        let (_, other) = run(&empty, sample::SOLVED_UNMATCH);
+        assert_eq!(state, other);
+        assert_eq!(state[super::CHECKED], F::ONE);
+    }
+}
@@ -0,0 +1,58 @@
+use std::time::Instant;
+
+use zk_sudoku_core::{sample, SudokuProver};
+
+use crate::{
+    circuit::NUM_STEPS,
+    prover::{fold, setup, NovaProof, NovaProver},
+};
+
+/// Runs Nova over pallas/vesta, printing the cost of the folding apart from
+/// the cost of compressing it.
+pub fn run_nova() {
+    let puzzle = sample::puzzle();
+    let solution = sample::solution();
+
+    let setup_start = Instant::now();
+    let (pk, vk) = setup().unwrap();
+    let setup_time = setup_start.elapsed();
+    let (primary, secondary) = pk.pp.num_constraints();
+    println!(
+        "num constraints per step: {} primary, {} secondary",
+        primary, secondary
+    );
+    println!(
+        "setup time {}ms, {}s",
+        setup_time.as_millis(),
+        setup_time.as_secs()
+    );
+
+    let fold_start = Instant::now();
+    let recursive_snark = fold(&pk.pp, &puzzle, &solution).unwrap();
+    let fold_time = fold_start.elapsed();
+    println!(
+        "folding time of {} steps {}ms, {}s",
+        NUM_STEPS,
+        fold_time.as_millis(),
+        fold_time.as_secs()
+    );
+
+    let compress_start = Instant::now();
+    let proof = NovaProof::prove(&pk.pp, &pk.pk, &recursive_snark).unwrap();
+    let compress_time = compress_start.elapsed();
+    println!(
+        "compression time {}ms, {}s",
+        compress_time.as_millis(),
+        compress_time.as_secs()
+    );
+
+    let verify_start = Instant::now();
+    let valid_proof = NovaProver.verify(&vk, &puzzle, &proof).unwrap();
+    let verify_time = verify_start.elapsed();
+    println!(
+        "verify time {}ms, {}s",
+        verify_time.as_millis(),
+        verify_time.as_secs()
+    );
+    assert!(valid_proof);
+}
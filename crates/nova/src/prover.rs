@@ -0,0 +1,144 @@
+use ff::Field;
+use nova_snark::{
+    errors::NovaError,
+    provider::{ipa_pc::EvaluationEngine, PallasEngine, VestaEngine},
+    spartan::snark::RelaxedR1CSSNARK,
+    traits::{circuit::TrivialCircuit, snark::RelaxedR1CSSNARKTrait, Engine},
+    CompressedSNARK, ProverKey, PublicParams, RecursiveSNARK, VerifierKey,
+};
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{Puzzle, Solution, SudokuProver};
+
+use crate::circuit::{final_state, initial_state, SudokuStep, NUM_STEPS};
+
+/// The steps are folded over pallas, the secondary circuit over vesta.
+pub type E1 = PallasEngine;
+pub type E2 = VestaEngine;
+type C1 = SudokuStep;
+type C2 = TrivialCircuit<<E2 as Engine>::Scalar>;
+// Spartan with the IPA commitment on both curves, so no trusted setup
+type S1 = RelaxedR1CSSNARK<E1, EvaluationEngine<E1>>;
+type S2 = RelaxedR1CSSNARK<E2, EvaluationEngine<E2>>;
+
+pub type NovaPublicParams = PublicParams<E1, E2, C1, C2>;
+pub type NovaProof = CompressedSNARK<E1, E2, C1, C2, S1, S2>;
+pub type NovaVerifyingKey = VerifierKey<E1, E2, C1, C2, S1, S2>;
+
+/// The public parameters fold the steps, the prover key of the compressed
+/// SNARK proves the folded instance.
+pub struct NovaProvingKey {
+    pub pp: NovaPublicParams,
+    pub pk: ProverKey<E1, E2, C1, C2, S1, S2>,
+}
+
+/// Nova over the pallas/vesta cycle, folding one step per band of the
+/// solution and compressing the result with Spartan. The puzzle is the
+/// initial state of the folding, so the verifier binds it as the public input.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NovaProver;
+
+/// Generates the public parameters and the keys of the compressed SNARK.
+pub fn setup() -> Result<(NovaProvingKey, NovaVerifyingKey), NovaError> {
+    let pp = NovaPublicParams::setup(
+        &SudokuStep::default(),
+        &C2::default(),
+        &*S1::ck_floor(),
+        &*S2::ck_floor(),
+    )?;
+    let (pk, vk) = NovaProof::setup(&pp)?;
+    Ok((NovaProvingKey { pp, pk }, vk))
+}
+
+/// Folds the steps proving `solution`, uncompressed.
+pub fn fold(
+    pp: &NovaPublicParams,
+    puzzle: &Puzzle,
+    solution: &Solution,
+) -> Result<RecursiveSNARK<E1, E2, C1, C2>, NovaError> {
+    let steps = SudokuStep::steps(solution);
+    let secondary = C2::default();
+    let mut recursive_snark = RecursiveSNARK::new(
+        pp,
+        &steps[0],
+        &secondary,
+        &initial_state(puzzle),
+        &[<E2 as Engine>::Scalar::ZERO],
+    )?;
+    for step in &steps {
+        recursive_snark.prove_step(pp, step, &secondary)?;
+    }
+    Ok(recursive_snark)
+}
+
+impl SudokuProver for NovaProver {
+    type ProvingKey = NovaProvingKey;
+    type VerifyingKey = NovaVerifyingKey;
+    type Proof = NovaProof;
+    type Error = NovaError;
+
+    // The setup is transparent, no randomness is needed
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        _rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        setup()
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        _rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        let recursive_snark = fold(&pk.pp, puzzle, solution)?;
+        NovaProof::prove(&pk.pp, &pk.pk, &recursive_snark)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        let z0_secondary = [<E2 as Engine>::Scalar::ZERO];
+        match proof.verify(vk, NUM_STEPS, &initial_state(puzzle), &z0_secondary) {
+            Ok((state, _)) => Ok(state == final_state(puzzle)),
+            // Nova has no error of its own for a rejected proof, each check
+            // failing with a different one
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{sample, Puzzle, Solution, SudokuProver};
+
+    use super::NovaProver;
+
+    #[test]
+    fn test_nova_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let prover = NovaProver;
+
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+
+        // every step holds, only the column counts of the last state differ
+        let mut repeated_in_column = *solution.cells();
+        repeated_in_column[4].swap(0, 1);
+        let empty = Puzzle::new([[0; 9]; 9]).unwrap();
+        let solution = Solution::new_unchecked(repeated_in_column);
+        let proof = prover.prove(&pk, &empty, &solution, &mut OsRng).unwrap();
+        assert!(!prover.verify(&vk, &empty, &proof).unwrap());
+    }
+}
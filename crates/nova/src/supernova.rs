@@ -0,0 +1,249 @@
+use std::{sync::Arc, time::Instant};
+
+use arecibo::{
+    provider::{ipa_pc::EvaluationEngine, PallasEngine, VestaEngine},
+    spartan::{batched::BatchedRelaxedR1CSSNARK, snark::RelaxedR1CSSNARK},
+    supernova::{
+        error::SuperNovaError,
+        snark::{CompressedSNARK, ProverKey, VerifierKey},
+        NonUniformCircuit, PublicParams, RecursiveSNARK,
+    },
+    traits::{
+        circuit_supernova::{StepCircuit, TrivialSecondaryCircuit},
+        snark::{BatchedRelaxedR1CSSNARKTrait, RelaxedR1CSSNARKTrait},
+        Engine,
+    },
+};
+use bellpepper_core::{num::AllocatedNum, test_cs::TestConstraintSystem, ConstraintSystem};
+use ff::Field;
+use rand_core::{CryptoRng, RngCore};
+use zk_sudoku_core::{sample, Puzzle, Solution, SudokuProver};
+
+use crate::non_uniform::{
+    final_state, initial_state, SudokuProgram, SudokuStep, COLUMN_CHECK, ROW_STEP, SQUARE_CHECK,
+};
+
+// The steps are folded over pallas, the secondary circuit over vesta
+type E1 = PallasEngine;
+type E2 = VestaEngine;
+type F = <E1 as Engine>::Scalar;
+type C2 = TrivialSecondaryCircuit<<E2 as Engine>::Scalar>;
+// Spartan batched over the three primary circuits, IPA on both curves
+type S1 = BatchedRelaxedR1CSSNARK<E1, EvaluationEngine<E1>>;
+type S2 = RelaxedR1CSSNARK<E2, EvaluationEngine<E2>>;
+
+pub type SuperNovaPublicParams = PublicParams<E1, E2, SudokuStep, C2>;
+pub type SuperNovaProof = CompressedSNARK<E1, E2, SudokuStep, C2, S1, S2>;
+
+/// The public parameters are needed to prove and to verify, both keys share
+/// them.
+pub struct SuperNovaProvingKey {
+    pub pp: Arc<SuperNovaPublicParams>,
+    pub pk: ProverKey<E1, E2, SudokuStep, C2, S1, S2>,
+}
+
+pub struct SuperNovaVerifyingKey {
+    pub pp: Arc<SuperNovaPublicParams>,
+    pub vk: VerifierKey<E1, E2, SudokuStep, C2, S1, S2>,
+}
+
+/// SuperNova over the pallas/vesta cycle: the rows, the squares and the
+/// columns are checked by three circuits, each step folding only the one the
+/// program counter picks. The puzzle is the initial state of the folding, so
+/// the verifier binds it as the public input.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SuperNovaProver;
+
+/// Generates the public parameters and the keys of the compressed SNARK.
+pub fn setup() -> Result<(SuperNovaProvingKey, SuperNovaVerifyingKey), SuperNovaError> {
+    let pp = Arc::new(SuperNovaPublicParams::setup(
+        &SudokuProgram,
+        &*S1::ck_floor(),
+        &*S2::ck_floor(),
+    ));
+    let (pk, vk) = SuperNovaProof::setup(&pp)?;
+    Ok((
+        SuperNovaProvingKey { pp: pp.clone(), pk },
+        SuperNovaVerifyingKey { pp, vk },
+    ))
+}
+
+/// Folds the steps proving `solution`, uncompressed.
+pub fn fold(
+    pp: &SuperNovaPublicParams,
+    puzzle: &Puzzle,
+    solution: &Solution,
+) -> Result<RecursiveSNARK<E1, E2>, SuperNovaError> {
+    let steps = SudokuStep::steps(solution);
+    let secondary = NonUniformCircuit::<E1, E2, _, C2>::secondary_circuit(&SudokuProgram);
+    let mut recursive_snark = RecursiveSNARK::new(
+        pp,
+        &SudokuProgram,
+        &steps[0],
+        &secondary,
+        &initial_state(puzzle),
+        &[<E2 as Engine>::Scalar::ZERO],
+    )?;
+    for step in &steps {
+        recursive_snark.prove_step(pp, step, &secondary)?;
+    }
+    Ok(recursive_snark)
+}
+
+impl SudokuProver for SuperNovaProver {
+    type ProvingKey = SuperNovaProvingKey;
+    type VerifyingKey = SuperNovaVerifyingKey;
+    type Proof = SuperNovaProof;
+    type Error = SuperNovaError;
+
+    // The setup is transparent, no randomness is needed
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        _rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error> {
+        setup()
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        _rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error> {
+        let recursive_snark = fold(&pk.pp, puzzle, solution)?;
+        SuperNovaProof::prove(&pk.pp, &pk.pk, &recursive_snark)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error> {
+        let z0_secondary = [<E2 as Engine>::Scalar::ZERO];
+        match proof.verify(&vk.pp, &vk.vk, &initial_state(puzzle), &z0_secondary) {
+            Ok((state, _)) => Ok(state == final_state(puzzle)),
+            // a failed check of the folding or of Spartan is a rejection
+            Err(_) => Ok(false),
+        }
+    }
+}
+
+// Constraints of each primary circuit alone, without the augmentation
+fn num_constraints(step: &SudokuStep) -> usize {
+    let mut cs = TestConstraintSystem::<F>::new();
+    let z = initial_state::<F>(&sample::puzzle())
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| AllocatedNum::alloc(cs.namespace(|| format!("z0 {}", i)), || Ok(x)))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    let before = cs.num_constraints();
+    step.synthesize(&mut cs, None, &z).unwrap();
+    cs.num_constraints() - before
+}
+
+/// Runs SuperNova over pallas/vesta, printing the cost of each circuit and of
+/// the folding apart from the cost of compressing it.
+pub fn run_supernova() {
+    let puzzle = sample::puzzle();
+    let solution = sample::solution();
+    let steps = SudokuStep::steps(&solution);
+
+    // A uniform folding would pay for the largest circuit on every step
+    let mut folded = 0;
+    let mut largest = 0;
+    for (index, name) in [
+        (ROW_STEP, "row step"),
+        (SQUARE_CHECK, "square check"),
+        (COLUMN_CHECK, "column check"),
+    ] {
+        let circuit = NonUniformCircuit::<E1, E2, _, C2>::primary_circuit(&SudokuProgram, index);
+        let constraints = num_constraints(&circuit);
+        let count = steps
+            .iter()
+            .filter(|step| StepCircuit::<F>::circuit_index(*step) == index)
+            .count();
+        println!(
+            "{}: {} constraints, folded {} times",
+            name, constraints, count
+        );
+        folded += constraints * count;
+        largest = largest.max(constraints);
+    }
+    println!(
+        "constraints folded: {} non-uniform, {} if uniform",
+        folded,
+        largest * steps.len()
+    );
+
+    let setup_start = Instant::now();
+    let (pk, vk) = setup().unwrap();
+    let setup_time = setup_start.elapsed();
+    println!(
+        "setup time {}ms, {}s",
+        setup_time.as_millis(),
+        setup_time.as_secs()
+    );
+
+    let fold_start = Instant::now();
+    let recursive_snark = fold(&pk.pp, &puzzle, &solution).unwrap();
+    let fold_time = fold_start.elapsed();
+    println!(
+        "folding time of {} steps {}ms, {}s",
+        steps.len(),
+        fold_time.as_millis(),
+        fold_time.as_secs()
+    );
+
+    let compress_start = Instant::now();
+    let proof = SuperNovaProof::prove(&pk.pp, &pk.pk, &recursive_snark).unwrap();
+    let compress_time = compress_start.elapsed();
+    println!(
+        "compression time {}ms, {}s",
+        compress_time.as_millis(),
+        compress_time.as_secs()
+    );
+
+    let verify_start = Instant::now();
+    let valid_proof = SuperNovaProver.verify(&vk, &puzzle, &proof).unwrap();
+    let verify_time = verify_start.elapsed();
+    println!(
+        "verify time {}ms, {}s",
+        verify_time.as_millis(),
+        verify_time.as_secs()
+    );
+    assert!(valid_proof);
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{sample, Puzzle, Solution, SudokuProver};
+
+    use super::SuperNovaProver;
+
+    #[test]
+    fn test_supernova_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let prover = SuperNovaProver;
+
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+
+        let mut repeated_in_column = *solution.cells();
+        repeated_in_column[4].swap(0, 1);
+        let empty = Puzzle::new([[0; 9]; 9]).unwrap();
+        let solution = Solution::new_unchecked(repeated_in_column);
+        let proof = prover.prove(&pk, &empty, &solution, &mut OsRng).unwrap();
+        assert!(!prover.verify(&vk, &empty, &proof).unwrap());
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+use anyhow::Result;
+use plonky2::{iop::target::Target, plonk::circuit_data::CircuitData};
+
+use crate::{
+    circuit::{SudokuCircuit, SudokuTargets, C, F},
+    config::CircuitTuning,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grid {
+    Unsolved,
+    Solved,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cell {
+    pub grid: Grid,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let grid = match self.grid {
+            Grid::Unsolved => "unsolved",
+            Grid::Solved => "solved",
+        };
+        write!(f, "{}[{}][{}]", grid, self.row, self.col)
+    }
+}
+
+/// Builds the circuit and returns the cells whose target is never copied into
+/// a gate wire, i.e. cells the prover can set to anything.
+pub fn audit(tuning: &CircuitTuning) -> Result<Vec<Cell>> {
+    let (data, targets) = SudokuCircuit::build_with_tuning(tuning)?;
+    Ok(unconstrained_cells(&data, &targets))
+}
+
+pub(crate) fn unconstrained_cells(
+    data: &CircuitData<F, C, 2>,
+    targets: &SudokuTargets,
+) -> Vec<Cell> {
+    let num_wires = data.common.config.num_wires;
+    let degree = data.common.degree();
+    let representative_map = &data.prover_only.representative_map;
+
+    // Targets are indexed wires first, then virtual targets. A partition of
+    // the copy constraints is constrained by some gate only if one of its
+    // members is a wire.
+    let mut has_wire = vec![false; representative_map.len()];
+    for index in 0..degree * num_wires {
+        has_wire[representative_map[index]] = true;
+    }
+    let is_constrained =
+        |target: Target| has_wire[representative_map[target.index(num_wires, degree)]];
+
+    let mut cells = vec![];
+    for (grid, rows) in [
+        (Grid::Unsolved, &targets.unsolved),
+        (Grid::Solved, &targets.solved),
+    ] {
+        for (row, line) in rows.iter().enumerate() {
+            for (col, target) in line.iter().enumerate() {
+                if !is_constrained(*target) {
+                    cells.push(Cell { grid, row, col });
+                }
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::plonk::{circuit_builder::CircuitBuilder, circuit_data::CircuitConfig};
+
+    use super::{audit, unconstrained_cells, Cell, Grid};
+    use crate::{
+        circuit::{SudokuTargets, C, F},
+        config::CircuitTuning,
+        utils::range_check,
+    };
+
+    #[test]
+    fn test_audit_sudoku_circuit() {
+        let cells = audit(&CircuitTuning::default()).unwrap();
+        assert!(cells.is_empty(), "{:?}", cells);
+    }
+
+    #[test]
+    fn test_audit_skipped_cell() {
+        // Range check every solved cell but solved[4][4]
+        let mut builder =
+            CircuitBuilder::<F, 2>::new(CircuitConfig::standard_recursion_zk_config());
+        let unsolved = [0; 9].map(|_| [0; 9].map(|_| builder.add_virtual_public_input()));
+        let solved = [0; 9].map(|_| [0; 9].map(|_| builder.add_virtual_target()));
+        for i in 0..9 {
+            for j in 0..9 {
+                if (i, j) != (4, 4) {
+                    range_check(&mut builder, solved[i][j]);
+                }
+            }
+        }
+        let data = builder.build::<C>();
+
+        let cells = unconstrained_cells(&data, &SudokuTargets { unsolved, solved });
+        assert_eq!(
+            cells,
+            vec![Cell {
+                grid: Grid::Solved,
+                row: 4,
+                col: 4,
+            }]
+        );
+    }
+}
@@ -1,20 +1,51 @@
 use plonky2::{
     field::{goldilocks_field::GoldilocksField, types::Field},
-    iop::witness::{PartialWitness, Witness},
+    hash::{hash_types::HashOut, poseidon::PoseidonHash},
+    iop::{
+        target::Target,
+        witness::{PartialWitness, Witness},
+    },
     plonk::{
         circuit_builder::CircuitBuilder,
-        circuit_data::{CircuitConfig, CircuitData},
-        config::PoseidonGoldilocksConfig,
+        circuit_data::CircuitData,
+        config::{GenericConfig, Hasher, PoseidonGoldilocksConfig},
         proof::ProofWithPublicInputs,
     },
 };
 
 pub type F = GoldilocksField;
+/// The default config, hashing the Merkle trees and the transcript with
+/// Poseidon. Every proving function is generic over the config, e.g.
+/// `KeccakGoldilocksConfig` commits with Keccak256 instead, which an EVM
+/// verifier computes with its precompile but which is expensive to verify
+/// in another plonky2 circuit. plonky2 has no Poseidon2 config.
 pub type C = PoseidonGoldilocksConfig;
 
 use anyhow::{Ok, Result};
+use zk_sudoku_core::{Puzzle, Solution};
+
+use crate::{
+    config::{BaseConfig, CircuitTuning},
+    utils::{range_check, range_check_between},
+};
 
-use crate::utils::range_check;
+/// Poseidon hash of the unsolved sudoku, whose 4 elements are the public
+/// inputs of the circuit.
+pub fn unsolved_hash(unsolved: &[[u64; 9]; 9]) -> HashOut<F> {
+    let cells = unsolved
+        .iter()
+        .flatten()
+        .map(|cell| F::from_canonical_u64(*cell))
+        .collect::<Vec<_>>();
+    PoseidonHash::hash_no_pad(&cells)
+}
+
+/// The targets of the cells of both grids, set by `prove` for every proof
+/// with the same circuit.
+pub struct SudokuTargets {
+    pub unsolved: [[Target; 9]; 9],
+    pub solved: [[Target; 9]; 9],
+}
 
 pub struct SudokuCircuit {
     pub unsolved: [[u64; 9]; 9],
@@ -22,12 +53,93 @@ pub struct SudokuCircuit {
 }
 
 impl SudokuCircuit {
+    pub fn new(puzzle: &Puzzle, solution: &Solution) -> Self {
+        Self {
+            unsolved: puzzle.map(u64::from),
+            solved: solution.map(u64::from),
+        }
+    }
+
+    /// Builds the circuit and proves with it, see `build` and `prove` to
+    /// build it once for many proofs.
     pub fn synthesize(&self) -> Result<(ProofWithPublicInputs<F, C, 2>, CircuitData<F, C, 2>)> {
-        let config = CircuitConfig::standard_recursion_zk_config();
+        self.synthesize_with_tuning(&CircuitTuning::default())
+    }
+
+    pub fn synthesize_with_tuning(
+        &self,
+        tuning: &CircuitTuning,
+    ) -> Result<(ProofWithPublicInputs<F, C, 2>, CircuitData<F, C, 2>)> {
+        let (circuit, targets) = Self::build_with_tuning(tuning)?;
+        let proof = self.prove(&circuit, &targets)?;
+        Ok((proof, circuit))
+    }
+
+    /// The witness setting the targets of both grids.
+    pub fn witness(&self, targets: &SudokuTargets) -> PartialWitness<F> {
+        let mut pw = PartialWitness::new();
+        let grids = [
+            (&targets.unsolved, &self.unsolved),
+            (&targets.solved, &self.solved),
+        ];
+        for (targets, cells) in grids {
+            for (target, cell) in targets.iter().flatten().zip(cells.iter().flatten()) {
+                pw.set_target(*target, F::from_canonical_u64(*cell));
+            }
+        }
+        pw
+    }
+
+    /// Proves with a circuit built by `build`.
+    pub fn prove<Cfg: GenericConfig<2, F = F>>(
+        &self,
+        circuit: &CircuitData<F, Cfg, 2>,
+        targets: &SudokuTargets,
+    ) -> Result<ProofWithPublicInputs<F, Cfg, 2>> {
+        circuit.prove(self.witness(targets))
+    }
+
+    /// Builds the circuit with the default tuning, see `build_with_tuning`.
+    pub fn build() -> Result<(CircuitData<F, C, 2>, SudokuTargets)> {
+        Self::build_with_tuning(&CircuitTuning::default())
+    }
+
+    /// Builds the circuit with the tuning applied to the zero-knowledge
+    /// config, see `build_with_config`.
+    pub fn build_with_tuning(
+        tuning: &CircuitTuning,
+    ) -> Result<(CircuitData<F, C, 2>, SudokuTargets)> {
+        Self::build_with_config(&BaseConfig::default(), tuning)
+    }
+
+    /// Builds the circuit without any witness, returning the targets of both
+    /// grids along with it. The in-circuit hash of the puzzle is Poseidon
+    /// whatever `Cfg` hashes the proof with.
+    pub fn build_with_config<Cfg: GenericConfig<2, F = F>>(
+        base: &BaseConfig,
+        tuning: &CircuitTuning,
+    ) -> Result<(CircuitData<F, Cfg, 2>, SudokuTargets)> {
+        let (builder, targets) = Self::builder(base, tuning)?;
+        Ok((builder.build(), targets))
+    }
+
+    /// Adds every rule of the circuit to a builder, which is returned before
+    /// building, e.g. to count its gates.
+    pub fn builder(
+        base: &BaseConfig,
+        tuning: &CircuitTuning,
+    ) -> Result<(CircuitBuilder<F, 2>, SudokuTargets)> {
+        let config = tuning.apply(base.config())?;
         let mut builder = CircuitBuilder::<F, 2>::new(config);
-        let unsolved_targets = [0; 9].map(|_| [0; 9].map(|_| builder.add_virtual_public_input()));
+        let unsolved_targets = [0; 9].map(|_| [0; 9].map(|_| builder.add_virtual_target()));
         let solved_targets = [0; 9].map(|_| [0; 9].map(|_| builder.add_virtual_target()));
 
+        // expose the hash of the unsolved sudoku rather than its 81 cells
+        let digest = builder.hash_n_to_hash_no_pad::<PoseidonHash>(
+            unsolved_targets.iter().flatten().copied().collect(),
+        );
+        builder.register_public_inputs(&digest.elements);
+
         // Check if the numbers of the solved sudoku are >=1 and <=9
         // Each number in the solved sudoku is checked to see if it is >=1 and <=9
         for i in 0..9 {
@@ -36,6 +148,14 @@ impl SudokuCircuit {
             }
         }
 
+        // Check if the numbers of the unsolved sudoku are >=0 and <=9, without
+        // relying on the givens check below
+        for i in 0..9 {
+            for j in 0..9 {
+                range_check_between(&mut builder, unsolved_targets[i][j], 0, 9);
+            }
+        }
+
         let zero_target = builder.zero();
         let one_target = builder.one();
         // Check if unsolved is the initial state of solved
@@ -76,13 +196,14 @@ impl SudokuCircuit {
 
         // Check if each square in solved has all the numbers from 1 to 9, both included
         // For each square and for each element in each square, check that the
-        // element is not equal to previous elements in the same square
+        // element is not equal to the elements of the previous rows of the same
+        // square, except the one in its own column (rows and columns are covered above)
         for i in [0, 3, 6] {
             for j in [0, 3, 6] {
                 for k in i..i + 3 {
                     for l in j..j + 3 {
-                        for m in i..=k {
-                            for n in j..l {
+                        for m in i..k {
+                            for n in (j..j + 3).filter(|n| *n != l) {
                                 let is_equal =
                                     builder.is_equal(solved_targets[m][n], solved_targets[k][l]);
                                 builder.assert_zero(is_equal.target);
@@ -93,59 +214,173 @@ impl SudokuCircuit {
             }
         }
 
-        // build circuit
-        let circuit = builder.build();
+        Ok((
+            builder,
+            SudokuTargets {
+                unsolved: unsolved_targets,
+                solved: solved_targets,
+            },
+        ))
+    }
+}
 
-        let mut pw = PartialWitness::new();
+/// Proves that `solution` solves `puzzle` with a circuit built once by
+/// `SudokuCircuit::build`.
+pub fn prove<Cfg: GenericConfig<2, F = F>>(
+    data: &CircuitData<F, Cfg, 2>,
+    targets: &SudokuTargets,
+    puzzle: &Puzzle,
+    solution: &Solution,
+) -> Result<ProofWithPublicInputs<F, Cfg, 2>> {
+    SudokuCircuit::new(puzzle, solution).prove(data, targets)
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{
+        field::types::Field, iop::witness::Witness, plonk::config::KeccakGoldilocksConfig,
+    };
+    use zk_sudoku_core::{
+        sample::{self, widen, SOLVED, UNSOLVED},
+        Puzzle,
+    };
+
+    use std::time::Instant;
+
+    use super::{prove, unsolved_hash, SudokuCircuit, C, F};
+    use crate::config::{BaseConfig, CircuitTuning};
+
+    #[test]
+    fn test_circuit() {
+        let circuit = SudokuCircuit {
+            unsolved: widen(UNSOLVED),
+            solved: widen(SOLVED),
+        };
+
+        let (proof, data) = circuit.synthesize().unwrap();
+        data.verify(proof).unwrap();
+
+        for tuning in [CircuitTuning::small_proof(), CircuitTuning::fast_prove()] {
+            let (proof, data) = circuit.synthesize_with_tuning(&tuning).unwrap();
+            data.verify(proof).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_witness() {
+        let mut circuit = SudokuCircuit {
+            unsolved: [[0; 9]; 9],
+            solved: [[0; 9]; 9],
+        };
+        for i in 0..9 {
+            for j in 0..9 {
+                circuit.unsolved[i][j] = (i * 9 + j) as u64;
+                circuit.solved[i][j] = (81 + i * 9 + j) as u64;
+            }
+        }
+        let (_, targets) = SudokuCircuit::build().unwrap();
+
+        let pw = circuit.witness(&targets);
         for i in 0..9 {
             for j in 0..9 {
-                pw.set_target(
-                    unsolved_targets[i][j],
-                    F::from_canonical_u64(self.unsolved[i][j]),
+                assert_eq!(
+                    pw.try_get_target(targets.unsolved[i][j]),
+                    Some(F::from_canonical_u64(circuit.unsolved[i][j]))
                 );
-                pw.set_target(
-                    solved_targets[i][j],
-                    F::from_canonical_u64(self.solved[i][j]),
+                assert_eq!(
+                    pw.try_get_target(targets.solved[i][j]),
+                    Some(F::from_canonical_u64(circuit.solved[i][j]))
                 );
             }
         }
-        let proof = circuit.prove(pw).unwrap();
-        Ok((proof, circuit))
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::SudokuCircuit;
+    #[test]
+    fn test_build_once() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+
+        // one circuit for both puzzles
+        let (data, targets) = SudokuCircuit::build().unwrap();
+        for puzzle in [puzzle, other] {
+            let proof = prove(&data, &targets, &puzzle, &solution).unwrap();
+            let digest = unsolved_hash(&puzzle.map(u64::from));
+            assert_eq!(proof.public_inputs, digest.elements);
+            data.verify(proof).unwrap();
+        }
+    }
 
     #[test]
-    fn test_circuit() {
+    fn test_base_configs() {
         let circuit = SudokuCircuit {
-            unsolved: [
-                [0, 0, 0, 0, 0, 6, 0, 0, 0],
-                [0, 0, 7, 2, 0, 0, 8, 0, 0],
-                [9, 0, 6, 8, 0, 0, 0, 1, 0],
-                [3, 0, 0, 7, 0, 0, 0, 2, 9],
-                [0, 0, 0, 0, 0, 0, 0, 0, 0],
-                [4, 0, 0, 5, 0, 0, 0, 7, 0],
-                [6, 5, 0, 1, 0, 0, 0, 0, 0],
-                [8, 0, 1, 0, 5, 0, 3, 0, 0],
-                [7, 9, 2, 0, 0, 0, 0, 0, 4],
-            ],
-            solved: [
-                [1, 8, 4, 3, 7, 6, 2, 9, 5],
-                [5, 3, 7, 2, 9, 1, 8, 4, 6],
-                [9, 2, 6, 8, 4, 5, 7, 1, 3],
-                [3, 6, 5, 7, 1, 8, 4, 2, 9],
-                [2, 7, 8, 4, 6, 9, 5, 3, 1],
-                [4, 1, 9, 5, 3, 2, 6, 7, 8],
-                [6, 5, 3, 1, 2, 4, 9, 8, 7],
-                [8, 4, 1, 9, 5, 7, 3, 6, 2],
-                [7, 9, 2, 6, 8, 3, 1, 5, 4],
-            ],
+            unsolved: widen(UNSOLVED),
+            solved: widen(SOLVED),
         };
 
-        let (proof, data) = circuit.synthesize().unwrap();
-        data.verify(proof).unwrap();
+        for base in [BaseConfig::StandardZk, BaseConfig::Standard] {
+            let (data, targets) =
+                SudokuCircuit::build_with_config::<C>(&base, &CircuitTuning::default()).unwrap();
+            assert_eq!(
+                data.common.config.zero_knowledge,
+                base == BaseConfig::StandardZk
+            );
+
+            let prove_start = Instant::now();
+            let proof = circuit.prove(&data, &targets).unwrap();
+            let prove_time = prove_start.elapsed();
+            println!(
+                "{:?}: degree {}, prove time {}ms",
+                base,
+                data.common.degree(),
+                prove_time.as_millis()
+            );
+            data.verify(proof).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_hash_configs() {
+        let circuit = SudokuCircuit {
+            unsolved: widen(UNSOLVED),
+            solved: widen(SOLVED),
+        };
+        let base = BaseConfig::default();
+        let tuning = CircuitTuning::default();
+
+        let (poseidon, targets) = SudokuCircuit::build_with_config::<C>(&base, &tuning).unwrap();
+        let prove_start = Instant::now();
+        let poseidon_proof = circuit.prove(&poseidon, &targets).unwrap();
+        let poseidon_time = prove_start.elapsed();
+
+        let (keccak, targets) =
+            SudokuCircuit::build_with_config::<KeccakGoldilocksConfig>(&base, &tuning).unwrap();
+        let prove_start = Instant::now();
+        let keccak_proof = circuit.prove(&keccak, &targets).unwrap();
+        let keccak_time = prove_start.elapsed();
+
+        println!(
+            "poseidon: {} bytes in {}ms, keccak: {} bytes in {}ms",
+            poseidon_proof.to_bytes().len(),
+            poseidon_time.as_millis(),
+            keccak_proof.to_bytes().len(),
+            keccak_time.as_millis()
+        );
+        // the hash only changes how the trees are committed, the soundness
+        // comes from the same FRI parameters either way
+        assert_eq!(
+            poseidon.common.config.security_bits,
+            keccak.common.config.security_bits
+        );
+        // a Keccak digest is truncated to 25 bytes, against the 4 field
+        // elements of a Poseidon digest, so the Merkle paths are shorter
+        assert!(keccak_proof.to_bytes().len() < poseidon_proof.to_bytes().len());
+        // the public inputs are the Poseidon hash of the puzzle with both
+        assert_eq!(keccak_proof.public_inputs, poseidon_proof.public_inputs);
+
+        poseidon.verify(poseidon_proof).unwrap();
+        keccak.verify(keccak_proof).unwrap();
     }
 }
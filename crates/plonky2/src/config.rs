@@ -0,0 +1,182 @@
+use anyhow::{ensure, Result};
+use plonky2::{
+    fri::reduction_strategies::FriReductionStrategy, plonk::circuit_data::CircuitConfig,
+};
+
+/// The Poseidon gate that hashes the public inputs routes its 12 inputs,
+/// 12 outputs and the swap flag, so fewer routed wires cannot fit it.
+pub const MIN_ROUTED_WIRES: usize = 25;
+
+/// Upper bound on the Merkle cap height, kept well below the height of the
+/// smallest tree committed for the sudoku circuit.
+pub const MAX_CAP_HEIGHT: usize = 8;
+
+/// The config the tuning is applied to, deciding whether the witness is
+/// blinded and which gates the builder may use.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum BaseConfig {
+    /// `CircuitConfig::standard_recursion_zk_config`, hiding the solution.
+    #[default]
+    StandardZk,
+    /// `CircuitConfig::standard_recursion_config`, which skips the blinding
+    /// to prove faster. The proof may then leak the solution.
+    Standard,
+    /// Any other config, e.g. without `use_base_arithmetic_gate` or with
+    /// other numbers of wires and constants.
+    Custom(CircuitConfig),
+}
+
+impl BaseConfig {
+    pub fn config(&self) -> CircuitConfig {
+        match self {
+            Self::StandardZk => CircuitConfig::standard_recursion_zk_config(),
+            Self::Standard => CircuitConfig::standard_recursion_config(),
+            Self::Custom(config) => config.clone(),
+        }
+    }
+}
+
+/// Advanced knobs of the plonky2 config that trade proof size against
+/// prove time. Everything else is taken from the base `CircuitConfig`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitTuning {
+    pub num_routed_wires: usize,
+    pub reduction_strategy: FriReductionStrategy,
+    pub cap_height: usize,
+}
+
+impl Default for CircuitTuning {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl CircuitTuning {
+    /// Same values as `CircuitConfig::standard_recursion_zk_config`.
+    pub fn standard() -> Self {
+        Self {
+            num_routed_wires: 80,
+            reduction_strategy: FriReductionStrategy::ConstantArityBits(4, 5),
+            cap_height: 4,
+        }
+    }
+
+    /// Lets plonky2 search for the arities giving the smallest proof, and
+    /// uses a lower cap so fewer hashes are sent.
+    pub fn small_proof() -> Self {
+        Self {
+            num_routed_wires: 80,
+            reduction_strategy: FriReductionStrategy::MinSize(None),
+            cap_height: 2,
+        }
+    }
+
+    /// Small arity steps are cheaper to commit to, at the cost of more FRI
+    /// rounds in the proof.
+    pub fn fast_prove() -> Self {
+        Self {
+            num_routed_wires: 80,
+            reduction_strategy: FriReductionStrategy::ConstantArityBits(2, 5),
+            cap_height: 4,
+        }
+    }
+
+    pub fn validate(&self, base: &CircuitConfig) -> Result<()> {
+        ensure!(
+            self.num_routed_wires >= MIN_ROUTED_WIRES,
+            "num_routed_wires must be at least {}, got {}",
+            MIN_ROUTED_WIRES,
+            self.num_routed_wires
+        );
+        ensure!(
+            self.num_routed_wires <= base.num_wires,
+            "num_routed_wires ({}) exceeds num_wires ({})",
+            self.num_routed_wires,
+            base.num_wires
+        );
+        ensure!(
+            self.cap_height <= MAX_CAP_HEIGHT,
+            "cap_height must be at most {}, got {}",
+            MAX_CAP_HEIGHT,
+            self.cap_height
+        );
+        match &self.reduction_strategy {
+            FriReductionStrategy::Fixed(arities) => ensure!(
+                arities.iter().all(|&bits| bits > 0),
+                "fixed FRI arities must be non-zero"
+            ),
+            FriReductionStrategy::ConstantArityBits(arity_bits, _) => {
+                ensure!(*arity_bits > 0, "FRI arity bits must be non-zero")
+            }
+            FriReductionStrategy::MinSize(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Validates the tuning and applies it on top of `base`.
+    pub fn apply(&self, mut base: CircuitConfig) -> Result<CircuitConfig> {
+        self.validate(&base)?;
+        base.num_routed_wires = self.num_routed_wires;
+        base.fri_config.reduction_strategy = self.reduction_strategy.clone();
+        base.fri_config.cap_height = self.cap_height;
+        Ok(base)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{
+        fri::reduction_strategies::FriReductionStrategy, plonk::circuit_data::CircuitConfig,
+    };
+
+    use super::{BaseConfig, CircuitTuning};
+
+    #[test]
+    fn test_presets_are_valid() {
+        let base = CircuitConfig::standard_recursion_zk_config();
+        for tuning in [
+            CircuitTuning::standard(),
+            CircuitTuning::small_proof(),
+            CircuitTuning::fast_prove(),
+        ] {
+            tuning.validate(&base).unwrap();
+        }
+
+        let standard = CircuitTuning::standard().apply(base.clone()).unwrap();
+        assert_eq!(standard.num_routed_wires, base.num_routed_wires);
+        assert_eq!(standard.fri_config.cap_height, base.fri_config.cap_height);
+    }
+
+    #[test]
+    fn test_invalid_tuning() {
+        let base = CircuitConfig::standard_recursion_zk_config();
+
+        let mut tuning = CircuitTuning::standard();
+        tuning.num_routed_wires = 4;
+        assert!(tuning.validate(&base).is_err());
+
+        tuning.num_routed_wires = base.num_wires + 1;
+        assert!(tuning.validate(&base).is_err());
+
+        let mut tuning = CircuitTuning::standard();
+        tuning.cap_height = 16;
+        assert!(tuning.validate(&base).is_err());
+
+        let mut tuning = CircuitTuning::standard();
+        tuning.reduction_strategy = FriReductionStrategy::ConstantArityBits(0, 5);
+        assert!(tuning.validate(&base).is_err());
+    }
+
+    #[test]
+    fn test_base_configs() {
+        assert!(BaseConfig::default().config().zero_knowledge);
+        assert!(!BaseConfig::Standard.config().zero_knowledge);
+
+        // the tuning needs as many wires as it routes
+        let mut narrow = CircuitConfig::standard_recursion_config();
+        narrow.num_wires = 64;
+        assert!(CircuitTuning::standard()
+            .apply(BaseConfig::Custom(narrow).config())
+            .is_err());
+    }
+}
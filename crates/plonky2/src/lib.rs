@@ -1,2 +1,6 @@
 pub mod utils;
-pub mod circuit;
\ No newline at end of file
+pub mod circuit;
+pub mod config;
+pub mod audit;
+pub mod prover;
+pub mod report;
\ No newline at end of file
@@ -0,0 +1,141 @@
+use std::{fmt, marker::PhantomData};
+
+use plonky2::plonk::{
+    circuit_data::CircuitData, config::GenericConfig, proof::ProofWithPublicInputs,
+};
+use zk_sudoku_core::{
+    rand_core::{CryptoRng, RngCore},
+    Puzzle, Solution, SudokuProver,
+};
+
+use crate::{
+    circuit::{prove, unsolved_hash, SudokuCircuit, SudokuTargets, C, F},
+    config::{BaseConfig, CircuitTuning},
+};
+
+/// The error of plonky2 building or proving the circuit, which plonky2
+/// reports as an `anyhow::Error`.
+#[derive(Debug)]
+pub struct Plonky2Error(pub anyhow::Error);
+
+impl fmt::Display for Plonky2Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Plonky2Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.source()
+    }
+}
+
+impl From<anyhow::Error> for Plonky2Error {
+    fn from(err: anyhow::Error) -> Self {
+        Plonky2Error(err)
+    }
+}
+
+/// plonky2 over Goldilocks, the Poseidon hash of the puzzle being the public
+/// inputs. Proving is deterministic, the rng is not used. `Cfg` hashes the
+/// proof, see `C` for the alternatives.
+#[derive(Debug, Clone)]
+pub struct Plonky2Prover<Cfg = C> {
+    pub base: BaseConfig,
+    pub tuning: CircuitTuning,
+    _config: PhantomData<Cfg>,
+}
+
+impl<Cfg> Plonky2Prover<Cfg> {
+    pub fn new(base: BaseConfig, tuning: CircuitTuning) -> Self {
+        Self {
+            base,
+            tuning,
+            _config: PhantomData,
+        }
+    }
+}
+
+impl<Cfg> Default for Plonky2Prover<Cfg> {
+    fn default() -> Self {
+        Self::new(BaseConfig::default(), CircuitTuning::default())
+    }
+}
+
+pub struct Plonky2ProvingKey<Cfg: GenericConfig<2, F = F> = C> {
+    data: CircuitData<F, Cfg, 2>,
+    targets: SudokuTargets,
+}
+
+impl<Cfg: GenericConfig<2, F = F>> SudokuProver for Plonky2Prover<Cfg> {
+    type ProvingKey = Plonky2ProvingKey<Cfg>;
+    type VerifyingKey = CircuitData<F, Cfg, 2>;
+    type Proof = ProofWithPublicInputs<F, Cfg, 2>;
+    type Error = Plonky2Error;
+
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        _rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Plonky2Error> {
+        // Building is deterministic, so the verifier gets its own copy of the
+        // same circuit
+        let (data, targets) = SudokuCircuit::build_with_config(&self.base, &self.tuning)?;
+        let (verifier_data, _) = SudokuCircuit::build_with_config(&self.base, &self.tuning)?;
+        Ok((Plonky2ProvingKey { data, targets }, verifier_data))
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        _rng: &mut R,
+    ) -> Result<Self::Proof, Plonky2Error> {
+        Ok(prove(&pk.data, &pk.targets, puzzle, solution)?)
+    }
+
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Plonky2Error> {
+        let digest = unsolved_hash(&puzzle.map(u64::from));
+        if proof.public_inputs != digest.elements {
+            return Ok(false);
+        }
+        Ok(vk.verify(proof.clone()).is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::plonk::config::KeccakGoldilocksConfig;
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{sample, Puzzle, SudokuProver};
+
+    use super::Plonky2Prover;
+    use crate::circuit::C;
+
+    #[test]
+    fn test_plonky2_prover() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let prover = Plonky2Prover::<C>::default();
+
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+
+        let prover = Plonky2Prover::<KeccakGoldilocksConfig>::default();
+        let (pk, vk) = prover.setup(&mut OsRng).unwrap();
+        let proof = prover.prove(&pk, &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&vk, &puzzle, &proof).unwrap());
+        assert!(!prover.verify(&vk, &other, &proof).unwrap());
+    }
+}
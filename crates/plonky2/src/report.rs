@@ -0,0 +1,146 @@
+//! Timings and sizes of the plonky2 prover on the sample sudoku, as the
+//! reports of the other backends.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use plonky2::plonk::config::{GenericConfig, KeccakGoldilocksConfig};
+use serde_json::json;
+use zk_sudoku_core::{
+    report::{print_table, Report},
+    sample,
+};
+
+use crate::{
+    circuit::{SudokuCircuit, C, F},
+    config::{BaseConfig, CircuitTuning},
+};
+
+const UNSOLVED: [[u64; 9]; 9] = sample::widen(sample::UNSOLVED);
+const SOLVED: [[u64; 9]; 9] = sample::widen(sample::SOLVED);
+
+/// Builds the circuit, proves the sample sudoku and verifies the proof,
+/// timing each step. The gates are reported as the constraints and the
+/// build as the setup. A rejected proof is an error of `verify`.
+pub fn run(config: &'static str, base: &BaseConfig, tuning: &CircuitTuning) -> Result<Report> {
+    run_with::<C>(config, base, tuning)
+}
+
+/// `run` with the proof hashed by `Cfg`.
+pub fn run_with<Cfg: GenericConfig<2, F = F>>(
+    config: &'static str,
+    base: &BaseConfig,
+    tuning: &CircuitTuning,
+) -> Result<Report> {
+    let build_start = Instant::now();
+    let (builder, targets) = SudokuCircuit::builder(base, tuning)?;
+    let num_gates = builder.num_gates();
+    let data = builder.build::<Cfg>();
+    let build_time = build_start.elapsed();
+
+    let circuit = SudokuCircuit {
+        unsolved: UNSOLVED,
+        solved: SOLVED,
+    };
+    let prove_start = Instant::now();
+    let pw = circuit.witness(&targets);
+    let witness_time = prove_start.elapsed();
+    let proof = data.prove(pw)?;
+    let prove_time = prove_start.elapsed();
+    let proof_len = proof.to_bytes().len();
+
+    let verify_start = Instant::now();
+    data.verify(proof)?;
+    let verify_time = verify_start.elapsed();
+
+    let gate_types = data
+        .common
+        .gates
+        .iter()
+        .map(|gate| gate.0.id())
+        .collect::<Vec<_>>();
+    Ok(Report {
+        backend: "plonky2",
+        curve: "goldilocks",
+        config: Some(config),
+        num_constraints: num_gates,
+        setup_time: build_time,
+        prove_time,
+        verify_time,
+        proof_len,
+        extra: vec![
+            // the gates padded to a power of two
+            ("rows", json!(data.common.degree())),
+            ("gate_types", json!(gate_types)),
+            // setting the targets of both grids, included in prove_ms
+            ("witness_ms", json!(witness_time.as_millis() as u64)),
+        ],
+    })
+}
+
+/// Runs the zero-knowledge config with each tuning preset, the config
+/// without blinding, and the default one hashed with Keccak256.
+pub fn test_plonky2() -> Vec<Report> {
+    let runs = [
+        (
+            "standard_zk",
+            BaseConfig::StandardZk,
+            CircuitTuning::standard(),
+        ),
+        (
+            "small_proof",
+            BaseConfig::StandardZk,
+            CircuitTuning::small_proof(),
+        ),
+        (
+            "fast_prove",
+            BaseConfig::StandardZk,
+            CircuitTuning::fast_prove(),
+        ),
+        ("standard", BaseConfig::Standard, CircuitTuning::standard()),
+    ];
+    let mut reports = runs
+        .iter()
+        .map(|(config, base, tuning)| run(config, base, tuning).unwrap())
+        .collect::<Vec<_>>();
+    reports.push(
+        run_with::<KeccakGoldilocksConfig>(
+            "keccak",
+            &BaseConfig::StandardZk,
+            &CircuitTuning::standard(),
+        )
+        .unwrap(),
+    );
+    print_table(&reports);
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::run;
+    use crate::config::{BaseConfig, CircuitTuning};
+
+    #[test]
+    fn test_report() {
+        let report = run(
+            "standard_zk",
+            &BaseConfig::StandardZk,
+            &CircuitTuning::standard(),
+        )
+        .unwrap();
+        println!("{}", report.to_json());
+        let rows = report.extra("rows").and_then(Value::as_u64).unwrap();
+        assert!(report.num_constraints as u64 <= rows);
+        assert!(report.proof_len > 0);
+        let gate_types = report
+            .extra("gate_types")
+            .and_then(Value::as_array)
+            .unwrap();
+        assert!(gate_types
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|gate| gate.starts_with("PoseidonGate")));
+    }
+}
@@ -0,0 +1,217 @@
+use p3_air::{Air, AirBuilder, AirBuilderWithPublicValues, BaseAir};
+use p3_field::{AbstractField, Field};
+use p3_matrix::{dense::RowMajorMatrix, Matrix};
+use zk_sudoku_core::{Puzzle, Solution};
+
+/// The trace has one row per row of the sudoku, padded to a power of two.
+pub const HEIGHT: usize = 16;
+
+// The columns of a row: which row of the sudoku it is as one boolean per
+// row, all unset on the padding, the digit of each cell as one boolean per
+// digit, then the digits counted so far in each column and in each square of
+// the current band.
+const ROW: usize = 0;
+const CELLS: usize = ROW + 9;
+const COLUMNS: usize = CELLS + 81;
+const SQUARES: usize = COLUMNS + 81;
+/// Number of columns of the trace.
+pub const WIDTH: usize = SQUARES + 27;
+
+fn cell(j: usize, digit: usize) -> usize {
+    CELLS + 9 * j + digit - 1
+}
+
+fn column(j: usize, digit: usize) -> usize {
+    COLUMNS + 9 * j + digit - 1
+}
+
+fn square(s: usize, digit: usize) -> usize {
+    SQUARES + 9 * s + digit - 1
+}
+
+fn sum<AB: AirBuilder>(row: &[AB::Var], cols: impl IntoIterator<Item = usize>) -> AB::Expr {
+    cols.into_iter().map(|i| row[i].into()).sum()
+}
+
+/// The sudoku AIR, the 81 cells of the puzzle being its public values.
+///
+/// Each row checks the cells of one row of the solution and its givens.
+/// Digits are counted from row to row in each column and, within a band, in
+/// each square, the counts being checked on the last row of the sudoku and
+/// of each band respectively.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SudokuAir;
+
+impl<F> BaseAir<F> for SudokuAir {
+    fn width(&self) -> usize {
+        WIDTH
+    }
+}
+
+impl<AB: AirBuilderWithPublicValues> Air<AB> for SudokuAir {
+    fn eval(&self, builder: &mut AB) {
+        let main = builder.main();
+        let local = main.row_slice(0);
+        let next = main.row_slice(1);
+        let puzzle = builder
+            .public_values()
+            .iter()
+            .map(|&x| x.into())
+            .collect::<Vec<AB::Expr>>();
+
+        let square_sum = |row: &[AB::Var], s: usize, digit: usize| {
+            sum::<AB>(row, (3 * s..3 * s + 3).map(|j| cell(j, digit)))
+        };
+        let active = sum::<AB>(&local, ROW..ROW + 9);
+
+        // The first row is row 0 of the sudoku, each next row the next one,
+        // until none is set on the padding
+        builder.when_first_row().assert_one(local[ROW]);
+        for k in 1..9 {
+            builder.when_first_row().assert_zero(local[ROW + k]);
+            builder
+                .when_transition()
+                .assert_eq(next[ROW + k], local[ROW + k - 1]);
+        }
+        builder.when_transition().assert_zero(next[ROW]);
+
+        // Check if the numbers of the row are >=1 and <=9: exactly one digit
+        // of each cell is set
+        for j in 0..9 {
+            for digit in 1..=9 {
+                builder.assert_bool(local[cell(j, digit)]);
+            }
+            let digits = sum::<AB>(&local, (1..=9).map(|digit| cell(j, digit)));
+            builder.when(active.clone()).assert_one(digits);
+        }
+
+        // Check if the row keeps the givens of the puzzle
+        // given * (solved - given) = 0, so if the given is not zero, the
+        // solved cell is equal to it
+        for j in 0..9 {
+            let given = (0..9)
+                .map(|k| puzzle[9 * k + j].clone() * local[ROW + k])
+                .sum::<AB::Expr>();
+            let solved = (1..=9)
+                .map(|digit| local[cell(j, digit)] * AB::F::from_canonical_usize(digit))
+                .sum::<AB::Expr>();
+            builder.assert_zero(given.clone() * (solved - given));
+        }
+
+        // Check if the row has all the numbers from 1 to 9
+        for digit in 1..=9 {
+            let count = sum::<AB>(&local, (0..9).map(|j| cell(j, digit)));
+            builder.when(active.clone()).assert_one(count);
+        }
+
+        // Check if each column has all the numbers from 1 to 9 once all the
+        // rows are counted
+        for j in 0..9 {
+            for digit in 1..=9 {
+                builder
+                    .when_first_row()
+                    .assert_eq(local[column(j, digit)], local[cell(j, digit)]);
+                builder.when_transition().assert_eq(
+                    next[column(j, digit)],
+                    local[column(j, digit)] + next[cell(j, digit)],
+                );
+                builder
+                    .when(local[ROW + 8])
+                    .assert_one(local[column(j, digit)]);
+            }
+        }
+
+        // Check if each square has all the numbers from 1 to 9 once the rows
+        // of its band are counted, the counts starting over with each band
+        let band_start = sum::<AB>(&next, [ROW, ROW + 3, ROW + 6]);
+        let band_end = sum::<AB>(&local, [ROW + 2, ROW + 5, ROW + 8]);
+        for s in 0..3 {
+            for digit in 1..=9 {
+                builder
+                    .when_first_row()
+                    .assert_eq(local[square(s, digit)], square_sum(&local, s, digit));
+                builder.when_transition().assert_eq(
+                    next[square(s, digit)],
+                    square_sum(&next, s, digit)
+                        + (AB::Expr::one() - band_start.clone()) * local[square(s, digit)],
+                );
+                builder
+                    .when(band_end.clone())
+                    .assert_one(local[square(s, digit)]);
+            }
+        }
+    }
+}
+
+/// The puzzle as the public values of the AIR.
+pub fn public_values<F: Field>(puzzle: &Puzzle) -> Vec<F> {
+    puzzle.iter().map(F::from_canonical_u8).collect()
+}
+
+/// Fills the trace of `solution`. Cells out of range set no digit, which the
+/// AIR rejects.
+pub fn generate_trace<F: Field>(solution: &Solution) -> RowMajorMatrix<F> {
+    let solved = solution.cells();
+    let mut values = vec![F::zero(); HEIGHT * WIDTH];
+    let mut columns = [[0u8; 9]; 9];
+    let mut squares = [[0u8; 9]; 3];
+    for (i, row) in values.chunks_mut(WIDTH).enumerate() {
+        if i < 9 {
+            if i % 3 == 0 {
+                squares = [[0; 9]; 3];
+            }
+            row[ROW + i] = F::one();
+            for (j, &digit) in solved[i].iter().enumerate() {
+                let digit = digit as usize;
+                if (1..=9).contains(&digit) {
+                    row[cell(j, digit)] = F::one();
+                    columns[j][digit - 1] += 1;
+                    squares[j / 3][digit - 1] += 1;
+                }
+            }
+        }
+        // the padding keeps the last counts
+        for (j, counts) in columns.iter().enumerate() {
+            for (digit, count) in (1..).zip(counts) {
+                row[column(j, digit)] = F::from_canonical_u8(*count);
+            }
+        }
+        for (s, counts) in squares.iter().enumerate() {
+            for (digit, count) in (1..).zip(counts) {
+                row[square(s, digit)] = F::from_canonical_u8(*count);
+            }
+        }
+    }
+    RowMajorMatrix::new(values, WIDTH)
+}
+
+#[cfg(test)]
+mod tests {
+    use p3_baby_bear::BabyBear;
+    use p3_field::AbstractField;
+    use p3_matrix::Matrix;
+    use zk_sudoku_core::sample;
+
+    use super::{column, generate_trace, square, HEIGHT, WIDTH};
+
+    #[test]
+    fn test_generate_trace() {
+        let trace = generate_trace::<BabyBear>(&sample::solution());
+        assert_eq!(trace.width(), WIDTH);
+        assert_eq!(trace.height(), HEIGHT);
+
+        // every digit is counted once in each column and in each square of
+        // the last band, and the counts are carried over the padding
+        for i in [8, HEIGHT - 1] {
+            let row = trace.row_slice(i);
+            for digit in 1..=9 {
+                for j in 0..9 {
+                    assert_eq!(row[column(j, digit)], BabyBear::one());
+                }
+                for s in 0..3 {
+                    assert_eq!(row[square(s, digit)], BabyBear::one());
+                }
+            }
+        }
+    }
+}
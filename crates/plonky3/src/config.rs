@@ -0,0 +1,116 @@
+use p3_baby_bear::{BabyBear, DiffusionMatrixBabyBear};
+use p3_challenger::{DuplexChallenger, HashChallenger, SerializingChallenger32};
+use p3_commit::ExtensionMmcs;
+use p3_dft::Radix2DitParallel;
+use p3_field::{extension::BinomialExtensionField, Field};
+use p3_fri::{FriConfig, TwoAdicFriPcs};
+use p3_keccak::Keccak256Hash;
+use p3_merkle_tree::FieldMerkleTreeMmcs;
+use p3_poseidon2::{Poseidon2, Poseidon2ExternalMatrixGeneral};
+use p3_symmetric::{
+    CompressionFunctionFromHasher, PaddingFreeSponge, SerializingHasher32, TruncatedPermutation,
+};
+use p3_uni_stark::StarkConfig;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+
+use crate::prover::Plonky3Prover;
+
+/// The traces are over babybear, the challenges over its degree 4 extension.
+pub type Val = BabyBear;
+pub type Challenge = BinomialExtensionField<Val, 4>;
+type Dft = Radix2DitParallel;
+
+type Perm = Poseidon2<Val, Poseidon2ExternalMatrixGeneral, DiffusionMatrixBabyBear, 16, 7>;
+type Poseidon2Hash = PaddingFreeSponge<Perm, 16, 8, 8>;
+type Poseidon2Compress = TruncatedPermutation<Perm, 2, 8, 16>;
+type Poseidon2Mmcs = FieldMerkleTreeMmcs<
+    <Val as Field>::Packing,
+    <Val as Field>::Packing,
+    Poseidon2Hash,
+    Poseidon2Compress,
+    8,
+>;
+type Poseidon2ChallengeMmcs = ExtensionMmcs<Val, Challenge, Poseidon2Mmcs>;
+type Poseidon2Challenger = DuplexChallenger<Val, Perm, 16, 8>;
+type Poseidon2Pcs = TwoAdicFriPcs<Val, Dft, Poseidon2Mmcs, Poseidon2ChallengeMmcs>;
+/// FRI with Poseidon2 merkle trees and a Poseidon2 duplex challenger, the
+/// cheapest to recurse on.
+pub type Poseidon2Config = StarkConfig<Poseidon2Pcs, Challenge, Poseidon2Challenger>;
+
+type ByteHash = Keccak256Hash;
+type KeccakFieldHash = SerializingHasher32<ByteHash>;
+type KeccakCompress = CompressionFunctionFromHasher<u8, ByteHash, 2, 32>;
+type KeccakMmcs = FieldMerkleTreeMmcs<Val, u8, KeccakFieldHash, KeccakCompress, 32>;
+type KeccakChallengeMmcs = ExtensionMmcs<Val, Challenge, KeccakMmcs>;
+type KeccakChallenger = SerializingChallenger32<Val, HashChallenger<u8, ByteHash, 32>>;
+type KeccakPcs = TwoAdicFriPcs<Val, Dft, KeccakMmcs, KeccakChallengeMmcs>;
+/// FRI with keccak256 merkle trees and a keccak256 challenger, the cheapest
+/// to verify on the EVM.
+pub type KeccakConfig = StarkConfig<KeccakPcs, Challenge, KeccakChallenger>;
+
+// Seeds the round constants of Poseidon2, so that every prover and verifier
+// derives the same ones
+const POSEIDON2_SEED: u64 = 1;
+
+/// The FRI parameters of both configurations.
+#[derive(Debug, Clone, Copy)]
+pub struct FriParameters {
+    pub log_blowup: usize,
+    pub num_queries: usize,
+    pub proof_of_work_bits: usize,
+}
+
+impl Default for FriParameters {
+    /// About 100 bits of conjectured security, with the smallest blowup the
+    /// degree 3 constraints of the AIR allow.
+    fn default() -> Self {
+        Self {
+            log_blowup: 1,
+            num_queries: 100,
+            proof_of_work_bits: 16,
+        }
+    }
+}
+
+impl FriParameters {
+    fn fri_config<M>(&self, mmcs: M) -> FriConfig<M> {
+        FriConfig {
+            log_blowup: self.log_blowup,
+            num_queries: self.num_queries,
+            proof_of_work_bits: self.proof_of_work_bits,
+            mmcs,
+        }
+    }
+}
+
+/// A prover hashing with Poseidon2 over babybear.
+pub fn poseidon2_prover(fri: FriParameters) -> Plonky3Prover<Poseidon2Config> {
+    let perm = Perm::new_from_rng_128(
+        Poseidon2ExternalMatrixGeneral,
+        DiffusionMatrixBabyBear::default(),
+        &mut ChaCha20Rng::seed_from_u64(POSEIDON2_SEED),
+    );
+    let hash = Poseidon2Hash::new(perm.clone());
+    let compress = Poseidon2Compress::new(perm.clone());
+    let val_mmcs = Poseidon2Mmcs::new(hash, compress);
+    let challenge_mmcs = Poseidon2ChallengeMmcs::new(val_mmcs.clone());
+    let pcs = Poseidon2Pcs::new(Dft {}, val_mmcs, fri.fri_config(challenge_mmcs));
+    Plonky3Prover {
+        config: Poseidon2Config::new(pcs),
+        challenger: Poseidon2Challenger::new(perm),
+    }
+}
+
+/// A prover hashing with keccak256.
+pub fn keccak_prover(fri: FriParameters) -> Plonky3Prover<KeccakConfig> {
+    let byte_hash = ByteHash {};
+    let field_hash = KeccakFieldHash::new(byte_hash);
+    let compress = KeccakCompress::new(byte_hash);
+    let val_mmcs = KeccakMmcs::new(field_hash, compress);
+    let challenge_mmcs = KeccakChallengeMmcs::new(val_mmcs.clone());
+    let pcs = KeccakPcs::new(Dft {}, val_mmcs, fri.fri_config(challenge_mmcs));
+    Plonky3Prover {
+        config: KeccakConfig::new(pcs),
+        challenger: KeccakChallenger::from_hasher(vec![], byte_hash),
+    }
+}
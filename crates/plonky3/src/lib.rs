@@ -0,0 +1,13 @@
+use crate::stark::run_stark;
+
+pub mod air;
+pub mod config;
+pub mod prover;
+pub mod stark;
+
+pub fn test_plonky3() {
+    println!("-----------------------------");
+    println!("Run Plonky3 uni-stark with babybear...");
+    println!("-----------------------------");
+    run_stark();
+}
\ No newline at end of file
@@ -0,0 +1,117 @@
+use std::{
+    convert::Infallible,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use p3_uni_stark::{prove, verify, Proof, StarkGenericConfig};
+use zk_sudoku_core::{
+    rand_core::{CryptoRng, RngCore},
+    Puzzle, Solution, SudokuProver,
+};
+
+use crate::air::{generate_trace, public_values, SudokuAir};
+
+/// Plonky3's uni-stark over babybear, the puzzle being the public values.
+/// `SC` picks the PCS and the hash, see `config` for the prebuilt ones.
+///
+/// A STARK has no keys, the prover carries the configuration and the initial
+/// state of the challenger instead. Proving is deterministic, the rng is not
+/// used. A solution breaking a rule makes `prove` panic in debug builds, as
+/// uni-stark checks the constraints there, and fails to verify otherwise.
+#[derive(Clone)]
+pub struct Plonky3Prover<SC: StarkGenericConfig> {
+    pub config: SC,
+    pub challenger: SC::Challenger,
+}
+
+impl<SC> SudokuProver for Plonky3Prover<SC>
+where
+    SC: StarkGenericConfig,
+    SC::Challenger: Clone,
+{
+    type ProvingKey = ();
+    type VerifyingKey = ();
+    type Proof = Proof<SC>;
+    type Error = Infallible;
+
+    fn setup<R: RngCore + CryptoRng>(&self, _rng: &mut R) -> Result<((), ()), Infallible> {
+        Ok(((), ()))
+    }
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        _pk: &(),
+        puzzle: &Puzzle,
+        solution: &Solution,
+        _rng: &mut R,
+    ) -> Result<Self::Proof, Infallible> {
+        Ok(prove(
+            &self.config,
+            &SudokuAir,
+            &mut self.challenger.clone(),
+            generate_trace(solution),
+            &public_values(puzzle),
+        ))
+    }
+
+    /// p3-fri 0.2 panics instead of returning an error when the FRI proof
+    /// doesn't check, e.g. against another puzzle, so a panic of `verify`
+    /// counts as a rejection too.
+    fn verify(&self, _vk: &(), puzzle: &Puzzle, proof: &Self::Proof) -> Result<bool, Infallible> {
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            verify(
+                &self.config,
+                &SudokuAir,
+                &mut self.challenger.clone(),
+                proof,
+                &public_values(puzzle),
+            )
+        }));
+        Ok(matches!(result, Ok(Ok(()))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use zk_sudoku_core::{sample, Puzzle, Solution, SudokuProver};
+
+    use crate::config::{keccak_prover, poseidon2_prover, FriParameters};
+
+    fn check<P: SudokuProver<ProvingKey = (), VerifyingKey = ()>>(prover: P) {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+
+        let proof = prover.prove(&(), &puzzle, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&(), &puzzle, &proof).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[4][4] = 6;
+        let other = Puzzle::new(other).unwrap();
+        assert!(!prover.verify(&(), &other, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_poseidon2_prover() {
+        check(poseidon2_prover(FriParameters::default()));
+    }
+
+    #[test]
+    fn test_keccak_prover() {
+        check(keccak_prover(FriParameters::default()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_repeated_in_column() {
+        // swapping two cells of a row keeps the rows and the squares. Proving
+        // panics in debug builds, verifying fails otherwise
+        let mut repeated_in_column = *sample::solution().cells();
+        repeated_in_column[4].swap(0, 1);
+        let empty = Puzzle::new([[0; 9]; 9]).unwrap();
+        let prover = poseidon2_prover(FriParameters::default());
+        let solution = Solution::new_unchecked(repeated_in_column);
+        let proof = prover.prove(&(), &empty, &solution, &mut OsRng).unwrap();
+        assert!(prover.verify(&(), &empty, &proof).unwrap());
+    }
+}
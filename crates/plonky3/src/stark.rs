@@ -0,0 +1,51 @@
+use std::time::Instant;
+
+use p3_uni_stark::StarkGenericConfig;
+use rand_chacha::{rand_core::SeedableRng, ChaCha20Rng};
+use zk_sudoku_core::{sample, SudokuProver};
+
+use crate::{
+    air::{HEIGHT, WIDTH},
+    config::{keccak_prover, poseidon2_prover, FriParameters},
+    prover::Plonky3Prover,
+};
+
+fn run<SC>(name: &str, prover: Plonky3Prover<SC>)
+where
+    SC: StarkGenericConfig,
+    SC::Challenger: Clone,
+{
+    let puzzle = sample::puzzle();
+    let solution = sample::solution();
+    // unused, proving is deterministic
+    let mut rng = ChaCha20Rng::seed_from_u64(0);
+
+    let prove_start = Instant::now();
+    let proof = prover.prove(&(), &puzzle, &solution, &mut rng).unwrap();
+    let prove_time = prove_start.elapsed();
+    println!(
+        "{}: prove time {}ms, {}s",
+        name,
+        prove_time.as_millis(),
+        prove_time.as_secs()
+    );
+
+    let verify_start = Instant::now();
+    let valid_proof = prover.verify(&(), &puzzle, &proof).unwrap();
+    let verify_time = verify_start.elapsed();
+    println!(
+        "{}: verify time {}ms, {}s",
+        name,
+        verify_time.as_millis(),
+        verify_time.as_secs()
+    );
+    assert!(valid_proof);
+}
+
+/// Proves the sample with both configurations, printing their times to set
+/// against the plonky2 report.
+pub fn run_stark() {
+    println!("trace: {} columns, {} rows", WIDTH, HEIGHT);
+    run("poseidon2", poseidon2_prover(FriParameters::default()));
+    run("keccak256", keccak_prover(FriParameters::default()));
+}
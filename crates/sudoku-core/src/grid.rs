@@ -0,0 +1,216 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridError {
+    /// The value is out of `0..=9` for a puzzle, `1..=9` for a solution.
+    OutOfRange { row: usize, col: usize, value: u8 },
+    /// The value appears twice in the same row, column or box.
+    Repeated { row: usize, col: usize, value: u8 },
+    /// The solution does not keep the given number of the puzzle.
+    Mismatch { row: usize, col: usize },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::OutOfRange { row, col, value } => {
+                write!(f, "value {} at [{}][{}] is out of range", value, row, col)
+            }
+            GridError::Repeated { row, col, value } => write!(
+                f,
+                "value {} at [{}][{}] is repeated in its row, column or box",
+                value, row, col
+            ),
+            GridError::Mismatch { row, col } => {
+                write!(
+                    f,
+                    "solution does not match the puzzle at [{}][{}]",
+                    row, col
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/// Checks that no non zero value is repeated in a row, column or box.
+fn check_repeated(cells: &[[u8; 9]; 9]) -> Result<(), GridError> {
+    for i in 0..9 {
+        for j in 0..9 {
+            let value = cells[i][j];
+            if value == 0 {
+                continue;
+            }
+            let (box_i, box_j) = (i / 3 * 3, j / 3 * 3);
+            let repeated = (0..j).any(|k| cells[i][k] == value)
+                || (0..i).any(|k| cells[k][j] == value)
+                || (box_i..i)
+                    .flat_map(|m| (box_j..box_j + 3).map(move |n| (m, n)))
+                    .any(|(m, n)| cells[m][n] == value);
+            if repeated {
+                return Err(GridError::Repeated {
+                    row: i,
+                    col: j,
+                    value,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The public sudoku, `0` marks an empty cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Puzzle([[u8; 9]; 9]);
+
+impl Puzzle {
+    /// Every cell must be in `0..=9` and the givens must not contradict each
+    /// other.
+    pub fn new(cells: [[u8; 9]; 9]) -> Result<Self, GridError> {
+        for (row, line) in cells.iter().enumerate() {
+            for (col, value) in line.iter().enumerate() {
+                if *value > 9 {
+                    return Err(GridError::OutOfRange {
+                        row,
+                        col,
+                        value: *value,
+                    });
+                }
+            }
+        }
+        check_repeated(&cells)?;
+        Ok(Self(cells))
+    }
+
+    pub fn cells(&self) -> &[[u8; 9]; 9] {
+        &self.0
+    }
+
+    /// Converts every cell, e.g. into the field of a backend.
+    pub fn map<T>(&self, mut f: impl FnMut(u8) -> T) -> [[T; 9]; 9] {
+        self.0.map(|line| line.map(&mut f))
+    }
+
+    /// The cells in row-major order, which is the order of the public inputs.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().flatten().copied()
+    }
+
+    /// Checks that `solution` keeps every given number of the puzzle.
+    pub fn check(&self, solution: &Solution) -> Result<(), GridError> {
+        for (row, line) in self.0.iter().enumerate() {
+            for (col, value) in line.iter().enumerate() {
+                if *value != 0 && *value != solution.0[row][col] {
+                    return Err(GridError::Mismatch { row, col });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The private solution, a complete and valid sudoku.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Solution([[u8; 9]; 9]);
+
+impl Solution {
+    /// Every cell must be in `1..=9` and each row, column and box must hold
+    /// every number once.
+    pub fn new(cells: [[u8; 9]; 9]) -> Result<Self, GridError> {
+        for (row, line) in cells.iter().enumerate() {
+            for (col, value) in line.iter().enumerate() {
+                if !(1..=9).contains(value) {
+                    return Err(GridError::OutOfRange {
+                        row,
+                        col,
+                        value: *value,
+                    });
+                }
+            }
+        }
+        check_repeated(&cells)?;
+        Ok(Self(cells))
+    }
+
+    /// Skips the checks, so that circuits can be fed invalid witnesses in
+    /// tests.
+    pub fn new_unchecked(cells: [[u8; 9]; 9]) -> Self {
+        Self(cells)
+    }
+
+    pub fn cells(&self) -> &[[u8; 9]; 9] {
+        &self.0
+    }
+
+    /// Converts every cell, e.g. into the field of a backend.
+    pub fn map<T>(&self, mut f: impl FnMut(u8) -> T) -> [[T; 9]; 9] {
+        self.0.map(|line| line.map(&mut f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GridError, Puzzle, Solution};
+    use crate::sample::{SOLVED, UNSOLVED};
+
+    #[test]
+    fn test_valid_grids() {
+        let puzzle = Puzzle::new(UNSOLVED).unwrap();
+        let solution = Solution::new(SOLVED).unwrap();
+        puzzle.check(&solution).unwrap();
+        assert_eq!(puzzle.iter().count(), 81);
+        assert_eq!(solution.map(u64::from)[8][8], 4);
+    }
+
+    #[test]
+    fn test_invalid_grids() {
+        let mut cells = UNSOLVED;
+        cells[0][0] = 10;
+        assert_eq!(
+            Puzzle::new(cells),
+            Err(GridError::OutOfRange {
+                row: 0,
+                col: 0,
+                value: 10
+            })
+        );
+
+        let mut cells = SOLVED;
+        cells[0][8] = 0;
+        assert!(matches!(
+            Solution::new(cells),
+            Err(GridError::OutOfRange { .. })
+        ));
+
+        // Swap two cells of the same row, which breaks their columns
+        let mut cells = SOLVED;
+        cells[0].swap(0, 1);
+        assert!(matches!(
+            Solution::new(cells),
+            Err(GridError::Repeated { .. })
+        ));
+
+        // (0, 0) and (1, 1) only share their box
+        let mut cells = UNSOLVED;
+        cells[0][0] = 3;
+        cells[1][1] = 3;
+        assert_eq!(
+            Puzzle::new(cells),
+            Err(GridError::Repeated {
+                row: 1,
+                col: 1,
+                value: 3
+            })
+        );
+
+        // The puzzle gives a 6 at [0][5]
+        let mut cells = SOLVED;
+        cells[0].swap(4, 5);
+        let puzzle = Puzzle::new(UNSOLVED).unwrap();
+        assert_eq!(
+            puzzle.check(&Solution::new_unchecked(cells)),
+            Err(GridError::Mismatch { row: 0, col: 5 })
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Key files of the backends serializing their keys with ark-serialize.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::Path,
+};
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+
+/// The error of writing or reading a key file, or of the backend generating
+/// or checking the key, `E`.
+#[derive(Debug)]
+pub enum KeyError<E> {
+    Io(io::Error),
+    Serialization(SerializationError),
+    Backend(E),
+}
+
+impl<E> From<io::Error> for KeyError<E> {
+    fn from(err: io::Error) -> Self {
+        KeyError::Io(err)
+    }
+}
+
+impl<E> From<SerializationError> for KeyError<E> {
+    fn from(err: SerializationError) -> Self {
+        KeyError::Serialization(err)
+    }
+}
+
+/// Writes a key, or any parameters of a setup, to `path`, compressed.
+pub fn write_key<T: CanonicalSerialize, E>(
+    key: &T,
+    path: impl AsRef<Path>,
+) -> Result<(), KeyError<E>> {
+    let writer = BufWriter::new(File::create(path)?);
+    key.serialize(writer)?;
+    Ok(())
+}
+
+/// Reads back a key written by `write_key`. The curve points are checked to
+/// be valid, not that the key belongs to the sudoku circuit.
+pub fn read_key<T: CanonicalDeserialize, E>(path: impl AsRef<Path>) -> Result<T, KeyError<E>> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(T::deserialize(reader)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::{read_key, write_key, KeyError};
+
+    #[test]
+    fn test_key_file() {
+        let path = env::temp_dir().join("zk_sudoku_core_key.bin");
+        let key = vec![1u64, 2, 3];
+        write_key::<_, ()>(&key, &path).unwrap();
+        assert_eq!(read_key::<Vec<u64>, ()>(&path).unwrap(), key);
+
+        let missing = env::temp_dir().join("zk_sudoku_core_missing_key.bin");
+        assert!(matches!(
+            read_key::<Vec<u64>, ()>(missing),
+            Err(KeyError::Io(_))
+        ));
+    }
+}
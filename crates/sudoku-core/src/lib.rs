@@ -0,0 +1,48 @@
+use rand_core::{CryptoRng, RngCore};
+
+pub use rand_core;
+
+pub mod grid;
+#[cfg(feature = "ark-serialize")]
+pub mod keys;
+pub mod public_input;
+pub mod report;
+pub mod sample;
+
+pub use grid::{GridError, Puzzle, Solution};
+
+/// A proof system able to prove knowledge of the solution of a sudoku.
+///
+/// The puzzle is public and the solution private. Every backend crate
+/// implements this for its own proving stack, so code written against the
+/// trait runs on all of them.
+pub trait SudokuProver {
+    type ProvingKey;
+    type VerifyingKey;
+    type Proof;
+    type Error: std::fmt::Debug;
+
+    /// Generates the keys of the sudoku circuit. Universal setups are run
+    /// here too, sized for this circuit only.
+    fn setup<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+    ) -> Result<(Self::ProvingKey, Self::VerifyingKey), Self::Error>;
+
+    fn prove<R: RngCore + CryptoRng>(
+        &self,
+        pk: &Self::ProvingKey,
+        puzzle: &Puzzle,
+        solution: &Solution,
+        rng: &mut R,
+    ) -> Result<Self::Proof, Self::Error>;
+
+    /// Returns `Ok(false)` if the proof is rejected for `puzzle`, errors are
+    /// kept for the cases where the verifier could not run at all.
+    fn verify(
+        &self,
+        vk: &Self::VerifyingKey,
+        puzzle: &Puzzle,
+        proof: &Self::Proof,
+    ) -> Result<bool, Self::Error>;
+}
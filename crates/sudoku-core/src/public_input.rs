@@ -0,0 +1,116 @@
+//! The public input of the circuits which commit to the puzzle with a hash.
+//!
+//! The puzzle is serialized as its 81 cells in row major order, one byte per
+//! cell, followed by the prover id if the proof is bound to one, and hashed.
+//! Only the first 31 bytes of the digest are kept so the value is below
+//! 2^248 and fits in the scalar field of every supported curve without being
+//! reduced. Both functions return that value as a little endian integer, to
+//! be read with e.g. `F::from_le_bytes_mod_order` in arkworks.
+
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+/// Number of digest bytes kept in the public input.
+pub const DIGEST_BYTES: usize = 31;
+
+/// The address a proof is bound to, e.g. the Ethereum account claiming a
+/// reward. Being part of the hashed public input, a proof copied from the
+/// mempool does not verify for another address.
+pub type ProverId = [u8; 20];
+
+/// The canonical serialization of a puzzle.
+pub fn cell_bytes(cells: &[[u8; 9]; 9]) -> [u8; 81] {
+    let mut bytes = [0; 81];
+    for (byte, cell) in bytes.iter_mut().zip(cells.iter().flatten()) {
+        *byte = *cell;
+    }
+    bytes
+}
+
+/// The hashed bytes: the cells, then the prover id if any.
+pub fn preimage(cells: &[[u8; 9]; 9], prover_id: Option<&ProverId>) -> Vec<u8> {
+    let mut bytes = cell_bytes(cells).to_vec();
+    if let Some(prover_id) = prover_id {
+        bytes.extend_from_slice(prover_id);
+    }
+    bytes
+}
+
+/// The first 31 bytes of the SHA-256 digest, read as a little endian integer.
+pub fn sha256_input(cells: &[[u8; 9]; 9], prover_id: Option<&ProverId>) -> [u8; DIGEST_BYTES] {
+    let digest = Sha256::digest(&preimage(cells, prover_id));
+    digest[..DIGEST_BYTES].try_into().unwrap()
+}
+
+/// The first 31 bytes of the Keccak256 digest, read as a big endian integer,
+/// returned in little endian. On the EVM this is
+/// `uint256(keccak256(cells)) >> 8`, with `cells` the 81 bytes of the puzzle
+/// (a `bytes`, not an abi encoded `uint8[81]`), or
+/// `abi.encodePacked(cells, msg.sender)` for a proof bound to the sender.
+pub fn keccak256_input(cells: &[[u8; 9]; 9], prover_id: Option<&ProverId>) -> [u8; DIGEST_BYTES] {
+    let digest = Keccak256::digest(&preimage(cells, prover_id));
+    let mut input: [u8; DIGEST_BYTES] = digest[..DIGEST_BYTES].try_into().unwrap();
+    input.reverse();
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cell_bytes, keccak256_input, sha256_input, ProverId};
+
+    const UNSOLVED: [[u8; 9]; 9] = [
+        [0, 0, 0, 0, 0, 6, 0, 0, 0],
+        [0, 0, 7, 2, 0, 0, 8, 0, 0],
+        [9, 0, 6, 8, 0, 0, 0, 1, 0],
+        [3, 0, 0, 7, 0, 0, 0, 2, 9],
+        [0, 0, 0, 0, 0, 0, 0, 0, 0],
+        [4, 0, 0, 5, 0, 0, 0, 7, 0],
+        [6, 5, 0, 1, 0, 0, 0, 0, 0],
+        [8, 0, 1, 0, 5, 0, 3, 0, 0],
+        [7, 9, 2, 0, 0, 0, 0, 0, 4],
+    ];
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn test_cell_bytes() {
+        let bytes = cell_bytes(&UNSOLVED);
+        assert_eq!(bytes[5], 6);
+        assert_eq!(bytes[9 + 2], 7);
+        assert_eq!(bytes[80], 4);
+    }
+
+    #[test]
+    fn test_vectors() {
+        // sha256 4623d6b4...11196c59, the last byte dropped
+        assert_eq!(
+            hex(&sha256_input(&UNSOLVED, None)),
+            "4623d6b4530f07009fb38ea0a07441a0c4a8ca45dbd005ec0415616e11196c"
+        );
+        // keccak256 5fa59f25...be9fcc1a, the last byte dropped then reversed
+        assert_eq!(
+            hex(&keccak256_input(&UNSOLVED, None)),
+            "cc9fbe1bbb8775d9a6bd2181c3c26324d02804c8e1388e966f9d33259fa55f"
+        );
+        assert_eq!(
+            hex(&sha256_input(&[[0; 9]; 9], None)),
+            "6778c7c7b6b6c1c273e668169a7652a681da86ad62d03f7c5aa120405069fe"
+        );
+        assert_eq!(
+            hex(&keccak256_input(&[[0; 9]; 9], None)),
+            "82e697dcfef4ed89cd023bd53668ac840292261fc26953f17819e462059851"
+        );
+
+        let prover_id: ProverId = core::array::from_fn(|i| i as u8 + 1);
+        assert_eq!(
+            hex(&sha256_input(&UNSOLVED, Some(&prover_id))),
+            "83e171f7da179ae89dab9ec860b93817a8868759f8b6ba7d2108ac3af8203c"
+        );
+        assert_eq!(
+            hex(&keccak256_input(&UNSOLVED, Some(&prover_id))),
+            "8ede0b1e83735f54bba722bd36a65c3c01c313f8a3b75fe0f6dbe32a7bbc12"
+        );
+    }
+}
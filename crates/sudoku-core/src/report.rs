@@ -0,0 +1,150 @@
+//! The figures of one run of a backend on the sample sudoku, in the same
+//! shape for every backend so their runs can be compared.
+
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub backend: &'static str,
+    pub curve: &'static str,
+    /// The variant run, for the backends run with several configs.
+    pub config: Option<&'static str>,
+    /// The constraints of the circuit, or its gates for the PLONKish
+    /// backends.
+    pub num_constraints: usize,
+    pub setup_time: Duration,
+    pub prove_time: Duration,
+    pub verify_time: Duration,
+    pub proof_len: usize,
+    /// Figures only some backends have, e.g. the padded size of the circuit.
+    pub extra: Vec<(&'static str, Value)>,
+}
+
+impl Report {
+    /// The figure `key` of `extra`.
+    pub fn extra(&self, key: &str) -> Option<&Value> {
+        self.extra
+            .iter()
+            .find(|(name, _)| *name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The report as a JSON object, times in milliseconds, with the figures
+    /// of `extra` next to the common ones.
+    pub fn to_json(&self) -> Value {
+        let mut json = Map::new();
+        json.insert("backend".into(), self.backend.into());
+        json.insert("curve".into(), self.curve.into());
+        if let Some(config) = self.config {
+            json.insert("config".into(), config.into());
+        }
+        json.insert("num_constraints".into(), self.num_constraints.into());
+        json.insert(
+            "setup_ms".into(),
+            (self.setup_time.as_millis() as u64).into(),
+        );
+        json.insert(
+            "prove_ms".into(),
+            (self.prove_time.as_millis() as u64).into(),
+        );
+        json.insert(
+            "verify_ms".into(),
+            (self.verify_time.as_millis() as u64).into(),
+        );
+        json.insert("proof_len".into(), self.proof_len.into());
+        for (key, value) in &self.extra {
+            json.insert((*key).into(), value.clone());
+        }
+        Value::Object(json)
+    }
+}
+
+/// The reports as a JSON array, to be collected and compared across runs.
+pub fn reports_json(reports: &[Report]) -> Value {
+    Value::Array(reports.iter().map(Report::to_json).collect())
+}
+
+/// Prints the reports as a table, one run per row, the figures of `extra`
+/// last.
+pub fn print_table(reports: &[Report]) {
+    println!(
+        "{:<12} {:<10} {:<12} {:>12} {:>10} {:>10} {:>10} {:>10}",
+        "backend",
+        "curve",
+        "config",
+        "constraints",
+        "setup ms",
+        "prove ms",
+        "verify ms",
+        "proof len"
+    );
+    for report in reports {
+        let extra = report
+            .extra
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(" ");
+        println!(
+            "{:<12} {:<10} {:<12} {:>12} {:>10} {:>10} {:>10} {:>10} {}",
+            report.backend,
+            report.curve,
+            report.config.unwrap_or("-"),
+            report.num_constraints,
+            report.setup_time.as_millis(),
+            report.prove_time.as_millis(),
+            report.verify_time.as_millis(),
+            report.proof_len,
+            extra
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::{reports_json, Report};
+
+    fn report() -> Report {
+        Report {
+            backend: "groth16",
+            curve: "bn254",
+            config: None,
+            num_constraints: 1000,
+            setup_time: Duration::from_millis(1500),
+            prove_time: Duration::from_millis(300),
+            verify_time: Duration::from_micros(2500),
+            proof_len: 128,
+            extra: vec![],
+        }
+    }
+
+    #[test]
+    fn test_reports_json() {
+        let json = reports_json(&[report()]);
+        assert_eq!(
+            json.to_string(),
+            r#"[{"backend":"groth16","curve":"bn254","num_constraints":1000,"proof_len":128,"prove_ms":300,"setup_ms":1500,"verify_ms":2}]"#
+        );
+    }
+
+    #[test]
+    fn test_extra() {
+        let report = Report {
+            config: Some("turbo"),
+            extra: vec![("srs_size", json!(2048))],
+            ..report()
+        };
+        assert_eq!(report.extra("srs_size"), Some(&json!(2048)));
+        assert_eq!(report.extra("rows"), None);
+
+        let json = report.to_json();
+        assert_eq!(json["config"], "turbo");
+        assert_eq!(json["srs_size"], 2048);
+    }
+}
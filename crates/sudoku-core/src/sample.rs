@@ -0,0 +1,124 @@
+//! The sudoku of the examples, tests and benchmarks of every backend: a
+//! puzzle, its solution, and solutions breaking each rule.
+
+use crate::{Puzzle, Solution};
+
+pub const UNSOLVED: [[u8; 9]; 9] = [
+    [0, 0, 0, 0, 0, 6, 0, 0, 0],
+    [0, 0, 7, 2, 0, 0, 8, 0, 0],
+    [9, 0, 6, 8, 0, 0, 0, 1, 0],
+    [3, 0, 0, 7, 0, 0, 0, 2, 9],
+    [0, 0, 0, 0, 0, 0, 0, 0, 0],
+    [4, 0, 0, 5, 0, 0, 0, 7, 0],
+    [6, 5, 0, 1, 0, 0, 0, 0, 0],
+    [8, 0, 1, 0, 5, 0, 3, 0, 0],
+    [7, 9, 2, 0, 0, 0, 0, 0, 4],
+];
+
+pub const SOLVED: [[u8; 9]; 9] = [
+    [1, 8, 4, 3, 7, 6, 2, 9, 5],
+    [5, 3, 7, 2, 9, 1, 8, 4, 6],
+    [9, 2, 6, 8, 4, 5, 7, 1, 3],
+    [3, 6, 5, 7, 1, 8, 4, 2, 9],
+    [2, 7, 8, 4, 6, 9, 5, 3, 1],
+    [4, 1, 9, 5, 3, 2, 6, 7, 8],
+    [6, 5, 3, 1, 2, 4, 9, 8, 7],
+    [8, 4, 1, 9, 5, 7, 3, 6, 2],
+    [7, 9, 2, 6, 8, 3, 1, 5, 4],
+];
+
+/// A valid sudoku which does not keep the givens of `UNSOLVED`.
+pub const SOLVED_UNMATCH: [[u8; 9]; 9] = [
+    [1, 2, 7, 5, 8, 4, 6, 9, 3],
+    [8, 5, 6, 3, 7, 9, 1, 2, 4],
+    [3, 4, 9, 6, 2, 1, 8, 7, 5],
+    [4, 7, 1, 9, 5, 8, 2, 3, 6],
+    [2, 6, 8, 7, 1, 3, 5, 4, 9],
+    [9, 3, 5, 4, 6, 2, 7, 1, 8],
+    [5, 8, 3, 2, 9, 7, 4, 6, 1],
+    [7, 1, 4, 8, 3, 6, 9, 5, 2],
+    [6, 9, 2, 1, 4, 5, 3, 8, 7],
+];
+
+pub const SOLVED_REPEATED_IN_ROW: [[u8; 9]; 9] = [
+    [1, 8, 4, 3, 7, 6, 2, 9, 1],
+    [5, 3, 7, 2, 9, 1, 8, 4, 6],
+    [9, 2, 6, 8, 4, 5, 7, 1, 3],
+    [3, 6, 5, 7, 1, 8, 4, 2, 9],
+    [2, 7, 8, 4, 6, 9, 5, 3, 1],
+    [4, 1, 9, 5, 3, 2, 6, 7, 8],
+    [6, 5, 3, 1, 2, 4, 9, 8, 7],
+    [8, 4, 1, 9, 5, 7, 3, 6, 2],
+    [7, 9, 2, 6, 8, 3, 1, 5, 4],
+];
+
+pub const SOLVED_REPEATED_IN_COLUMN: [[u8; 9]; 9] = [
+    [1, 8, 4, 3, 7, 6, 2, 9, 5],
+    [5, 3, 7, 2, 9, 1, 8, 4, 6],
+    [9, 2, 6, 8, 4, 5, 7, 1, 3],
+    [3, 6, 5, 7, 1, 8, 4, 2, 9],
+    [2, 7, 8, 4, 6, 9, 5, 3, 1],
+    [4, 1, 9, 5, 3, 2, 6, 7, 8],
+    [6, 5, 3, 1, 2, 4, 9, 8, 7],
+    [8, 4, 1, 9, 5, 7, 3, 6, 2],
+    [4, 9, 2, 6, 8, 3, 1, 5, 7],
+];
+
+pub const SOLVED_REPEATED_IN_SQUARE: [[u8; 9]; 9] = [
+    [1, 8, 4, 3, 7, 6, 2, 9, 5],
+    [5, 3, 7, 2, 9, 1, 8, 4, 6],
+    [9, 2, 1, 8, 4, 5, 7, 6, 3],
+    [3, 6, 5, 7, 1, 8, 4, 2, 9],
+    [2, 7, 8, 4, 6, 9, 5, 3, 1],
+    [4, 1, 9, 5, 3, 2, 6, 7, 8],
+    [6, 5, 3, 1, 2, 4, 9, 8, 7],
+    [8, 4, 6, 9, 5, 7, 3, 1, 2],
+    [7, 9, 2, 6, 8, 3, 1, 5, 4],
+];
+
+/// The cells as `u64`, for the circuits whose witnesses hold them so.
+pub const fn widen(cells: [[u8; 9]; 9]) -> [[u64; 9]; 9] {
+    let mut wide = [[0; 9]; 9];
+    let mut i = 0;
+    while i < 81 {
+        wide[i / 9][i % 9] = cells[i / 9][i % 9] as u64;
+        i += 1;
+    }
+    wide
+}
+
+/// `UNSOLVED` as a puzzle.
+pub fn puzzle() -> Puzzle {
+    Puzzle::new(UNSOLVED).expect("the sample puzzle is valid")
+}
+
+/// `SOLVED` as a solution.
+pub fn solution() -> Solution {
+    Solution::new(SOLVED).expect("the sample solution is valid")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        puzzle, solution, SOLVED_REPEATED_IN_COLUMN, SOLVED_REPEATED_IN_ROW,
+        SOLVED_REPEATED_IN_SQUARE, SOLVED_UNMATCH,
+    };
+    use crate::{GridError, Solution};
+
+    #[test]
+    fn test_sample() {
+        puzzle().check(&solution()).unwrap();
+        let unmatched = Solution::new(SOLVED_UNMATCH).unwrap();
+        assert!(matches!(
+            puzzle().check(&unmatched),
+            Err(GridError::Mismatch { .. })
+        ));
+        for cells in [
+            SOLVED_REPEATED_IN_ROW,
+            SOLVED_REPEATED_IN_COLUMN,
+            SOLVED_REPEATED_IN_SQUARE,
+        ] {
+            assert!(Solution::new(cells).is_err());
+        }
+    }
+}
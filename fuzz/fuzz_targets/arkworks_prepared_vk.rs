@@ -0,0 +1,10 @@
+//! The prepared verifying key embedded in the binaries, see
+//! `zk_sudoku_arkworks::embed::prepared_vk_bytes`.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_arkworks::embed::prepared_vk_from_bytes;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = prepared_vk_from_bytes(data);
+});
@@ -0,0 +1,10 @@
+#![no_main]
+
+use ark_bls12_381::Bls12_381;
+use ark_groth16::Proof;
+use ark_serialize::CanonicalDeserialize;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Proof::<Bls12_381>::deserialize(data);
+});
@@ -0,0 +1,16 @@
+//! The verifying key as written by `zk_sudoku_arkworks::keys::write_key`,
+//! read back from a file by `read_key`.
+#![no_main]
+
+use std::{env, fs, process};
+
+use ark_bls12_381::Bls12_381;
+use ark_groth16::VerifyingKey;
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_arkworks::keys::{read_key, CircuitKeyError};
+
+fuzz_target!(|data: &[u8]| {
+    let path = env::temp_dir().join(format!("zk_sudoku_fuzz_arkworks_vk_{}", process::id()));
+    fs::write(&path, data).unwrap();
+    let _ = read_key::<VerifyingKey<Bls12_381>, CircuitKeyError>(&path);
+});
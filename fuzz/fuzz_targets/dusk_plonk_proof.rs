@@ -0,0 +1,9 @@
+#![no_main]
+
+use dusk_bytes::DeserializableSlice;
+use dusk_plonk::prelude::Proof;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Proof::from_slice(data);
+});
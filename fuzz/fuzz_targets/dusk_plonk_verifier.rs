@@ -0,0 +1,10 @@
+//! The verifier as cached by `zk_sudoku_dusk_plonk::cache`.
+#![no_main]
+
+use dusk_plonk::prelude::Verifier;
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_dusk_plonk::SudokuCircuit;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Verifier::<SudokuCircuit>::try_from_bytes(data);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use ark_bls12_381::Fr as BlsScalar;
+use ark_serialize::CanonicalDeserialize;
+use libfuzzer_sys::fuzz_target;
+use plonk_core::prelude::Proof;
+use zk_sudoku_garage_plonk::prover::PC;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = Proof::<BlsScalar, PC>::deserialize(data);
+});
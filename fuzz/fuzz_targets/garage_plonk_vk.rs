@@ -0,0 +1,12 @@
+//! The verifier key as written by `zk_sudoku_garage_plonk::keys::write_key`.
+#![no_main]
+
+use ark_bls12_381::Fr as BlsScalar;
+use ark_serialize::CanonicalDeserialize;
+use libfuzzer_sys::fuzz_target;
+use plonk_core::prelude::VerifierKey;
+use zk_sudoku_garage_plonk::prover::PC;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = VerifierKey::<BlsScalar, PC>::deserialize(data);
+});
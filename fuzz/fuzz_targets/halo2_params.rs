@@ -0,0 +1,10 @@
+//! The parameters as fetched by the browser bindings, see
+//! `zk_sudoku_halo2::keys::write_params`.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_halo2::keys::params_from_bytes;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = params_from_bytes(data);
+});
@@ -0,0 +1,22 @@
+//! halo2 proofs are bytes read by the verifier itself, so the proof is
+//! verified for the sample puzzle with keys derived once.
+#![no_main]
+
+use std::sync::OnceLock;
+
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_core::sample;
+use zk_sudoku_halo2::{
+    circuit::K,
+    halo2_proofs::{pasta::vesta, plonk::VerifyingKey, poly::commitment::Params},
+    keygen, verify, Halo2Keys,
+};
+
+fn vk() -> &'static Halo2Keys<VerifyingKey<vesta::Affine>> {
+    static VK: OnceLock<Halo2Keys<VerifyingKey<vesta::Affine>>> = OnceLock::new();
+    VK.get_or_init(|| keygen(Params::new(K)).unwrap().1)
+}
+
+fuzz_target!(|data: &[u8]| {
+    let _ = verify(vk(), &sample::puzzle(), data);
+});
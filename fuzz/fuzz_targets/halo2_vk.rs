@@ -0,0 +1,14 @@
+//! The verifying key as written by `zk_sudoku_halo2::keys::write_verifying_key`,
+//! read back from a file by `read_verifying_key`.
+#![no_main]
+
+use std::{env, fs, process};
+
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_halo2::keys::read_verifying_key;
+
+fuzz_target!(|data: &[u8]| {
+    let path = env::temp_dir().join(format!("zk_sudoku_fuzz_halo2_vk_{}", process::id()));
+    fs::write(&path, data).unwrap();
+    let _ = read_verifying_key(&path);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use ark_bls12_381::Bls12_381;
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_jellyfish::prover::proof_from_bytes;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = proof_from_bytes::<Bls12_381>(data);
+});
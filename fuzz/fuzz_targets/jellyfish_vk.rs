@@ -0,0 +1,11 @@
+//! The verifying key as written by `zk_sudoku_jellyfish::keys::write_key`.
+#![no_main]
+
+use ark_bls12_381::Bls12_381;
+use ark_serialize::CanonicalDeserialize;
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_jellyfish::keys::VerifyingKey;
+
+fuzz_target!(|data: &[u8]| {
+    let _ = VerifyingKey::<Bls12_381>::deserialize(data);
+});
@@ -0,0 +1,23 @@
+//! Grids come from the user as 81 cells, 0 for an empty cell. Reading them
+//! must reject anything else without panicking.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use zk_sudoku_core::{Puzzle, Solution};
+
+fn cells(bytes: &[u8]) -> [[u8; 9]; 9] {
+    let mut cells = [[0; 9]; 9];
+    for (cell, byte) in cells.iter_mut().flatten().zip(bytes) {
+        *cell = *byte;
+    }
+    cells
+}
+
+fuzz_target!(|data: &[u8]| {
+    let (puzzle, solution) = data.split_at(data.len().min(81));
+    let puzzle = Puzzle::new(cells(puzzle));
+    let solution = Solution::new(cells(solution));
+    if let (Ok(puzzle), Ok(solution)) = (puzzle, solution) {
+        let _ = puzzle.check(&solution);
+    }
+});
@@ -1,3 +1,17 @@
+use std::{env, fs};
+
+use zk_sudoku_core::report::reports_json;
+
+// `test_arkworks --json <path>` also writes the reports to `path`
 fn main() {
-    zk_sudoku_arkworks::test_arkworks();
+    let reports = zk_sudoku_arkworks::test_arkworks();
+
+    let args = env::args().collect::<Vec<_>>();
+    if let Some(i) = args.iter().position(|arg| arg == "--json") {
+        let path = args
+            .get(i + 1)
+            .expect("--json takes the path of the report");
+        let json = serde_json::to_string_pretty(&reports_json(&reports)).unwrap();
+        fs::write(path, json).unwrap();
+    }
 }
@@ -0,0 +1,227 @@
+//! One error for every backend, so that callers going through
+//! `SudokuProver` can handle the failures of any backend the same way.
+
+use std::convert::Infallible;
+
+use ark_relations::r1cs::SynthesisError;
+use bulletproofs::r1cs::R1CSError;
+use jf_plonk::errors::PlonkError;
+use jf_relation::CircuitError;
+use thiserror::Error;
+use zk_sudoku_bellman::prover::BellmanError;
+use zk_sudoku_cairo::project::CairoError;
+use zk_sudoku_core::{
+    rand_core::{CryptoRng, RngCore},
+    GridError, Puzzle, Solution, SudokuProver,
+};
+use zk_sudoku_halo2::halo2_proofs::plonk::Error as Halo2Error;
+use zk_sudoku_halo2_kzg::halo2_proofs::plonk::Error as Halo2KzgError;
+use zk_sudoku_noir::project::NoirError;
+use zk_sudoku_nova::{arecibo::supernova::error::SuperNovaError, nova_snark::errors::NovaError};
+use zk_sudoku_plonky2::prover::Plonky2Error;
+
+/// The error of a backend, or of the grids given to it. The backends report
+/// their errors as different types, each one kept as is: those implementing
+/// `std::error::Error` are its source, the others are shown with `Debug`.
+#[derive(Debug, Error)]
+pub enum ZkSudokuError {
+    #[error("invalid grid: {0}")]
+    Grid(#[from] GridError),
+    #[error("arkworks: {0}")]
+    Arkworks(#[source] SynthesisError),
+    #[error("bellman: {0:?}")]
+    Bellman(BellmanError),
+    #[error("bulletproofs: {0:?}")]
+    Bulletproofs(R1CSError),
+    #[error("cairo: {0:?}")]
+    Cairo(CairoError),
+    #[error("dusk-plonk: {0:?}")]
+    DuskPlonk(dusk_plonk::prelude::Error),
+    #[error("garage-plonk: {0:?}")]
+    GaragePlonk(plonk_core::prelude::Error),
+    #[error("halo2: {0}")]
+    Halo2(#[source] Halo2Error),
+    #[error("halo2-kzg: {0}")]
+    Halo2Kzg(#[source] Halo2KzgError),
+    #[error("jellyfish: {0}")]
+    Jellyfish(#[source] PlonkError),
+    #[error("noir: {0:?}")]
+    Noir(NoirError),
+    #[error("nova: {0}")]
+    Nova(#[source] NovaError),
+    #[error("supernova: {0}")]
+    SuperNova(#[source] SuperNovaError),
+    #[error("plonky2: {0}")]
+    Plonky2(#[source] Plonky2Error),
+}
+
+impl ZkSudokuError {
+    /// The name of the backend which failed, `None` for an invalid grid.
+    pub fn backend(&self) -> Option<&'static str> {
+        match self {
+            ZkSudokuError::Grid(_) => None,
+            ZkSudokuError::Arkworks(_) => Some("arkworks"),
+            ZkSudokuError::Bellman(_) => Some("bellman"),
+            ZkSudokuError::Bulletproofs(_) => Some("bulletproofs"),
+            ZkSudokuError::Cairo(_) => Some("cairo"),
+            ZkSudokuError::DuskPlonk(_) => Some("dusk-plonk"),
+            ZkSudokuError::GaragePlonk(_) => Some("garage-plonk"),
+            ZkSudokuError::Halo2(_) => Some("halo2"),
+            ZkSudokuError::Halo2Kzg(_) => Some("halo2-kzg"),
+            ZkSudokuError::Jellyfish(_) => Some("jellyfish"),
+            ZkSudokuError::Noir(_) => Some("noir"),
+            ZkSudokuError::Nova(_) => Some("nova"),
+            ZkSudokuError::SuperNova(_) => Some("supernova"),
+            ZkSudokuError::Plonky2(_) => Some("plonky2"),
+        }
+    }
+}
+
+impl From<SynthesisError> for ZkSudokuError {
+    fn from(err: SynthesisError) -> Self {
+        ZkSudokuError::Arkworks(err)
+    }
+}
+
+impl From<BellmanError> for ZkSudokuError {
+    fn from(err: BellmanError) -> Self {
+        ZkSudokuError::Bellman(err)
+    }
+}
+
+impl From<R1CSError> for ZkSudokuError {
+    fn from(err: R1CSError) -> Self {
+        ZkSudokuError::Bulletproofs(err)
+    }
+}
+
+impl From<CairoError> for ZkSudokuError {
+    fn from(err: CairoError) -> Self {
+        ZkSudokuError::Cairo(err)
+    }
+}
+
+impl From<dusk_plonk::prelude::Error> for ZkSudokuError {
+    fn from(err: dusk_plonk::prelude::Error) -> Self {
+        ZkSudokuError::DuskPlonk(err)
+    }
+}
+
+impl From<plonk_core::prelude::Error> for ZkSudokuError {
+    fn from(err: plonk_core::prelude::Error) -> Self {
+        ZkSudokuError::GaragePlonk(err)
+    }
+}
+
+impl From<Halo2Error> for ZkSudokuError {
+    fn from(err: Halo2Error) -> Self {
+        ZkSudokuError::Halo2(err)
+    }
+}
+
+impl From<Halo2KzgError> for ZkSudokuError {
+    fn from(err: Halo2KzgError) -> Self {
+        ZkSudokuError::Halo2Kzg(err)
+    }
+}
+
+impl From<PlonkError> for ZkSudokuError {
+    fn from(err: PlonkError) -> Self {
+        ZkSudokuError::Jellyfish(err)
+    }
+}
+
+// the jellyfish circuit is built outside of the prover in `circuit`
+impl From<CircuitError> for ZkSudokuError {
+    fn from(err: CircuitError) -> Self {
+        ZkSudokuError::Jellyfish(err.into())
+    }
+}
+
+impl From<NoirError> for ZkSudokuError {
+    fn from(err: NoirError) -> Self {
+        ZkSudokuError::Noir(err)
+    }
+}
+
+impl From<NovaError> for ZkSudokuError {
+    fn from(err: NovaError) -> Self {
+        ZkSudokuError::Nova(err)
+    }
+}
+
+impl From<SuperNovaError> for ZkSudokuError {
+    fn from(err: SuperNovaError) -> Self {
+        ZkSudokuError::SuperNova(err)
+    }
+}
+
+impl From<Plonky2Error> for ZkSudokuError {
+    fn from(err: Plonky2Error) -> Self {
+        ZkSudokuError::Plonky2(err)
+    }
+}
+
+// plonky3 cannot fail, a rejected proof being `Ok(false)`
+impl From<Infallible> for ZkSudokuError {
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
+
+/// Sets up `prover`, proves that `solution` solves `puzzle` and verifies the
+/// proof, whatever the backend.
+pub fn prove_and_verify<P, R>(
+    prover: &P,
+    puzzle: &Puzzle,
+    solution: &Solution,
+    rng: &mut R,
+) -> Result<bool, ZkSudokuError>
+where
+    P: SudokuProver,
+    R: RngCore + CryptoRng,
+    ZkSudokuError: From<P::Error>,
+{
+    puzzle.check(solution)?;
+    let (pk, vk) = prover.setup(rng)?;
+    let proof = prover.prove(&pk, puzzle, solution, rng)?;
+    Ok(prover.verify(&vk, puzzle, &proof)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use zk_sudoku_bulletproofs::prover::BulletproofsProver;
+    use zk_sudoku_core::{sample, GridError, Puzzle};
+
+    use zk_sudoku_plonky2::prover::Plonky2Error;
+
+    use super::{prove_and_verify, ZkSudokuError};
+
+    #[test]
+    fn test_errors() {
+        let puzzle = sample::puzzle();
+        let solution = sample::solution();
+        let prover = BulletproofsProver::default();
+        assert!(prove_and_verify(&prover, &puzzle, &solution, &mut OsRng).unwrap());
+
+        let mut other = *puzzle.cells();
+        other[0][0] = 2;
+        let other = Puzzle::new(other).unwrap();
+        let err = prove_and_verify(&prover, &other, &solution, &mut OsRng).unwrap_err();
+        assert!(matches!(
+            err,
+            ZkSudokuError::Grid(GridError::Mismatch { row: 0, col: 0 })
+        ));
+        assert_eq!(err.backend(), None);
+        assert_eq!(
+            err.to_string(),
+            "invalid grid: solution does not match the puzzle at [0][0]"
+        );
+
+        let err = ZkSudokuError::from(Plonky2Error(anyhow::anyhow!("unsatisfied")));
+        assert_eq!(err.backend(), Some("plonky2"));
+        assert_eq!(err.to_string(), "plonky2: unsatisfied");
+        assert!(std::error::Error::source(&err).is_some());
+    }
+}
@@ -0,0 +1,68 @@
+use std::{env, fs};
+
+use rand::rngs::OsRng;
+use serde_json::json;
+use zk_sudoku_halo2::{
+    circuit::{SudokuCircuit, V1SudokuCircuit},
+    cost::{sudoku_cost, CostEstimate},
+    halo2_proofs::pasta::Fp,
+    layout::sudoku_stats,
+    Halo2Prover,
+};
+use zk_sudoku_halo2_kzg::{KzgProver, PuzzleHash};
+
+// `test_halo2 --stats <path>` also writes the layout statistics and costs to
+// `path`, with the runs of the same circuit under the IPA and KZG commitments
+// side by side and the KZG run with the keccak256 puzzle hash, whose rows are
+// `hash_rows` against the ones of Poseidon, `test_halo2 --layout <dir>` draws
+// both layouts to `dir` with the `halo2-dev-graph` feature
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    let value = |name: &str| {
+        args.iter().position(|arg| arg == name).map(|i| {
+            args.get(i + 1)
+                .unwrap_or_else(|| panic!("{} takes a path", name))
+                .clone()
+        })
+    };
+
+    let stats = json!({
+        "custom_gate": sudoku_stats(false).unwrap().to_json(),
+        "lookup": sudoku_stats(true).unwrap().to_json(),
+        "cost": {
+            "custom_gate": sudoku_cost::<false, 1, true>().unwrap().to_json(),
+            "lookup": sudoku_cost::<true, 1, true>().unwrap().to_json(),
+            "pairwise": sudoku_cost::<false, 1, false>().unwrap().to_json(),
+            "pairwise_4_lanes": sudoku_cost::<false, 4, false>().unwrap().to_json(),
+        },
+        "floor_planner": {
+            "simple": CostEstimate::estimate(&SudokuCircuit::<Fp>::default(), 1).unwrap().to_json(),
+            "v1": CostEstimate::estimate(&V1SudokuCircuit::<Fp>::default(), 1).unwrap().to_json(),
+        },
+        "commitment": {
+            "ipa": zk_sudoku_halo2::report::run(&Halo2Prover::default(), &mut OsRng).unwrap().to_json(),
+            "kzg": zk_sudoku_halo2_kzg::report::run(&KzgProver::default(), &mut OsRng).unwrap().to_json(),
+        },
+        "puzzle_hash": {
+            "keccak256": zk_sudoku_halo2_kzg::report::run(&KzgProver::new(PuzzleHash::Keccak256), &mut OsRng).unwrap().to_json(),
+        },
+    });
+    let json = serde_json::to_string_pretty(&stats).unwrap();
+    println!("{}", json);
+    if let Some(path) = value("--stats") {
+        fs::write(path, json).unwrap();
+    }
+
+    if let Some(dir) = value("--layout") {
+        #[cfg(feature = "halo2-dev-graph")]
+        {
+            let dir = std::path::Path::new(&dir);
+            fs::create_dir_all(dir).unwrap();
+            zk_sudoku_halo2::layout::render_sudoku(false, &dir.join("custom-gate-layout.png"))
+                .unwrap();
+            zk_sudoku_halo2::layout::render_sudoku(true, &dir.join("lookup-layout.png")).unwrap();
+        }
+        #[cfg(not(feature = "halo2-dev-graph"))]
+        panic!("--layout {} needs the halo2-dev-graph feature", dir);
+    }
+}
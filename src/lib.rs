@@ -0,0 +1,6 @@
+//! The backends behind one error type, for the binaries and for callers
+//! which switch backends at run time.
+
+pub mod error;
+
+pub use error::{prove_and_verify, ZkSudokuError};
@@ -0,0 +1,18 @@
+use std::{env, fs};
+
+use zk_sudoku_core::report::reports_json;
+use zk_sudoku_noir::test_noir;
+
+// `test_noir --json <path>` also writes the reports to `path`
+fn main() {
+    let reports = test_noir();
+
+    let args = env::args().collect::<Vec<_>>();
+    if let Some(i) = args.iter().position(|arg| arg == "--json") {
+        let path = args
+            .get(i + 1)
+            .expect("--json takes the path of the report");
+        let json = serde_json::to_string_pretty(&reports_json(&reports)).unwrap();
+        fs::write(path, json).unwrap();
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    zk_sudoku_plonky3::test_plonky3();
+}
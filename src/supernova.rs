@@ -0,0 +1,3 @@
+fn main() {
+    zk_sudoku_nova::test_supernova();
+}